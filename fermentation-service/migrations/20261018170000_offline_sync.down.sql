@@ -0,0 +1,11 @@
+DROP INDEX IF EXISTS idx_sync_tombstones_version;
+DROP INDEX IF EXISTS idx_readings_sync_version;
+
+DROP TRIGGER IF EXISTS trg_readings_tombstone ON fermentation_readings;
+DROP FUNCTION IF EXISTS record_sync_tombstone();
+DROP TABLE IF EXISTS sync_tombstones;
+
+DROP TRIGGER IF EXISTS trg_readings_sync_version ON fermentation_readings;
+DROP FUNCTION IF EXISTS bump_sync_version();
+
+ALTER TABLE fermentation_readings DROP COLUMN IF EXISTS sync_version;
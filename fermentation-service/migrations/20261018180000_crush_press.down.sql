@@ -0,0 +1,10 @@
+DROP INDEX IF EXISTS idx_batch_must_sources_crush;
+DROP INDEX IF EXISTS idx_batch_must_sources_batch;
+DROP INDEX IF EXISTS idx_crush_inputs_harvest_id;
+DROP INDEX IF EXISTS idx_crush_operations_crushed_at;
+
+DROP TABLE IF EXISTS batch_must_sources;
+DROP TABLE IF EXISTS crush_inputs;
+DROP TABLE IF EXISTS crush_operations;
+
+DROP TYPE IF EXISTS must_fraction;
@@ -0,0 +1,46 @@
+-- Muljanje/presovanje: berbe (kg) → šira (L) → batch-evi, sa porekom kg → L
+CREATE TYPE must_fraction AS ENUM ('free_run', 'press');
+
+CREATE TABLE crush_operations (
+                                  id               UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                  crushed_at       TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                                  grape_variety    VARCHAR(255) NOT NULL,
+                                  total_grape_kg   DOUBLE PRECISION NOT NULL CHECK (total_grape_kg > 0),
+    -- Šira po frakcijama
+                                  free_run_liters  DOUBLE PRECISION NOT NULL CHECK (free_run_liters >= 0),
+                                  press_liters     DOUBLE PRECISION NOT NULL DEFAULT 0 CHECK (press_liters >= 0),
+                                  pomace_kg        DOUBLE PRECISION CHECK (pomace_kg >= 0),
+                                  press_program    VARCHAR(255),
+                                  notes            TEXT,
+                                  created_by       UUID NOT NULL,
+                                  created_at       TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                                  CHECK (free_run_liters + press_liters > 0)
+);
+
+-- Berbe u muljanju (bez FK - harvest-service); naziv parcele se kopira
+CREATE TABLE crush_inputs (
+                              id             UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                              crush_id       UUID NOT NULL REFERENCES crush_operations(id) ON DELETE CASCADE,
+                              harvest_id     UUID NOT NULL,
+                              weight_kg      DOUBLE PRECISION NOT NULL CHECK (weight_kg > 0),
+                              grape_variety  VARCHAR(255),
+                              vineyard_name  VARCHAR(255),
+                              parcel_name    VARCHAR(255),
+                              UNIQUE (crush_id, harvest_id)
+);
+
+-- Šira iz muljanja u batch-u
+CREATE TABLE batch_must_sources (
+                                    id          UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                    batch_id    UUID NOT NULL REFERENCES fermentation_batches(id) ON DELETE CASCADE,
+                                    crush_id    UUID NOT NULL REFERENCES crush_operations(id),
+                                    fraction    must_fraction NOT NULL,
+                                    liters      DOUBLE PRECISION NOT NULL CHECK (liters > 0),
+                                    created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                                    UNIQUE (batch_id, crush_id, fraction)
+);
+
+CREATE INDEX idx_crush_operations_crushed_at ON crush_operations(crushed_at DESC);
+CREATE INDEX idx_crush_inputs_harvest_id     ON crush_inputs(harvest_id);
+CREATE INDEX idx_batch_must_sources_batch    ON batch_must_sources(batch_id);
+CREATE INDEX idx_batch_must_sources_crush    ON batch_must_sources(crush_id);
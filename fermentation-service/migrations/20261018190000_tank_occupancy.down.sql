@@ -0,0 +1 @@
+DROP INDEX IF EXISTS uq_batches_occupied_tank;
@@ -0,0 +1,44 @@
+-- Tankovi koji već drže više aktivnih/pauziranih batch-eva moraju se razrešiti ručno
+-- pre migracije; inače bi kreiranje indeksa palo bez podatka o kojim batch-evima je reč.
+DO $$
+DECLARE
+    conflicts TEXT;
+BEGIN
+    SELECT string_agg(
+                   format('tank %s: %s', tank_id, batch_ids), '; ' ORDER BY tank_id
+           )
+    INTO conflicts
+    FROM (
+             SELECT tank_id, string_agg(id::TEXT, ', ' ORDER BY start_date, id) AS batch_ids
+             FROM fermentation_batches
+             WHERE status IN ('active', 'paused')
+             GROUP BY tank_id
+             HAVING COUNT(*) > 1
+         ) occupied;
+
+    IF conflicts IS NOT NULL THEN
+        RAISE EXCEPTION 'Tanks hold more than one active or paused batch: %', conflicts
+            USING HINT = 'Complete, cancel or move the extra batches, then rerun the migration.';
+    END IF;
+END
+$$;
+
+-- Najviše jedan batch koji drži vino (aktivan ili pauziran) po tanku
+CREATE UNIQUE INDEX uq_batches_occupied_tank
+    ON fermentation_batches(tank_id)
+    WHERE status IN ('active', 'paused');
+
+-- Uskladi status tankova sa batch-evima (ranije se menjao van transakcije)
+UPDATE tanks t SET status = 'in_use', updated_at = NOW()
+WHERE t.status <> 'in_use'
+  AND EXISTS (
+    SELECT 1 FROM fermentation_batches b
+    WHERE b.tank_id = t.id AND b.status IN ('active', 'paused')
+);
+
+UPDATE tanks t SET status = 'available', updated_at = NOW()
+WHERE t.status = 'in_use'
+  AND NOT EXISTS (
+    SELECT 1 FROM fermentation_batches b
+    WHERE b.tank_id = t.id AND b.status IN ('active', 'paused')
+);
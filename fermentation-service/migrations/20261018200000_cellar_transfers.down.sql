@@ -0,0 +1,31 @@
+-- Ispražnjeni batch-evi (0 L) ne mogu nazad pod stari CHECK; zapremina se ne izmišlja
+DO $$
+DECLARE
+    emptied TEXT;
+BEGIN
+    SELECT string_agg(id::TEXT, ', ' ORDER BY id)
+    INTO emptied
+    FROM fermentation_batches
+    WHERE volume_liters = 0;
+
+    IF emptied IS NOT NULL THEN
+        RAISE EXCEPTION 'Batches emptied into blends have 0 L and cannot be reverted: %', emptied
+            USING HINT = 'Delete or archive these batches before reverting the migration.';
+    END IF;
+END
+$$;
+
+ALTER TABLE fermentation_batches DROP CONSTRAINT fermentation_batches_volume_liters_check;
+ALTER TABLE fermentation_batches ADD CONSTRAINT fermentation_batches_volume_liters_check CHECK (volume_liters > 0);
+
+DROP INDEX IF EXISTS idx_batch_vessel_stays_batch;
+DROP INDEX IF EXISTS idx_cellar_movements_target;
+DROP INDEX IF EXISTS idx_cellar_movements_source;
+DROP INDEX IF EXISTS idx_cellar_movements_operation;
+DROP INDEX IF EXISTS idx_cellar_operations_performed_at;
+
+DROP TABLE IF EXISTS batch_vessel_stays;
+DROP TABLE IF EXISTS cellar_movements;
+DROP TABLE IF EXISTS cellar_operations;
+
+DROP TYPE IF EXISTS cellar_operation_kind;
@@ -0,0 +1,56 @@
+-- Pretakanje, premeštanje i kupažiranje vina između tankova
+CREATE TYPE cellar_operation_kind AS ENUM ('racking', 'transfer', 'blend');
+
+CREATE TABLE cellar_operations (
+                                   id            UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                   kind          cellar_operation_kind NOT NULL,
+                                   performed_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- Talog, kvasac, prosipanje
+                                   loss_liters   DOUBLE PRECISION NOT NULL DEFAULT 0 CHECK (loss_liters >= 0),
+                                   notes         TEXT,
+                                   created_by    UUID NOT NULL,
+                                   created_at    TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Pojedinačni tokovi vina u operaciji (batch/tank → batch/tank)
+CREATE TABLE cellar_movements (
+                                  id               UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                  operation_id     UUID NOT NULL REFERENCES cellar_operations(id) ON DELETE CASCADE,
+                                  source_batch_id  UUID REFERENCES fermentation_batches(id) ON DELETE SET NULL,
+                                  from_tank_id     UUID NOT NULL REFERENCES tanks(id),
+                                  target_batch_id  UUID REFERENCES fermentation_batches(id) ON DELETE SET NULL,
+                                  to_tank_id       UUID NOT NULL REFERENCES tanks(id),
+                                  volume_liters    DOUBLE PRECISION NOT NULL CHECK (volume_liters > 0)
+);
+
+-- Istorija sudova: u kom tanku je batch bio i koliko dugo
+CREATE TABLE batch_vessel_stays (
+                                    id                 UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                    batch_id           UUID NOT NULL REFERENCES fermentation_batches(id) ON DELETE CASCADE,
+                                    tank_id            UUID NOT NULL REFERENCES tanks(id),
+                                    operation_id       UUID REFERENCES cellar_operations(id) ON DELETE SET NULL,
+                                    volume_in_liters   DOUBLE PRECISION NOT NULL,
+                                    volume_out_liters  DOUBLE PRECISION,
+                                    entered_at         TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                                    left_at            TIMESTAMPTZ
+);
+
+CREATE INDEX idx_cellar_operations_performed_at ON cellar_operations(performed_at DESC);
+CREATE INDEX idx_cellar_movements_operation     ON cellar_movements(operation_id);
+CREATE INDEX idx_cellar_movements_source        ON cellar_movements(source_batch_id);
+CREATE INDEX idx_cellar_movements_target        ON cellar_movements(target_batch_id);
+CREATE INDEX idx_batch_vessel_stays_batch       ON batch_vessel_stays(batch_id, entered_at);
+
+-- Batch ispražnjen u kupažu ostaje u evidenciji sa 0 L
+ALTER TABLE fermentation_batches DROP CONSTRAINT fermentation_batches_volume_liters_check;
+ALTER TABLE fermentation_batches ADD CONSTRAINT fermentation_batches_volume_liters_check CHECK (volume_liters >= 0);
+
+-- Postojeći batch-evi: boravak u trenutnom tanku
+INSERT INTO batch_vessel_stays (batch_id, tank_id, volume_in_liters, volume_out_liters, entered_at, left_at)
+SELECT id,
+       tank_id,
+       volume_liters,
+       CASE WHEN status IN ('completed', 'cancelled') THEN volume_liters END,
+       COALESCE(start_date, created_at),
+       CASE WHEN status IN ('completed', 'cancelled') THEN COALESCE(end_date, updated_at) END
+FROM fermentation_batches;
@@ -0,0 +1,11 @@
+DROP INDEX IF EXISTS idx_batch_additions_product;
+DROP INDEX IF EXISTS idx_batch_additions_batch;
+
+DROP TABLE IF EXISTS batch_additions;
+DROP TABLE IF EXISTS addition_products;
+
+ALTER TABLE fermentation_batches DROP COLUMN IF EXISTS wine_type;
+
+DROP TYPE IF EXISTS wine_type;
+DROP TYPE IF EXISTS addition_unit;
+DROP TYPE IF EXISTS addition_category;
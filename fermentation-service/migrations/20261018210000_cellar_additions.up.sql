@@ -0,0 +1,44 @@
+-- Podrumski dodaci (SO2, kvasac, hranljive materije, enzimi, sredstva za bistrenje)
+CREATE TYPE addition_category AS ENUM ('so2', 'yeast', 'nutrient', 'enzyme', 'fining_agent', 'acid', 'tannin', 'other');
+CREATE TYPE addition_unit AS ENUM ('g', 'kg', 'ml', 'l');
+CREATE TYPE wine_type AS ENUM ('red', 'white', 'rose', 'sweet_red', 'sweet_white', 'sparkling');
+
+-- Tip vina određuje zakonski maksimum ukupnog SO2
+ALTER TABLE fermentation_batches ADD COLUMN wine_type wine_type;
+
+-- Katalog proizvoda
+CREATE TABLE addition_products (
+                                   id               UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                   name             VARCHAR(255) NOT NULL UNIQUE,
+                                   category         addition_category NOT NULL,
+                                   unit             addition_unit NOT NULL,
+    -- Grama SO2 po gramu/ml proizvoda (npr. K-metabisulfit 0.57)
+                                   so2_factor       DOUBLE PRECISION CHECK (so2_factor > 0 AND so2_factor <= 1),
+    -- Zakonski ili tehnološki maksimum po hL (u jedinici proizvoda)
+                                   max_dose_per_hl  DOUBLE PRECISION CHECK (max_dose_per_hl > 0),
+                                   supplier         VARCHAR(255),
+                                   notes            TEXT,
+                                   active           BOOLEAN NOT NULL DEFAULT TRUE,
+                                   created_at       TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                                   updated_at       TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Dodaci po batch-u
+CREATE TABLE batch_additions (
+                                 id                   UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                 batch_id             UUID NOT NULL REFERENCES fermentation_batches(id) ON DELETE CASCADE,
+                                 product_id           UUID NOT NULL REFERENCES addition_products(id),
+                                 dose_per_hl          DOUBLE PRECISION NOT NULL CHECK (dose_per_hl > 0),
+                                 quantity             DOUBLE PRECISION NOT NULL CHECK (quantity > 0),
+                                 batch_volume_liters  DOUBLE PRECISION NOT NULL,
+                                 so2_added_mg_l       DOUBLE PRECISION,
+                                 lot_number           VARCHAR(100),
+                                 added_by             UUID NOT NULL,
+                                 operator_email       VARCHAR(255) NOT NULL,
+                                 added_at             TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                                 notes                TEXT,
+                                 created_at           TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_batch_additions_batch   ON batch_additions(batch_id, added_at);
+CREATE INDEX idx_batch_additions_product ON batch_additions(product_id);
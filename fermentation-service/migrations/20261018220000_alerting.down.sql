@@ -0,0 +1,15 @@
+DROP TABLE IF EXISTS notification_channels;
+
+DROP INDEX IF EXISTS idx_alert_rules_tank;
+DROP INDEX IF EXISTS idx_alert_rules_batch;
+DROP INDEX IF EXISTS idx_alerts_batch;
+DROP INDEX IF EXISTS idx_alerts_status;
+DROP INDEX IF EXISTS uq_alerts_unresolved;
+
+DROP TABLE IF EXISTS alerts;
+DROP TABLE IF EXISTS alert_rules;
+
+DROP TYPE IF EXISTS notification_channel_kind;
+DROP TYPE IF EXISTS alert_status;
+DROP TYPE IF EXISTS alert_severity;
+DROP TYPE IF EXISTS alert_condition;
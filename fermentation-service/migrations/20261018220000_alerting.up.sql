@@ -0,0 +1,63 @@
+-- Pravila za uzbunjivanje, alarmi i kanali za obaveštenja
+CREATE TYPE alert_condition AS ENUM ('temperature_above', 'temperature_below', 'volatile_acidity_above', 'free_so2_below', 'missing_readings');
+CREATE TYPE alert_severity AS ENUM ('info', 'warning', 'critical');
+CREATE TYPE alert_status AS ENUM ('open', 'acknowledged', 'resolved');
+CREATE TYPE notification_channel_kind AS ENUM ('webhook', 'email', 'log');
+
+-- Pravilo važi za jedan batch, za sve batch-eve u tanku ili (bez oba) za sve aktivne batch-eve
+CREATE TABLE alert_rules (
+                             id                UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                             name              VARCHAR(255) NOT NULL,
+                             batch_id          UUID REFERENCES fermentation_batches(id) ON DELETE CASCADE,
+                             tank_id           UUID REFERENCES tanks(id) ON DELETE CASCADE,
+                             condition         alert_condition NOT NULL,
+    -- °C, g/L, mg/L ili sati bez merenja, zavisno od uslova
+                             threshold         DOUBLE PRECISION NOT NULL,
+    -- Koliko dugo uslov mora da traje pre alarma
+                             duration_minutes  INTEGER NOT NULL DEFAULT 0 CHECK (duration_minutes >= 0 AND duration_minutes <= 1440),
+                             severity          alert_severity NOT NULL DEFAULT 'warning',
+                             enabled           BOOLEAN NOT NULL DEFAULT TRUE,
+                             created_by        UUID NOT NULL,
+                             created_at        TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                             updated_at        TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                             CHECK (batch_id IS NULL OR tank_id IS NULL)
+);
+
+CREATE TABLE alerts (
+                        id               UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                        rule_id          UUID NOT NULL REFERENCES alert_rules(id) ON DELETE CASCADE,
+                        batch_id         UUID NOT NULL REFERENCES fermentation_batches(id) ON DELETE CASCADE,
+                        tank_id          UUID NOT NULL REFERENCES tanks(id) ON DELETE CASCADE,
+                        status           alert_status NOT NULL DEFAULT 'open',
+                        severity         alert_severity NOT NULL,
+                        message          TEXT NOT NULL,
+                        value            DOUBLE PRECISION,
+                        triggered_at     TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                        acknowledged_by  UUID,
+                        acknowledged_at  TIMESTAMPTZ,
+    -- resolved_by je NULL kada se alarm sam razreši
+                        resolved_by      UUID,
+                        resolved_at      TIMESTAMPTZ,
+                        created_at       TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                        updated_at       TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Najviše jedan nerazrešen alarm po pravilu i batch-u
+CREATE UNIQUE INDEX uq_alerts_unresolved ON alerts(rule_id, batch_id) WHERE status <> 'resolved';
+CREATE INDEX idx_alerts_status ON alerts(status, triggered_at DESC);
+CREATE INDEX idx_alerts_batch ON alerts(batch_id);
+CREATE INDEX idx_alert_rules_batch ON alert_rules(batch_id);
+CREATE INDEX idx_alert_rules_tank ON alert_rules(tank_id);
+
+CREATE TABLE notification_channels (
+                                       id            UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                       name          VARCHAR(255) NOT NULL,
+                                       kind          notification_channel_kind NOT NULL,
+    -- URL za webhook, adresa za email, prazno za log
+                                       target        VARCHAR(500),
+                                       min_severity  alert_severity NOT NULL DEFAULT 'warning',
+                                       enabled       BOOLEAN NOT NULL DEFAULT TRUE,
+                                       created_at    TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                                       updated_at    TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                                       CHECK (kind = 'log' OR target IS NOT NULL)
+);
@@ -0,0 +1,9 @@
+DROP INDEX IF EXISTS idx_readings_device;
+
+ALTER TABLE fermentation_readings DROP COLUMN IF EXISTS device_id;
+
+DROP INDEX IF EXISTS idx_iot_devices_tank;
+
+DROP TABLE IF EXISTS iot_devices;
+
+DROP TYPE IF EXISTS iot_device_type;
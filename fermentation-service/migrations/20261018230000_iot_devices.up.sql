@@ -0,0 +1,28 @@
+-- Registar IoT uređaja vezanih za tankove
+CREATE TYPE iot_device_type AS ENUM ('temperature_probe', 'temperature_humidity', 'density_meter', 'other');
+
+CREATE TABLE iot_devices (
+                             id                  UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- Hardverski identifikator koji uređaj šalje (npr. MAC ili ime ESP32 čipa)
+                             device_id           VARCHAR(100) NOT NULL UNIQUE,
+                             name                VARCHAR(255) NOT NULL,
+                             device_type         iot_device_type NOT NULL,
+                             firmware_version    VARCHAR(50),
+                             tank_id             UUID REFERENCES tanks(id) ON DELETE SET NULL,
+    -- °C koje se dodaju izmerenoj temperaturi
+                             calibration_offset  DOUBLE PRECISION NOT NULL DEFAULT 0,
+                             battery_percent     DOUBLE PRECISION CHECK (battery_percent >= 0 AND battery_percent <= 100),
+                             last_seen_at        TIMESTAMPTZ,
+                             active              BOOLEAN NOT NULL DEFAULT TRUE,
+                             notes               TEXT,
+                             created_at          TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                             updated_at          TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_iot_devices_tank ON iot_devices(tank_id);
+
+-- Koji uređaj je poslao merenje
+ALTER TABLE fermentation_readings
+    ADD COLUMN device_id UUID REFERENCES iot_devices(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_readings_device ON fermentation_readings(device_id);
@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS uq_readings_source_device_time;
+
+ALTER TABLE fermentation_readings DROP COLUMN IF EXISTS source_device;
+
+DROP TABLE IF EXISTS reading_sensor_values;
+
+DROP TYPE IF EXISTS sensor_channel;
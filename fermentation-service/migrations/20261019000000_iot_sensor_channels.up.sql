@@ -0,0 +1,18 @@
+-- Ostali kanali IoT senzora (vlažnost, pritisak, CO2, rastvoreni O2, nivo) kao vrednosti po kanalu
+CREATE TYPE sensor_channel AS ENUM ('humidity', 'pressure', 'co2', 'dissolved_oxygen', 'level');
+
+CREATE TABLE reading_sensor_values (
+                                       reading_id  UUID NOT NULL REFERENCES fermentation_readings(id) ON DELETE CASCADE,
+                                       channel     sensor_channel NOT NULL,
+                                       value       DOUBLE PRECISION NOT NULL,
+                                       PRIMARY KEY (reading_id, channel)
+);
+
+-- Hardverski id uređaja koji je poslao merenje (i neregistrovanog)
+ALTER TABLE fermentation_readings
+    ADD COLUMN source_device VARCHAR(100);
+
+-- Isti uređaj u istom trenutku = isto merenje (QoS 1 i ponovljeni upload šalju duplikate)
+CREATE UNIQUE INDEX uq_readings_source_device_time
+    ON fermentation_readings(source_device, recorded_at)
+    WHERE source_device IS NOT NULL;
@@ -0,0 +1,23 @@
+DROP TABLE IF EXISTS sync_tombstone_state;
+
+DROP TRIGGER IF EXISTS trg_readings_tombstone ON fermentation_readings;
+
+CREATE TRIGGER trg_readings_tombstone
+    AFTER DELETE ON fermentation_readings
+    FOR EACH ROW EXECUTE FUNCTION record_sync_tombstone('reading', 'batch_id');
+
+DROP TRIGGER IF EXISTS trg_readings_rollup_invalidation_move ON fermentation_readings;
+
+DROP TRIGGER IF EXISTS trg_readings_rollup_invalidation_delete ON fermentation_readings;
+
+DROP FUNCTION IF EXISTS record_rollup_invalidation();
+
+DROP TABLE IF EXISTS reading_rollup_invalidations;
+
+DROP TABLE IF EXISTS reading_rollup_state;
+
+DROP TABLE IF EXISTS reading_rollups;
+
+DROP TYPE IF EXISTS reading_metric;
+
+DROP TYPE IF EXISTS rollup_resolution;
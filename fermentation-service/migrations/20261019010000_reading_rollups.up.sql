@@ -0,0 +1,77 @@
+-- Agregati merenja po vremenskim intervalima (min/max/avg) za grafike i duge periode
+CREATE TYPE rollup_resolution AS ENUM ('five_minutes', 'hour', 'day');
+CREATE TYPE reading_metric AS ENUM (
+    'temperature', 'brix', 'density', 'ph',
+    'humidity', 'pressure', 'co2', 'dissolved_oxygen', 'level'
+);
+
+CREATE TABLE reading_rollups (
+                                 batch_id      UUID NOT NULL REFERENCES fermentation_batches(id) ON DELETE CASCADE,
+                                 resolution    rollup_resolution NOT NULL,
+                                 metric        reading_metric NOT NULL,
+                                 bucket_start  TIMESTAMPTZ NOT NULL,
+                                 min_value     DOUBLE PRECISION NOT NULL,
+                                 max_value     DOUBLE PRECISION NOT NULL,
+                                 avg_value     DOUBLE PRECISION NOT NULL,
+                                 sample_count  INTEGER NOT NULL,
+                                 PRIMARY KEY (batch_id, resolution, metric, bucket_start)
+);
+
+-- Dokle su agregati ažurirani: poslednja obrađena sync verzija merenja
+-- (obrađuju se samo verzije ispod watermark-a, pa se ništa ne preskače)
+CREATE TABLE reading_rollup_state (
+                                      id                 SMALLINT PRIMARY KEY DEFAULT 1 CHECK (id = 1),
+                                      last_sync_version  BIGINT NOT NULL DEFAULT 0,
+                                      refreshed_at       TIMESTAMPTZ
+);
+
+INSERT INTO reading_rollup_state (id) VALUES (1);
+
+-- Intervali koje treba ponovo izračunati jer je merenje iz njih obrisano ili
+-- premešteno; redovi se brišu kad ih osvežavanje obradi
+CREATE TABLE reading_rollup_invalidations (
+                                              batch_id      UUID NOT NULL,
+                                              recorded_at   TIMESTAMPTZ NOT NULL,
+                                              sync_version  BIGINT NOT NULL
+);
+
+CREATE INDEX idx_rollup_invalidations_version ON reading_rollup_invalidations(sync_version);
+
+CREATE FUNCTION record_rollup_invalidation() RETURNS TRIGGER AS $$
+BEGIN
+    INSERT INTO reading_rollup_invalidations (batch_id, recorded_at, sync_version)
+    VALUES (OLD.batch_id, OLD.recorded_at, pg_current_xact_id()::TEXT::BIGINT);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Brisanje zbog retencije postavlja `fermentation.retention_purge`: obrisani intervali
+-- su već agregirani, a klijenti takva merenja ne treba da brišu lokalno
+CREATE TRIGGER trg_readings_rollup_invalidation_delete
+    AFTER DELETE ON fermentation_readings
+    FOR EACH ROW
+    WHEN (current_setting('fermentation.retention_purge', true) IS DISTINCT FROM 'on')
+    EXECUTE FUNCTION record_rollup_invalidation();
+
+CREATE TRIGGER trg_readings_rollup_invalidation_move
+    AFTER UPDATE OF batch_id, recorded_at ON fermentation_readings
+    FOR EACH ROW
+    WHEN (OLD.batch_id IS DISTINCT FROM NEW.batch_id OR OLD.recorded_at IS DISTINCT FROM NEW.recorded_at)
+    EXECUTE FUNCTION record_rollup_invalidation();
+
+DROP TRIGGER trg_readings_tombstone ON fermentation_readings;
+
+CREATE TRIGGER trg_readings_tombstone
+    AFTER DELETE ON fermentation_readings
+    FOR EACH ROW
+    WHEN (current_setting('fermentation.retention_purge', true) IS DISTINCT FROM 'on')
+    EXECUTE FUNCTION record_sync_tombstone('reading', 'batch_id');
+
+-- Do koje verzije su tombstone-i obrisani; klijent sa starijim kursorom mora
+-- ponovo da sinhronizuje sve
+CREATE TABLE sync_tombstone_state (
+                                      id                      SMALLINT PRIMARY KEY DEFAULT 1 CHECK (id = 1),
+                                      pruned_through_version  BIGINT NOT NULL DEFAULT 0
+);
+
+INSERT INTO sync_tombstone_state (id) VALUES (1);
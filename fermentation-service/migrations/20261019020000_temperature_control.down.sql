@@ -0,0 +1,11 @@
+DROP INDEX IF EXISTS idx_control_commands_tank;
+
+DROP TABLE IF EXISTS control_commands;
+
+DROP TABLE IF EXISTS tank_setpoint_steps;
+
+DROP TABLE IF EXISTS tank_controls;
+
+DROP TYPE IF EXISTS control_command_source;
+
+DROP TYPE IF EXISTS control_action;
@@ -0,0 +1,49 @@
+-- Regulacija temperature tankova: raspored zadatih temperatura, stanje aktuatora i log komandi
+CREATE TYPE control_action AS ENUM ('idle', 'cool', 'heat');
+CREATE TYPE control_command_source AS ENUM ('controller', 'manual');
+
+CREATE TABLE tank_controls (
+                               tank_id              UUID PRIMARY KEY REFERENCES tanks(id) ON DELETE CASCADE,
+                               enabled              BOOLEAN NOT NULL DEFAULT FALSE,
+    -- Dozvoljeno odstupanje od zadate temperature pre uključivanja hlađenja/grejanja (°C)
+                               deadband             DOUBLE PRECISION NOT NULL DEFAULT 0.5 CHECK (deadband > 0 AND deadband <= 5),
+                               schedule_started_at  TIMESTAMPTZ,
+    -- Poslednja komanda koju je aktuator prihvatio
+                               current_action       control_action NOT NULL DEFAULT 'idle',
+    -- Ručno preuzimanje; bez override_until važi dok se ne ukine
+                               override_action      control_action,
+                               override_until       TIMESTAMPTZ,
+                               override_reason      TEXT,
+                               override_by          UUID,
+                               created_at           TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                               updated_at           TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Koraci rasporeda: prelaz (ramp) od prethodne temperature pa držanje
+CREATE TABLE tank_setpoint_steps (
+                                     id                  UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                     tank_id             UUID NOT NULL REFERENCES tank_controls(tank_id) ON DELETE CASCADE,
+                                     position            INTEGER NOT NULL,
+                                     name                VARCHAR(100) NOT NULL,
+                                     target_temperature  DOUBLE PRECISION NOT NULL CHECK (target_temperature >= -5 AND target_temperature <= 45),
+                                     ramp_hours          DOUBLE PRECISION NOT NULL DEFAULT 0 CHECK (ramp_hours >= 0),
+    -- NULL = drži se do kraja rasporeda
+                                     hold_hours          DOUBLE PRECISION CHECK (hold_hours > 0),
+                                     UNIQUE (tank_id, position)
+);
+
+CREATE TABLE control_commands (
+                                  id                    UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                  tank_id               UUID NOT NULL REFERENCES tanks(id) ON DELETE CASCADE,
+                                  action                control_action NOT NULL,
+                                  source                control_command_source NOT NULL,
+                                  setpoint              DOUBLE PRECISION,
+                                  measured_temperature  DOUBLE PRECISION,
+                                  driver                VARCHAR(50) NOT NULL,
+                                  success               BOOLEAN NOT NULL,
+                                  error                 TEXT,
+                                  issued_by             UUID,
+                                  issued_at             TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_control_commands_tank ON control_commands(tank_id, issued_at DESC);
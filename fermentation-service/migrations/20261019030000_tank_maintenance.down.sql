@@ -0,0 +1,18 @@
+DROP INDEX IF EXISTS idx_batch_vessel_stays_tank_left;
+DROP INDEX IF EXISTS idx_tank_maintenance_records_tank;
+DROP INDEX IF EXISTS idx_tank_maintenance_tasks_due;
+DROP INDEX IF EXISTS idx_tank_cleanings_tank;
+
+ALTER TABLE tanks DROP COLUMN IF EXISTS dirty_since;
+
+DROP TABLE IF EXISTS tank_maintenance_records;
+
+DROP TABLE IF EXISTS tank_maintenance_tasks;
+
+DROP TABLE IF EXISTS tank_cleanings;
+
+DROP TYPE IF EXISTS maintenance_kind;
+
+DROP TYPE IF EXISTS cleaning_verification;
+
+DROP TYPE IF EXISTS cleaning_kind;
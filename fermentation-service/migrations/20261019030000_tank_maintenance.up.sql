@@ -0,0 +1,60 @@
+-- Čišćenje/sanitacija i održavanje tankova
+CREATE TYPE cleaning_kind AS ENUM ('cleaning', 'sanitation', 'cleaning_and_sanitation');
+CREATE TYPE cleaning_verification AS ENUM ('passed', 'failed', 'not_verified');
+CREATE TYPE maintenance_kind AS ENUM ('valve_seals', 'cooling_jacket', 'gaskets', 'pressure_test', 'calibration', 'other');
+
+CREATE TABLE tank_cleanings (
+                                id                 UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                tank_id            UUID NOT NULL REFERENCES tanks(id) ON DELETE CASCADE,
+                                kind               cleaning_kind NOT NULL,
+                                procedure          TEXT NOT NULL,
+    -- Sredstva sa koncentracijom, npr. "NaOH 2%", "persirćetna kiselina 0.2%"
+                                chemicals          TEXT[] NOT NULL DEFAULT '{}',
+                                water_temperature  DOUBLE PRECISION,
+                                duration_minutes   INTEGER CHECK (duration_minutes > 0),
+    -- ATP bris (RLU) i vizuelni pregled
+                                atp_rlu            DOUBLE PRECISION CHECK (atp_rlu >= 0),
+                                visual_check       BOOLEAN,
+                                verification       cleaning_verification NOT NULL,
+                                operator_id        UUID NOT NULL,
+                                operator_email     VARCHAR(255) NOT NULL,
+                                performed_at       TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                                notes              TEXT,
+                                created_at         TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Planirano održavanje; ponavljajući zadatak posle izvršenja dobija novi rok
+CREATE TABLE tank_maintenance_tasks (
+                                        id             UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                        tank_id        UUID NOT NULL REFERENCES tanks(id) ON DELETE CASCADE,
+                                        kind           maintenance_kind NOT NULL,
+                                        title          VARCHAR(255) NOT NULL,
+                                        due_date       DATE NOT NULL,
+                                        interval_days  INTEGER CHECK (interval_days > 0),
+                                        active         BOOLEAN NOT NULL DEFAULT TRUE,
+                                        notes          TEXT,
+                                        created_at     TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                                        updated_at     TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE tank_maintenance_records (
+                                          id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                          tank_id         UUID NOT NULL REFERENCES tanks(id) ON DELETE CASCADE,
+                                          task_id         UUID REFERENCES tank_maintenance_tasks(id) ON DELETE SET NULL,
+                                          kind            maintenance_kind NOT NULL,
+                                          description     TEXT NOT NULL,
+                                          operator_id     UUID NOT NULL,
+                                          operator_email  VARCHAR(255) NOT NULL,
+                                          performed_at    TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                                          notes           TEXT,
+                                          created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Od kada je tank prljav po batch-evima koji su obrisani zajedno sa boravcima u
+-- tanku; noviji boravci se čitaju iz batch_vessel_stays
+ALTER TABLE tanks ADD COLUMN dirty_since TIMESTAMPTZ;
+
+CREATE INDEX idx_tank_cleanings_tank             ON tank_cleanings(tank_id, performed_at DESC);
+CREATE INDEX idx_tank_maintenance_tasks_due      ON tank_maintenance_tasks(due_date) WHERE active;
+CREATE INDEX idx_tank_maintenance_records_tank   ON tank_maintenance_records(tank_id, performed_at DESC);
+CREATE INDEX idx_batch_vessel_stays_tank_left    ON batch_vessel_stays(tank_id, left_at);
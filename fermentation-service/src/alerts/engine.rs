@@ -0,0 +1,168 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::{
+    alerts::Notifier,
+    db::{AlertRepository, FermentationRepository},
+    error::AppError,
+    models::{
+        alert_message, evaluate_missing, evaluate_threshold, Alert, AlertCondition, AlertEvent,
+        FermentationBatch, FermentationStatus, RuleEvaluation, StreamEvent,
+    },
+    stream::StreamHub,
+};
+
+/// Koliko merenja unazad se gleda (najduže trajanje uslova je 24h)
+const EVALUATION_WINDOW_HOURS: i64 = 25;
+/// Merenja jednog batch-a pristigla u ovom razmaku proveravaju se jednom
+const CHECK_DEBOUNCE: Duration = Duration::from_secs(2);
+
+/// Proverava pravila posle svakog merenja i periodično (nedostajuća merenja)
+#[derive(Clone)]
+pub struct AlertEngine {
+    repo: FermentationRepository,
+    alert_repo: AlertRepository,
+    notifier: Notifier,
+    stream: StreamHub,
+    /// Batch-evi kojima je provera već zakazana
+    pending: Arc<Mutex<HashSet<Uuid>>>,
+}
+
+impl AlertEngine {
+    pub fn new(
+        repo: FermentationRepository,
+        alert_repo: AlertRepository,
+        notifier: Notifier,
+        stream: StreamHub,
+    ) -> Self {
+        Self {
+            repo,
+            alert_repo,
+            notifier,
+            stream,
+            pending: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// Promena alarma ide u live stream, a otvaranje i razrešavanje i na kanale
+    pub async fn publish(&self, event: AlertEvent, alert: &Alert) -> Result<(), AppError> {
+        self.stream.publish(StreamEvent::Alert {
+            event,
+            alert: alert.clone(),
+        });
+
+        match event {
+            AlertEvent::Triggered | AlertEvent::Resolved => self.notifier.notify(event, alert).await,
+            _ => Ok(()),
+        }
+    }
+
+    pub fn notifier(&self) -> &Notifier {
+        &self.notifier
+    }
+
+    /// Provera u pozadini, da upis merenja ne čeka na obaveštenja. Dok je provera
+    /// batch-a zakazana, nova merenja ne zakazuju još jednu; skidanje sa liste pre
+    /// provere znači da merenje koje stigne tokom nje zakazuje sledeću.
+    pub fn check_batch(&self, batch_id: Uuid) {
+        if !self.pending.lock().unwrap().insert(batch_id) {
+            return;
+        }
+
+        let engine = self.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(CHECK_DEBOUNCE).await;
+            engine.pending.lock().unwrap().remove(&batch_id);
+
+            if let Err(e) = engine.evaluate_batch(batch_id).await {
+                tracing::error!("Alert evaluation for batch {} failed: {}", batch_id, e);
+            }
+        });
+    }
+
+    pub async fn evaluate_batch(&self, batch_id: Uuid) -> Result<(), AppError> {
+        let batch = self.repo.find_batch_by_id(batch_id).await?;
+        if batch.status != FermentationStatus::Active {
+            return Ok(());
+        }
+
+        self.evaluate(&batch).await
+    }
+
+    /// Periodična provera svih aktivnih batch-eva
+    pub async fn evaluate_active(&self) -> Result<(), AppError> {
+        let resolved = self.alert_repo.resolve_stale_alerts().await?;
+        if resolved > 0 {
+            tracing::info!("Resolved {} alerts of inactive batches or disabled rules", resolved);
+        }
+
+        for batch in self.repo.list_active_batches().await? {
+            if let Err(e) = self.evaluate(&batch).await {
+                tracing::error!("Alert evaluation for batch {} failed: {}", batch.id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn spawn_scheduler(self, interval: Duration) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.evaluate_active().await {
+                    tracing::error!("Scheduled alert evaluation failed: {}", e);
+                }
+            }
+        });
+    }
+
+    async fn evaluate(&self, batch: &FermentationBatch) -> Result<(), AppError> {
+        let rules = self.alert_repo.list_rules_for_batch(batch.id, batch.tank_id).await?;
+        if rules.is_empty() {
+            return Ok(());
+        }
+
+        let now = Utc::now();
+        let points = self
+            .alert_repo
+            .list_alert_points(batch.id, now - chrono::Duration::hours(EVALUATION_WINDOW_HOURS))
+            .await?;
+        let last_reading_at = self.alert_repo.last_reading_at(batch.id).await?;
+        let unresolved = self.alert_repo.list_unresolved_alerts(batch.id).await?;
+        let tank = self.repo.find_tank_by_id(batch.tank_id).await?;
+
+        for rule in &rules {
+            let evaluation = match rule.condition {
+                AlertCondition::MissingReadings => evaluate_missing(
+                    rule,
+                    last_reading_at,
+                    batch.start_date.unwrap_or(batch.created_at),
+                    now,
+                ),
+                _ => evaluate_threshold(rule, &points),
+            };
+            let existing = unresolved.iter().find(|a| a.rule_id == rule.id);
+
+            match (evaluation, existing) {
+                (RuleEvaluation::Triggered(value), None) => {
+                    let message = alert_message(rule, &batch.name, &tank.name, value);
+                    if let Some(alert) = self.alert_repo.open_alert(rule, batch, &message, value).await? {
+                        self.publish(AlertEvent::Triggered, &alert).await?;
+                    }
+                }
+                (RuleEvaluation::Clear, Some(alert)) => {
+                    let alert = self.alert_repo.resolve_alert(alert.id, None).await?;
+                    self.publish(AlertEvent::Resolved, &alert).await?;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+pub mod engine;
+pub mod notifier;
+
+pub use engine::*;
+pub use notifier::*;
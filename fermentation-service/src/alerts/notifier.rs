@@ -0,0 +1,150 @@
+use std::time::Duration;
+
+use lettre::{
+    message::Mailbox, AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+
+use crate::{
+    db::AlertRepository,
+    error::AppError,
+    models::{Alert, AlertEvent, AlertNotification, NotificationChannel, NotificationChannelKind},
+};
+
+/// Slanje obaveštenja o alarmima na konfigurisane kanale (webhook, email, log)
+#[derive(Clone)]
+pub struct Notifier {
+    alert_repo: AlertRepository,
+    http: reqwest::Client,
+    mailer: Option<Mailer>,
+}
+
+#[derive(Clone)]
+struct Mailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl Notifier {
+    /// Bez SMTP_URL email kanali samo beleže upozorenje u log
+    pub fn new(
+        alert_repo: AlertRepository,
+        smtp_url: Option<&str>,
+        email_from: &str,
+    ) -> anyhow::Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()?;
+
+        let mailer = match smtp_url {
+            Some(url) => Some(Mailer {
+                transport: AsyncSmtpTransport::<Tokio1Executor>::from_url(url)?.build(),
+                from: email_from.parse()?,
+            }),
+            None => None,
+        };
+
+        Ok(Self { alert_repo, http, mailer })
+    }
+
+    /// Šalje alarm na sve uključene kanale čiji je prag ozbiljnosti dostignut;
+    /// greška jednog kanala ne sprečava ostale
+    pub async fn notify(&self, event: AlertEvent, alert: &Alert) -> Result<(), AppError> {
+        let channels = self.alert_repo.list_notification_channels(true).await?;
+
+        for channel in channels.iter().filter(|c| alert.severity >= c.min_severity) {
+            if let Err(e) = self.send(channel, event, alert).await {
+                tracing::error!(
+                    "Alert {} notification via '{}' failed: {}",
+                    alert.id,
+                    channel.name,
+                    e
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn send(
+        &self,
+        channel: &NotificationChannel,
+        event: AlertEvent,
+        alert: &Alert,
+    ) -> Result<(), AppError> {
+        match channel.kind {
+            NotificationChannelKind::Log => {
+                tracing::warn!(
+                    alert_id = %alert.id,
+                    severity = ?alert.severity,
+                    "Alert {:?}: {}",
+                    event,
+                    alert.message
+                );
+                Ok(())
+            }
+            NotificationChannelKind::Webhook => {
+                let url = channel.target.as_deref().unwrap_or_default();
+                let response = self
+                    .http
+                    .post(url)
+                    .json(&AlertNotification { event, alert })
+                    .send()
+                    .await
+                    .map_err(|e| AppError::UpstreamError(format!("webhook: {}", e)))?;
+
+                if !response.status().is_success() {
+                    return Err(AppError::UpstreamError(format!(
+                        "webhook responded with {}",
+                        response.status()
+                    )));
+                }
+                Ok(())
+            }
+            NotificationChannelKind::Email => {
+                let Some(mailer) = &self.mailer else {
+                    tracing::warn!(
+                        "SMTP is not configured, alert email to '{}' skipped: {}",
+                        channel.name,
+                        alert.message
+                    );
+                    return Ok(());
+                };
+
+                let to: Mailbox = channel
+                    .target
+                    .as_deref()
+                    .unwrap_or_default()
+                    .parse()
+                    .map_err(|e| AppError::InternalError(format!("Invalid email address: {}", e)))?;
+
+                let subject = match event {
+                    AlertEvent::Triggered => format!("[{:?}] {}", alert.severity, alert.rule_name),
+                    AlertEvent::Resolved => format!("[Resolved] {}", alert.rule_name),
+                    AlertEvent::Acknowledged => format!("[Acknowledged] {}", alert.rule_name),
+                    AlertEvent::Test => "Test notification".to_string(),
+                };
+                let body = format!(
+                    "{}\n\nBatch: {}\nTank: {}\nTriggered at: {}\n",
+                    alert.message,
+                    alert.batch_name,
+                    alert.tank_name,
+                    alert.triggered_at.format("%Y-%m-%d %H:%M UTC")
+                );
+
+                let message = Message::builder()
+                    .from(mailer.from.clone())
+                    .to(to)
+                    .subject(subject)
+                    .body(body)
+                    .map_err(|e| AppError::InternalError(format!("Email build failed: {}", e)))?;
+
+                mailer
+                    .transport
+                    .send(message)
+                    .await
+                    .map_err(|e| AppError::UpstreamError(format!("smtp: {}", e)))?;
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::error::AppError;
+
+/// Berba kako je vraća harvest-service (samo polja koja nam trebaju)
+#[derive(Debug, Clone, Deserialize)]
+pub struct RemoteHarvest {
+    pub id: Uuid,
+    pub status: String, // planned | in_progress | completed | cancelled
+    pub total_weight_kg: Option<f64>,
+    pub grape_variety: Option<String>,
+    pub vineyard_name: Option<String>,
+    pub parcel_name: Option<String>,
+}
+
+/// HTTP klijent za harvest-service
+#[derive(Clone)]
+pub struct HarvestClient {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+impl HarvestClient {
+    pub fn new(base_url: &str) -> anyhow::Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()?;
+
+        Ok(Self {
+            http,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        })
+    }
+
+    pub async fn get_harvest(&self, token: &str, harvest_id: Uuid) -> Result<RemoteHarvest, AppError> {
+        let url = format!("{}/api/v1/harvests/{}", self.base_url, harvest_id);
+
+        let response = self
+            .http
+            .get(&url)
+            .bearer_auth(token)
+            .send()
+            .await
+            .map_err(|e| AppError::UpstreamError(format!("harvest-service: {}", e)))?;
+
+        match response.status() {
+            s if s.is_success() => response
+                .json::<RemoteHarvest>()
+                .await
+                .map_err(|e| AppError::UpstreamError(format!("harvest-service: {}", e))),
+            StatusCode::NOT_FOUND => Err(AppError::ValidationError(format!(
+                "Harvest {} not found",
+                harvest_id
+            ))),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(AppError::Forbidden(
+                "Access to harvest denied".to_string(),
+            )),
+            s => Err(AppError::UpstreamError(format!(
+                "harvest-service returned {} for {}",
+                s, url
+            ))),
+        }
+    }
+
+    /// Obriši priloge obrisanog batch-a ili tanka (`entity_type` = "batch" | "tank")
+    pub async fn purge_attachments(
+        &self,
+        token: &str,
+        entity_type: &str,
+        entity_id: Uuid,
+    ) -> Result<(), AppError> {
+        let url = format!(
+            "{}/api/v1/attachments?entity_type={}&entity_id={}",
+            self.base_url, entity_type, entity_id
+        );
+
+        let response = self
+            .http
+            .delete(&url)
+            .bearer_auth(token)
+            .send()
+            .await
+            .map_err(|e| AppError::UpstreamError(format!("harvest-service: {}", e)))?;
+
+        match response.status() {
+            s if s.is_success() => Ok(()),
+            s => Err(AppError::UpstreamError(format!(
+                "harvest-service returned {} for {}",
+                s, url
+            ))),
+        }
+    }
+}
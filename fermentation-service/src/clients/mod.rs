@@ -0,0 +1,3 @@
+pub mod harvest_client;
+
+pub use harvest_client::*;
@@ -1,14 +1,48 @@
 ﻿use std::env;
 
+/// MQTT broker za IoT senzore; bez MQTT_HOST pretplata je isključena
+#[derive(Clone, Debug)]
+pub struct MqttSettings {
+    pub host: String,
+    pub port: u16,
+    pub client_id: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub topic_prefix: String,
+}
+
 #[derive(Clone, Debug)]
 pub struct Settings {
     pub database_url: String,
     pub host: String,
     pub port: u16,
     pub jwt_secret: String,
+    pub harvest_service_url: String,
+    pub smtp_url: Option<String>,
+    pub alert_email_from: String,
+    pub alert_check_interval_secs: u64,
+    pub rollup_interval_secs: u64,
+    pub control_interval_secs: u64,
+    /// Sirova IoT merenja starija od ovoga se brišu (agregati ostaju); bez vrednosti se čuvaju
+    pub raw_reading_retention_days: Option<i64>,
+    /// Tombstone-i stariji od ovoga se brišu; klijent sa starijim kursorom sinhronizuje sve iznova
+    pub sync_tombstone_retention_days: Option<i64>,
+    pub mqtt: Option<MqttSettings>,
     pub allowed_origins: Vec<String>,
 }
 
+/// Svaka instanca mora imati svoj id: broker drugu vezu sa istim id-jem prekida prvu.
+/// Id mora biti i stabilan, jer je trajna sesija vezana za njega; u Docker-u je
+/// HOSTNAME id kontejnera.
+fn default_mqtt_client_id() -> String {
+    let instance = env::var("HOSTNAME")
+        .ok()
+        .filter(|h| !h.is_empty())
+        .unwrap_or_else(|| uuid::Uuid::new_v4().simple().to_string());
+
+    format!("fermentation-service-{}", instance)
+}
+
 impl Settings {
     pub fn from_env() -> anyhow::Result<Self> {
         dotenvy::dotenv().ok();
@@ -19,6 +53,53 @@ impl Settings {
             .map(|s| s.trim().to_string())
             .collect();
 
+        let mqtt = match env::var("MQTT_HOST").ok().filter(|h| !h.is_empty()) {
+            Some(host) => Some(MqttSettings {
+                host,
+                port: env::var("MQTT_PORT")
+                    .unwrap_or_else(|_| "1883".to_string())
+                    .parse()?,
+                client_id: env::var("MQTT_CLIENT_ID")
+                    .ok()
+                    .filter(|id| !id.is_empty())
+                    .unwrap_or_else(default_mqtt_client_id),
+                username: env::var("MQTT_USERNAME").ok(),
+                password: env::var("MQTT_PASSWORD").ok(),
+                topic_prefix: env::var("MQTT_TOPIC_PREFIX")
+                    .unwrap_or_else(|_| "vinomonitor".to_string()),
+            }),
+            None => None,
+        };
+
+        // Analitika i alarmi rade nad sirovim merenjima poslednjih 7 dana
+        let raw_reading_retention_days = match env::var("RAW_READING_RETENTION_DAYS")
+            .ok()
+            .filter(|d| !d.is_empty())
+        {
+            Some(days) => {
+                let days: i64 = days.parse()?;
+                if days < 8 {
+                    anyhow::bail!("RAW_READING_RETENTION_DAYS must be at least 8");
+                }
+                Some(days)
+            }
+            None => None,
+        };
+
+        let sync_tombstone_retention_days = match env::var("SYNC_TOMBSTONE_RETENTION_DAYS")
+            .ok()
+            .filter(|d| !d.is_empty())
+        {
+            Some(days) => {
+                let days: i64 = days.parse()?;
+                if days < 1 {
+                    anyhow::bail!("SYNC_TOMBSTONE_RETENTION_DAYS must be at least 1");
+                }
+                Some(days)
+            }
+            None => None,
+        };
+
         Ok(Settings {
             database_url: env::var("DATABASE_URL")?,
             host: env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string()),
@@ -26,6 +107,23 @@ impl Settings {
                 .unwrap_or_else(|_| "8004".to_string())
                 .parse()?,
             jwt_secret: env::var("JWT_SECRET")?,
+            harvest_service_url: env::var("HARVEST_SERVICE_URL")
+                .unwrap_or_else(|_| "http://localhost:8003".to_string()),
+            smtp_url: env::var("SMTP_URL").ok().filter(|s| !s.is_empty()),
+            alert_email_from: env::var("ALERT_EMAIL_FROM")
+                .unwrap_or_else(|_| "VinoMonitor <alerts@vinomonitor.local>".to_string()),
+            alert_check_interval_secs: env::var("ALERT_CHECK_INTERVAL_SECS")
+                .unwrap_or_else(|_| "300".to_string())
+                .parse()?,
+            rollup_interval_secs: env::var("ROLLUP_INTERVAL_SECS")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()?,
+            raw_reading_retention_days,
+            sync_tombstone_retention_days,
+            control_interval_secs: env::var("CONTROL_INTERVAL_SECS")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()?,
+            mqtt,
             allowed_origins,
         })
     }
@@ -0,0 +1,115 @@
+use std::sync::{Arc, Mutex};
+
+use axum::async_trait;
+use rumqttc::{AsyncClient, QoS};
+
+use crate::models::ActuatorCommand;
+
+/// Upravljanje ventilom glikola i grejačem tanka. Implementacija se bira pri
+/// pokretanju servisa (MQTT kontroler na tanku ili simulator).
+#[async_trait]
+pub trait ActuatorDriver: Send + Sync {
+    /// Naziv drajvera koji se upisuje u log komandi
+    fn name(&self) -> &'static str;
+
+    /// `Ok` znači da je drajver preuzeo komandu, ne nužno i da je izvršena
+    async fn send(&self, command: &ActuatorCommand) -> Result<(), String>;
+}
+
+/// Komanda se objavljuje na `{prefix}/tanks/{tank_id}/actuator` kao retained poruka,
+/// pa kontroler na tanku posle restarta odmah dobija poslednje stanje.
+///
+/// `send` ne čeka PubAck brokera: uspeh znači da je poruka u redu MQTT klijenta.
+/// Event loop je šalje sa QoS 1 i, zbog trajne sesije, ponavlja je posle prekida
+/// veze dok broker ne potvrdi, pa se komanda gubi samo ako servis stane pre toga.
+/// Pun red (broker dugo nedostupan) je greška, pa regulator komandu ponavlja.
+pub struct MqttActuator {
+    client: AsyncClient,
+    topic_prefix: String,
+}
+
+impl MqttActuator {
+    pub fn new(client: AsyncClient, topic_prefix: &str) -> Self {
+        Self {
+            client,
+            topic_prefix: topic_prefix.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl ActuatorDriver for MqttActuator {
+    fn name(&self) -> &'static str {
+        "mqtt"
+    }
+
+    async fn send(&self, command: &ActuatorCommand) -> Result<(), String> {
+        let topic = format!("{}/tanks/{}/actuator", self.topic_prefix, command.tank_id);
+        let payload = serde_json::to_vec(command).map_err(|e| e.to_string())?;
+
+        self.client
+            .try_publish(topic, QoS::AtLeastOnce, true, payload)
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Simulator bez hardvera (razvoj i testovi): komande samo pamti i beleži u log
+#[derive(Clone, Default)]
+pub struct SimulatedActuator {
+    sent: Arc<Mutex<Vec<ActuatorCommand>>>,
+}
+
+impl SimulatedActuator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[cfg(test)]
+    pub fn sent(&self) -> Vec<ActuatorCommand> {
+        self.sent.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl ActuatorDriver for SimulatedActuator {
+    fn name(&self) -> &'static str {
+        "simulated"
+    }
+
+    async fn send(&self, command: &ActuatorCommand) -> Result<(), String> {
+        tracing::info!(
+            "Simulated actuator: tank {} -> {:?} (setpoint {:?})",
+            command.tank_id,
+            command.action,
+            command.setpoint
+        );
+        self.sent.lock().unwrap().push(command.clone());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ControlAction;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn test_simulated_actuator_records_commands() {
+        let simulator = SimulatedActuator::new();
+        let driver: Arc<dyn ActuatorDriver> = Arc::new(simulator.clone());
+        let command = ActuatorCommand {
+            tank_id: Uuid::new_v4(),
+            action: ControlAction::Cool,
+            setpoint: Some(18.0),
+            issued_at: Utc::now(),
+        };
+
+        driver.send(&command).await.unwrap();
+
+        assert_eq!(driver.name(), "simulated");
+        assert_eq!(simulator.sent(), vec![command]);
+    }
+}
@@ -0,0 +1,207 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::{DateTime, Utc};
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    control::ActuatorDriver,
+    db::{ControlRepository, FermentationRepository},
+    error::AppError,
+    models::{
+        control_decision, schedule_setpoint, ActiveSetpoint, ActuatorCommand, ControlAction,
+        ControlCommand, ControlCommandSource, SetpointSource, SetpointStep, TankControl,
+        TankControlStatus,
+    },
+};
+
+/// Regulacija temperature tankova: poredi poslednje merenje sa zadatom temperaturom
+/// i šalje komandu aktuatoru kad treba promeniti stanje
+#[derive(Clone)]
+pub struct TemperatureController {
+    repo: FermentationRepository,
+    control_repo: ControlRepository,
+    actuator: Arc<dyn ActuatorDriver>,
+}
+
+impl TemperatureController {
+    pub fn new(
+        repo: FermentationRepository,
+        control_repo: ControlRepository,
+        actuator: Arc<dyn ActuatorDriver>,
+    ) -> Self {
+        Self {
+            repo,
+            control_repo,
+            actuator,
+        }
+    }
+
+    /// Zadata temperatura: raspored tanka, a bez njega ciljna temperatura aktivnog batch-a
+    pub async fn setpoint(
+        &self,
+        control: &TankControl,
+        steps: &[SetpointStep],
+        now: DateTime<Utc>,
+    ) -> Result<Option<ActiveSetpoint>, AppError> {
+        if let Some(started_at) = control.schedule_started_at.filter(|_| !steps.is_empty()) {
+            if let Some(setpoint) = schedule_setpoint(steps, started_at, now) {
+                return Ok(Some(setpoint));
+            }
+        }
+
+        let batch = self.repo.find_active_batch_in_tank(control.tank_id).await?;
+        Ok(batch
+            .and_then(|b| b.target_temperature)
+            .map(|temperature| ActiveSetpoint {
+                temperature,
+                source: SetpointSource::BatchTarget,
+                step: None,
+            }))
+    }
+
+    pub async fn status(&self, control: TankControl) -> Result<TankControlStatus, AppError> {
+        let now = Utc::now();
+        let steps = self.control_repo.list_setpoint_steps(control.tank_id).await?;
+        let setpoint = self.setpoint(&control, &steps, now).await?;
+        let latest = self.control_repo.latest_tank_temperature(control.tank_id).await?;
+
+        Ok(TankControlStatus {
+            override_active: control.active_override(now).is_some(),
+            desired_action: control_decision(&control, setpoint.as_ref(), latest, now),
+            latest_temperature: latest.map(|(temperature, _)| temperature),
+            temperature_recorded_at: latest.map(|(_, at)| at),
+            setpoint,
+            steps,
+            control,
+        })
+    }
+
+    /// Jedan korak regulacije za tank; komanda se šalje samo kad se stanje menja
+    pub async fn evaluate_tank(&self, control: &TankControl) -> Result<Option<ControlCommand>, AppError> {
+        let now = Utc::now();
+        let control = if control.override_action.is_some() && control.active_override(now).is_none() {
+            tracing::info!("Manual override of tank {} expired", control.tank_id);
+            self.control_repo.clear_control_override(control.tank_id).await?
+        } else {
+            control.clone()
+        };
+
+        let steps = self.control_repo.list_setpoint_steps(control.tank_id).await?;
+        let setpoint = self.setpoint(&control, &steps, now).await?;
+        let latest = self.control_repo.latest_tank_temperature(control.tank_id).await?;
+        let action = control_decision(&control, setpoint.as_ref(), latest, now);
+        if action == control.current_action {
+            return Ok(None);
+        }
+
+        // Ručna komanda ili izmena podešavanja posle čitanja čini odluku zastarelom;
+        // sledeći krug odlučuje iznova
+        let mut tx = self.control_repo.begin().await?;
+        let locked = ControlRepository::lock_tank_control(&mut tx, control.tank_id).await?;
+        if locked.updated_at != control.updated_at || locked.active_override(Utc::now()).is_some() {
+            return Ok(None);
+        }
+
+        let command = ActuatorCommand {
+            tank_id: control.tank_id,
+            action,
+            setpoint: setpoint.map(|s| s.temperature),
+            issued_at: Utc::now(),
+        };
+        let command = self
+            .send_locked(
+                &mut tx,
+                command,
+                ControlCommandSource::Controller,
+                latest.map(|(temperature, _)| temperature),
+                None,
+            )
+            .await?;
+        tx.commit().await?;
+
+        Ok(Some(command))
+    }
+
+    /// Slanje komande aktuatoru i upis u log. Neuspela komanda ne menja stanje
+    /// tanka, pa je regulator ponavlja u sledećem krugu.
+    pub async fn issue(
+        &self,
+        tank_id: Uuid,
+        action: ControlAction,
+        source: ControlCommandSource,
+        setpoint: Option<f64>,
+        measured_temperature: Option<f64>,
+        issued_by: Option<Uuid>,
+    ) -> Result<ControlCommand, AppError> {
+        let mut tx = self.control_repo.begin().await?;
+        ControlRepository::lock_tank_control(&mut tx, tank_id).await?;
+
+        let command = ActuatorCommand {
+            tank_id,
+            action,
+            setpoint,
+            issued_at: Utc::now(),
+        };
+        let command = self
+            .send_locked(&mut tx, command, source, measured_temperature, issued_by)
+            .await?;
+        tx.commit().await?;
+
+        Ok(command)
+    }
+
+    /// Regulacija tanka mora biti zaključana u `tx`
+    async fn send_locked(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        command: ActuatorCommand,
+        source: ControlCommandSource,
+        measured_temperature: Option<f64>,
+        issued_by: Option<Uuid>,
+    ) -> Result<ControlCommand, AppError> {
+        let (tank_id, action) = (command.tank_id, command.action);
+        let error = self.actuator.send(&command).await.err();
+        if let Some(e) = &error {
+            tracing::warn!("Actuator command {:?} for tank {} failed: {}", action, tank_id, e);
+        }
+
+        let logged = ControlRepository::log_control_command(
+            tx,
+            &command,
+            source,
+            measured_temperature,
+            self.actuator.name(),
+            error.as_deref(),
+            issued_by,
+        )
+        .await?;
+        if logged.success {
+            ControlRepository::set_control_action(tx, tank_id, action).await?;
+        }
+
+        Ok(logged)
+    }
+
+    pub async fn evaluate_all(&self) -> Result<(), AppError> {
+        for control in self.control_repo.list_controlled_tanks().await? {
+            if let Err(e) = self.evaluate_tank(&control).await {
+                tracing::error!("Temperature control of tank {} failed: {}", control.tank_id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn spawn_loop(self, interval: Duration) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.evaluate_all().await {
+                    tracing::error!("Temperature control loop failed: {}", e);
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,5 @@
+pub mod actuator;
+pub mod controller;
+
+pub use actuator::*;
+pub use controller::*;
@@ -0,0 +1,326 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{
+    check_addition_time, projected_total_so2, resolve_dose, so2_mg_per_liter, AdditionProduct,
+    BatchAddition, CreateAdditionProductRequest, CreateAdditionRequest, FermentationBatch,
+    UpdateAdditionProductRequest, WineType,
+};
+
+#[derive(Clone)]
+pub struct AdditionRepository {
+    pool: PgPool,
+}
+
+impl AdditionRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create_addition_product(
+        &self,
+        req: CreateAdditionProductRequest,
+    ) -> Result<AdditionProduct, AppError> {
+        sqlx::query_as::<_, AdditionProduct>(
+            r#"
+            INSERT INTO addition_products (name, category, unit, so2_factor, max_dose_per_hl, supplier, notes)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#,
+        )
+            .bind(&req.name)
+            .bind(req.category)
+            .bind(req.unit)
+            .bind(req.so2_factor)
+            .bind(req.max_dose_per_hl)
+            .bind(&req.supplier)
+            .bind(&req.notes)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(ref db) if db.is_unique_violation() => {
+                    AppError::Conflict(format!("Product '{}' already exists", req.name))
+                }
+                e => AppError::DatabaseError(e),
+            })
+    }
+
+    pub async fn find_addition_product(&self, id: Uuid) -> Result<AdditionProduct, AppError> {
+        sqlx::query_as::<_, AdditionProduct>("SELECT * FROM addition_products WHERE id = $1")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::NotFound("Addition product not found".to_string()),
+                _ => AppError::DatabaseError(e),
+            })
+    }
+
+    pub async fn list_addition_products(&self, include_inactive: bool) -> Result<Vec<AdditionProduct>, AppError> {
+        let products = sqlx::query_as::<_, AdditionProduct>(
+            "SELECT * FROM addition_products WHERE active OR $1 ORDER BY category, name",
+        )
+            .bind(include_inactive)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(products)
+    }
+
+    pub async fn update_addition_product(
+        &self,
+        id: Uuid,
+        req: UpdateAdditionProductRequest,
+    ) -> Result<AdditionProduct, AppError> {
+        sqlx::query_as::<_, AdditionProduct>(
+            r#"
+            UPDATE addition_products SET
+                name            = COALESCE($2, name),
+                so2_factor      = COALESCE($3, so2_factor),
+                max_dose_per_hl = COALESCE($4, max_dose_per_hl),
+                supplier        = COALESCE($5, supplier),
+                notes           = COALESCE($6, notes),
+                active          = COALESCE($7, active),
+                updated_at      = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+            .bind(id)
+            .bind(req.name)
+            .bind(req.so2_factor)
+            .bind(req.max_dose_per_hl)
+            .bind(req.supplier)
+            .bind(req.notes)
+            .bind(req.active)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::NotFound("Addition product not found".to_string()),
+                sqlx::Error::Database(ref db) if db.is_unique_violation() => {
+                    AppError::Conflict("Product with this name already exists".to_string())
+                }
+                e => AppError::DatabaseError(e),
+            })
+    }
+
+    /// Proizvod koji je već korišćen ostaje u registru; može se samo deaktivirati
+    pub async fn delete_addition_product(&self, id: Uuid) -> Result<(), AppError> {
+        self.find_addition_product(id).await?;
+
+        let used: Option<(Uuid,)> = sqlx::query_as(
+            "SELECT id FROM batch_additions WHERE product_id = $1 LIMIT 1",
+        )
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        if used.is_some() {
+            return Err(AppError::Conflict(
+                "Product is used in batch additions; deactivate it instead".to_string(),
+            ));
+        }
+
+        sqlx::query("DELETE FROM addition_products WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Upis dodatka uz proveru maksimalne doze proizvoda i zakonskog maksimuma
+    /// ukupnog SO2; batch je zaključan da se provere ne bi preklopile
+    pub async fn add_batch_addition(
+        &self,
+        batch_id: Uuid,
+        product: &AdditionProduct,
+        req: &CreateAdditionRequest,
+        added_by: Uuid,
+        operator_email: &str,
+    ) -> Result<BatchAddition, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let batch = sqlx::query_as::<_, FermentationBatch>(
+            "SELECT * FROM fermentation_batches WHERE id = $1 FOR UPDATE",
+        )
+            .bind(batch_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Fermentation batch not found".to_string()))?;
+
+        if !batch.status.occupies_tank() {
+            return Err(AppError::Conflict(
+                "Additions can only be recorded for active or paused batches".to_string(),
+            ));
+        }
+
+        let now = Utc::now();
+        let added_at = req.added_at.unwrap_or(now);
+        check_addition_time(added_at, batch.start_date, now).map_err(AppError::ValidationError)?;
+
+        let (dose_per_hl, quantity) = resolve_dose(req.dose_per_hl, req.quantity, batch.volume_liters)
+            .map_err(AppError::ValidationError)?;
+
+        if let Some(max_dose) = product.max_dose_per_hl {
+            let (used,): (f64,) = sqlx::query_as(
+                "SELECT COALESCE(SUM(dose_per_hl), 0) FROM batch_additions WHERE batch_id = $1 AND product_id = $2",
+            )
+                .bind(batch_id)
+                .bind(product.id)
+                .fetch_one(&mut *tx)
+                .await?;
+
+            if used + dose_per_hl > max_dose + 1e-9 {
+                return Err(AppError::ValidationError(format!(
+                    "Total dose of '{}' would be {:.2} {}/hL, above the maximum of {:.2} {}/hL",
+                    product.name,
+                    used + dose_per_hl,
+                    product.unit.as_str(),
+                    max_dose,
+                    product.unit.as_str()
+                )));
+            }
+        }
+
+        let so2_added_mg_l = product
+            .so2_factor
+            .map(|factor| so2_mg_per_liter(quantity, product.unit, factor, batch.volume_liters));
+
+        if let Some(so2) = so2_added_mg_l {
+            let latest: Option<(DateTime<Utc>, f64)> = sqlx::query_as(
+                r#"
+                SELECT recorded_at, total_so2 FROM fermentation_readings
+                WHERE batch_id = $1 AND total_so2 IS NOT NULL
+                ORDER BY recorded_at DESC
+                LIMIT 1
+                "#,
+            )
+                .bind(batch_id)
+                .fetch_optional(&mut *tx)
+                .await?;
+
+            let previous: Vec<(DateTime<Utc>, f64)> = sqlx::query_as(
+                "SELECT added_at, so2_added_mg_l FROM batch_additions WHERE batch_id = $1 AND so2_added_mg_l IS NOT NULL",
+            )
+                .bind(batch_id)
+                .fetch_all(&mut *tx)
+                .await?;
+
+            // Bez tipa vina važi najstroži limit (crveno)
+            let wine_type = batch.wine_type.unwrap_or(WineType::Red);
+            let limit = wine_type.total_so2_limit_mg_l();
+            let projected = projected_total_so2(latest, &previous, so2);
+
+            if projected > limit {
+                return Err(AppError::ValidationError(format!(
+                    "Total SO2 would reach {:.0} mg/L, above the legal limit of {:.0} mg/L for {:?} wine",
+                    projected, limit, wine_type
+                )));
+            }
+        }
+
+        let (id,): (Uuid,) = sqlx::query_as(
+            r#"
+            INSERT INTO batch_additions (
+                batch_id, product_id, dose_per_hl, quantity, batch_volume_liters,
+                so2_added_mg_l, lot_number, added_by, operator_email, added_at, notes
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            RETURNING id
+            "#,
+        )
+            .bind(batch_id)
+            .bind(product.id)
+            .bind(dose_per_hl)
+            .bind(quantity)
+            .bind(batch.volume_liters)
+            .bind(so2_added_mg_l)
+            .bind(&req.lot_number)
+            .bind(added_by)
+            .bind(operator_email)
+            .bind(added_at)
+            .bind(&req.notes)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        let addition = sqlx::query_as::<_, BatchAddition>(&format!("{} WHERE a.id = $1", ADDITION_SELECT))
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(addition)
+    }
+
+    pub async fn find_batch_addition(&self, batch_id: Uuid, id: Uuid) -> Result<BatchAddition, AppError> {
+        sqlx::query_as::<_, BatchAddition>(&format!(
+            "{} WHERE a.id = $1 AND a.batch_id = $2",
+            ADDITION_SELECT
+        ))
+            .bind(id)
+            .bind(batch_id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::NotFound("Addition not found".to_string()),
+                _ => AppError::DatabaseError(e),
+            })
+    }
+
+    /// Registar dodataka batch-a, hronološki
+    pub async fn list_batch_additions(&self, batch_id: Uuid) -> Result<Vec<BatchAddition>, AppError> {
+        let additions = sqlx::query_as::<_, BatchAddition>(&format!(
+            "{} WHERE a.batch_id = $1 ORDER BY a.added_at ASC",
+            ADDITION_SELECT
+        ))
+            .bind(batch_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(additions)
+    }
+
+    /// Ispravka je moguća dok batch drži vino; registar završenog batch-a je zaključan
+    pub async fn delete_batch_addition(&self, batch_id: Uuid, id: Uuid) -> Result<(), AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let batch = sqlx::query_as::<_, FermentationBatch>(
+            "SELECT * FROM fermentation_batches WHERE id = $1 FOR UPDATE",
+        )
+            .bind(batch_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Fermentation batch not found".to_string()))?;
+
+        if !batch.status.occupies_tank() {
+            return Err(AppError::Conflict(
+                "Additions can only be removed from active or paused batches".to_string(),
+            ));
+        }
+
+        let result = sqlx::query("DELETE FROM batch_additions WHERE id = $1 AND batch_id = $2")
+            .bind(id)
+            .bind(batch_id)
+            .execute(&mut *tx)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Addition not found".to_string()));
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+const ADDITION_SELECT: &str = r#"
+    SELECT a.id, a.batch_id, a.product_id, p.name AS product_name, p.category, p.unit,
+           a.dose_per_hl, a.quantity, a.batch_volume_liters, a.so2_added_mg_l, a.lot_number,
+           a.added_by, a.operator_email, a.added_at, a.notes, a.created_at
+    FROM batch_additions a
+    JOIN addition_products p ON p.id = a.product_id
+"#;
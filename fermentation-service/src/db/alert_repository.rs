@@ -0,0 +1,432 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{
+    Alert, AlertPoint, AlertRule, AlertSeverity, AlertStatus, CreateAlertRuleRequest,
+    CreateNotificationChannelRequest, FermentationBatch, NotificationChannel,
+    UpdateAlertRuleRequest, UpdateNotificationChannelRequest,
+};
+
+#[derive(Clone)]
+pub struct AlertRepository {
+    pool: PgPool,
+}
+
+impl AlertRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create_alert_rule(
+        &self,
+        created_by: Uuid,
+        req: CreateAlertRuleRequest,
+    ) -> Result<AlertRule, AppError> {
+        let rule = sqlx::query_as::<_, AlertRule>(
+            r#"
+            INSERT INTO alert_rules (
+                name, batch_id, tank_id, condition, threshold, duration_minutes, severity, created_by
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING *
+            "#,
+        )
+            .bind(&req.name)
+            .bind(req.batch_id)
+            .bind(req.tank_id)
+            .bind(req.condition)
+            .bind(req.threshold)
+            .bind(req.duration_minutes)
+            .bind(req.severity.unwrap_or(AlertSeverity::Warning))
+            .bind(created_by)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(rule)
+    }
+
+    pub async fn find_alert_rule(&self, id: Uuid) -> Result<AlertRule, AppError> {
+        sqlx::query_as::<_, AlertRule>("SELECT * FROM alert_rules WHERE id = $1")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::NotFound("Alert rule not found".to_string()),
+                _ => AppError::DatabaseError(e),
+            })
+    }
+
+    pub async fn list_alert_rules(
+        &self,
+        batch_id: Option<Uuid>,
+        tank_id: Option<Uuid>,
+    ) -> Result<Vec<AlertRule>, AppError> {
+        let rules = sqlx::query_as::<_, AlertRule>(
+            r#"
+            SELECT * FROM alert_rules
+            WHERE ($1::UUID IS NULL OR batch_id = $1)
+              AND ($2::UUID IS NULL OR tank_id = $2)
+            ORDER BY created_at DESC
+            "#,
+        )
+            .bind(batch_id)
+            .bind(tank_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rules)
+    }
+
+    /// Uključena pravila koja važe za batch: njegova, njegovog tanka i globalna
+    pub async fn list_rules_for_batch(
+        &self,
+        batch_id: Uuid,
+        tank_id: Uuid,
+    ) -> Result<Vec<AlertRule>, AppError> {
+        let rules = sqlx::query_as::<_, AlertRule>(
+            r#"
+            SELECT * FROM alert_rules
+            WHERE enabled
+              AND (batch_id = $1 OR tank_id = $2 OR (batch_id IS NULL AND tank_id IS NULL))
+            "#,
+        )
+            .bind(batch_id)
+            .bind(tank_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rules)
+    }
+
+    pub async fn update_alert_rule(
+        &self,
+        id: Uuid,
+        req: UpdateAlertRuleRequest,
+    ) -> Result<AlertRule, AppError> {
+        sqlx::query_as::<_, AlertRule>(
+            r#"
+            UPDATE alert_rules SET
+                name             = COALESCE($2, name),
+                threshold        = COALESCE($3, threshold),
+                duration_minutes = COALESCE($4, duration_minutes),
+                severity         = COALESCE($5, severity),
+                enabled          = COALESCE($6, enabled),
+                updated_at       = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+            .bind(id)
+            .bind(req.name)
+            .bind(req.threshold)
+            .bind(req.duration_minutes)
+            .bind(req.severity)
+            .bind(req.enabled)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::NotFound("Alert rule not found".to_string()),
+                _ => AppError::DatabaseError(e),
+            })
+    }
+
+    /// Brisanje pravila briše i njegove alarme
+    pub async fn delete_alert_rule(&self, id: Uuid) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM alert_rules WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Alert rule not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Vrednosti koje pravila prate, od `since`, sortirane po vremenu
+    pub async fn list_alert_points(
+        &self,
+        batch_id: Uuid,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<AlertPoint>, AppError> {
+        let points = sqlx::query_as::<_, AlertPoint>(
+            r#"
+            SELECT recorded_at, temperature, volatile_acidity, free_so2
+            FROM fermentation_readings
+            WHERE batch_id = $1 AND recorded_at >= $2
+            ORDER BY recorded_at
+            "#,
+        )
+            .bind(batch_id)
+            .bind(since)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(points)
+    }
+
+    pub async fn last_reading_at(&self, batch_id: Uuid) -> Result<Option<DateTime<Utc>>, AppError> {
+        let last = sqlx::query_scalar::<_, Option<DateTime<Utc>>>(
+            "SELECT MAX(recorded_at) FROM fermentation_readings WHERE batch_id = $1",
+        )
+            .bind(batch_id)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(last)
+    }
+
+    pub async fn find_alert(&self, id: Uuid) -> Result<Alert, AppError> {
+        sqlx::query_as::<_, Alert>(&format!("{} WHERE a.id = $1", ALERT_SELECT))
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::NotFound("Alert not found".to_string()),
+                _ => AppError::DatabaseError(e),
+            })
+    }
+
+    pub async fn list_alerts(
+        &self,
+        status: Option<AlertStatus>,
+        batch_id: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<Alert>, AppError> {
+        let alerts = sqlx::query_as::<_, Alert>(&format!(
+            r#"
+            {}
+            WHERE ($1::alert_status IS NULL OR a.status = $1)
+              AND ($2::UUID IS NULL OR a.batch_id = $2)
+            ORDER BY a.triggered_at DESC
+            LIMIT $3
+            "#,
+            ALERT_SELECT
+        ))
+            .bind(status)
+            .bind(batch_id)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(alerts)
+    }
+
+    /// Otvoreni i potvrđeni alarmi batch-a
+    pub async fn list_unresolved_alerts(&self, batch_id: Uuid) -> Result<Vec<Alert>, AppError> {
+        let alerts = sqlx::query_as::<_, Alert>(&format!(
+            "{} WHERE a.batch_id = $1 AND a.status <> 'resolved'",
+            ALERT_SELECT
+        ))
+            .bind(batch_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(alerts)
+    }
+
+    /// Novi alarm; `None` ako za pravilo i batch već postoji nerazrešen alarm
+    pub async fn open_alert(
+        &self,
+        rule: &AlertRule,
+        batch: &FermentationBatch,
+        message: &str,
+        value: f64,
+    ) -> Result<Option<Alert>, AppError> {
+        let id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            INSERT INTO alerts (rule_id, batch_id, tank_id, severity, message, value)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (rule_id, batch_id) WHERE status <> 'resolved' DO NOTHING
+            RETURNING id
+            "#,
+        )
+            .bind(rule.id)
+            .bind(batch.id)
+            .bind(batch.tank_id)
+            .bind(rule.severity)
+            .bind(message)
+            .bind(value)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        match id {
+            Some(id) => Ok(Some(self.find_alert(id).await?)),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn acknowledge_alert(&self, id: Uuid, user_id: Uuid) -> Result<Alert, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE alerts SET
+                status          = 'acknowledged',
+                acknowledged_by = $2,
+                acknowledged_at = NOW(),
+                updated_at      = NOW()
+            WHERE id = $1 AND status = 'open'
+            "#,
+        )
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        let alert = self.find_alert(id).await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::Conflict("Only open alerts can be acknowledged".to_string()));
+        }
+
+        Ok(alert)
+    }
+
+    /// Razrešavanje alarma; `resolved_by` je `None` kada se uslov sam povukao
+    pub async fn resolve_alert(&self, id: Uuid, resolved_by: Option<Uuid>) -> Result<Alert, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE alerts SET
+                status      = 'resolved',
+                resolved_by = $2,
+                resolved_at = NOW(),
+                updated_at  = NOW()
+            WHERE id = $1 AND status <> 'resolved'
+            "#,
+        )
+            .bind(id)
+            .bind(resolved_by)
+            .execute(&self.pool)
+            .await?;
+
+        let alert = self.find_alert(id).await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::Conflict("Alert is already resolved".to_string()));
+        }
+
+        Ok(alert)
+    }
+
+    /// Alarmi batch-eva koji više nisu aktivni i isključenih pravila se razrešavaju
+    pub async fn resolve_stale_alerts(&self) -> Result<u64, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE alerts a SET
+                status      = 'resolved',
+                resolved_at = NOW(),
+                updated_at  = NOW()
+            FROM fermentation_batches b, alert_rules r
+            WHERE b.id = a.batch_id
+              AND r.id = a.rule_id
+              AND a.status <> 'resolved'
+              AND (b.status <> 'active' OR NOT r.enabled)
+            "#,
+        )
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn create_notification_channel(
+        &self,
+        req: CreateNotificationChannelRequest,
+    ) -> Result<NotificationChannel, AppError> {
+        let channel = sqlx::query_as::<_, NotificationChannel>(
+            r#"
+            INSERT INTO notification_channels (name, kind, target, min_severity)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *
+            "#,
+        )
+            .bind(&req.name)
+            .bind(req.kind)
+            .bind(&req.target)
+            .bind(req.min_severity.unwrap_or(AlertSeverity::Warning))
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(channel)
+    }
+
+    pub async fn find_notification_channel(&self, id: Uuid) -> Result<NotificationChannel, AppError> {
+        sqlx::query_as::<_, NotificationChannel>("SELECT * FROM notification_channels WHERE id = $1")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::NotFound("Notification channel not found".to_string()),
+                _ => AppError::DatabaseError(e),
+            })
+    }
+
+    pub async fn list_notification_channels(
+        &self,
+        enabled_only: bool,
+    ) -> Result<Vec<NotificationChannel>, AppError> {
+        let channels = sqlx::query_as::<_, NotificationChannel>(
+            "SELECT * FROM notification_channels WHERE enabled OR NOT $1 ORDER BY name",
+        )
+            .bind(enabled_only)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(channels)
+    }
+
+    pub async fn update_notification_channel(
+        &self,
+        id: Uuid,
+        req: UpdateNotificationChannelRequest,
+    ) -> Result<NotificationChannel, AppError> {
+        sqlx::query_as::<_, NotificationChannel>(
+            r#"
+            UPDATE notification_channels SET
+                name         = COALESCE($2, name),
+                target       = COALESCE($3, target),
+                min_severity = COALESCE($4, min_severity),
+                enabled      = COALESCE($5, enabled),
+                updated_at   = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+            .bind(id)
+            .bind(req.name)
+            .bind(req.target)
+            .bind(req.min_severity)
+            .bind(req.enabled)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::NotFound("Notification channel not found".to_string()),
+                _ => AppError::DatabaseError(e),
+            })
+    }
+
+    pub async fn delete_notification_channel(&self, id: Uuid) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM notification_channels WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Notification channel not found".to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+const ALERT_SELECT: &str = r#"
+    SELECT a.id, a.rule_id, r.name AS rule_name, r.condition,
+           a.batch_id, b.name AS batch_name, a.tank_id, t.name AS tank_name,
+           a.status, a.severity, a.message, a.value, a.triggered_at,
+           a.acknowledged_by, a.acknowledged_at, a.resolved_by, a.resolved_at,
+           a.created_at, a.updated_at
+    FROM alerts a
+    JOIN alert_rules r ON r.id = a.rule_id
+    JOIN fermentation_batches b ON b.id = a.batch_id
+    JOIN tanks t ON t.id = a.tank_id
+"#;
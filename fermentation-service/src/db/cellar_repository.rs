@@ -0,0 +1,527 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::db::{occupancy_conflict, FermentationRepository};
+use crate::error::AppError;
+use crate::models::{
+    blend_wine_type, transfer_loss, BlendRequest, CellarMovement, CellarOperation,
+    CellarOperationKind, FermentationBatch, Tank, TankStatus, TransferRequest, VesselStay,
+};
+
+#[derive(Clone)]
+pub struct CellarRepository {
+    pool: PgPool,
+}
+
+impl CellarRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Pretakanje celog batch-a u jedan ili više tankova. Prvi odredišni tank
+    /// nastavlja batch, ostali dobijaju nove lotove sa istim parametrima.
+    pub async fn transfer_batch(
+        &self,
+        created_by: Uuid,
+        kind: CellarOperationKind,
+        req: &TransferRequest,
+    ) -> Result<CellarOperation, AppError> {
+        let current = self
+            .find_batches(&[req.batch_id])
+            .await?
+            .pop()
+            .ok_or_else(|| AppError::NotFound("Fermentation batch not found".to_string()))?;
+        let performed_at = req.performed_at.unwrap_or_else(Utc::now);
+
+        let mut tx = self.pool.begin().await?;
+
+        let mut tank_ids: Vec<Uuid> = req.destinations.iter().map(|d| d.tank_id).collect();
+        tank_ids.push(current.tank_id);
+        let tanks = Self::lock_tanks(&mut tx, &tank_ids).await?;
+
+        let batch = Self::lock_batches(&mut tx, &[req.batch_id])
+            .await?
+            .pop()
+            .ok_or_else(|| AppError::NotFound("Fermentation batch not found".to_string()))?;
+        if batch.tank_id != current.tank_id {
+            return Err(AppError::Conflict(
+                "Batch was moved to another tank, please retry".to_string(),
+            ));
+        }
+        if !batch.status.occupies_tank() {
+            return Err(AppError::Conflict(
+                "Only active or paused batches can be transferred".to_string(),
+            ));
+        }
+
+        let loss_liters =
+            transfer_loss(batch.volume_liters, &req.destinations).map_err(AppError::ValidationError)?;
+
+        for destination in &req.destinations {
+            if destination.tank_id == batch.tank_id {
+                return Err(AppError::ValidationError(
+                    "Destination tank must differ from the source tank".to_string(),
+                ));
+            }
+            let tank = find_locked_tank(&tanks, destination.tank_id)?;
+            Self::check_tank_accepts(tank, destination.volume_liters)?;
+        }
+
+        let operation = Self::insert_cellar_operation(
+            &mut tx,
+            kind,
+            performed_at,
+            loss_liters,
+            &req.notes,
+            created_by,
+        )
+            .await?;
+
+        FermentationRepository::close_vessel_stay(&mut tx, batch.id, batch.volume_liters, performed_at).await?;
+
+        for (index, destination) in req.destinations.iter().enumerate() {
+            let target = if index == 0 {
+                sqlx::query_as::<_, FermentationBatch>(
+                    r#"
+                    UPDATE fermentation_batches SET
+                        tank_id       = $2,
+                        volume_liters = $3,
+                        updated_at    = NOW()
+                    WHERE id = $1
+                    RETURNING *
+                    "#,
+                )
+                    .bind(batch.id)
+                    .bind(destination.tank_id)
+                    .bind(destination.volume_liters)
+                    .fetch_one(&mut *tx)
+                    .await
+                    .map_err(occupancy_conflict)?
+            } else {
+                let tank = find_locked_tank(&tanks, destination.tank_id)?;
+                sqlx::query_as::<_, FermentationBatch>(
+                    r#"
+                    INSERT INTO fermentation_batches (
+                        tank_id, harvest_id, name, grape_variety, wine_type, volume_liters, status,
+                        target_temperature, yeast_strain, initial_brix, initial_ph,
+                        start_date, expected_end_date, created_by
+                    )
+                    SELECT $2, harvest_id, $3, grape_variety, wine_type, $4, status,
+                           target_temperature, yeast_strain, initial_brix, initial_ph,
+                           start_date, expected_end_date, $5
+                    FROM fermentation_batches
+                    WHERE id = $1
+                    RETURNING *
+                    "#,
+                )
+                    .bind(batch.id)
+                    .bind(destination.tank_id)
+                    .bind(format!("{} ({})", batch.name, tank.name))
+                    .bind(destination.volume_liters)
+                    .bind(created_by)
+                    .fetch_one(&mut *tx)
+                    .await
+                    .map_err(occupancy_conflict)?
+            };
+
+            FermentationRepository::open_vessel_stay(
+                &mut tx,
+                target.id,
+                destination.tank_id,
+                destination.volume_liters,
+                Some(operation.id),
+                performed_at,
+            )
+                .await?;
+            Self::insert_cellar_movement(
+                &mut tx,
+                operation.id,
+                (batch.id, batch.tank_id),
+                (target.id, destination.tank_id),
+                destination.volume_liters,
+            )
+                .await?;
+        }
+
+        for tank in &tanks {
+            FermentationRepository::sync_tank_status(&mut tx, tank).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(operation)
+    }
+
+    /// Kupaža delova više batch-eva u novi lot. Izvor koji je potpuno ispražnjen
+    /// se završava i oslobađa svoj tank.
+    pub async fn blend_batches(
+        &self,
+        created_by: Uuid,
+        grape_variety: &str,
+        req: &BlendRequest,
+    ) -> Result<(CellarOperation, FermentationBatch), AppError> {
+        let source_ids: Vec<Uuid> = req.sources.iter().map(|s| s.batch_id).collect();
+        let current = self.find_batches(&source_ids).await?;
+        if let Some(missing) = source_ids.iter().find(|id| !current.iter().any(|b| b.id == **id)) {
+            return Err(AppError::ValidationError(format!(
+                "Fermentation batch {} not found",
+                missing
+            )));
+        }
+        let performed_at = req.performed_at.unwrap_or_else(Utc::now);
+
+        let mut tx = self.pool.begin().await?;
+
+        let mut tank_ids: Vec<Uuid> = current.iter().map(|b| b.tank_id).collect();
+        tank_ids.push(req.tank_id);
+        let tanks = Self::lock_tanks(&mut tx, &tank_ids).await?;
+
+        let batches = Self::lock_batches(&mut tx, &source_ids).await?;
+        for batch in &batches {
+            if current.iter().any(|c| c.id == batch.id && c.tank_id != batch.tank_id) {
+                return Err(AppError::Conflict(
+                    "A source batch was moved to another tank, please retry".to_string(),
+                ));
+            }
+        }
+
+        let mut total_liters = 0.0;
+        for source in &req.sources {
+            let batch = batches
+                .iter()
+                .find(|b| b.id == source.batch_id)
+                .ok_or_else(|| AppError::NotFound("Fermentation batch not found".to_string()))?;
+            if !batch.status.occupies_tank() {
+                return Err(AppError::Conflict(format!(
+                    "Batch '{}' is not active or paused",
+                    batch.name
+                )));
+            }
+            if source.volume_liters > batch.volume_liters + 1e-6 {
+                return Err(AppError::ValidationError(format!(
+                    "Batch '{}' has only {} L",
+                    batch.name, batch.volume_liters
+                )));
+            }
+            total_liters += source.volume_liters;
+        }
+
+        if req.loss_liters >= total_liters {
+            return Err(AppError::ValidationError(
+                "Loss cannot exceed the blended volume".to_string(),
+            ));
+        }
+        let volume_liters = total_liters - req.loss_liters;
+        let wine_type = blend_wine_type(req.wine_type, batches.iter().map(|b| b.wine_type))
+            .map_err(AppError::ValidationError)?;
+
+        let tank = find_locked_tank(&tanks, req.tank_id)?;
+        Self::check_tank_accepts(tank, volume_liters)?;
+
+        let operation = Self::insert_cellar_operation(
+            &mut tx,
+            CellarOperationKind::Blend,
+            performed_at,
+            req.loss_liters,
+            &req.notes,
+            created_by,
+        )
+            .await?;
+
+        let blend = sqlx::query_as::<_, FermentationBatch>(
+            r#"
+            INSERT INTO fermentation_batches (
+                tank_id, name, grape_variety, volume_liters, target_temperature,
+                notes, created_by, start_date, wine_type
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING *
+            "#,
+        )
+            .bind(req.tank_id)
+            .bind(&req.name)
+            .bind(grape_variety)
+            .bind(volume_liters)
+            .bind(req.target_temperature)
+            .bind(&req.notes)
+            .bind(created_by)
+            .bind(performed_at)
+            .bind(wine_type)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(occupancy_conflict)?;
+
+        FermentationRepository::open_vessel_stay(&mut tx, blend.id, req.tank_id, volume_liters, Some(operation.id), performed_at)
+            .await?;
+
+        for source in &req.sources {
+            let batch = batches
+                .iter()
+                .find(|b| b.id == source.batch_id)
+                .ok_or_else(|| AppError::NotFound("Fermentation batch not found".to_string()))?;
+            let remaining = batch.volume_liters - source.volume_liters;
+
+            if remaining <= 1e-6 {
+                sqlx::query(
+                    r#"
+                    UPDATE fermentation_batches SET
+                        volume_liters = 0,
+                        status        = 'completed',
+                        end_date      = $2,
+                        updated_at    = NOW()
+                    WHERE id = $1
+                    "#,
+                )
+                    .bind(batch.id)
+                    .bind(performed_at)
+                    .execute(&mut *tx)
+                    .await?;
+                FermentationRepository::close_vessel_stay(&mut tx, batch.id, source.volume_liters, performed_at).await?;
+            } else {
+                sqlx::query(
+                    "UPDATE fermentation_batches SET volume_liters = $2, updated_at = NOW() WHERE id = $1",
+                )
+                    .bind(batch.id)
+                    .bind(remaining)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+
+            Self::insert_cellar_movement(
+                &mut tx,
+                operation.id,
+                (batch.id, batch.tank_id),
+                (blend.id, req.tank_id),
+                source.volume_liters,
+            )
+                .await?;
+        }
+
+        for tank in &tanks {
+            FermentationRepository::sync_tank_status(&mut tx, tank).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok((operation, blend))
+    }
+
+    pub async fn find_batches(&self, ids: &[Uuid]) -> Result<Vec<FermentationBatch>, AppError> {
+        let batches = sqlx::query_as::<_, FermentationBatch>(
+            "SELECT * FROM fermentation_batches WHERE id = ANY($1)",
+        )
+            .bind(ids)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(batches)
+    }
+
+    pub async fn find_cellar_operation(&self, id: Uuid) -> Result<CellarOperation, AppError> {
+        sqlx::query_as::<_, CellarOperation>("SELECT * FROM cellar_operations WHERE id = $1")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::NotFound("Cellar operation not found".to_string()),
+                _ => AppError::DatabaseError(e),
+            })
+    }
+
+    /// Podrumske operacije, najnovije prvo; sa batch_id samo one koje ga se tiču
+    pub async fn list_cellar_operations(
+        &self,
+        batch_id: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<CellarOperation>, AppError> {
+        let operations = sqlx::query_as::<_, CellarOperation>(
+            r#"
+            SELECT o.* FROM cellar_operations o
+            WHERE $1::UUID IS NULL OR EXISTS (
+                SELECT 1 FROM cellar_movements m
+                WHERE m.operation_id = o.id
+                  AND (m.source_batch_id = $1 OR m.target_batch_id = $1)
+            )
+            ORDER BY o.performed_at DESC
+            LIMIT $2
+            "#,
+        )
+            .bind(batch_id)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(operations)
+    }
+
+    pub async fn list_cellar_movements(&self, operation_ids: &[Uuid]) -> Result<Vec<CellarMovement>, AppError> {
+        let movements = sqlx::query_as::<_, CellarMovement>(&format!(
+            "{} WHERE m.operation_id = ANY($1) ORDER BY m.volume_liters DESC",
+            MOVEMENT_SELECT
+        ))
+            .bind(operation_ids)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(movements)
+    }
+
+    /// Tokovi koji su ušli u batch (za sastav kupaže)
+    pub async fn list_batch_inflows(&self, batch_id: Uuid) -> Result<Vec<CellarMovement>, AppError> {
+        let movements = sqlx::query_as::<_, CellarMovement>(&format!(
+            "{} WHERE m.target_batch_id = $1",
+            MOVEMENT_SELECT
+        ))
+            .bind(batch_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(movements)
+    }
+
+    pub async fn list_vessel_stays(&self, batch_id: Uuid) -> Result<Vec<VesselStay>, AppError> {
+        let stays = sqlx::query_as::<_, VesselStay>(
+            r#"
+            SELECT s.id, s.batch_id, s.tank_id, t.name AS tank_name, s.operation_id,
+                   s.volume_in_liters, s.volume_out_liters, s.entered_at, s.left_at
+            FROM batch_vessel_stays s
+            JOIN tanks t ON t.id = s.tank_id
+            WHERE s.batch_id = $1
+            ORDER BY s.entered_at ASC
+            "#,
+        )
+            .bind(batch_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(stays)
+    }
+
+    /// Zaključaj više tankova (po id-ju, da se transakcije ne bi zaglavile)
+    async fn lock_tanks(tx: &mut Transaction<'_, Postgres>, ids: &[Uuid]) -> Result<Vec<Tank>, AppError> {
+        let mut ids = ids.to_vec();
+        ids.sort_unstable();
+        ids.dedup();
+
+        let tanks = sqlx::query_as::<_, Tank>(
+            "SELECT * FROM tanks WHERE id = ANY($1) ORDER BY id FOR UPDATE",
+        )
+            .bind(&ids)
+            .fetch_all(&mut **tx)
+            .await?;
+
+        if tanks.len() != ids.len() {
+            return Err(AppError::NotFound("Tank not found".to_string()));
+        }
+
+        Ok(tanks)
+    }
+
+    async fn lock_batches(
+        tx: &mut Transaction<'_, Postgres>,
+        ids: &[Uuid],
+    ) -> Result<Vec<FermentationBatch>, AppError> {
+        let batches = sqlx::query_as::<_, FermentationBatch>(
+            "SELECT * FROM fermentation_batches WHERE id = ANY($1) ORDER BY id FOR UPDATE",
+        )
+            .bind(ids)
+            .fetch_all(&mut **tx)
+            .await?;
+
+        Ok(batches)
+    }
+
+    /// Odredišni tank mora biti prazan i dovoljno velik
+    fn check_tank_accepts(tank: &Tank, volume_liters: f64) -> Result<(), AppError> {
+        if tank.status != TankStatus::Available {
+            return Err(AppError::Conflict(format!(
+                "Tank '{}' is not available (status: {:?})",
+                tank.name, tank.status
+            )));
+        }
+        if volume_liters > tank.capacity_liters {
+            return Err(AppError::Conflict(format!(
+                "Volume ({} L) exceeds tank '{}' capacity ({} L)",
+                volume_liters, tank.name, tank.capacity_liters
+            )));
+        }
+
+        Ok(())
+    }
+
+    async fn insert_cellar_operation(
+        tx: &mut Transaction<'_, Postgres>,
+        kind: CellarOperationKind,
+        performed_at: DateTime<Utc>,
+        loss_liters: f64,
+        notes: &Option<String>,
+        created_by: Uuid,
+    ) -> Result<CellarOperation, AppError> {
+        let operation = sqlx::query_as::<_, CellarOperation>(
+            r#"
+            INSERT INTO cellar_operations (kind, performed_at, loss_liters, notes, created_by)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+        )
+            .bind(kind)
+            .bind(performed_at)
+            .bind(loss_liters)
+            .bind(notes)
+            .bind(created_by)
+            .fetch_one(&mut **tx)
+            .await?;
+
+        Ok(operation)
+    }
+
+    /// Tok vina: (batch, tank) iz kog se toči → (batch, tank) u koji se toči
+    async fn insert_cellar_movement(
+        tx: &mut Transaction<'_, Postgres>,
+        operation_id: Uuid,
+        (source_batch_id, from_tank_id): (Uuid, Uuid),
+        (target_batch_id, to_tank_id): (Uuid, Uuid),
+        volume_liters: f64,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO cellar_movements (
+                operation_id, source_batch_id, from_tank_id, target_batch_id, to_tank_id, volume_liters
+            )
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+            .bind(operation_id)
+            .bind(source_batch_id)
+            .bind(from_tank_id)
+            .bind(target_batch_id)
+            .bind(to_tank_id)
+            .bind(volume_liters)
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+}
+
+const MOVEMENT_SELECT: &str = r#"
+    SELECT m.id, m.operation_id,
+           m.source_batch_id, sb.name AS source_batch_name,
+           m.from_tank_id, ft.name AS from_tank_name,
+           m.target_batch_id, tb.name AS target_batch_name,
+           m.to_tank_id, tt.name AS to_tank_name,
+           m.volume_liters
+    FROM cellar_movements m
+    LEFT JOIN fermentation_batches sb ON sb.id = m.source_batch_id
+    LEFT JOIN fermentation_batches tb ON tb.id = m.target_batch_id
+    JOIN tanks ft ON ft.id = m.from_tank_id
+    JOIN tanks tt ON tt.id = m.to_tank_id
+"#;
+
+fn find_locked_tank(tanks: &[Tank], id: Uuid) -> Result<&Tank, AppError> {
+    tanks
+        .iter()
+        .find(|t| t.id == id)
+        .ok_or_else(|| AppError::NotFound("Tank not found".to_string()))
+}
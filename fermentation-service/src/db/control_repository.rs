@@ -0,0 +1,318 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{
+    ActuatorCommand, ControlAction, ControlCommand, ControlCommandSource, ControlOverrideRequest,
+    SetpointStep, SetpointStepInput, TankControl, UpdateTankControlRequest,
+};
+
+#[derive(Clone)]
+pub struct ControlRepository {
+    pool: PgPool,
+}
+
+impl ControlRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Transakcija za pozivaoce koji između upisa rade nešto van baze (komanda aktuatoru)
+    pub async fn begin(&self) -> Result<Transaction<'static, Postgres>, AppError> {
+        Ok(self.pool.begin().await?)
+    }
+
+    pub async fn find_tank_control(&self, tank_id: Uuid) -> Result<Option<TankControl>, AppError> {
+        let control = sqlx::query_as::<_, TankControl>("SELECT * FROM tank_controls WHERE tank_id = $1")
+            .bind(tank_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(control)
+    }
+
+    /// Podešavanja se prave pri prvoj izmeni (regulacija je podrazumevano isključena)
+    pub async fn upsert_tank_control(
+        &self,
+        tank_id: Uuid,
+        req: &UpdateTankControlRequest,
+    ) -> Result<TankControl, AppError> {
+        let control = sqlx::query_as::<_, TankControl>(
+            r#"
+            INSERT INTO tank_controls (tank_id, enabled, deadband)
+            VALUES ($1, COALESCE($2, FALSE), COALESCE($3, 0.5))
+            ON CONFLICT (tank_id) DO UPDATE SET
+                enabled    = COALESCE($2, tank_controls.enabled),
+                deadband   = COALESCE($3, tank_controls.deadband),
+                updated_at = NOW()
+            RETURNING *
+            "#,
+        )
+            .bind(tank_id)
+            .bind(req.enabled)
+            .bind(req.deadband)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(control)
+    }
+
+    pub async fn list_setpoint_steps(&self, tank_id: Uuid) -> Result<Vec<SetpointStep>, AppError> {
+        let steps = sqlx::query_as::<_, SetpointStep>(
+            "SELECT * FROM tank_setpoint_steps WHERE tank_id = $1 ORDER BY position",
+        )
+            .bind(tank_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(steps)
+    }
+
+    /// Zamenjuje raspored tanka; koraci se numerišu redom kojim su zadati
+    pub async fn replace_setpoint_schedule(
+        &self,
+        tank_id: Uuid,
+        start_at: DateTime<Utc>,
+        steps: &[SetpointStepInput],
+    ) -> Result<Vec<SetpointStep>, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO tank_controls (tank_id, schedule_started_at)
+            VALUES ($1, $2)
+            ON CONFLICT (tank_id) DO UPDATE SET schedule_started_at = $2, updated_at = NOW()
+            "#,
+        )
+            .bind(tank_id)
+            .bind(start_at)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("DELETE FROM tank_setpoint_steps WHERE tank_id = $1")
+            .bind(tank_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let positions: Vec<i32> = (1..=steps.len() as i32).collect();
+        let names: Vec<&str> = steps.iter().map(|s| s.name.as_str()).collect();
+        let targets: Vec<f64> = steps.iter().map(|s| s.target_temperature).collect();
+        let ramps: Vec<f64> = steps.iter().map(|s| s.ramp_hours).collect();
+        let holds: Vec<Option<f64>> = steps.iter().map(|s| s.hold_hours).collect();
+
+        let steps = sqlx::query_as::<_, SetpointStep>(
+            r#"
+            INSERT INTO tank_setpoint_steps (
+                tank_id, position, name, target_temperature, ramp_hours, hold_hours
+            )
+            SELECT $1, t.position, t.name, t.target_temperature, t.ramp_hours, t.hold_hours
+            FROM UNNEST($2::INTEGER[], $3::TEXT[], $4::DOUBLE PRECISION[],
+                        $5::DOUBLE PRECISION[], $6::DOUBLE PRECISION[])
+                AS t(position, name, target_temperature, ramp_hours, hold_hours)
+            RETURNING *
+            "#,
+        )
+            .bind(tank_id)
+            .bind(&positions)
+            .bind(&names)
+            .bind(&targets)
+            .bind(&ramps)
+            .bind(&holds)
+            .fetch_all(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(steps)
+    }
+
+    /// Bez rasporeda regulator koristi ciljnu temperaturu aktivnog batch-a
+    pub async fn clear_setpoint_schedule(&self, tank_id: Uuid) -> Result<(), AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM tank_setpoint_steps WHERE tank_id = $1")
+            .bind(tank_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            "UPDATE tank_controls SET schedule_started_at = NULL, updated_at = NOW() WHERE tank_id = $1",
+        )
+            .bind(tank_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn set_control_override(
+        &self,
+        tank_id: Uuid,
+        req: &ControlOverrideRequest,
+        user_id: Uuid,
+    ) -> Result<TankControl, AppError> {
+        let control = sqlx::query_as::<_, TankControl>(
+            r#"
+            INSERT INTO tank_controls (
+                tank_id, override_action, override_until, override_reason, override_by
+            )
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (tank_id) DO UPDATE SET
+                override_action = $2,
+                override_until  = $3,
+                override_reason = $4,
+                override_by     = $5,
+                updated_at      = NOW()
+            RETURNING *
+            "#,
+        )
+            .bind(tank_id)
+            .bind(req.action)
+            .bind(req.until)
+            .bind(&req.reason)
+            .bind(user_id)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(control)
+    }
+
+    pub async fn clear_control_override(&self, tank_id: Uuid) -> Result<TankControl, AppError> {
+        sqlx::query_as::<_, TankControl>(
+            r#"
+            UPDATE tank_controls SET
+                override_action = NULL,
+                override_until  = NULL,
+                override_reason = NULL,
+                override_by     = NULL,
+                updated_at      = NOW()
+            WHERE tank_id = $1
+            RETURNING *
+            "#,
+        )
+            .bind(tank_id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => {
+                    AppError::NotFound("Temperature control is not configured for this tank".to_string())
+                }
+                _ => AppError::DatabaseError(e),
+            })
+    }
+
+    /// Zaključaj regulaciju tanka do kraja transakcije; komande aktuatoru se šalju pod
+    /// ovim zaključavanjem, pa ručna komanda i regulator ne mogu da se prepliću
+    pub async fn lock_tank_control(
+        tx: &mut Transaction<'_, Postgres>,
+        tank_id: Uuid,
+    ) -> Result<TankControl, AppError> {
+        sqlx::query_as::<_, TankControl>("SELECT * FROM tank_controls WHERE tank_id = $1 FOR UPDATE")
+            .bind(tank_id)
+            .fetch_optional(&mut **tx)
+            .await?
+            .ok_or_else(|| {
+                AppError::NotFound("Temperature control is not configured for this tank".to_string())
+            })
+    }
+
+    pub async fn set_control_action(
+        tx: &mut Transaction<'_, Postgres>,
+        tank_id: Uuid,
+        action: ControlAction,
+    ) -> Result<(), AppError> {
+        sqlx::query("UPDATE tank_controls SET current_action = $2, updated_at = NOW() WHERE tank_id = $1")
+            .bind(tank_id)
+            .bind(action)
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Tankovi o kojima regulator brine: uključena regulacija, ručna komanda
+    /// ili aktuator koji još nije ugašen
+    pub async fn list_controlled_tanks(&self) -> Result<Vec<TankControl>, AppError> {
+        let controls = sqlx::query_as::<_, TankControl>(
+            r#"
+            SELECT * FROM tank_controls
+            WHERE enabled OR override_action IS NOT NULL OR current_action <> 'idle'
+            "#,
+        )
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(controls)
+    }
+
+    /// Poslednja temperatura aktivnog batch-a u tanku
+    pub async fn latest_tank_temperature(
+        &self,
+        tank_id: Uuid,
+    ) -> Result<Option<(f64, DateTime<Utc>)>, AppError> {
+        let latest = sqlx::query_as::<_, (f64, DateTime<Utc>)>(
+            r#"
+            SELECT r.temperature, r.recorded_at
+            FROM fermentation_readings r
+            JOIN fermentation_batches b ON b.id = r.batch_id
+            WHERE b.tank_id = $1 AND b.status = 'active' AND r.temperature IS NOT NULL
+            ORDER BY r.recorded_at DESC
+            LIMIT 1
+            "#,
+        )
+            .bind(tank_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(latest)
+    }
+
+    pub async fn log_control_command(
+        tx: &mut Transaction<'_, Postgres>,
+        command: &ActuatorCommand,
+        source: ControlCommandSource,
+        measured_temperature: Option<f64>,
+        driver: &str,
+        error: Option<&str>,
+        issued_by: Option<Uuid>,
+    ) -> Result<ControlCommand, AppError> {
+        let logged = sqlx::query_as::<_, ControlCommand>(
+            r#"
+            INSERT INTO control_commands (
+                tank_id, action, source, setpoint, measured_temperature,
+                driver, success, error, issued_by, issued_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING *
+            "#,
+        )
+            .bind(command.tank_id)
+            .bind(command.action)
+            .bind(source)
+            .bind(command.setpoint)
+            .bind(measured_temperature)
+            .bind(driver)
+            .bind(error.is_none())
+            .bind(error)
+            .bind(issued_by)
+            .bind(command.issued_at)
+            .fetch_one(&mut **tx)
+            .await?;
+
+        Ok(logged)
+    }
+
+    pub async fn list_control_commands(&self, tank_id: Uuid, limit: i64) -> Result<Vec<ControlCommand>, AppError> {
+        let commands = sqlx::query_as::<_, ControlCommand>(
+            "SELECT * FROM control_commands WHERE tank_id = $1 ORDER BY issued_at DESC LIMIT $2",
+        )
+            .bind(tank_id)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(commands)
+    }
+}
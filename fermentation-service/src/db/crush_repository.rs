@@ -0,0 +1,336 @@
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::db::{occupancy_conflict, FermentationRepository};
+use crate::error::AppError;
+use crate::models::{
+    group_must_sources, CreateBatchFromMustRequest, CreateCrushRequest, CrushInput, CrushOperation,
+    FermentationBatch, MustAllocation, MustFraction, NewCrushInput, TankStatus,
+};
+
+#[derive(Clone)]
+pub struct CrushRepository {
+    pool: PgPool,
+}
+
+impl CrushRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Muljanje sa berbama u jednoj transakciji. Berbe se zaključavaju da dva
+    /// istovremena muljanja ne bi potrošila isto grožđe.
+    pub async fn create_crush(
+        &self,
+        created_by: Uuid,
+        grape_variety: &str,
+        inputs: &[NewCrushInput],
+        req: &CreateCrushRequest,
+    ) -> Result<CrushOperation, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        // Uvek istim redom, da se transakcije ne bi zaglavile jedna na drugoj
+        let mut ordered: Vec<&NewCrushInput> = inputs.iter().collect();
+        ordered.sort_by_key(|i| i.harvest_id);
+        for input in ordered {
+            sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1::UUID::TEXT))")
+                .bind(input.harvest_id)
+                .execute(&mut *tx)
+                .await?;
+
+            let (crushed_kg,): (f64,) = sqlx::query_as(
+                "SELECT COALESCE(SUM(weight_kg), 0) FROM crush_inputs WHERE harvest_id = $1",
+            )
+                .bind(input.harvest_id)
+                .fetch_one(&mut *tx)
+                .await?;
+
+            if crushed_kg + input.weight_kg > input.harvest_weight_kg + 1e-6 {
+                return Err(AppError::Conflict(format!(
+                    "Harvest {} has only {:.1} kg left to crush (requested {:.1} kg)",
+                    input.harvest_id,
+                    (input.harvest_weight_kg - crushed_kg).max(0.0),
+                    input.weight_kg
+                )));
+            }
+        }
+
+        let crush = sqlx::query_as::<_, CrushOperation>(
+            r#"
+            INSERT INTO crush_operations (
+                crushed_at, grape_variety, total_grape_kg, free_run_liters,
+                press_liters, pomace_kg, press_program, notes, created_by
+            )
+            VALUES (COALESCE($1, NOW()), $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING *
+            "#,
+        )
+            .bind(req.crushed_at)
+            .bind(grape_variety)
+            .bind(inputs.iter().map(|i| i.weight_kg).sum::<f64>())
+            .bind(req.free_run_liters)
+            .bind(req.press_liters)
+            .bind(req.pomace_kg)
+            .bind(&req.press_program)
+            .bind(&req.notes)
+            .bind(created_by)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        for input in inputs {
+            sqlx::query(
+                r#"
+                INSERT INTO crush_inputs (
+                    crush_id, harvest_id, weight_kg, grape_variety, vineyard_name, parcel_name
+                )
+                VALUES ($1, $2, $3, $4, $5, $6)
+                "#,
+            )
+                .bind(crush.id)
+                .bind(input.harvest_id)
+                .bind(input.weight_kg)
+                .bind(&input.grape_variety)
+                .bind(&input.vineyard_name)
+                .bind(&input.parcel_name)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(crush)
+    }
+
+    pub async fn find_crush(&self, id: Uuid) -> Result<CrushOperation, AppError> {
+        sqlx::query_as::<_, CrushOperation>("SELECT * FROM crush_operations WHERE id = $1")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::NotFound("Crush operation not found".to_string()),
+                _ => AppError::DatabaseError(e),
+            })
+    }
+
+    pub async fn find_crushes(&self, ids: &[Uuid]) -> Result<Vec<CrushOperation>, AppError> {
+        let crushes = sqlx::query_as::<_, CrushOperation>(
+            "SELECT * FROM crush_operations WHERE id = ANY($1) ORDER BY crushed_at",
+        )
+            .bind(ids)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(crushes)
+    }
+
+    pub async fn list_crushes(&self) -> Result<Vec<CrushOperation>, AppError> {
+        let crushes = sqlx::query_as::<_, CrushOperation>(
+            "SELECT * FROM crush_operations ORDER BY crushed_at DESC",
+        )
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(crushes)
+    }
+
+    pub async fn list_crush_inputs(&self, crush_ids: &[Uuid]) -> Result<Vec<CrushInput>, AppError> {
+        let inputs = sqlx::query_as::<_, CrushInput>(
+            "SELECT * FROM crush_inputs WHERE crush_id = ANY($1) ORDER BY weight_kg DESC",
+        )
+            .bind(crush_ids)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(inputs)
+    }
+
+    pub async fn list_harvest_crush_inputs(&self, harvest_id: Uuid) -> Result<Vec<CrushInput>, AppError> {
+        let inputs = sqlx::query_as::<_, CrushInput>(
+            "SELECT * FROM crush_inputs WHERE harvest_id = $1",
+        )
+            .bind(harvest_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(inputs)
+    }
+
+    /// Šira iz datih muljanja raspoređena po batch-evima
+    pub async fn list_crush_allocations(&self, crush_ids: &[Uuid]) -> Result<Vec<MustAllocation>, AppError> {
+        let allocations = sqlx::query_as::<_, MustAllocation>(
+            r#"
+            SELECT s.batch_id, b.name AS batch_name, b.status AS batch_status,
+                   s.crush_id, s.fraction, s.liters
+            FROM batch_must_sources s
+            JOIN fermentation_batches b ON b.id = s.batch_id
+            WHERE s.crush_id = ANY($1)
+            ORDER BY s.created_at
+            "#,
+        )
+            .bind(crush_ids)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(allocations)
+    }
+
+    pub async fn list_batch_allocations(&self, batch_id: Uuid) -> Result<Vec<MustAllocation>, AppError> {
+        let allocations = sqlx::query_as::<_, MustAllocation>(
+            r#"
+            SELECT s.batch_id, b.name AS batch_name, b.status AS batch_status,
+                   s.crush_id, s.fraction, s.liters
+            FROM batch_must_sources s
+            JOIN fermentation_batches b ON b.id = s.batch_id
+            WHERE s.batch_id = $1
+            ORDER BY s.created_at
+            "#,
+        )
+            .bind(batch_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(allocations)
+    }
+
+    pub async fn delete_crush(&self, id: Uuid) -> Result<(), AppError> {
+        self.find_crush(id).await?;
+
+        let used: Option<(Uuid,)> = sqlx::query_as(
+            "SELECT batch_id FROM batch_must_sources WHERE crush_id = $1 LIMIT 1",
+        )
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        if used.is_some() {
+            return Err(AppError::Conflict(
+                "Cannot delete crush operation whose must is already in a batch".to_string(),
+            ));
+        }
+
+        sqlx::query("DELETE FROM crush_operations WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Batch od šire muljanja: provera preostale šire po frakciji i slobodnog
+    /// tanka, upis batch-a i izvora - sve u jednoj transakciji
+    pub async fn create_batch_from_must(
+        &self,
+        created_by: Uuid,
+        grape_variety: &str,
+        harvest_id: Option<Uuid>,
+        req: &CreateBatchFromMustRequest,
+    ) -> Result<FermentationBatch, AppError> {
+        let sources = group_must_sources(&req.sources);
+        let mut crush_ids: Vec<Uuid> = sources.keys().map(|(id, _)| *id).collect();
+        crush_ids.dedup();
+
+        let mut tx = self.pool.begin().await?;
+
+        let crushes = sqlx::query_as::<_, CrushOperation>(
+            "SELECT * FROM crush_operations WHERE id = ANY($1) ORDER BY id FOR UPDATE",
+        )
+            .bind(&crush_ids)
+            .fetch_all(&mut *tx)
+            .await?;
+
+        let allocated: Vec<(Uuid, MustFraction, f64)> = sqlx::query_as(
+            r#"
+            SELECT crush_id, fraction, SUM(liters)
+            FROM batch_must_sources
+            WHERE crush_id = ANY($1)
+            GROUP BY crush_id, fraction
+            "#,
+        )
+            .bind(&crush_ids)
+            .fetch_all(&mut *tx)
+            .await?;
+
+        for (&(crush_id, fraction), &liters) in &sources {
+            let crush = crushes.iter().find(|c| c.id == crush_id).ok_or_else(|| {
+                AppError::ValidationError(format!("Crush operation {} not found", crush_id))
+            })?;
+            let used: f64 = allocated
+                .iter()
+                .filter(|(id, f, _)| *id == crush_id && *f == fraction)
+                .map(|(_, _, l)| l)
+                .sum();
+            let remaining = (crush.fraction_liters(fraction) - used).max(0.0);
+
+            if liters > remaining + 1e-6 {
+                return Err(AppError::Conflict(format!(
+                    "Crush {} has only {:.1} L of {:?} must left (requested {:.1} L)",
+                    crush_id, remaining, fraction, liters
+                )));
+            }
+        }
+
+        let tank = FermentationRepository::lock_tank(&mut tx, req.tank_id).await?;
+
+        if tank.status != TankStatus::Available {
+            return Err(AppError::Conflict(format!(
+                "Tank '{}' is not available (status: {:?})",
+                tank.name, tank.status
+            )));
+        }
+
+        let volume_liters: f64 = sources.values().sum();
+        if volume_liters > tank.capacity_liters {
+            return Err(AppError::Conflict(format!(
+                "Volume ({} L) exceeds tank capacity ({} L)",
+                volume_liters, tank.capacity_liters
+            )));
+        }
+
+        let batch = sqlx::query_as::<_, FermentationBatch>(
+            r#"
+            INSERT INTO fermentation_batches (
+                tank_id, harvest_id, name, grape_variety, volume_liters,
+                target_temperature, yeast_strain, initial_brix, initial_ph,
+                expected_end_date, notes, created_by, start_date
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, NOW())
+            RETURNING *
+            "#,
+        )
+            .bind(req.tank_id)
+            .bind(harvest_id)
+            .bind(&req.name)
+            .bind(grape_variety)
+            .bind(volume_liters)
+            .bind(req.target_temperature)
+            .bind(&req.yeast_strain)
+            .bind(req.initial_brix)
+            .bind(req.initial_ph)
+            .bind(req.expected_end_date)
+            .bind(&req.notes)
+            .bind(created_by)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(occupancy_conflict)?;
+
+        for (&(crush_id, fraction), &liters) in &sources {
+            sqlx::query(
+                "INSERT INTO batch_must_sources (batch_id, crush_id, fraction, liters) VALUES ($1, $2, $3, $4)",
+            )
+                .bind(batch.id)
+                .bind(crush_id)
+                .bind(fraction)
+                .bind(liters)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        FermentationRepository::open_vessel_stay(&mut tx, batch.id, tank.id, volume_liters, None, Utc::now()).await?;
+        FermentationRepository::sync_tank_status(&mut tx, &tank).await?;
+
+        tx.commit().await?;
+
+        Ok(batch)
+    }
+}
@@ -0,0 +1,162 @@
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{CreateDeviceRequest, IotDevice, UpdateDeviceRequest};
+
+#[derive(Clone)]
+pub struct DeviceRepository {
+    pool: PgPool,
+}
+
+impl DeviceRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create_device(&self, req: CreateDeviceRequest) -> Result<IotDevice, AppError> {
+        sqlx::query_as::<_, IotDevice>(
+            r#"
+            INSERT INTO iot_devices (
+                device_id, name, device_type, firmware_version, tank_id, calibration_offset, notes
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#,
+        )
+            .bind(&req.device_id)
+            .bind(&req.name)
+            .bind(req.device_type)
+            .bind(&req.firmware_version)
+            .bind(req.tank_id)
+            .bind(req.calibration_offset)
+            .bind(&req.notes)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(ref db) if db.is_unique_violation() => {
+                    AppError::Conflict(format!("Device '{}' is already registered", req.device_id))
+                }
+                e => AppError::DatabaseError(e),
+            })
+    }
+
+    pub async fn find_device(&self, id: Uuid) -> Result<IotDevice, AppError> {
+        sqlx::query_as::<_, IotDevice>("SELECT * FROM iot_devices WHERE id = $1")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::NotFound("Device not found".to_string()),
+                _ => AppError::DatabaseError(e),
+            })
+    }
+
+    /// Uređaj po hardverskom id-ju iz merenja
+    pub async fn find_device_by_hardware_id(&self, device_id: &str) -> Result<Option<IotDevice>, AppError> {
+        let device = sqlx::query_as::<_, IotDevice>("SELECT * FROM iot_devices WHERE device_id = $1")
+            .bind(device_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(device)
+    }
+
+    pub async fn list_devices(&self, tank_id: Option<Uuid>) -> Result<Vec<IotDevice>, AppError> {
+        let devices = sqlx::query_as::<_, IotDevice>(
+            r#"
+            SELECT * FROM iot_devices
+            WHERE ($1::UUID IS NULL OR tank_id = $1)
+            ORDER BY name
+            "#,
+        )
+            .bind(tank_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(devices)
+    }
+
+    pub async fn update_device(&self, id: Uuid, req: UpdateDeviceRequest) -> Result<IotDevice, AppError> {
+        sqlx::query_as::<_, IotDevice>(
+            r#"
+            UPDATE iot_devices SET
+                name               = COALESCE($2, name),
+                device_type        = COALESCE($3, device_type),
+                firmware_version   = COALESCE($4, firmware_version),
+                calibration_offset = COALESCE($5, calibration_offset),
+                active             = COALESCE($6, active),
+                notes              = COALESCE($7, notes),
+                updated_at         = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+            .bind(id)
+            .bind(req.name)
+            .bind(req.device_type)
+            .bind(req.firmware_version)
+            .bind(req.calibration_offset)
+            .bind(req.active)
+            .bind(req.notes)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::NotFound("Device not found".to_string()),
+                _ => AppError::DatabaseError(e),
+            })
+    }
+
+    pub async fn bind_device(&self, id: Uuid, tank_id: Option<Uuid>) -> Result<IotDevice, AppError> {
+        sqlx::query_as::<_, IotDevice>(
+            "UPDATE iot_devices SET tank_id = $2, updated_at = NOW() WHERE id = $1 RETURNING *",
+        )
+            .bind(id)
+            .bind(tank_id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::NotFound("Device not found".to_string()),
+                _ => AppError::DatabaseError(e),
+            })
+    }
+
+    /// Uređaj se javio: vreme, baterija i firmware (ako ga šalje)
+    pub(crate) async fn touch_device(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        battery_percent: Option<f64>,
+        firmware_version: Option<&str>,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE iot_devices SET
+                last_seen_at     = NOW(),
+                battery_percent  = COALESCE($2, battery_percent),
+                firmware_version = COALESCE($3, firmware_version)
+            WHERE id = $1
+            "#,
+        )
+            .bind(id)
+            .bind(battery_percent)
+            .bind(firmware_version)
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Merenja ostaju, samo gube vezu sa uređajem
+    pub async fn delete_device(&self, id: Uuid) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM iot_devices WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Device not found".to_string()));
+        }
+
+        Ok(())
+    }
+}
@@ -1,11 +1,16 @@
-﻿use sqlx::PgPool;
+﻿use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Postgres, Transaction};
 use uuid::Uuid;
 
+use crate::db::DeviceRepository;
 use crate::error::AppError;
 use crate::models::{
-    AddReadingRequest, BatchStats, CreateBatchRequest, CreateTankRequest,
-    FermentationBatch, FermentationReading, FermentationStatus, IotReadingRequest,
-    Tank, TankStatus, UpdateBatchRequest, UpdateTankRequest,
+    derive_tank_status, AddReadingRequest, BatchStats, CreateBatchRequest, CreateTankRequest,
+    CurvePoint, FermentationBatch, FermentationReading, FermentationStatus, IotIngestTarget,
+    SensorValue, Tank, TankStatus, UpdateBatchRequest, UpdateTankRequest, RATE_WINDOW_HOURS,
+    TEMPERATURE_WINDOW_DAYS,
 };
 
 #[derive(Clone)]
@@ -70,7 +75,34 @@ impl FermentationRepository {
         Ok(tanks)
     }
 
+    /// Ručna promena statusa ne sme da se razilazi sa batch-evima u tanku
     pub async fn update_tank(&self, id: Uuid, req: UpdateTankRequest) -> Result<Tank, AppError> {
+        let mut tx = self.pool.begin().await?;
+        let tank = Self::lock_tank(&mut tx, id).await?;
+
+        if let Some(status) = &req.status {
+            let occupied = Self::tank_is_occupied(&mut tx, id).await?;
+            if occupied && *status != TankStatus::InUse {
+                return Err(AppError::Conflict(
+                    "Tank holds an active or paused batch".to_string(),
+                ));
+            }
+            if !occupied && *status == TankStatus::InUse {
+                return Err(AppError::ValidationError(
+                    "Tank becomes in_use only by starting a batch in it".to_string(),
+                ));
+            }
+            if *status == TankStatus::Available
+                && tank.status != TankStatus::Available
+                && Self::tank_needs_cleaning(&mut tx, id).await?
+            {
+                return Err(AppError::Conflict(format!(
+                    "Tank '{}' needs a verified cleaning before it becomes available",
+                    tank.name
+                )));
+            }
+        }
+
         let tank = sqlx::query_as::<_, Tank>(
             r#"
             UPDATE tanks SET
@@ -92,51 +124,142 @@ impl FermentationRepository {
             .bind(req.status)
             .bind(req.location)
             .bind(req.notes)
-            .fetch_one(&self.pool)
+            .fetch_one(&mut *tx)
             .await?;
 
+        tx.commit().await?;
+
         Ok(tank)
     }
 
-    pub async fn update_tank_status(&self, id: Uuid, status: TankStatus) -> Result<Tank, AppError> {
-        let tank = sqlx::query_as::<_, Tank>(
+    pub async fn delete_tank(&self, id: Uuid) -> Result<(), AppError> {
+        let mut tx = self.pool.begin().await?;
+        Self::lock_tank(&mut tx, id).await?;
+
+        // Proveri da li je u tanku batch (aktivan ili pauziran)
+        if Self::tank_is_occupied(&mut tx, id).await? {
+            return Err(AppError::Conflict(
+                "Cannot delete tank with active fermentation batch".to_string(),
+            ));
+        }
+
+        sqlx::query("DELETE FROM tanks WHERE id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Zaključaj tank do kraja transakcije; sve izmene zauzetosti prvo zaključavaju tank
+    pub(crate) async fn lock_tank(tx: &mut Transaction<'_, Postgres>, id: Uuid) -> Result<Tank, AppError> {
+        sqlx::query_as::<_, Tank>("SELECT * FROM tanks WHERE id = $1 FOR UPDATE")
+            .bind(id)
+            .fetch_optional(&mut **tx)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Tank not found".to_string()))
+    }
+
+    pub(crate) async fn tank_is_occupied(tx: &mut Transaction<'_, Postgres>, tank_id: Uuid) -> Result<bool, AppError> {
+        let (occupied,): (bool,) = sqlx::query_as(
             r#"
-            UPDATE tanks SET status = $2, updated_at = NOW()
-            WHERE id = $1
-            RETURNING *
+            SELECT EXISTS (
+                SELECT 1 FROM fermentation_batches
+                WHERE tank_id = $1 AND status IN ('active', 'paused')
+            )
             "#,
         )
-            .bind(id)
-            .bind(status)
-            .fetch_one(&self.pool)
+            .bind(tank_id)
+            .fetch_one(&mut **tx)
             .await?;
 
-        Ok(tank)
+        Ok(occupied)
     }
 
-    pub async fn delete_tank(&self, id: Uuid) -> Result<(), AppError> {
-        // Proveri da li postoji aktivan batch
-        let active: Option<(Uuid,)> = sqlx::query_as(
-            "SELECT id FROM fermentation_batches WHERE tank_id = $1 AND status = 'active' LIMIT 1",
+    /// Posle poslednjeg batch-a u tanku (i obrisanih, preko `dirty_since`) nije
+    /// upisano uspešno čišćenje
+    pub(crate) async fn tank_needs_cleaning(tx: &mut Transaction<'_, Postgres>, tank_id: Uuid) -> Result<bool, AppError> {
+        let (needs_cleaning,): (bool,) = sqlx::query_as(
+            r#"
+            WITH last_stay AS (
+                SELECT GREATEST(
+                    (SELECT MAX(left_at) FROM batch_vessel_stays WHERE tank_id = $1),
+                    (SELECT dirty_since FROM tanks WHERE id = $1)
+                ) AS left_at
+            ),
+            last_cleaning AS (
+                SELECT MAX(performed_at) AS performed_at FROM tank_cleanings
+                WHERE tank_id = $1 AND verification = 'passed'
+            )
+            SELECT s.left_at IS NOT NULL
+                   AND (c.performed_at IS NULL OR c.performed_at < s.left_at)
+            FROM last_stay s, last_cleaning c
+            "#,
         )
-            .bind(id)
-            .fetch_optional(&self.pool)
+            .bind(tank_id)
+            .fetch_one(&mut **tx)
             .await?;
 
-        if active.is_some() {
-            return Err(AppError::Conflict(
-                "Cannot delete tank with active fermentation batch".to_string(),
-            ));
-        }
+        Ok(needs_cleaning)
+    }
 
-        sqlx::query("DELETE FROM tanks WHERE id = $1")
-            .bind(id)
-            .execute(&self.pool)
+    /// Boravci batch-a se brišu sa njim, pa tankovi u kojima je bio pamte od kada
+    /// su prljavi; tank u kom je batch još uvek prljav je od sada
+    async fn mark_tanks_dirty(tx: &mut Transaction<'_, Postgres>, batch_id: Uuid) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE tanks t SET dirty_since = GREATEST(t.dirty_since, s.left_at)
+            FROM (
+                SELECT tank_id, MAX(COALESCE(left_at, NOW())) AS left_at
+                FROM batch_vessel_stays
+                WHERE batch_id = $1
+                GROUP BY tank_id
+            ) s
+            WHERE t.id = s.tank_id
+            "#,
+        )
+            .bind(batch_id)
+            .execute(&mut **tx)
             .await?;
 
         Ok(())
     }
 
+    /// Batch koji je poslednji napustio tank
+    async fn last_tank_occupant(tx: &mut Transaction<'_, Postgres>, tank_id: Uuid) -> Result<Option<Uuid>, AppError> {
+        let occupant: Option<(Uuid,)> = sqlx::query_as(
+            r#"
+            SELECT batch_id FROM batch_vessel_stays
+            WHERE tank_id = $1 AND left_at IS NOT NULL
+            ORDER BY left_at DESC
+            LIMIT 1
+            "#,
+        )
+            .bind(tank_id)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        Ok(occupant.map(|(batch_id,)| batch_id))
+    }
+
+    /// Postavi status tanka prema batch-evima u njemu (tank mora biti zaključan)
+    pub(crate) async fn sync_tank_status(tx: &mut Transaction<'_, Postgres>, tank: &Tank) -> Result<(), AppError> {
+        let occupied = Self::tank_is_occupied(tx, tank.id).await?;
+        let status = derive_tank_status(&tank.status, occupied);
+
+        if status != tank.status {
+            sqlx::query("UPDATE tanks SET status = $2, updated_at = NOW() WHERE id = $1")
+                .bind(tank.id)
+                .bind(status)
+                .execute(&mut **tx)
+                .await?;
+        }
+
+        Ok(())
+    }
+
     // ============== Batch CRUD ==============
 
     pub async fn create_batch(
@@ -144,8 +267,10 @@ impl FermentationRepository {
         created_by: Uuid,
         req: CreateBatchRequest,
     ) -> Result<FermentationBatch, AppError> {
+        let mut tx = self.pool.begin().await?;
+
         // Proveri da li je tank dostupan
-        let tank = self.find_tank_by_id(req.tank_id).await?;
+        let tank = Self::lock_tank(&mut tx, req.tank_id).await?;
         if tank.status != TankStatus::Available {
             return Err(AppError::Conflict(format!(
                 "Tank '{}' is not available (status: {:?})",
@@ -166,9 +291,9 @@ impl FermentationRepository {
             INSERT INTO fermentation_batches (
                 tank_id, harvest_id, name, grape_variety, volume_liters,
                 target_temperature, yeast_strain, initial_brix, initial_ph,
-                expected_end_date, notes, created_by, start_date
+                expected_end_date, notes, created_by, start_date, wine_type
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, NOW())
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, NOW(), $13)
             RETURNING *
             "#,
         )
@@ -184,11 +309,17 @@ impl FermentationRepository {
             .bind(req.expected_end_date)
             .bind(&req.notes)
             .bind(created_by)
-            .fetch_one(&self.pool)
-            .await?;
+            .bind(req.wine_type)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(occupancy_conflict)?;
+
+        Self::open_vessel_stay(&mut tx, batch.id, tank.id, batch.volume_liters, None, Utc::now()).await?;
 
         // Postavi tank na in_use
-        self.update_tank_status(req.tank_id, TankStatus::InUse).await?;
+        Self::sync_tank_status(&mut tx, &tank).await?;
+
+        tx.commit().await?;
 
         Ok(batch)
     }
@@ -226,6 +357,21 @@ impl FermentationRepository {
         Ok(batches)
     }
 
+    /// Aktivan batch u tanku (najviše jedan, v. uq_batches_occupied_tank)
+    pub async fn find_active_batch_in_tank(
+        &self,
+        tank_id: Uuid,
+    ) -> Result<Option<FermentationBatch>, AppError> {
+        let batch = sqlx::query_as::<_, FermentationBatch>(
+            "SELECT * FROM fermentation_batches WHERE tank_id = $1 AND status = 'active'",
+        )
+            .bind(tank_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(batch)
+    }
+
     pub async fn list_batches_by_tank(&self, tank_id: Uuid) -> Result<Vec<FermentationBatch>, AppError> {
         let batches = sqlx::query_as::<_, FermentationBatch>(
             "SELECT * FROM fermentation_batches WHERE tank_id = $1 ORDER BY created_at DESC",
@@ -242,12 +388,33 @@ impl FermentationRepository {
         id: Uuid,
         req: UpdateBatchRequest,
     ) -> Result<FermentationBatch, AppError> {
-        let batch = self.find_batch_by_id(id).await?;
+        let mut tx = self.pool.begin().await?;
+        let (tank, batch) = self.lock_batch_with_tank(&mut tx, id).await?;
+
+        // Nastavak završenog/otkazanog batch-a ponovo zauzima tank; neočišćen
+        // tank prima samo batch koji ga je poslednji napustio (greškom završen)
+        if let Some(status) = &req.status {
+            if status.occupies_tank() && !batch.status.occupies_tank() {
+                let accepts = match tank.status {
+                    TankStatus::Available => true,
+                    TankStatus::Cleaning => Self::last_tank_occupant(&mut tx, tank.id).await? == Some(id),
+                    _ => false,
+                };
+                if !accepts {
+                    return Err(AppError::Conflict(format!(
+                        "Tank '{}' is not available (status: {:?})",
+                        tank.name, tank.status
+                    )));
+                }
+            }
+        }
 
-        // Ako se završava batch, oslobodi tank
-        if let Some(FermentationStatus::Completed) | Some(FermentationStatus::Cancelled) = &req.status {
-            if batch.status == FermentationStatus::Active {
-                self.update_tank_status(batch.tank_id, TankStatus::Available).await?;
+        if let Some(volume) = req.volume_liters {
+            if volume > tank.capacity_liters {
+                return Err(AppError::Conflict(format!(
+                    "Volume ({} L) exceeds tank capacity ({} L)",
+                    volume, tank.capacity_liters
+                )));
             }
         }
 
@@ -262,6 +429,7 @@ impl FermentationRepository {
                 expected_end_date = COALESCE($7, expected_end_date),
                 end_date          = COALESCE($8, end_date),
                 notes             = COALESCE($9, notes),
+                wine_type         = COALESCE($10, wine_type),
                 updated_at        = NOW()
             WHERE id = $1
             RETURNING *
@@ -276,14 +444,34 @@ impl FermentationRepository {
             .bind(req.expected_end_date)
             .bind(req.end_date)
             .bind(req.notes)
-            .fetch_one(&self.pool)
-            .await?;
+            .bind(req.wine_type)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(occupancy_conflict)?;
+
+        // Istorija sudova prati zauzetost: završetak zatvara boravak, nastavak otvara novi
+        match (batch.status.occupies_tank(), updated.status.occupies_tank()) {
+            (true, false) => {
+                Self::close_vessel_stay(&mut tx, id, updated.volume_liters, Utc::now()).await?
+            }
+            (false, true) => {
+                Self::open_vessel_stay(&mut tx, id, tank.id, updated.volume_liters, None, Utc::now())
+                    .await?
+            }
+            _ => {}
+        }
+
+        // Završen ili otkazan batch oslobađa tank
+        Self::sync_tank_status(&mut tx, &tank).await?;
+
+        tx.commit().await?;
 
         Ok(updated)
     }
 
     pub async fn delete_batch(&self, id: Uuid) -> Result<(), AppError> {
-        let batch = self.find_batch_by_id(id).await?;
+        let mut tx = self.pool.begin().await?;
+        let (tank, batch) = self.lock_batch_with_tank(&mut tx, id).await?;
 
         // Ne možeš obrisati aktivan batch
         if batch.status == FermentationStatus::Active {
@@ -292,9 +480,87 @@ impl FermentationRepository {
             ));
         }
 
+        Self::mark_tanks_dirty(&mut tx, id).await?;
         sqlx::query("DELETE FROM fermentation_batches WHERE id = $1")
             .bind(id)
-            .execute(&self.pool)
+            .execute(&mut *tx)
+            .await?;
+
+        Self::sync_tank_status(&mut tx, &tank).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Zaključaj tank pa batch (uvek tim redom). Ako je batch u međuvremenu
+    /// prebačen u drugi tank, zahtev se odbija.
+    async fn lock_batch_with_tank(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+    ) -> Result<(Tank, FermentationBatch), AppError> {
+        let current = self.find_batch_by_id(id).await?;
+        let tank = Self::lock_tank(tx, current.tank_id).await?;
+
+        let batch = sqlx::query_as::<_, FermentationBatch>(
+            "SELECT * FROM fermentation_batches WHERE id = $1 FOR UPDATE",
+        )
+            .bind(id)
+            .fetch_optional(&mut **tx)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Fermentation batch not found".to_string()))?;
+
+        if batch.tank_id != tank.id {
+            return Err(AppError::Conflict(
+                "Batch was moved to another tank, please retry".to_string(),
+            ));
+        }
+
+        Ok((tank, batch))
+    }
+
+    pub(crate) async fn open_vessel_stay(
+        tx: &mut Transaction<'_, Postgres>,
+        batch_id: Uuid,
+        tank_id: Uuid,
+        volume_liters: f64,
+        operation_id: Option<Uuid>,
+        entered_at: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO batch_vessel_stays (batch_id, tank_id, operation_id, volume_in_liters, entered_at)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+            .bind(batch_id)
+            .bind(tank_id)
+            .bind(operation_id)
+            .bind(volume_liters)
+            .bind(entered_at)
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn close_vessel_stay(
+        tx: &mut Transaction<'_, Postgres>,
+        batch_id: Uuid,
+        volume_out_liters: f64,
+        left_at: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE batch_vessel_stays SET volume_out_liters = $2, left_at = $3
+            WHERE batch_id = $1 AND left_at IS NULL
+            "#,
+        )
+            .bind(batch_id)
+            .bind(volume_out_liters)
+            .bind(left_at)
+            .execute(&mut **tx)
             .await?;
 
         Ok(())
@@ -340,41 +606,136 @@ impl FermentationRepository {
         Ok(reading)
     }
 
-    pub async fn add_iot_reading(
+    /// Upis svih grupa jednog IoT upload-a u jednoj transakciji, da greška u jednoj
+    /// grupi ne ostavi ostale upisane. Vraća novo upisana merenja po grupi; uređaj se
+    /// beleži kao aktivan samo ako je upisao bar jedno novo merenje.
+    pub async fn add_iot_readings(
         &self,
-        req: IotReadingRequest,
-    ) -> Result<FermentationReading, AppError> {
+        targets: &[IotIngestTarget],
+    ) -> Result<Vec<Vec<FermentationReading>>, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let mut stored = Vec::with_capacity(targets.len());
+        for target in targets {
+            let readings = Self::insert_iot_group(&mut tx, target).await?;
+
+            if let Some(device) = target.device.as_ref().filter(|_| !readings.is_empty()) {
+                let samples = &target.group.samples;
+                let battery = samples.iter().rev().find_map(|s| s.battery_percent);
+                let firmware = samples.iter().rev().find_map(|s| s.firmware_version.as_deref());
+                DeviceRepository::touch_device(&mut tx, device.id, battery, firmware).await?;
+            }
+            stored.push(readings);
+        }
+
+        tx.commit().await?;
+
+        Ok(stored)
+    }
+
+    /// Bulk upis merenja iz jednog izvora (temperature su već kalibrisane); merenja
+    /// koja već postoje (isti uređaj i vreme) se preskaču
+    async fn insert_iot_group(
+        tx: &mut Transaction<'_, Postgres>,
+        target: &IotIngestTarget,
+    ) -> Result<Vec<FermentationReading>, AppError> {
+        let batch_id = target.batch_id;
+        let samples = &target.group.samples;
+
         // Provjeri da batch postoji i aktivan je
-        let batch = self.find_batch_by_id(req.batch_id).await?;
+        let batch = sqlx::query_as::<_, FermentationBatch>(
+            "SELECT * FROM fermentation_batches WHERE id = $1 FOR SHARE",
+        )
+            .bind(batch_id)
+            .fetch_optional(&mut **tx)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Fermentation batch not found".to_string()))?;
         if batch.status != FermentationStatus::Active {
             return Err(AppError::Conflict(
                 "Can only add IoT readings to active batches".to_string(),
             ));
         }
 
-        let recorded_at = req.recorded_at.unwrap_or_else(chrono::Utc::now);
+        let now = Utc::now();
+        let ids: Vec<Uuid> = samples.iter().map(|_| Uuid::new_v4()).collect();
+        let temperatures: Vec<Option<f64>> = samples.iter().map(|s| s.temperature).collect();
+        let recorded_at: Vec<DateTime<Utc>> = samples
+            .iter()
+            .map(|s| s.recorded_at.unwrap_or(now))
+            .collect();
 
-        let reading = sqlx::query_as::<_, FermentationReading>(
+        let readings = sqlx::query_as::<_, FermentationReading>(
             r#"
             INSERT INTO fermentation_readings (
-                batch_id, temperature, source, recorded_at
+                id, batch_id, temperature, source, device_id, source_device, recorded_at
             )
-            VALUES ($1, $2, 'iot', $3)
+            SELECT t.id, $1, t.temperature, 'iot', $2, $3, t.recorded_at
+            FROM UNNEST($4::UUID[], $5::DOUBLE PRECISION[], $6::TIMESTAMPTZ[])
+                AS t(id, temperature, recorded_at)
+            ON CONFLICT (source_device, recorded_at) WHERE source_device IS NOT NULL DO NOTHING
             RETURNING *
             "#,
         )
-            .bind(req.batch_id)
-            .bind(req.temperature)
-            .bind(recorded_at)
-            .fetch_one(&self.pool)
+            .bind(batch_id)
+            .bind(target.device.as_ref().map(|d| d.id))
+            .bind(target.group.device_id.as_deref())
+            .bind(&ids)
+            .bind(&temperatures)
+            .bind(&recorded_at)
+            .fetch_all(&mut **tx)
             .await?;
 
-        Ok(reading)
+        // Ostali kanali samo za merenja koja su stvarno upisana
+        let stored: HashSet<Uuid> = readings.iter().map(|r| r.id).collect();
+        let (mut value_ids, mut channels, mut values) = (Vec::new(), Vec::new(), Vec::new());
+        for (id, sample) in ids.iter().zip(samples).filter(|(id, _)| stored.contains(id)) {
+            for (channel, value) in sample.sensor_values() {
+                value_ids.push(*id);
+                channels.push(channel.as_str());
+                values.push(value);
+            }
+        }
+
+        if !value_ids.is_empty() {
+            sqlx::query(
+                r#"
+                INSERT INTO reading_sensor_values (reading_id, channel, value)
+                SELECT * FROM UNNEST($1::UUID[], $2::TEXT[]::sensor_channel[], $3::DOUBLE PRECISION[])
+                "#,
+            )
+                .bind(&value_ids)
+                .bind(&channels)
+                .bind(&values)
+                .execute(&mut **tx)
+                .await?;
+        }
+
+        Ok(readings)
     }
 
+    /// Vrednosti ostalih kanala za data merenja
+    pub async fn list_sensor_values(&self, reading_ids: &[Uuid]) -> Result<Vec<SensorValue>, AppError> {
+        let values = sqlx::query_as::<_, SensorValue>(
+            r#"
+            SELECT reading_id, channel, value
+            FROM reading_sensor_values
+            WHERE reading_id = ANY($1)
+            ORDER BY reading_id, channel
+            "#,
+        )
+            .bind(reading_ids)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(values)
+    }
+
+    /// Najnovija merenja batch-a, opciono u vremenskom opsegu [from, to)
     pub async fn list_readings(
         &self,
         batch_id: Uuid,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
         limit: Option<i64>,
     ) -> Result<Vec<FermentationReading>, AppError> {
         let limit = limit.unwrap_or(100);
@@ -383,11 +744,15 @@ impl FermentationRepository {
             r#"
             SELECT * FROM fermentation_readings
             WHERE batch_id = $1
+              AND ($2::TIMESTAMPTZ IS NULL OR recorded_at >= $2)
+              AND ($3::TIMESTAMPTZ IS NULL OR recorded_at < $3)
             ORDER BY recorded_at DESC
-            LIMIT $2
+            LIMIT $4
             "#,
         )
             .bind(batch_id)
+            .bind(from)
+            .bind(to)
             .bind(limit)
             .fetch_all(&self.pool)
             .await?;
@@ -452,4 +817,48 @@ impl FermentationRepository {
 
         Ok(stats)
     }
-}
\ No newline at end of file
+
+    /// Tačke krive za analizu: merenja šećera iz prozora brzine (48h do poslednjeg
+    /// merenja), poslednja dva i prvo merenje šećera, i temperature iz poslednjih 7 dana.
+    /// Analiza nad ovim tačkama daje isto što i nad celom istorijom batch-a.
+    pub async fn list_curve_points(&self, batch_ids: &[Uuid]) -> Result<Vec<CurvePoint>, AppError> {
+        let points = sqlx::query_as::<_, CurvePoint>(
+            r#"
+            WITH sugar AS (
+                SELECT id,
+                       ROW_NUMBER() OVER (PARTITION BY batch_id ORDER BY recorded_at DESC) AS from_last,
+                       ROW_NUMBER() OVER (PARTITION BY batch_id ORDER BY recorded_at)      AS from_first,
+                       recorded_at >= MAX(recorded_at) OVER (PARTITION BY batch_id)
+                                      - make_interval(hours => $3)                        AS in_rate_window
+                FROM fermentation_readings
+                WHERE batch_id = ANY($1)
+                  AND (brix IS NOT NULL OR density IS NOT NULL)
+            )
+            SELECT r.batch_id, r.recorded_at, r.brix, r.density, r.temperature
+            FROM fermentation_readings r
+            LEFT JOIN sugar s ON s.id = r.id
+            WHERE r.batch_id = ANY($1)
+              AND ((s.id IS NOT NULL AND (s.in_rate_window OR s.from_last <= 2 OR s.from_first = 1))
+                   OR (r.temperature IS NOT NULL AND r.recorded_at > NOW() - make_interval(days => $2)))
+            ORDER BY r.batch_id, r.recorded_at
+            "#,
+        )
+            .bind(batch_ids)
+            .bind(TEMPERATURE_WINDOW_DAYS as i32)
+            .bind(RATE_WINDOW_HOURS as i32)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(points)
+    }
+}
+
+/// Jedinstveni indeks dozvoljava samo jedan aktivan ili pauziran batch po tanku
+pub(crate) fn occupancy_conflict(e: sqlx::Error) -> AppError {
+    match e {
+        sqlx::Error::Database(ref db) if db.constraint() == Some("uq_batches_occupied_tank") => {
+            AppError::Conflict("Tank already holds an active or paused batch".to_string())
+        }
+        e => AppError::DatabaseError(e),
+    }
+}
@@ -0,0 +1,283 @@
+use chrono::NaiveDate;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::db::FermentationRepository;
+use crate::error::AppError;
+use crate::models::{
+    cleaning_verification, next_due_date, CleaningVerification, CreateCleaningRequest,
+    CreateMaintenanceRecordRequest, CreateMaintenanceTaskRequest, MaintenanceRecord,
+    MaintenanceTask, TankCleaning, TankStatus, UpdateMaintenanceTaskRequest,
+};
+
+#[derive(Clone)]
+pub struct MaintenanceRepository {
+    pool: PgPool,
+}
+
+impl MaintenanceRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Upis čišćenja; uspešno čišćenje praznog tanka koji čeka čišćenje
+    /// vraća ga u available
+    pub async fn add_tank_cleaning(
+        &self,
+        tank_id: Uuid,
+        req: &CreateCleaningRequest,
+        operator_id: Uuid,
+        operator_email: &str,
+    ) -> Result<(TankCleaning, TankStatus), AppError> {
+        let mut tx = self.pool.begin().await?;
+        let tank = FermentationRepository::lock_tank(&mut tx, tank_id).await?;
+
+        if FermentationRepository::tank_is_occupied(&mut tx, tank_id).await? {
+            return Err(AppError::Conflict(
+                "Cannot clean a tank holding an active or paused batch".to_string(),
+            ));
+        }
+
+        let verification = cleaning_verification(req.atp_rlu, req.visual_check);
+        let cleaning = sqlx::query_as::<_, TankCleaning>(
+            r#"
+            INSERT INTO tank_cleanings (
+                tank_id, kind, procedure, chemicals, water_temperature, duration_minutes,
+                atp_rlu, visual_check, verification, operator_id, operator_email, performed_at, notes
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, COALESCE($12, NOW()), $13)
+            RETURNING *
+            "#,
+        )
+            .bind(tank_id)
+            .bind(req.kind)
+            .bind(&req.procedure)
+            .bind(&req.chemicals)
+            .bind(req.water_temperature)
+            .bind(req.duration_minutes)
+            .bind(req.atp_rlu)
+            .bind(req.visual_check)
+            .bind(verification)
+            .bind(operator_id)
+            .bind(operator_email)
+            .bind(req.performed_at)
+            .bind(&req.notes)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        let mut status = tank.status;
+        if verification == CleaningVerification::Passed
+            && status == TankStatus::Cleaning
+            && !FermentationRepository::tank_needs_cleaning(&mut tx, tank_id).await?
+        {
+            sqlx::query("UPDATE tanks SET status = 'available', updated_at = NOW() WHERE id = $1")
+                .bind(tank_id)
+                .execute(&mut *tx)
+                .await?;
+            status = TankStatus::Available;
+        }
+
+        tx.commit().await?;
+
+        Ok((cleaning, status))
+    }
+
+    pub async fn list_tank_cleanings(&self, tank_id: Uuid, limit: i64) -> Result<Vec<TankCleaning>, AppError> {
+        let cleanings = sqlx::query_as::<_, TankCleaning>(
+            "SELECT * FROM tank_cleanings WHERE tank_id = $1 ORDER BY performed_at DESC LIMIT $2",
+        )
+            .bind(tank_id)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(cleanings)
+    }
+
+    pub async fn create_maintenance_task(
+        &self,
+        tank_id: Uuid,
+        req: &CreateMaintenanceTaskRequest,
+    ) -> Result<MaintenanceTask, AppError> {
+        let task = sqlx::query_as::<_, MaintenanceTask>(
+            r#"
+            INSERT INTO tank_maintenance_tasks (tank_id, kind, title, due_date, interval_days, notes)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#,
+        )
+            .bind(tank_id)
+            .bind(req.kind)
+            .bind(&req.title)
+            .bind(req.due_date)
+            .bind(req.interval_days)
+            .bind(&req.notes)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(task)
+    }
+
+    pub async fn list_maintenance_tasks(&self, tank_id: Uuid) -> Result<Vec<MaintenanceTask>, AppError> {
+        let tasks = sqlx::query_as::<_, MaintenanceTask>(
+            "SELECT * FROM tank_maintenance_tasks WHERE tank_id = $1 ORDER BY active DESC, due_date ASC",
+        )
+            .bind(tank_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(tasks)
+    }
+
+    /// Aktivni zadaci sa rokom do `until` (uključujući zakasnele)
+    pub async fn list_due_maintenance(&self, until: NaiveDate) -> Result<Vec<MaintenanceTask>, AppError> {
+        let tasks = sqlx::query_as::<_, MaintenanceTask>(
+            "SELECT * FROM tank_maintenance_tasks WHERE active AND due_date <= $1 ORDER BY due_date ASC",
+        )
+            .bind(until)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(tasks)
+    }
+
+    pub async fn update_maintenance_task(
+        &self,
+        id: Uuid,
+        req: &UpdateMaintenanceTaskRequest,
+    ) -> Result<MaintenanceTask, AppError> {
+        sqlx::query_as::<_, MaintenanceTask>(
+            r#"
+            UPDATE tank_maintenance_tasks SET
+                kind          = COALESCE($2, kind),
+                title         = COALESCE($3, title),
+                due_date      = COALESCE($4, due_date),
+                interval_days = COALESCE($5, interval_days),
+                active        = COALESCE($6, active),
+                notes         = COALESCE($7, notes),
+                updated_at    = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+            .bind(id)
+            .bind(req.kind)
+            .bind(&req.title)
+            .bind(req.due_date)
+            .bind(req.interval_days)
+            .bind(req.active)
+            .bind(&req.notes)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Maintenance task not found".to_string()))
+    }
+
+    pub async fn delete_maintenance_task(&self, id: Uuid) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM tank_maintenance_tasks WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Maintenance task not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Upis održavanja; izvršen zadatak dobija sledeći rok ili se zatvara
+    pub async fn add_maintenance_record(
+        &self,
+        tank_id: Uuid,
+        req: &CreateMaintenanceRecordRequest,
+        operator_id: Uuid,
+        operator_email: &str,
+    ) -> Result<(MaintenanceRecord, Option<MaintenanceTask>), AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let task = match req.task_id {
+            Some(task_id) => {
+                let task = sqlx::query_as::<_, MaintenanceTask>(
+                    "SELECT * FROM tank_maintenance_tasks WHERE id = $1 AND tank_id = $2 FOR UPDATE",
+                )
+                    .bind(task_id)
+                    .bind(tank_id)
+                    .fetch_optional(&mut *tx)
+                    .await?
+                    .ok_or_else(|| AppError::NotFound("Maintenance task not found".to_string()))?;
+                if !task.active {
+                    return Err(AppError::Conflict(
+                        "Maintenance task is already closed".to_string(),
+                    ));
+                }
+                Some(task)
+            }
+            None => None,
+        };
+
+        let kind = req
+            .kind
+            .or(task.as_ref().map(|t| t.kind))
+            .ok_or_else(|| {
+                AppError::ValidationError("Maintenance kind is required without a task".to_string())
+            })?;
+
+        let record = sqlx::query_as::<_, MaintenanceRecord>(
+            r#"
+            INSERT INTO tank_maintenance_records (
+                tank_id, task_id, kind, description, operator_id, operator_email, performed_at, notes
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, COALESCE($7, NOW()), $8)
+            RETURNING *
+            "#,
+        )
+            .bind(tank_id)
+            .bind(req.task_id)
+            .bind(kind)
+            .bind(&req.description)
+            .bind(operator_id)
+            .bind(operator_email)
+            .bind(req.performed_at)
+            .bind(&req.notes)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        let task = match task {
+            Some(task) => {
+                let next_due = next_due_date(&task, record.performed_at.date_naive());
+                let task = sqlx::query_as::<_, MaintenanceTask>(
+                    r#"
+                    UPDATE tank_maintenance_tasks SET
+                        due_date   = COALESCE($2, due_date),
+                        active     = $2 IS NOT NULL,
+                        updated_at = NOW()
+                    WHERE id = $1
+                    RETURNING *
+                    "#,
+                )
+                    .bind(task.id)
+                    .bind(next_due)
+                    .fetch_one(&mut *tx)
+                    .await?;
+                Some(task)
+            }
+            None => None,
+        };
+
+        tx.commit().await?;
+
+        Ok((record, task))
+    }
+
+    pub async fn list_maintenance_records(&self, tank_id: Uuid, limit: i64) -> Result<Vec<MaintenanceRecord>, AppError> {
+        let records = sqlx::query_as::<_, MaintenanceRecord>(
+            "SELECT * FROM tank_maintenance_records WHERE tank_id = $1 ORDER BY performed_at DESC LIMIT $2",
+        )
+            .bind(tank_id)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(records)
+    }
+}
@@ -1,5 +1,23 @@
-﻿pub mod fermentation_repository;
+﻿pub mod addition_repository;
+pub mod alert_repository;
+pub mod cellar_repository;
+pub mod control_repository;
+pub mod crush_repository;
+pub mod device_repository;
+pub mod fermentation_repository;
+pub mod maintenance_repository;
 pub mod pool;
+pub mod rollup_repository;
+pub mod sync_repository;
 
+pub use addition_repository::*;
+pub use alert_repository::*;
+pub use cellar_repository::*;
+pub use control_repository::*;
+pub use crush_repository::*;
+pub use device_repository::*;
 pub use fermentation_repository::*;
-pub use pool::*;
\ No newline at end of file
+pub use maintenance_repository::*;
+pub use pool::*;
+pub use rollup_repository::*;
+pub use sync_repository::*;
\ No newline at end of file
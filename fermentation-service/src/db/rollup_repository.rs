@@ -0,0 +1,243 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{ReadingMetric, RollupResolution, SeriesRow};
+
+#[derive(Clone)]
+pub struct RollupRepository {
+    pool: PgPool,
+}
+
+impl RollupRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Preračunava agregate za intervale u kojima su se merenja menjala od
+    /// prethodnog osvežavanja, uključujući obrisana i premeštena merenja. Intervali
+    /// stariji od `keep_from` se ne diraju, jer su njihova sirova merenja možda već
+    /// obrisana. Vraća broj preračunatih intervala.
+    pub async fn refresh_rollups(&self, keep_from: Option<DateTime<Utc>>) -> Result<u64, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        // Zaključava stanje, pa paralelne instance servisa ne rade isti posao
+        let last_version: i64 = sqlx::query_scalar(
+            "SELECT last_sync_version FROM reading_rollup_state WHERE id = 1 FOR UPDATE",
+        )
+            .fetch_one(&mut *tx)
+            .await?;
+        // Samo verzije završenih transakcija; kasnije vidljive ulaze u sledeće osvežavanje
+        let current_version = sync_common::sync_watermark(&mut *tx).await? - 1;
+
+        let mut updated = 0;
+        if current_version > last_version {
+            for resolution in RollupResolution::ALL {
+                updated += Self::refresh_rollup_resolution(
+                    &mut tx,
+                    resolution,
+                    last_version,
+                    current_version,
+                    keep_from,
+                )
+                .await?;
+            }
+
+            sqlx::query("DELETE FROM reading_rollup_invalidations WHERE sync_version <= $1")
+                .bind(current_version)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        sqlx::query(
+            "UPDATE reading_rollup_state SET last_sync_version = $1, refreshed_at = NOW() WHERE id = 1",
+        )
+            .bind(current_version.max(last_version))
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(updated)
+    }
+
+    async fn refresh_rollup_resolution(
+        tx: &mut Transaction<'_, Postgres>,
+        resolution: RollupResolution,
+        after_version: i64,
+        up_to_version: i64,
+        keep_from: Option<DateTime<Utc>>,
+    ) -> Result<u64, AppError> {
+        let touched = sqlx::query_as::<_, (Uuid, DateTime<Utc>)>(
+            r#"
+            WITH changed AS (
+                SELECT batch_id, recorded_at FROM fermentation_readings
+                WHERE sync_version > $2 AND sync_version <= $3
+                UNION ALL
+                SELECT batch_id, recorded_at FROM reading_rollup_invalidations
+                WHERE sync_version > $2 AND sync_version <= $3
+            )
+            SELECT DISTINCT
+                batch_id,
+                date_bin($1::TEXT::INTERVAL, recorded_at, TIMESTAMPTZ '2000-01-01') AS bucket_start
+            FROM changed
+            WHERE $4::TIMESTAMPTZ IS NULL
+               OR date_bin($1::TEXT::INTERVAL, recorded_at, TIMESTAMPTZ '2000-01-01') >= $4
+            "#,
+        )
+            .bind(resolution.interval())
+            .bind(after_version)
+            .bind(up_to_version)
+            .bind(keep_from)
+            .fetch_all(&mut **tx)
+            .await?;
+
+        if touched.is_empty() {
+            return Ok(0);
+        }
+        let (batch_ids, bucket_starts): (Vec<Uuid>, Vec<DateTime<Utc>>) = touched.into_iter().unzip();
+
+        // Interval iz kog su obrisana sva merenja neke veličine ne sme da zadrži stari agregat
+        sqlx::query(
+            r#"
+            DELETE FROM reading_rollups r
+            USING UNNEST($1::UUID[], $2::TIMESTAMPTZ[]) AS t(batch_id, bucket_start)
+            WHERE r.batch_id = t.batch_id
+              AND r.bucket_start = t.bucket_start
+              AND r.resolution = $3
+            "#,
+        )
+            .bind(&batch_ids)
+            .bind(&bucket_starts)
+            .bind(resolution)
+            .execute(&mut **tx)
+            .await?;
+
+        sqlx::query(
+            r#"
+            WITH touched AS (
+                SELECT * FROM UNNEST($2::UUID[], $3::TIMESTAMPTZ[]) AS t(batch_id, bucket_start)
+            ),
+            bucket_readings AS (
+                SELECT r.*, t.bucket_start
+                FROM touched t
+                JOIN fermentation_readings r
+                  ON r.batch_id = t.batch_id
+                 AND r.recorded_at >= t.bucket_start
+                 AND r.recorded_at < t.bucket_start + $1::TEXT::INTERVAL
+            ),
+            points AS (
+                SELECT r.batch_id, r.bucket_start, m.metric, m.value
+                FROM bucket_readings r
+                CROSS JOIN LATERAL (
+                    VALUES ('temperature', r.temperature), ('brix', r.brix),
+                           ('density', r.density), ('ph', r.ph)
+                ) AS m(metric, value)
+                WHERE m.value IS NOT NULL
+                UNION ALL
+                SELECT r.batch_id, r.bucket_start, v.channel::TEXT, v.value
+                FROM bucket_readings r
+                JOIN reading_sensor_values v ON v.reading_id = r.id
+            )
+            INSERT INTO reading_rollups (
+                batch_id, resolution, metric, bucket_start,
+                min_value, max_value, avg_value, sample_count
+            )
+            SELECT batch_id, $4, metric::reading_metric, bucket_start,
+                   MIN(value), MAX(value), AVG(value), COUNT(*)
+            FROM points
+            GROUP BY batch_id, metric, bucket_start
+            "#,
+        )
+            .bind(resolution.interval())
+            .bind(&batch_ids)
+            .bind(&bucket_starts)
+            .bind(resolution)
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(batch_ids.len() as u64)
+    }
+
+    /// Briše sirova IoT merenja starija od `before` koja su već agregirana, u delovima
+    /// da transakcije ostanu kratke. Ručna merenja se čuvaju. Za ovo brisanje se ne
+    /// prave tombstone-i: klijenti zadržavaju merenja koja su već preuzeli.
+    pub async fn purge_raw_iot_readings(&self, before: DateTime<Utc>, chunk: i64) -> Result<u64, AppError> {
+        let mut deleted = 0;
+
+        loop {
+            let mut tx = self.pool.begin().await?;
+            sqlx::query("SELECT set_config('fermentation.retention_purge', 'on', true)")
+                .execute(&mut *tx)
+                .await?;
+
+            let result = sqlx::query(
+                r#"
+                DELETE FROM fermentation_readings
+                WHERE id IN (
+                    SELECT id FROM fermentation_readings
+                    WHERE source = 'iot'
+                      AND recorded_at < $1
+                      AND sync_version <= (SELECT last_sync_version FROM reading_rollup_state WHERE id = 1)
+                    LIMIT $2
+                )
+                "#,
+            )
+                .bind(before)
+                .bind(chunk)
+                .execute(&mut *tx)
+                .await?;
+
+            tx.commit().await?;
+
+            deleted += result.rows_affected();
+            if result.rows_affected() < chunk as u64 {
+                return Ok(deleted);
+            }
+        }
+    }
+
+    pub async fn rollups_refreshed_at(&self) -> Result<Option<DateTime<Utc>>, AppError> {
+        let refreshed_at = sqlx::query_scalar("SELECT refreshed_at FROM reading_rollup_state WHERE id = 1")
+            .fetch_optional(&self.pool)
+            .await?
+            .flatten();
+
+        Ok(refreshed_at)
+    }
+
+    pub async fn list_rollups(
+        &self,
+        batch_id: Uuid,
+        resolution: RollupResolution,
+        metrics: &[ReadingMetric],
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<SeriesRow>, AppError> {
+        let metrics: Vec<&str> = metrics.iter().map(|m| m.as_str()).collect();
+
+        let rows = sqlx::query_as::<_, SeriesRow>(
+            r#"
+            SELECT metric, bucket_start, min_value, max_value, avg_value, sample_count
+            FROM reading_rollups
+            WHERE batch_id = $1
+              AND resolution = $2
+              AND metric::TEXT = ANY($3)
+              AND bucket_start >= date_bin($4::TEXT::INTERVAL, $5, TIMESTAMPTZ '2000-01-01')
+              AND bucket_start < $6
+            ORDER BY metric, bucket_start
+            "#,
+        )
+            .bind(batch_id)
+            .bind(resolution)
+            .bind(&metrics)
+            .bind(resolution.interval())
+            .bind(from)
+            .bind(to)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows)
+    }
+}
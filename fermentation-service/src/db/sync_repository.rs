@@ -0,0 +1,170 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{AddReadingRequest, SyncCursor, SyncReadingRecord, SyncTombstone};
+
+#[derive(Clone)]
+pub struct SyncRepository {
+    pool: PgPool,
+}
+
+impl SyncRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn find_sync_reading(&self, id: Uuid) -> Result<Option<SyncReadingRecord>, AppError> {
+        let record = sqlx::query_as::<_, SyncReadingRecord>(
+            "SELECT * FROM fermentation_readings WHERE id = $1",
+        )
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(record)
+    }
+
+    /// Upis merenja sa id-jem klijenta; ako isti id stigne istovremeno dva puta,
+    /// drugi upis ne radi ništa
+    pub async fn insert_sync_reading(
+        &self,
+        id: Uuid,
+        batch_id: Uuid,
+        req: &AddReadingRequest,
+        recorded_at: DateTime<Utc>,
+    ) -> Result<Option<SyncReadingRecord>, AppError> {
+        let record = sqlx::query_as::<_, SyncReadingRecord>(
+            r#"
+            INSERT INTO fermentation_readings (
+                id, batch_id, temperature, brix, ph, density,
+                alcohol_percent, volatile_acidity, free_so2, total_so2,
+                color, clarity, aroma_notes, source, notes, recorded_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, 'manual', $14, $15)
+            ON CONFLICT (id) DO NOTHING
+            RETURNING *
+            "#,
+        )
+            .bind(id)
+            .bind(batch_id)
+            .bind(req.temperature)
+            .bind(req.brix)
+            .bind(req.ph)
+            .bind(req.density)
+            .bind(req.alcohol_percent)
+            .bind(req.volatile_acidity)
+            .bind(req.free_so2)
+            .bind(req.total_so2)
+            .bind(&req.color)
+            .bind(&req.clarity)
+            .bind(&req.aroma_notes)
+            .bind(&req.notes)
+            .bind(recorded_at)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(record)
+    }
+
+    pub async fn sync_watermark(&self) -> Result<i64, AppError> {
+        Ok(sync_common::sync_watermark(&self.pool).await?)
+    }
+
+    /// Promene posle `since`, samo iz završenih transakcija (ispod `watermark`)
+    pub async fn reading_changes(
+        &self,
+        since: SyncCursor,
+        watermark: i64,
+        limit: i64,
+        batch_id: Option<Uuid>,
+    ) -> Result<Vec<SyncReadingRecord>, AppError> {
+        let records = sqlx::query_as::<_, SyncReadingRecord>(
+            r#"
+            SELECT * FROM fermentation_readings
+            WHERE (sync_version, id) > ($1, $2)
+              AND sync_version < $3
+              AND ($5::UUID IS NULL OR batch_id = $5)
+            ORDER BY sync_version ASC, id ASC
+            LIMIT $4
+            "#,
+        )
+            .bind(since.version)
+            .bind(since.id)
+            .bind(watermark)
+            .bind(limit)
+            .bind(batch_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(records)
+    }
+
+    pub async fn sync_tombstones(
+        &self,
+        since: SyncCursor,
+        watermark: i64,
+        limit: i64,
+        batch_id: Option<Uuid>,
+    ) -> Result<Vec<SyncTombstone>, AppError> {
+        let records = sqlx::query_as::<_, SyncTombstone>(
+            r#"
+            SELECT entity, entity_id, sync_version, deleted_at
+            FROM sync_tombstones
+            WHERE (sync_version, entity_id) > ($1, $2)
+              AND sync_version < $3
+              AND ($5::UUID IS NULL OR scope->>'batch_id' = $5::TEXT)
+            ORDER BY sync_version ASC, entity_id ASC
+            LIMIT $4
+            "#,
+        )
+            .bind(since.version)
+            .bind(since.id)
+            .bind(watermark)
+            .bind(limit)
+            .bind(batch_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(records)
+    }
+
+    /// Briše tombstone-e starije od `before` i pamti najveću obrisanu verziju
+    pub async fn prune_sync_tombstones(&self, before: DateTime<Utc>) -> Result<u64, AppError> {
+        let pruned: i64 = sqlx::query_scalar(
+            r#"
+            WITH pruned AS (
+                DELETE FROM sync_tombstones
+                WHERE deleted_at < $1
+                RETURNING sync_version
+            ),
+            state AS (
+                UPDATE sync_tombstone_state
+                SET pruned_through_version = GREATEST(
+                    pruned_through_version,
+                    (SELECT MAX(sync_version) FROM pruned)
+                )
+                WHERE id = 1
+            )
+            SELECT COUNT(*) FROM pruned
+            "#,
+        )
+            .bind(before)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(pruned as u64)
+    }
+
+    /// Najveća verzija obrisanih tombstone-a
+    pub async fn sync_tombstones_pruned_through(&self) -> Result<i64, AppError> {
+        let version = sqlx::query_scalar(
+            "SELECT pruned_through_version FROM sync_tombstone_state WHERE id = 1",
+        )
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(version)
+    }
+}
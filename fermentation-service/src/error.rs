@@ -31,6 +31,9 @@ pub enum AppError {
 
     #[error("Conflict: {0}")]
     Conflict(String),
+
+    #[error("Upstream service error: {0}")]
+    UpstreamError(String),
 }
 
 impl IntoResponse for AppError {
@@ -50,6 +53,10 @@ impl IntoResponse for AppError {
             AppError::Unauthorized(m) => (StatusCode::UNAUTHORIZED, m.as_str()),
             AppError::Forbidden(m) => (StatusCode::FORBIDDEN, m.as_str()),
             AppError::Conflict(m) => (StatusCode::CONFLICT, m.as_str()),
+            AppError::UpstreamError(m) => {
+                tracing::error!("Upstream error: {}", m);
+                (StatusCode::BAD_GATEWAY, "Upstream service unavailable")
+            }
         };
 
         (status, Json(json!({ "error": message, "status": status.as_u16() }))).into_response()
@@ -0,0 +1,160 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    error::AppError,
+    extractors::AuthenticatedUser,
+    handlers::AppState,
+    models::{
+        AdditionProduct, BatchAddition, CreateAdditionProductRequest, CreateAdditionRequest,
+        UpdateAdditionProductRequest, UserRole,
+    },
+};
+
+#[derive(Debug, Deserialize)]
+pub struct AdditionProductsQuery {
+    #[serde(default)]
+    pub include_inactive: bool,
+}
+
+// ============== Katalog proizvoda ==============
+
+pub async fn create_addition_product(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(req): Json<CreateAdditionProductRequest>,
+) -> Result<(StatusCode, Json<AdditionProduct>), AppError> {
+    if auth.claims.role == UserRole::Worker {
+        return Err(AppError::Forbidden(
+            "Workers cannot manage addition products".to_string(),
+        ));
+    }
+
+    req.validate()?;
+
+    let product = state.addition_repo.create_addition_product(req).await?;
+
+    Ok((StatusCode::CREATED, Json(product)))
+}
+
+pub async fn list_addition_products(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Query(query): Query<AdditionProductsQuery>,
+) -> Result<Json<Vec<AdditionProduct>>, AppError> {
+    let products = state
+        .addition_repo
+        .list_addition_products(query.include_inactive)
+        .await?;
+
+    Ok(Json(products))
+}
+
+pub async fn get_addition_product(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(product_id): Path<Uuid>,
+) -> Result<Json<AdditionProduct>, AppError> {
+    let product = state.addition_repo.find_addition_product(product_id).await?;
+
+    Ok(Json(product))
+}
+
+pub async fn update_addition_product(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(product_id): Path<Uuid>,
+    Json(req): Json<UpdateAdditionProductRequest>,
+) -> Result<Json<AdditionProduct>, AppError> {
+    if auth.claims.role == UserRole::Worker {
+        return Err(AppError::Forbidden(
+            "Workers cannot manage addition products".to_string(),
+        ));
+    }
+
+    req.validate()?;
+
+    let product = state.addition_repo.update_addition_product(product_id, req).await?;
+
+    Ok(Json(product))
+}
+
+pub async fn delete_addition_product(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(product_id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    if auth.claims.role != UserRole::Admin {
+        return Err(AppError::Forbidden(
+            "Only admins can delete addition products".to_string(),
+        ));
+    }
+
+    state.addition_repo.delete_addition_product(product_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============== Dodaci po batch-u ==============
+
+/// Upis dodatka (svi podrumski radnici); operater je prijavljeni korisnik
+pub async fn add_batch_addition(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(batch_id): Path<Uuid>,
+    Json(req): Json<CreateAdditionRequest>,
+) -> Result<(StatusCode, Json<BatchAddition>), AppError> {
+    req.validate()?;
+
+    let product = state.addition_repo.find_addition_product(req.product_id).await?;
+    if !product.active {
+        return Err(AppError::ValidationError(format!(
+            "Product '{}' is no longer in use",
+            product.name
+        )));
+    }
+
+    let user_id = auth.claims.user_id()?;
+    let addition = state
+        .addition_repo
+        .add_batch_addition(batch_id, &product, &req, user_id, &auth.claims.email)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(addition)))
+}
+
+pub async fn list_batch_additions(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(batch_id): Path<Uuid>,
+) -> Result<Json<Vec<BatchAddition>>, AppError> {
+    state.repo.find_batch_by_id(batch_id).await?;
+
+    let additions = state.addition_repo.list_batch_additions(batch_id).await?;
+
+    Ok(Json(additions))
+}
+
+/// Ispravka pogrešnog upisa - operater koji je upisao dodatak ili enolog/admin
+pub async fn delete_batch_addition(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path((batch_id, addition_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, AppError> {
+    let addition = state.addition_repo.find_batch_addition(batch_id, addition_id).await?;
+    let user_id = auth.claims.user_id()?;
+
+    if auth.claims.role == UserRole::Worker && addition.added_by != user_id {
+        return Err(AppError::Forbidden("Access denied".to_string()));
+    }
+
+    state.addition_repo.delete_batch_addition(batch_id, addition_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,318 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    error::AppError,
+    extractors::AuthenticatedUser,
+    handlers::AppState,
+    models::{
+        validate_channel_target, Alert, AlertCondition, AlertEvent, AlertRule, AlertSeverity,
+        AlertStatus, CreateAlertRuleRequest, CreateNotificationChannelRequest, NotificationChannel,
+        UpdateAlertRuleRequest, UpdateNotificationChannelRequest, UserRole,
+    },
+};
+
+#[derive(Debug, Deserialize)]
+pub struct AlertRulesQuery {
+    pub batch_id: Option<Uuid>,
+    pub tank_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AlertsQuery {
+    pub status: Option<AlertStatus>,
+    pub batch_id: Option<Uuid>,
+    pub limit: Option<i64>,
+}
+
+// ============== Pravila ==============
+
+pub async fn create_alert_rule(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(req): Json<CreateAlertRuleRequest>,
+) -> Result<(StatusCode, Json<AlertRule>), AppError> {
+    if auth.claims.role == UserRole::Worker {
+        return Err(AppError::Forbidden(
+            "Workers cannot manage alert rules".to_string(),
+        ));
+    }
+
+    req.validate()?;
+
+    if req.batch_id.is_some() && req.tank_id.is_some() {
+        return Err(AppError::ValidationError(
+            "A rule applies to either a batch or a tank, not both".to_string(),
+        ));
+    }
+    if req.condition == AlertCondition::MissingReadings && req.threshold <= 0.0 {
+        return Err(AppError::ValidationError(
+            "Missing readings threshold must be a positive number of hours".to_string(),
+        ));
+    }
+    if let Some(batch_id) = req.batch_id {
+        state.repo.find_batch_by_id(batch_id).await?;
+    }
+    if let Some(tank_id) = req.tank_id {
+        state.repo.find_tank_by_id(tank_id).await?;
+    }
+
+    let user_id = auth.claims.user_id()?;
+    let rule = state.alert_repo.create_alert_rule(user_id, req).await?;
+
+    Ok((StatusCode::CREATED, Json(rule)))
+}
+
+/// Pravila (?batch_id=...&tank_id=...)
+pub async fn list_alert_rules(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Query(query): Query<AlertRulesQuery>,
+) -> Result<Json<Vec<AlertRule>>, AppError> {
+    let rules = state.alert_repo.list_alert_rules(query.batch_id, query.tank_id).await?;
+
+    Ok(Json(rules))
+}
+
+pub async fn get_alert_rule(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(rule_id): Path<Uuid>,
+) -> Result<Json<AlertRule>, AppError> {
+    let rule = state.alert_repo.find_alert_rule(rule_id).await?;
+
+    Ok(Json(rule))
+}
+
+pub async fn update_alert_rule(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(rule_id): Path<Uuid>,
+    Json(req): Json<UpdateAlertRuleRequest>,
+) -> Result<Json<AlertRule>, AppError> {
+    if auth.claims.role == UserRole::Worker {
+        return Err(AppError::Forbidden(
+            "Workers cannot manage alert rules".to_string(),
+        ));
+    }
+
+    req.validate()?;
+
+    let rule = state.alert_repo.update_alert_rule(rule_id, req).await?;
+
+    Ok(Json(rule))
+}
+
+pub async fn delete_alert_rule(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(rule_id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    if auth.claims.role == UserRole::Worker {
+        return Err(AppError::Forbidden(
+            "Workers cannot manage alert rules".to_string(),
+        ));
+    }
+
+    state.alert_repo.delete_alert_rule(rule_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============== Alarmi ==============
+
+/// Alarmi (?status=open&batch_id=...&limit=...)
+pub async fn list_alerts(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Query(query): Query<AlertsQuery>,
+) -> Result<Json<Vec<Alert>>, AppError> {
+    let limit = query.limit.unwrap_or(100).clamp(1, 500);
+    let alerts = state.alert_repo.list_alerts(query.status, query.batch_id, limit).await?;
+
+    Ok(Json(alerts))
+}
+
+pub async fn get_alert(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(alert_id): Path<Uuid>,
+) -> Result<Json<Alert>, AppError> {
+    let alert = state.alert_repo.find_alert(alert_id).await?;
+
+    Ok(Json(alert))
+}
+
+/// Potvrda da je neko preuzeo alarm; radnik samo za svoje batch-eve
+pub async fn acknowledge_alert(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(alert_id): Path<Uuid>,
+) -> Result<Json<Alert>, AppError> {
+    let user_id = auth.claims.user_id()?;
+    if auth.claims.role == UserRole::Worker {
+        let alert = state.alert_repo.find_alert(alert_id).await?;
+        let batch = state.repo.find_batch_by_id(alert.batch_id).await?;
+        if batch.created_by != user_id {
+            return Err(AppError::Forbidden("Access denied".to_string()));
+        }
+    }
+
+    let alert = state.alert_repo.acknowledge_alert(alert_id, user_id).await?;
+    // Potvrda je već upisana; neuspelo obaveštenje ne sme da je prikaže kao neuspelu
+    if let Err(e) = state.alerts.publish(AlertEvent::Acknowledged, &alert).await {
+        tracing::warn!("Publishing acknowledgement of alert {} failed: {}", alert.id, e);
+    }
+
+    Ok(Json(alert))
+}
+
+/// Ručno razrešavanje (enolog/admin); ako uslov i dalje važi, sledeća provera
+/// otvara novi alarm
+pub async fn resolve_alert(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(alert_id): Path<Uuid>,
+) -> Result<Json<Alert>, AppError> {
+    if auth.claims.role == UserRole::Worker {
+        return Err(AppError::Forbidden(
+            "Workers cannot resolve alerts".to_string(),
+        ));
+    }
+
+    let user_id = auth.claims.user_id()?;
+    let alert = state.alert_repo.resolve_alert(alert_id, Some(user_id)).await?;
+    if let Err(e) = state.alerts.publish(AlertEvent::Resolved, &alert).await {
+        tracing::warn!("Publishing resolution of alert {} failed: {}", alert.id, e);
+    }
+
+    Ok(Json(alert))
+}
+
+// ============== Kanali za obaveštenja ==============
+
+pub async fn create_notification_channel(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(req): Json<CreateNotificationChannelRequest>,
+) -> Result<(StatusCode, Json<NotificationChannel>), AppError> {
+    if auth.claims.role != UserRole::Admin {
+        return Err(AppError::Forbidden(
+            "Only admins can manage notification channels".to_string(),
+        ));
+    }
+
+    req.validate()?;
+    validate_channel_target(req.kind, req.target.as_deref()).map_err(AppError::ValidationError)?;
+
+    let channel = state.alert_repo.create_notification_channel(req).await?;
+
+    Ok((StatusCode::CREATED, Json(channel)))
+}
+
+pub async fn list_notification_channels(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<NotificationChannel>>, AppError> {
+    if auth.claims.role == UserRole::Worker {
+        return Err(AppError::Forbidden("Access denied".to_string()));
+    }
+
+    let channels = state.alert_repo.list_notification_channels(false).await?;
+
+    Ok(Json(channels))
+}
+
+pub async fn update_notification_channel(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(channel_id): Path<Uuid>,
+    Json(req): Json<UpdateNotificationChannelRequest>,
+) -> Result<Json<NotificationChannel>, AppError> {
+    if auth.claims.role != UserRole::Admin {
+        return Err(AppError::Forbidden(
+            "Only admins can manage notification channels".to_string(),
+        ));
+    }
+
+    req.validate()?;
+
+    if req.target.is_some() {
+        let channel = state.alert_repo.find_notification_channel(channel_id).await?;
+        validate_channel_target(channel.kind, req.target.as_deref())
+            .map_err(AppError::ValidationError)?;
+    }
+
+    let channel = state.alert_repo.update_notification_channel(channel_id, req).await?;
+
+    Ok(Json(channel))
+}
+
+pub async fn delete_notification_channel(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(channel_id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    if auth.claims.role != UserRole::Admin {
+        return Err(AppError::Forbidden(
+            "Only admins can manage notification channels".to_string(),
+        ));
+    }
+
+    state.alert_repo.delete_notification_channel(channel_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Probno obaveštenje na jedan kanal, da se proveri webhook/SMTP
+pub async fn test_notification_channel(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(channel_id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    if auth.claims.role != UserRole::Admin {
+        return Err(AppError::Forbidden(
+            "Only admins can manage notification channels".to_string(),
+        ));
+    }
+
+    let channel = state.alert_repo.find_notification_channel(channel_id).await?;
+
+    let now = Utc::now();
+    let alert = Alert {
+        id: Uuid::nil(),
+        rule_id: Uuid::nil(),
+        rule_name: "Test".to_string(),
+        condition: AlertCondition::TemperatureAbove,
+        batch_id: Uuid::nil(),
+        batch_name: "Test batch".to_string(),
+        tank_id: Uuid::nil(),
+        tank_name: "Test tank".to_string(),
+        status: AlertStatus::Open,
+        severity: AlertSeverity::Info,
+        message: format!("Test notification for channel '{}'", channel.name),
+        value: None,
+        triggered_at: now,
+        acknowledged_by: None,
+        acknowledged_at: None,
+        resolved_by: None,
+        resolved_at: None,
+        created_at: now,
+        updated_at: now,
+    };
+
+    state
+        .alerts
+        .notifier()
+        .send(&channel, AlertEvent::Test, &alert)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+use axum::{extract::State, Json};
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::{
+    error::AppError,
+    extractors::AuthenticatedUser,
+    handlers::AppState,
+    models::{
+        analyze_fermentation, BatchResponse, FermentationAnalysis, FermentationBatch,
+        FermentationDashboard, FermentationProgress, FermentationStatus, ReadingResponse,
+    },
+};
+
+/// Analiza krive za aktivne batch-eve iz liste (jedan upit za sve)
+pub(crate) async fn analyze_batches(
+    state: &AppState,
+    batches: &[FermentationBatch],
+) -> Result<HashMap<Uuid, FermentationAnalysis>, AppError> {
+    let active: Vec<&FermentationBatch> = batches
+        .iter()
+        .filter(|b| b.status == FermentationStatus::Active)
+        .collect();
+    if active.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let ids: Vec<Uuid> = active.iter().map(|b| b.id).collect();
+    let points = state.repo.list_curve_points(&ids).await?;
+    let now = Utc::now();
+
+    Ok(active
+        .into_iter()
+        .map(|batch| {
+            let batch_points: Vec<_> = points
+                .iter()
+                .filter(|p| p.batch_id == batch.id)
+                .cloned()
+                .collect();
+            (batch.id, analyze_fermentation(batch, &batch_points, now))
+        })
+        .collect())
+}
+
+/// Pregled aktivnih fermentacija: usporene, zaustavljene i van temperature prve
+pub async fn get_fermentation_dashboard(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<Json<FermentationDashboard>, AppError> {
+    let batches = state.repo.list_active_batches().await?;
+    let mut analytics = analyze_batches(&state, &batches).await?;
+
+    let mut responses: Vec<BatchResponse> = vec![];
+    for batch in batches {
+        let batch_id = batch.id;
+        let mut response = BatchResponse::from(batch);
+        if let Some(reading) = state.repo.get_latest_reading(batch_id).await? {
+            response.latest_reading = Some(ReadingResponse::from(reading));
+        }
+        response.analytics = analytics.remove(&batch_id);
+        responses.push(response);
+    }
+
+    responses.sort_by(|a, b| {
+        let severity = |r: &BatchResponse| {
+            r.analytics
+                .as_ref()
+                .map(|a| (a.needs_attention(), a.progress))
+                .unwrap_or((false, FermentationProgress::InsufficientData))
+        };
+        severity(b).cmp(&severity(a)).then_with(|| a.name.cmp(&b.name))
+    });
+
+    let count = |progress: FermentationProgress| {
+        responses
+            .iter()
+            .filter(|r| r.analytics.as_ref().is_some_and(|a| a.progress == progress))
+            .count()
+    };
+
+    Ok(Json(FermentationDashboard {
+        generated_at: Utc::now(),
+        active_batches: responses.len(),
+        sluggish: count(FermentationProgress::Sluggish),
+        stuck: count(FermentationProgress::Stuck),
+        temperature_alerts: responses
+            .iter()
+            .filter(|r| r.analytics.as_ref().is_some_and(|a| a.temperature_alert))
+            .count(),
+        batches: responses,
+    }))
+}
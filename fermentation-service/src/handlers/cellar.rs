@@ -0,0 +1,180 @@
+use std::collections::HashSet;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    error::AppError,
+    extractors::AuthenticatedUser,
+    handlers::AppState,
+    models::{
+        blend_composition, blend_variety_name, BatchComposition, BatchResponse, BlendRequest,
+        BlendResponse, CellarOperation, CellarOperationKind, CellarOperationResponse,
+        TransferRequest, UserRole, VesselStay,
+    },
+};
+
+#[derive(Debug, Deserialize)]
+pub struct CellarOperationsQuery {
+    pub batch_id: Option<Uuid>,
+    pub limit: Option<i64>,
+}
+
+/// Pretakanje/premeštanje batch-a u drugi tank (ili više tankova)
+pub async fn transfer_batch(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(req): Json<TransferRequest>,
+) -> Result<(StatusCode, Json<CellarOperationResponse>), AppError> {
+    if auth.claims.role == UserRole::Worker {
+        return Err(AppError::Forbidden(
+            "Workers cannot transfer batches".to_string(),
+        ));
+    }
+
+    req.validate()?;
+
+    let kind = req.kind.unwrap_or(CellarOperationKind::Racking);
+    if kind == CellarOperationKind::Blend {
+        return Err(AppError::ValidationError(
+            "Use the blends endpoint to blend batches".to_string(),
+        ));
+    }
+
+    let mut seen = HashSet::new();
+    if req.destinations.iter().any(|d| !seen.insert(d.tank_id)) {
+        return Err(AppError::ValidationError(
+            "Each destination tank can be listed only once".to_string(),
+        ));
+    }
+
+    let user_id = auth.claims.user_id()?;
+    let operation = state.cellar_repo.transfer_batch(user_id, kind, &req).await?;
+
+    Ok((StatusCode::CREATED, Json(operation_response(&state, operation).await?)))
+}
+
+/// Kupaža više batch-eva u novi lot
+pub async fn blend_batches(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(req): Json<BlendRequest>,
+) -> Result<(StatusCode, Json<BlendResponse>), AppError> {
+    if auth.claims.role == UserRole::Worker {
+        return Err(AppError::Forbidden(
+            "Workers cannot blend batches".to_string(),
+        ));
+    }
+
+    req.validate()?;
+
+    let mut seen = HashSet::new();
+    if req.sources.iter().any(|s| !seen.insert(s.batch_id)) {
+        return Err(AppError::ValidationError(
+            "Each source batch can be listed only once".to_string(),
+        ));
+    }
+
+    let grape_variety = match &req.grape_variety {
+        Some(variety) => variety.clone(),
+        None => {
+            let ids: Vec<Uuid> = req.sources.iter().map(|s| s.batch_id).collect();
+            let sources = state.cellar_repo.find_batches(&ids).await?;
+            blend_variety_name(sources.iter().map(|b| b.grape_variety.as_str()))
+                .unwrap_or_else(|| "Blend".to_string())
+        }
+    };
+
+    let user_id = auth.claims.user_id()?;
+    let (operation, batch) = state.cellar_repo.blend_batches(user_id, &grape_variety, &req).await?;
+
+    let operation = operation_response(&state, operation).await?;
+    let composition = blend_composition(batch.id, &operation.movements);
+
+    Ok((
+        StatusCode::CREATED,
+        Json(BlendResponse {
+            operation,
+            batch: BatchResponse::from(batch),
+            composition,
+        }),
+    ))
+}
+
+/// Podrumske operacije (?batch_id=...&limit=...)
+pub async fn list_cellar_operations(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Query(query): Query<CellarOperationsQuery>,
+) -> Result<Json<Vec<CellarOperationResponse>>, AppError> {
+    let limit = query.limit.unwrap_or(100).clamp(1, 500);
+    let operations = state.cellar_repo.list_cellar_operations(query.batch_id, limit).await?;
+
+    let ids: Vec<Uuid> = operations.iter().map(|o| o.id).collect();
+    let movements = state.cellar_repo.list_cellar_movements(&ids).await?;
+
+    let responses = operations
+        .into_iter()
+        .map(|operation| CellarOperationResponse {
+            movements: movements
+                .iter()
+                .filter(|m| m.operation_id == operation.id)
+                .cloned()
+                .collect(),
+            operation,
+        })
+        .collect();
+
+    Ok(Json(responses))
+}
+
+pub async fn get_cellar_operation(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(operation_id): Path<Uuid>,
+) -> Result<Json<CellarOperationResponse>, AppError> {
+    let operation = state.cellar_repo.find_cellar_operation(operation_id).await?;
+
+    Ok(Json(operation_response(&state, operation).await?))
+}
+
+/// Istorija sudova batch-a
+pub async fn list_batch_vessels(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(batch_id): Path<Uuid>,
+) -> Result<Json<Vec<VesselStay>>, AppError> {
+    state.repo.find_batch_by_id(batch_id).await?;
+
+    let stays = state.cellar_repo.list_vessel_stays(batch_id).await?;
+
+    Ok(Json(stays))
+}
+
+/// Sastav lota po izvornim batch-evima (prazan za batch koji nije kupaža)
+pub async fn get_batch_composition(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(batch_id): Path<Uuid>,
+) -> Result<Json<BatchComposition>, AppError> {
+    state.repo.find_batch_by_id(batch_id).await?;
+
+    let inflows = state.cellar_repo.list_batch_inflows(batch_id).await?;
+
+    Ok(Json(blend_composition(batch_id, &inflows)))
+}
+
+async fn operation_response(
+    state: &AppState,
+    operation: CellarOperation,
+) -> Result<CellarOperationResponse, AppError> {
+    let movements = state.cellar_repo.list_cellar_movements(&[operation.id]).await?;
+
+    Ok(CellarOperationResponse { operation, movements })
+}
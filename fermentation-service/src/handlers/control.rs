@@ -0,0 +1,165 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    error::AppError,
+    extractors::AuthenticatedUser,
+    handlers::AppState,
+    models::{
+        check_schedule, ControlCommand, ControlCommandSource, ControlOverrideRequest,
+        SetScheduleRequest, TankControl, TankControlStatus, UpdateTankControlRequest, UserRole,
+    },
+};
+
+#[derive(Debug, Deserialize)]
+pub struct ControlCommandsQuery {
+    pub limit: Option<i64>,
+}
+
+async fn find_control(state: &AppState, tank_id: Uuid) -> Result<TankControl, AppError> {
+    state.control_repo.find_tank_control(tank_id).await?.ok_or_else(|| {
+        AppError::NotFound("Temperature control is not configured for this tank".to_string())
+    })
+}
+
+pub async fn get_tank_control(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(tank_id): Path<Uuid>,
+) -> Result<Json<TankControlStatus>, AppError> {
+    state.repo.find_tank_by_id(tank_id).await?;
+    let control = find_control(&state, tank_id).await?;
+
+    Ok(Json(state.control.status(control).await?))
+}
+
+/// Uključivanje regulacije i deadband; podešavanja se prave pri prvom pozivu
+pub async fn update_tank_control(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(tank_id): Path<Uuid>,
+    Json(req): Json<UpdateTankControlRequest>,
+) -> Result<Json<TankControlStatus>, AppError> {
+    if auth.claims.role == UserRole::Worker {
+        return Err(AppError::Forbidden(
+            "Workers cannot configure temperature control".to_string(),
+        ));
+    }
+
+    req.validate()?;
+    state.repo.find_tank_by_id(tank_id).await?;
+
+    let control = state.control_repo.upsert_tank_control(tank_id, &req).await?;
+    state.control.evaluate_tank(&control).await?;
+    let control = find_control(&state, tank_id).await?;
+
+    Ok(Json(state.control.status(control).await?))
+}
+
+pub async fn set_setpoint_schedule(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(tank_id): Path<Uuid>,
+    Json(req): Json<SetScheduleRequest>,
+) -> Result<Json<TankControlStatus>, AppError> {
+    if auth.claims.role == UserRole::Worker {
+        return Err(AppError::Forbidden(
+            "Workers cannot configure temperature control".to_string(),
+        ));
+    }
+
+    req.validate()?;
+    check_schedule(&req.steps).map_err(AppError::ValidationError)?;
+    state.repo.find_tank_by_id(tank_id).await?;
+
+    let start_at = req.start_at.unwrap_or_else(Utc::now);
+    state
+        .control_repo
+        .replace_setpoint_schedule(tank_id, start_at, &req.steps)
+        .await?;
+    let control = find_control(&state, tank_id).await?;
+
+    Ok(Json(state.control.status(control).await?))
+}
+
+pub async fn delete_setpoint_schedule(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(tank_id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    if auth.claims.role == UserRole::Worker {
+        return Err(AppError::Forbidden(
+            "Workers cannot configure temperature control".to_string(),
+        ));
+    }
+
+    find_control(&state, tank_id).await?;
+    state.control_repo.clear_setpoint_schedule(tank_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Ručno preuzimanje aktuatora (svi podrumski radnici); komanda se šalje odmah
+pub async fn set_control_override(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(tank_id): Path<Uuid>,
+    Json(req): Json<ControlOverrideRequest>,
+) -> Result<(StatusCode, Json<ControlCommand>), AppError> {
+    req.validate()?;
+    if req.until.is_some_and(|until| until <= Utc::now()) {
+        return Err(AppError::ValidationError(
+            "Override must end in the future".to_string(),
+        ));
+    }
+    state.repo.find_tank_by_id(tank_id).await?;
+
+    let user_id = auth.claims.user_id()?;
+    state.control_repo.set_control_override(tank_id, &req, user_id).await?;
+
+    let measured = state
+        .control_repo
+        .latest_tank_temperature(tank_id)
+        .await?
+        .map(|(temperature, _)| temperature);
+    let command = state
+        .control
+        .issue(tank_id, req.action, ControlCommandSource::Manual, None, measured, Some(user_id))
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(command)))
+}
+
+/// Vraća tank regulatoru, koji odmah određuje novo stanje
+pub async fn clear_control_override(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(tank_id): Path<Uuid>,
+) -> Result<Json<TankControlStatus>, AppError> {
+    let control = state.control_repo.clear_control_override(tank_id).await?;
+    state.control.evaluate_tank(&control).await?;
+    let control = find_control(&state, tank_id).await?;
+
+    Ok(Json(state.control.status(control).await?))
+}
+
+pub async fn list_control_commands(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(tank_id): Path<Uuid>,
+    Query(query): Query<ControlCommandsQuery>,
+) -> Result<Json<Vec<ControlCommand>>, AppError> {
+    state.repo.find_tank_by_id(tank_id).await?;
+
+    let limit = query.limit.unwrap_or(100).clamp(1, 1000);
+    let commands = state.control_repo.list_control_commands(tank_id, limit).await?;
+
+    Ok(Json(commands))
+}
@@ -0,0 +1,250 @@
+use std::collections::HashSet;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    error::AppError,
+    extractors::AuthenticatedUser,
+    handlers::AppState,
+    models::{
+        blend_variety_name, build_batch_lineage, build_harvest_lineage, BatchLineage,
+        BatchResponse, CreateBatchFromMustRequest, CreateCrushRequest, CrushResponse,
+        HarvestLineage, NewCrushInput, UserRole,
+    },
+};
+
+/// Muljanje/presovanje jedne ili više berbi. Berbe se proveravaju u harvest-service,
+/// a ukupno izmuljano grožđe berbe ne sme preći njenu izmerenu masu.
+pub async fn create_crush(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(req): Json<CreateCrushRequest>,
+) -> Result<(StatusCode, Json<CrushResponse>), AppError> {
+    if auth.claims.role == UserRole::Worker {
+        return Err(AppError::Forbidden(
+            "Workers cannot record crush operations".to_string(),
+        ));
+    }
+
+    req.validate()?;
+
+    if req.free_run_liters + req.press_liters <= 0.0 {
+        return Err(AppError::ValidationError(
+            "Crush must produce some must".to_string(),
+        ));
+    }
+
+    let mut seen = HashSet::new();
+    if let Some(duplicate) = req.inputs.iter().find(|i| !seen.insert(i.harvest_id)) {
+        return Err(AppError::ValidationError(format!(
+            "Harvest {} is listed more than once",
+            duplicate.harvest_id
+        )));
+    }
+
+    let mut inputs = vec![];
+    for input in &req.inputs {
+        let harvest = state
+            .harvest_client
+            .get_harvest(&auth.token, input.harvest_id)
+            .await?;
+
+        if harvest.status == "planned" || harvest.status == "cancelled" {
+            return Err(AppError::ValidationError(format!(
+                "Harvest {} is {} and cannot be crushed",
+                harvest.id, harvest.status
+            )));
+        }
+        let harvest_weight_kg = harvest.total_weight_kg.ok_or_else(|| {
+            AppError::ValidationError(format!("Harvest {} has no recorded weight", harvest.id))
+        })?;
+
+        inputs.push(NewCrushInput {
+            harvest_id: harvest.id,
+            weight_kg: input.weight_kg,
+            harvest_weight_kg,
+            grape_variety: harvest.grape_variety,
+            vineyard_name: harvest.vineyard_name,
+            parcel_name: harvest.parcel_name,
+        });
+    }
+
+    let grape_variety = blend_variety_name(inputs.iter().filter_map(|i| i.grape_variety.as_deref()))
+        .unwrap_or_else(|| "Unknown".to_string());
+
+    let user_id = auth.claims.user_id()?;
+    let crush = state
+        .crush_repo
+        .create_crush(user_id, &grape_variety, &inputs, &req)
+        .await?;
+    let inputs = state.crush_repo.list_crush_inputs(&[crush.id]).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CrushResponse::new(crush, inputs, vec![])),
+    ))
+}
+
+pub async fn list_crushes(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<CrushResponse>>, AppError> {
+    let crushes = state.crush_repo.list_crushes().await?;
+    let ids: Vec<Uuid> = crushes.iter().map(|c| c.id).collect();
+    let inputs = state.crush_repo.list_crush_inputs(&ids).await?;
+    let allocations = state.crush_repo.list_crush_allocations(&ids).await?;
+
+    let responses = crushes
+        .into_iter()
+        .map(|crush| {
+            let crush_inputs = inputs.iter().filter(|i| i.crush_id == crush.id).cloned().collect();
+            let crush_allocations = allocations
+                .iter()
+                .filter(|a| a.crush_id == crush.id)
+                .cloned()
+                .collect();
+            CrushResponse::new(crush, crush_inputs, crush_allocations)
+        })
+        .collect();
+
+    Ok(Json(responses))
+}
+
+pub async fn get_crush(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(crush_id): Path<Uuid>,
+) -> Result<Json<CrushResponse>, AppError> {
+    let crush = state.crush_repo.find_crush(crush_id).await?;
+    let inputs = state.crush_repo.list_crush_inputs(&[crush_id]).await?;
+    let allocations = state.crush_repo.list_crush_allocations(&[crush_id]).await?;
+
+    Ok(Json(CrushResponse::new(crush, inputs, allocations)))
+}
+
+pub async fn delete_crush(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(crush_id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    if auth.claims.role == UserRole::Worker {
+        return Err(AppError::Forbidden(
+            "Workers cannot delete crush operations".to_string(),
+        ));
+    }
+
+    state.crush_repo.delete_crush(crush_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Novi batch od šire; zapremina je zbir izvora, a harvest_id se popunjava
+/// samo kada sva šira potiče od jedne berbe
+pub async fn create_batch_from_must(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(req): Json<CreateBatchFromMustRequest>,
+) -> Result<(StatusCode, Json<BatchResponse>), AppError> {
+    if auth.claims.role == UserRole::Worker {
+        return Err(AppError::Forbidden(
+            "Workers cannot create fermentation batches".to_string(),
+        ));
+    }
+
+    req.validate()?;
+
+    let mut crush_ids: Vec<Uuid> = req.sources.iter().map(|s| s.crush_id).collect();
+    crush_ids.sort_unstable();
+    crush_ids.dedup();
+
+    let crushes = state.crush_repo.find_crushes(&crush_ids).await?;
+    if let Some(missing) = crush_ids.iter().find(|id| !crushes.iter().any(|c| c.id == **id)) {
+        return Err(AppError::ValidationError(format!(
+            "Crush operation {} not found",
+            missing
+        )));
+    }
+
+    let grape_variety = match &req.grape_variety {
+        Some(variety) => variety.clone(),
+        None => blend_variety_name(crushes.iter().map(|c| c.grape_variety.as_str()))
+            .unwrap_or_else(|| "Unknown".to_string()),
+    };
+
+    let inputs = state.crush_repo.list_crush_inputs(&crush_ids).await?;
+    let harvest_ids: HashSet<Uuid> = inputs.iter().map(|i| i.harvest_id).collect();
+    let harvest_id = match harvest_ids.len() {
+        1 => harvest_ids.into_iter().next(),
+        _ => None,
+    };
+
+    let user_id = auth.claims.user_id()?;
+    let batch = state
+        .crush_repo
+        .create_batch_from_must(user_id, &grape_variety, harvest_id, &req)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(BatchResponse::from(batch))))
+}
+
+/// Poreklo batch-a: muljanja i berbe (kg → L)
+pub async fn get_batch_lineage(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(batch_id): Path<Uuid>,
+) -> Result<Json<BatchLineage>, AppError> {
+    let batch = state.repo.find_batch_by_id(batch_id).await?;
+
+    // Poreklo otkriva berbe i vinograde, pa radnik vidi samo svoje batch-eve
+    let user_id = auth.claims.user_id()?;
+    if auth.claims.role == UserRole::Worker && batch.created_by != user_id {
+        return Err(AppError::Forbidden("Access denied".to_string()));
+    }
+    let allocations = state.crush_repo.list_batch_allocations(batch_id).await?;
+
+    let crush_ids: Vec<Uuid> = allocations.iter().map(|a| a.crush_id).collect();
+    let crushes = state.crush_repo.find_crushes(&crush_ids).await?;
+    let inputs = state.crush_repo.list_crush_inputs(&crush_ids).await?;
+
+    Ok(Json(build_batch_lineage(
+        batch.id,
+        batch.volume_liters,
+        &allocations,
+        &crushes,
+        &inputs,
+    )))
+}
+
+/// Gde je završilo grožđe jedne berbe (poziva ga i harvest-service)
+pub async fn get_harvest_lineage(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(harvest_id): Path<Uuid>,
+) -> Result<Json<HarvestLineage>, AppError> {
+    // Vlasništvo nad berbom proverava harvest-service (403 za tuđu berbu radnika)
+    if auth.claims.role == UserRole::Worker {
+        state
+            .harvest_client
+            .get_harvest(&auth.token, harvest_id)
+            .await?;
+    }
+
+    let inputs = state.crush_repo.list_harvest_crush_inputs(harvest_id).await?;
+
+    let crush_ids: Vec<Uuid> = inputs.iter().map(|i| i.crush_id).collect();
+    let crushes = state.crush_repo.find_crushes(&crush_ids).await?;
+    let allocations = state.crush_repo.list_crush_allocations(&crush_ids).await?;
+
+    Ok(Json(build_harvest_lineage(
+        harvest_id,
+        &crushes,
+        &inputs,
+        &allocations,
+    )))
+}
@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    error::AppError,
+    extractors::AuthenticatedUser,
+    handlers::AppState,
+    models::{
+        device_health_summary, BindDeviceRequest, CreateDeviceRequest, DeviceHealthSummary,
+        DeviceResponse, IotDevice, UpdateDeviceRequest, UserRole,
+    },
+};
+
+#[derive(Debug, Deserialize)]
+pub struct DevicesQuery {
+    pub tank_id: Option<Uuid>,
+}
+
+/// Uređaji sa nazivima tankova i stanjem
+async fn device_responses(
+    state: &AppState,
+    devices: Vec<IotDevice>,
+) -> Result<Vec<DeviceResponse>, AppError> {
+    let tank_names: HashMap<Uuid, String> = state
+        .repo
+        .list_tanks()
+        .await?
+        .into_iter()
+        .map(|tank| (tank.id, tank.name))
+        .collect();
+    let now = Utc::now();
+
+    Ok(devices
+        .into_iter()
+        .map(|device| {
+            let tank_name = device.tank_id.and_then(|id| tank_names.get(&id).cloned());
+            DeviceResponse::new(device, tank_name, now)
+        })
+        .collect())
+}
+
+async fn device_response(state: &AppState, device: IotDevice) -> Result<DeviceResponse, AppError> {
+    let tank_name = match device.tank_id {
+        Some(tank_id) => Some(state.repo.find_tank_by_id(tank_id).await?.name),
+        None => None,
+    };
+
+    Ok(DeviceResponse::new(device, tank_name, Utc::now()))
+}
+
+pub async fn create_device(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(req): Json<CreateDeviceRequest>,
+) -> Result<(StatusCode, Json<DeviceResponse>), AppError> {
+    if auth.claims.role == UserRole::Worker {
+        return Err(AppError::Forbidden("Workers cannot register devices".to_string()));
+    }
+
+    req.validate()?;
+
+    if let Some(tank_id) = req.tank_id {
+        state.repo.find_tank_by_id(tank_id).await?;
+    }
+
+    let device = state.device_repo.create_device(req).await?;
+
+    Ok((StatusCode::CREATED, Json(device_response(&state, device).await?)))
+}
+
+pub async fn list_devices(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Query(query): Query<DevicesQuery>,
+) -> Result<Json<Vec<DeviceResponse>>, AppError> {
+    let devices = state.device_repo.list_devices(query.tank_id).await?;
+
+    Ok(Json(device_responses(&state, devices).await?))
+}
+
+pub async fn list_tank_devices(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(tank_id): Path<Uuid>,
+) -> Result<Json<Vec<DeviceResponse>>, AppError> {
+    state.repo.find_tank_by_id(tank_id).await?;
+
+    let devices = state.device_repo.list_devices(Some(tank_id)).await?;
+
+    Ok(Json(device_responses(&state, devices).await?))
+}
+
+/// Pregled stanja svih uređaja - nedostupni i sa slabom baterijom su prvi
+pub async fn get_device_health(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<Json<DeviceHealthSummary>, AppError> {
+    let devices = state.device_repo.list_devices(None).await?;
+    let responses = device_responses(&state, devices).await?;
+
+    Ok(Json(device_health_summary(responses)))
+}
+
+pub async fn get_device(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(device_id): Path<Uuid>,
+) -> Result<Json<DeviceResponse>, AppError> {
+    let device = state.device_repo.find_device(device_id).await?;
+
+    Ok(Json(device_response(&state, device).await?))
+}
+
+pub async fn update_device(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(device_id): Path<Uuid>,
+    Json(req): Json<UpdateDeviceRequest>,
+) -> Result<Json<DeviceResponse>, AppError> {
+    if auth.claims.role == UserRole::Worker {
+        return Err(AppError::Forbidden("Workers cannot manage devices".to_string()));
+    }
+
+    req.validate()?;
+
+    let device = state.device_repo.update_device(device_id, req).await?;
+
+    Ok(Json(device_response(&state, device).await?))
+}
+
+/// Premeštanje sonde u drugi tank (ili odvezivanje)
+pub async fn bind_device(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(device_id): Path<Uuid>,
+    Json(req): Json<BindDeviceRequest>,
+) -> Result<Json<DeviceResponse>, AppError> {
+    if auth.claims.role == UserRole::Worker {
+        return Err(AppError::Forbidden("Workers cannot manage devices".to_string()));
+    }
+
+    if let Some(tank_id) = req.tank_id {
+        state.repo.find_tank_by_id(tank_id).await?;
+    }
+
+    let device = state.device_repo.bind_device(device_id, req.tank_id).await?;
+
+    Ok(Json(device_response(&state, device).await?))
+}
+
+pub async fn delete_device(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(device_id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    if auth.claims.role != UserRole::Admin {
+        return Err(AppError::Forbidden("Only admins can delete devices".to_string()));
+    }
+
+    state.device_repo.delete_device(device_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,35 +1,67 @@
-﻿use axum::{
+﻿use std::collections::{HashMap, HashSet};
+
+use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
     Json,
 };
 use axum::http::HeaderValue;
 use axum::http::header;
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
-use tracing::log::__private_api::log;
 use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
+    alerts::AlertEngine,
+    clients::HarvestClient,
     config::Settings,
-    db::FermentationRepository,
+    control::TemperatureController,
+    db::{
+        AdditionRepository, AlertRepository, CellarRepository, ControlRepository, CrushRepository,
+        DeviceRepository, FermentationRepository, MaintenanceRepository, RollupRepository,
+        SyncRepository,
+    },
     error::AppError,
     extractors::AuthenticatedUser,
+    handlers::analyze_batches,
     models::{
-        AddReadingRequest, BatchResponse, BatchStats, CreateBatchRequest, CreateTankRequest,
-        FermentationStatus, IotReadingRequest, ReadingResponse, TankResponse, TankStatus,
-        UpdateBatchRequest, UpdateTankRequest, UserRole,
+        check_iot_batch, group_iot_samples, AddReadingRequest, BatchResponse, BatchStats,
+        CreateBatchRequest, CreateTankRequest, FermentationStatus, IotDevice, IotIngestSummary,
+        IotIngestTarget, IotReadingBatchRequest, IotReadingRequest, IotSampleGroup,
+        ReadingResponse, SensorValue, StreamEvent, TankResponse, UpdateBatchRequest,
+        UpdateTankRequest, UserRole, IOT_BATCH_MAX,
     },
+    stream::StreamHub,
 };
 
+/// Najviše merenja po stranici; duži periodi idu preko agregirane serije
+const MAX_READINGS_PAGE: i64 = 1000;
+
 #[derive(Clone)]
 pub struct AppState {
     pub repo: FermentationRepository,
+    pub sync_repo: SyncRepository,
+    pub crush_repo: CrushRepository,
+    pub cellar_repo: CellarRepository,
+    pub addition_repo: AdditionRepository,
+    pub alert_repo: AlertRepository,
+    pub device_repo: DeviceRepository,
+    pub rollup_repo: RollupRepository,
+    pub control_repo: ControlRepository,
+    pub maintenance_repo: MaintenanceRepository,
+    pub harvest_client: HarvestClient,
+    pub alerts: AlertEngine,
+    pub stream: StreamHub,
+    pub control: TemperatureController,
+    #[allow(dead_code)]
     pub settings: Settings,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct ReadingsQuery {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
     pub limit: Option<i64>,
 }
 
@@ -117,6 +149,7 @@ pub async fn delete_tank(
     }
 
     state.repo.delete_tank(tank_id).await?;
+    purge_attachments(&state, &auth, "tank", tank_id).await;
 
     Ok(StatusCode::NO_CONTENT)
 }
@@ -134,6 +167,14 @@ pub async fn create_batch(
 
     req.validate()?;
 
+    // Berba mora postojati u harvest-service
+    if let Some(harvest_id) = req.harvest_id {
+        state
+            .harvest_client
+            .get_harvest(&auth.token, harvest_id)
+            .await?;
+    }
+
     let user_id = auth.claims.user_id()?;
     let batch = state.repo.create_batch(user_id, req).await?;
 
@@ -145,6 +186,7 @@ pub async fn list_batches(
     State(state): State<AppState>,
 ) -> Result<Json<Vec<BatchResponse>>, AppError> {
     let batches = state.repo.list_batches().await?;
+    let mut analytics = analyze_batches(&state, &batches).await?;
 
     let mut responses: Vec<BatchResponse> = vec![];
     for batch in batches {
@@ -154,6 +196,7 @@ pub async fn list_batches(
         if let Ok(Some(reading)) = state.repo.get_latest_reading(batch_id).await {
             response.latest_reading = Some(ReadingResponse::from(reading));
         }
+        response.analytics = analytics.remove(&batch_id);
         responses.push(response);
     }
 
@@ -165,6 +208,7 @@ pub async fn list_active_batches(
     State(state): State<AppState>,
 ) -> Result<Json<Vec<BatchResponse>>, AppError> {
     let batches = state.repo.list_active_batches().await?;
+    let mut analytics = analyze_batches(&state, &batches).await?;
 
     let mut responses: Vec<BatchResponse> = vec![];
     for batch in batches {
@@ -173,6 +217,7 @@ pub async fn list_active_batches(
         if let Ok(Some(reading)) = state.repo.get_latest_reading(batch_id).await {
             response.latest_reading = Some(ReadingResponse::from(reading));
         }
+        response.analytics = analytics.remove(&batch_id);
         responses.push(response);
     }
 
@@ -185,6 +230,7 @@ pub async fn get_batch(
     Path(batch_id): Path<Uuid>,
 ) -> Result<Json<BatchResponse>, AppError> {
     let batch = state.repo.find_batch_by_id(batch_id).await?;
+    let mut analytics = analyze_batches(&state, std::slice::from_ref(&batch)).await?;
 
     let mut response = BatchResponse::from(batch);
 
@@ -192,6 +238,7 @@ pub async fn get_batch(
     if let Ok(Some(reading)) = state.repo.get_latest_reading(batch_id).await {
         response.latest_reading = Some(ReadingResponse::from(reading));
     }
+    response.analytics = analytics.remove(&batch_id);
 
     Ok(Json(response))
 }
@@ -253,10 +300,27 @@ pub async fn delete_batch(
     }
 
     state.repo.delete_batch(batch_id).await?;
+    purge_attachments(&state, &auth, "batch", batch_id).await;
 
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// Prilozi su u harvest-service; brisanje je već izvršeno, pa se greška samo loguje
+async fn purge_attachments(
+    state: &AppState,
+    auth: &AuthenticatedUser,
+    entity_type: &str,
+    id: Uuid,
+) {
+    if let Err(e) = state
+        .harvest_client
+        .purge_attachments(&auth.token, entity_type, id)
+        .await
+    {
+        tracing::warn!("Failed to purge attachments of {} {}: {}", entity_type, id, e);
+    }
+}
+
 pub async fn get_batch_stats(
     _auth: AuthenticatedUser,
     State(state): State<AppState>,
@@ -289,9 +353,15 @@ pub async fn add_reading(
         return Err(AppError::Forbidden("Access denied".to_string()));
     }
 
-    let reading = state.repo.add_reading(batch_id, req).await?;
+    let reading = ReadingResponse::from(state.repo.add_reading(batch_id, req).await?);
+    state.alerts.check_batch(batch_id);
+    state.stream.publish(StreamEvent::Reading {
+        batch_id,
+        tank_id: batch.tank_id,
+        reading: reading.clone(),
+    });
 
-    Ok((StatusCode::CREATED, Json(ReadingResponse::from(reading))))
+    Ok((StatusCode::CREATED, Json(reading)))
 }
 
 pub async fn list_readings(
@@ -303,9 +373,14 @@ pub async fn list_readings(
 
     state.repo.find_batch_by_id(batch_id).await?;
 
-    let readings = state.repo.list_readings(batch_id, query.limit).await?;
+    let limit = query.limit.map(|limit| limit.clamp(1, MAX_READINGS_PAGE));
+    let readings = state
+        .repo
+        .list_readings(batch_id, query.from, query.to, limit)
+        .await?;
 
-    let responses = readings.into_iter().map(ReadingResponse::from).collect();
+    let mut responses: Vec<ReadingResponse> = readings.into_iter().map(ReadingResponse::from).collect();
+    attach_sensor_values(&state, &mut responses).await?;
 
     Ok(Json(responses))
 }
@@ -331,9 +406,162 @@ pub async fn iot_reading(
     State(state): State<AppState>,
     Json(req): Json<IotReadingRequest>,
 ) -> Result<(StatusCode, Json<ReadingResponse>), AppError> {
-    let reading = state.repo.add_iot_reading(req).await?;
+    let summary = ingest_iot_readings(&state, vec![req]).await?;
+    let reading = summary.readings.into_iter().next().ok_or_else(|| {
+        AppError::Conflict("Reading from this device at this time is already recorded".to_string())
+    })?;
+
+    Ok((StatusCode::CREATED, Json(reading)))
+}
 
-    Ok((StatusCode::CREATED, Json(ReadingResponse::from(reading))))
+/// Više merenja u jednom zahtevu; duplikati se preskaču i samo prebroje
+pub async fn iot_readings_batch(
+    State(state): State<AppState>,
+    Json(req): Json<IotReadingBatchRequest>,
+) -> Result<Json<IotIngestSummary>, AppError> {
+    if req.readings.is_empty() || req.readings.len() > IOT_BATCH_MAX {
+        return Err(AppError::ValidationError(format!(
+            "Upload must contain 1-{} readings",
+            IOT_BATCH_MAX
+        )));
+    }
+    req.validate()?;
+
+    let summary = ingest_iot_readings(&state, req.readings).await?;
+
+    Ok(Json(summary))
+}
+
+/// Zajednički put za IoT merenja (HTTP i MQTT): validacija, razrešavanje izvora,
+/// bulk upis bez duplikata, alarmi i live stream
+pub async fn ingest_iot_readings(
+    state: &AppState,
+    samples: Vec<IotReadingRequest>,
+) -> Result<IotIngestSummary, AppError> {
+    for sample in &samples {
+        sample.validate()?;
+    }
+    check_iot_batch(&samples).map_err(AppError::ValidationError)?;
+
+    // Izvori se razrešavaju pre upisa, a upis je jedna transakcija: upload se
+    // upisuje ceo ili nimalo
+    let mut targets = Vec::new();
+    for mut group in group_iot_samples(samples) {
+        let (batch_id, device) = resolve_iot_source(state, &group).await?;
+        group.calibrate(device.as_ref()).map_err(AppError::ValidationError)?;
+        targets.push(IotIngestTarget { batch_id, device, group });
+    }
+
+    let stored = state.repo.add_iot_readings(&targets).await?;
+
+    let mut summary = IotIngestSummary::default();
+    let mut batches = HashSet::new();
+
+    for (target, readings) in targets.iter().zip(stored) {
+        let received = target.group.samples.len();
+        summary.received += received;
+        summary.stored += readings.len();
+        summary.duplicates += received - readings.len();
+        if !readings.is_empty() {
+            batches.insert(target.batch_id);
+        }
+        summary.readings.extend(readings.into_iter().map(ReadingResponse::from));
+    }
+
+    attach_sensor_values(state, &mut summary.readings).await?;
+
+    for batch_id in &batches {
+        state.alerts.check_batch(*batch_id);
+    }
+
+    if state.stream.has_subscribers() {
+        for batch_id in batches {
+            let batch = state.repo.find_batch_by_id(batch_id).await?;
+            for reading in summary.readings.iter().filter(|r| r.batch_id == batch_id) {
+                state.stream.publish(StreamEvent::Reading {
+                    batch_id: batch.id,
+                    tank_id: batch.tank_id,
+                    reading: reading.clone(),
+                });
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Batch i registrovan uređaj za grupu merenja. Registrovan uređaj vezan za tank
+/// ne mora da zna batch - uzima se aktivan batch tanka; ako ga navede, batch mora
+/// biti u tom tanku.
+async fn resolve_iot_source(
+    state: &AppState,
+    group: &IotSampleGroup,
+) -> Result<(Uuid, Option<IotDevice>), AppError> {
+    let device = match group.device_id.as_deref() {
+        Some(hardware_id) => state.device_repo.find_device_by_hardware_id(hardware_id).await?,
+        None => None,
+    };
+    if let Some(device) = &device {
+        if !device.active {
+            return Err(AppError::Conflict(format!(
+                "Device '{}' is disabled",
+                device.device_id
+            )));
+        }
+    }
+
+    let batch_id = match (group.batch_id, &device) {
+        (Some(batch_id), Some(IotDevice { tank_id: Some(tank_id), device_id, .. })) => {
+            let batch = state.repo.find_batch_by_id(batch_id).await?;
+            if batch.tank_id != *tank_id {
+                return Err(AppError::ValidationError(format!(
+                    "Device '{}' is bound to another tank than batch {}",
+                    device_id, batch_id
+                )));
+            }
+            batch_id
+        }
+        (Some(batch_id), _) => batch_id,
+        (None, Some(device)) => {
+            let tank_id = device.tank_id.ok_or_else(|| {
+                AppError::Conflict("Device is not bound to a tank".to_string())
+            })?;
+            state
+                .repo
+                .find_active_batch_in_tank(tank_id)
+                .await?
+                .ok_or_else(|| AppError::Conflict("Tank has no active batch".to_string()))?
+                .id
+        }
+        (None, None) => {
+            return Err(AppError::ValidationError(
+                "Reading needs a batch_id or a registered device_id".to_string(),
+            ))
+        }
+    };
+
+    Ok((batch_id, device))
+}
+
+/// Dopunjava merenja vrednostima ostalih kanala senzora
+async fn attach_sensor_values(
+    state: &AppState,
+    readings: &mut [ReadingResponse],
+) -> Result<(), AppError> {
+    let ids: Vec<Uuid> = readings.iter().map(|r| r.id).collect();
+    if ids.is_empty() {
+        return Ok(());
+    }
+
+    let mut values: HashMap<Uuid, Vec<SensorValue>> = HashMap::new();
+    for value in state.repo.list_sensor_values(&ids).await? {
+        values.entry(value.reading_id).or_default().push(value);
+    }
+    for reading in readings {
+        reading.sensor_values = values.remove(&reading.id).unwrap_or_default();
+    }
+
+    Ok(())
 }
 
 use axum::{
@@ -360,7 +588,7 @@ pub async fn export_batch_pdf(
     // Get readings
     let readings = state
         .repo
-        .list_readings(batch_id, Some(100))
+        .list_readings(batch_id, None, None, Some(100))
         .await
         .unwrap_or_default();
 
@@ -369,25 +597,26 @@ pub async fn export_batch_pdf(
         .repo
         .get_batch_stats(batch_id)
         .await
-        .unwrap_or_else(|_| {
-            // Return default stats if none exist
-            crate::models::BatchStats {
-                batch_id,
-                total_readings: 0,
-                avg_temperature: None,
-                min_temperature: None,
-                max_temperature: None,
-                latest_brix: None,
-                latest_ph: None,
-                latest_alcohol: None,
-            }
+        // Return default stats if none exist
+        .unwrap_or(crate::models::BatchStats {
+            batch_id,
+            total_readings: 0,
+            avg_temperature: None,
+            min_temperature: None,
+            max_temperature: None,
+            latest_brix: None,
+            latest_ph: None,
+            latest_alcohol: None,
         });
 
     // Get tank name
     let tank = state.repo.find_tank_by_id(batch.tank_id).await?;
 
+    // Registar dodataka
+    let additions = state.addition_repo.list_batch_additions(batch_id).await?;
+
     // Generate PDF
-    let pdf_bytes = crate::pdf::generate_batch_report(&batch, &readings, &stats, &tank.name)
+    let pdf_bytes = crate::pdf::generate_batch_report(&batch, &readings, &stats, &additions, &tank.name)
         .map_err(|e| AppError::InternalError(format!("Failed to generate PDF: {}", e)))?;
 
     // Return PDF
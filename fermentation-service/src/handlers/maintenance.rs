@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    error::AppError,
+    extractors::AuthenticatedUser,
+    handlers::AppState,
+    models::{
+        CleaningResponse, CreateCleaningRequest, CreateMaintenanceRecordRequest,
+        CreateMaintenanceTaskRequest, DueMaintenance, DueMaintenanceQuery, MaintenanceRecord,
+        MaintenanceRecordResponse, MaintenanceTask, TankCleaning, UpdateMaintenanceTaskRequest,
+        UserRole, DEFAULT_DUE_WITHIN_DAYS,
+    },
+};
+
+#[derive(Debug, Deserialize)]
+pub struct MaintenanceLogQuery {
+    pub limit: Option<i64>,
+}
+
+fn check_not_future(performed_at: Option<DateTime<Utc>>) -> Result<(), AppError> {
+    if performed_at.is_some_and(|at| at > Utc::now() + Duration::minutes(5)) {
+        return Err(AppError::ValidationError(
+            "performed_at cannot be in the future".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+// ============== Čišćenje i sanitacija ==============
+
+/// Upis čišćenja (svi podrumski radnici); uspešno provereno čišćenje
+/// vraća ispražnjen tank u available
+pub async fn create_tank_cleaning(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(tank_id): Path<Uuid>,
+    Json(req): Json<CreateCleaningRequest>,
+) -> Result<(StatusCode, Json<CleaningResponse>), AppError> {
+    req.validate()?;
+    check_not_future(req.performed_at)?;
+
+    let user_id = auth.claims.user_id()?;
+    let (cleaning, tank_status) = state
+        .maintenance_repo
+        .add_tank_cleaning(tank_id, &req, user_id, &auth.claims.email)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(CleaningResponse { cleaning, tank_status })))
+}
+
+pub async fn list_tank_cleanings(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(tank_id): Path<Uuid>,
+    Query(query): Query<MaintenanceLogQuery>,
+) -> Result<Json<Vec<TankCleaning>>, AppError> {
+    state.repo.find_tank_by_id(tank_id).await?;
+
+    let limit = query.limit.unwrap_or(100).clamp(1, 1000);
+    let cleanings = state.maintenance_repo.list_tank_cleanings(tank_id, limit).await?;
+
+    Ok(Json(cleanings))
+}
+
+// ============== Planirano održavanje ==============
+
+pub async fn create_maintenance_task(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(tank_id): Path<Uuid>,
+    Json(req): Json<CreateMaintenanceTaskRequest>,
+) -> Result<(StatusCode, Json<MaintenanceTask>), AppError> {
+    if auth.claims.role == UserRole::Worker {
+        return Err(AppError::Forbidden(
+            "Workers cannot schedule maintenance".to_string(),
+        ));
+    }
+
+    req.validate()?;
+    state.repo.find_tank_by_id(tank_id).await?;
+
+    let task = state.maintenance_repo.create_maintenance_task(tank_id, &req).await?;
+
+    Ok((StatusCode::CREATED, Json(task)))
+}
+
+pub async fn list_maintenance_tasks(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(tank_id): Path<Uuid>,
+) -> Result<Json<Vec<MaintenanceTask>>, AppError> {
+    state.repo.find_tank_by_id(tank_id).await?;
+
+    let tasks = state.maintenance_repo.list_maintenance_tasks(tank_id).await?;
+
+    Ok(Json(tasks))
+}
+
+pub async fn update_maintenance_task(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(task_id): Path<Uuid>,
+    Json(req): Json<UpdateMaintenanceTaskRequest>,
+) -> Result<Json<MaintenanceTask>, AppError> {
+    if auth.claims.role == UserRole::Worker {
+        return Err(AppError::Forbidden(
+            "Workers cannot schedule maintenance".to_string(),
+        ));
+    }
+
+    req.validate()?;
+    let task = state.maintenance_repo.update_maintenance_task(task_id, &req).await?;
+
+    Ok(Json(task))
+}
+
+pub async fn delete_maintenance_task(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(task_id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    if auth.claims.role != UserRole::Admin {
+        return Err(AppError::Forbidden(
+            "Only admins can delete maintenance tasks".to_string(),
+        ));
+    }
+
+    state.maintenance_repo.delete_maintenance_task(task_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Zadaci kojima rok ističe u narednih `within_days` dana (podrazumevano 14),
+/// zajedno sa zakasnelim
+pub async fn list_due_maintenance(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Query(query): Query<DueMaintenanceQuery>,
+) -> Result<Json<Vec<DueMaintenance>>, AppError> {
+    let within_days = query.within_days.unwrap_or(DEFAULT_DUE_WITHIN_DAYS).clamp(0, 365);
+    let today = Utc::now().date_naive();
+
+    let tasks = state
+        .maintenance_repo
+        .list_due_maintenance(today + Duration::days(within_days))
+        .await?;
+    let tank_names: HashMap<Uuid, String> = state
+        .repo
+        .list_tanks()
+        .await?
+        .into_iter()
+        .map(|tank| (tank.id, tank.name))
+        .collect();
+
+    let due = tasks
+        .into_iter()
+        .map(|task| {
+            let tank_name = tank_names.get(&task.tank_id).cloned().unwrap_or_default();
+            DueMaintenance::new(task, tank_name, today)
+        })
+        .collect();
+
+    Ok(Json(due))
+}
+
+// ============== Izvršeno održavanje ==============
+
+pub async fn create_maintenance_record(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(tank_id): Path<Uuid>,
+    Json(req): Json<CreateMaintenanceRecordRequest>,
+) -> Result<(StatusCode, Json<MaintenanceRecordResponse>), AppError> {
+    req.validate()?;
+    check_not_future(req.performed_at)?;
+    state.repo.find_tank_by_id(tank_id).await?;
+
+    let user_id = auth.claims.user_id()?;
+    let (record, task) = state
+        .maintenance_repo
+        .add_maintenance_record(tank_id, &req, user_id, &auth.claims.email)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(MaintenanceRecordResponse { record, task })))
+}
+
+pub async fn list_maintenance_records(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(tank_id): Path<Uuid>,
+    Query(query): Query<MaintenanceLogQuery>,
+) -> Result<Json<Vec<MaintenanceRecord>>, AppError> {
+    state.repo.find_tank_by_id(tank_id).await?;
+
+    let limit = query.limit.unwrap_or(100).clamp(1, 1000);
+    let records = state.maintenance_repo.list_maintenance_records(tank_id, limit).await?;
+
+    Ok(Json(records))
+}
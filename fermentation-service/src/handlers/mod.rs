@@ -1,3 +1,25 @@
-﻿pub mod fermentation;
+﻿pub mod addition;
+pub mod alert;
+pub mod analytics;
+pub mod cellar;
+pub mod control;
+pub mod crush;
+pub mod device;
+pub mod fermentation;
+pub mod maintenance;
+pub mod series;
+pub mod stream;
+pub mod sync;
 
-pub use fermentation::*;
\ No newline at end of file
+pub use addition::*;
+pub use alert::*;
+pub use analytics::*;
+pub use cellar::*;
+pub use control::*;
+pub use crush::*;
+pub use device::*;
+pub use fermentation::*;
+pub use maintenance::*;
+pub use series::*;
+pub use stream::*;
+pub use sync::*;
\ No newline at end of file
@@ -0,0 +1,79 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::{
+    error::AppError,
+    extractors::AuthenticatedUser,
+    handlers::AppState,
+    models::{
+        auto_resolution, build_series, parse_metrics, raw_series_rows, ReadingSeries,
+        SeriesQuery, MAX_RAW_POINTS, MAX_SERIES_BUCKETS,
+    },
+};
+
+/// Vremenska serija merenja za grafike: min/max/avg po intervalu iz agregata,
+/// ili pojedinačna merenja za `resolution=raw`
+pub async fn get_reading_series(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(batch_id): Path<Uuid>,
+    Query(query): Query<SeriesQuery>,
+) -> Result<Json<ReadingSeries>, AppError> {
+    let batch = state.repo.find_batch_by_id(batch_id).await?;
+
+    let metrics = parse_metrics(query.metrics.as_deref()).map_err(AppError::ValidationError)?;
+    let to = query.to.unwrap_or_else(Utc::now);
+    let from = query
+        .from
+        .unwrap_or_else(|| batch.start_date.unwrap_or(batch.created_at));
+    if from >= to {
+        return Err(AppError::ValidationError("'from' must be before 'to'".to_string()));
+    }
+    let resolution = query.resolution.unwrap_or_else(|| auto_resolution(from, to));
+
+    let rows = match resolution.rollup() {
+        Some(rollup) => {
+            let buckets = (to - from).num_seconds() / rollup.duration().num_seconds();
+            if buckets > MAX_SERIES_BUCKETS {
+                return Err(AppError::ValidationError(format!(
+                    "Range has {} intervals, at most {} allowed; use a coarser resolution",
+                    buckets, MAX_SERIES_BUCKETS
+                )));
+            }
+            state
+                .rollup_repo
+                .list_rollups(batch_id, rollup, &metrics, from, to)
+                .await?
+        }
+        None => {
+            let readings = state
+                .repo
+                .list_readings(batch_id, Some(from), Some(to), Some(MAX_RAW_POINTS + 1))
+                .await?;
+            if readings.len() as i64 > MAX_RAW_POINTS {
+                return Err(AppError::ValidationError(format!(
+                    "More than {} readings in range; use an aggregated resolution",
+                    MAX_RAW_POINTS
+                )));
+            }
+            let ids: Vec<Uuid> = readings.iter().map(|r| r.id).collect();
+            let sensor_values = state.repo.list_sensor_values(&ids).await?;
+            raw_series_rows(&readings, &sensor_values)
+        }
+    };
+
+    let rolled_up_at = state.rollup_repo.rollups_refreshed_at().await?;
+
+    Ok(Json(ReadingSeries {
+        batch_id,
+        from,
+        to,
+        resolution,
+        rolled_up_at,
+        series: build_series(&metrics, rows),
+    }))
+}
@@ -0,0 +1,143 @@
+use std::convert::Infallible;
+use std::time::Duration;
+
+use axum::{
+    extract::{
+        ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Response,
+    },
+    Json,
+};
+use chrono::Utc;
+use tokio::{
+    sync::broadcast::{self, error::RecvError},
+    time::Instant,
+};
+use tokio_stream::{
+    wrappers::{errors::BroadcastStreamRecvError, BroadcastStream},
+    Stream, StreamExt,
+};
+
+use crate::{
+    error::AppError,
+    extractors::AuthenticatedUser,
+    handlers::AppState,
+    models::{StreamEvent, StreamQuery, StreamTicketQuery, StreamTicketResponse},
+};
+
+/// Jednokratna karta za otvaranje stream-a; JWT tako ne završava u URL-u i logovima
+pub async fn create_stream_ticket(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Json<StreamTicketResponse> {
+    Json(state.stream.issue_ticket(auth.claims))
+}
+
+/// WebSocket sa novim merenjima i alarmima (?ticket=...&batch_id=... ili &tank_id=...)
+pub async fn stream_ws(
+    State(state): State<AppState>,
+    Query(ticket): Query<StreamTicketQuery>,
+    Query(query): Query<StreamQuery>,
+    ws: WebSocketUpgrade,
+) -> Result<Response, AppError> {
+    let deadline = redeem_ticket(&state, &ticket)?;
+    check_scope(&state, &query).await?;
+
+    let rx = state.stream.subscribe();
+
+    Ok(ws.on_upgrade(move |socket| forward_to_socket(socket, rx, query, deadline)))
+}
+
+/// Isti događaji kao Server-Sent Events; ime događaja je njegov tip
+pub async fn stream_sse(
+    State(state): State<AppState>,
+    Query(ticket): Query<StreamTicketQuery>,
+    Query(query): Query<StreamQuery>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    let deadline = redeem_ticket(&state, &ticket)?;
+    check_scope(&state, &query).await?;
+
+    let events = BroadcastStream::new(state.stream.subscribe()).filter_map(move |event| {
+        let event = match event {
+            Ok(event) if query.matches(&event) => event,
+            Ok(_) => return None,
+            Err(BroadcastStreamRecvError::Lagged(skipped)) => StreamEvent::Lagged { skipped },
+        };
+        Event::default().event(event.name()).json_data(&event).ok().map(Some)
+    });
+    // Kad token istekne, stream se završava; klijent se ponovo prijavljuje novom kartom
+    let expiry = tokio_stream::once(())
+        .then(move |_| tokio::time::sleep_until(deadline))
+        .map(|_| None);
+    let events = events.merge(expiry).map_while(|event| event.map(Ok));
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+/// Troši kartu i vraća trenutak isteka tokena kojim je izdata
+fn redeem_ticket(state: &AppState, query: &StreamTicketQuery) -> Result<Instant, AppError> {
+    let claims = state
+        .stream
+        .redeem_ticket(query.ticket)
+        .ok_or_else(|| AppError::Unauthorized("Invalid or expired stream ticket".to_string()))?;
+
+    let remaining = (claims.exp - Utc::now().timestamp()).max(0) as u64;
+    Ok(Instant::now() + Duration::from_secs(remaining))
+}
+
+async fn check_scope(state: &AppState, query: &StreamQuery) -> Result<(), AppError> {
+    if let Some(batch_id) = query.batch_id {
+        state.repo.find_batch_by_id(batch_id).await?;
+    }
+    if let Some(tank_id) = query.tank_id {
+        state.repo.find_tank_by_id(tank_id).await?;
+    }
+    Ok(())
+}
+
+async fn forward_to_socket(
+    mut socket: WebSocket,
+    mut rx: broadcast::Receiver<StreamEvent>,
+    query: StreamQuery,
+    deadline: Instant,
+) {
+    let expiry = tokio::time::sleep_until(deadline);
+    tokio::pin!(expiry);
+
+    loop {
+        tokio::select! {
+            _ = &mut expiry => {
+                let _ = socket
+                    .send(Message::Close(Some(CloseFrame {
+                        code: close_code::POLICY,
+                        reason: "Token expired".into(),
+                    })))
+                    .await;
+                break;
+            }
+            event = rx.recv() => {
+                let event = match event {
+                    Ok(event) if query.matches(&event) => event,
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(skipped)) => StreamEvent::Lagged { skipped },
+                    Err(RecvError::Closed) => break,
+                };
+                let Ok(text) = serde_json::to_string(&event) else {
+                    continue;
+                };
+                if socket.send(Message::Text(text)).await.is_err() {
+                    break;
+                }
+            }
+            // Klijent ne šalje ništa osim ping/close; ping odgovara axum
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
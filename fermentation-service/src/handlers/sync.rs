@@ -0,0 +1,196 @@
+use std::collections::{hash_map::Entry, HashMap};
+
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use chrono::Utc;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    error::AppError,
+    extractors::AuthenticatedUser,
+    handlers::AppState,
+    models::{
+        accepts_offline_reading, sync_page_cursor, FermentationBatch, ReadingResponse,
+        StreamEvent, SyncChangesQuery, SyncChangesResponse, SyncOutcome, SyncPushRequest,
+        SyncPushResponse, SyncPushResult, SyncReadingPush, SyncReadingRecord, UserRole,
+        SYNC_PAGE_MAX,
+    },
+};
+
+/// Najviše merenja u jednom upload-u
+const SYNC_PUSH_MAX: usize = 1000;
+
+/// Batch upload merenja sa uređaja. Svako merenje dobija svoj ishod;
+/// ponovljen upload istog sadržaja ne pravi duplikate.
+pub async fn push_sync_changes(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(req): Json<SyncPushRequest>,
+) -> Result<Json<SyncPushResponse>, AppError> {
+    if req.readings.len() > SYNC_PUSH_MAX {
+        return Err(AppError::ValidationError(format!(
+            "At most {} readings per sync upload",
+            SYNC_PUSH_MAX
+        )));
+    }
+
+    let user_id = auth.claims.user_id()?;
+    let mut batches: HashMap<Uuid, Option<FermentationBatch>> = HashMap::new();
+    let mut readings = Vec::with_capacity(req.readings.len());
+
+    for push in req.readings {
+        if let Entry::Vacant(entry) = batches.entry(push.batch_id) {
+            let batch = match state.repo.find_batch_by_id(push.batch_id).await {
+                Ok(batch) => Some(batch),
+                Err(AppError::NotFound(_)) => None,
+                Err(e) => return Err(e),
+            };
+            entry.insert(batch);
+        }
+
+        let result = match &batches[&push.batch_id] {
+            None => SyncPushResult::rejected(push.id, "Batch not found"),
+            Some(batch) if auth.claims.role == UserRole::Worker && batch.created_by != user_id => {
+                SyncPushResult::rejected(push.id, "Access denied")
+            }
+            Some(batch) => sync_reading(&state, batch, push).await?,
+        };
+        readings.push(result);
+    }
+
+    for (batch_id, batch) in &batches {
+        if batch.is_some() {
+            state.alerts.check_batch(*batch_id);
+        }
+    }
+
+    Ok(Json(SyncPushResponse { readings }))
+}
+
+/// Feed promena posle kursora `since` (uključujući brisanja)
+pub async fn get_sync_changes(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Query(query): Query<SyncChangesQuery>,
+) -> Result<Json<SyncChangesResponse>, AppError> {
+    let limit = query.limit.unwrap_or(SYNC_PAGE_MAX).clamp(1, SYNC_PAGE_MAX);
+
+    let watermark = state.sync_repo.sync_watermark().await?;
+    let mut readings = state
+        .sync_repo
+        .reading_changes(query.since, watermark, limit + 1, query.batch_id)
+        .await?;
+    let mut deleted = state
+        .sync_repo
+        .sync_tombstones(query.since, watermark, limit + 1, query.batch_id)
+        .await?;
+
+    // Proverava se posle čitanja tombstone-a, pa brisanje između ne može da prođe neopaženo
+    let pruned_through = state.sync_repo.sync_tombstones_pruned_through().await?;
+    if query.since.version > 0 && query.since.version < pruned_through {
+        return Err(AppError::Conflict(
+            "Sync cursor is older than the retained deletions; sync again from the start"
+                .to_string(),
+        ));
+    }
+
+    let (next_since, has_more) = sync_page_cursor(
+        query.since,
+        limit as usize,
+        &[
+            &readings.iter().map(|r| r.cursor()).collect::<Vec<_>>(),
+            &deleted.iter().map(|r| r.cursor()).collect::<Vec<_>>(),
+        ],
+    );
+
+    readings.retain(|r| r.cursor() <= next_since);
+    deleted.retain(|r| r.cursor() <= next_since);
+
+    Ok(Json(SyncChangesResponse {
+        readings,
+        deleted,
+        next_since,
+        has_more,
+    }))
+}
+
+async fn sync_reading(
+    state: &AppState,
+    batch: &FermentationBatch,
+    push: SyncReadingPush,
+) -> Result<SyncPushResult, AppError> {
+    if let Err(e) = push.fields.validate() {
+        return Ok(SyncPushResult::rejected(push.id, e.to_string()));
+    }
+
+    // Merenja se samo dodaju; postojeći id je ili ponovljen upload ili konflikt
+    if let Some(current) = state.sync_repo.find_sync_reading(push.id).await? {
+        return Ok(existing(push, current));
+    }
+
+    let now = Utc::now();
+    let recorded_at = push.fields.recorded_at.unwrap_or(now);
+    if recorded_at > now {
+        return Ok(SyncPushResult::rejected(
+            push.id,
+            "Reading time cannot be in the future",
+        ));
+    }
+    if !accepts_offline_reading(batch, recorded_at) {
+        return Ok(SyncPushResult::rejected(
+            push.id,
+            "Reading was not taken while the batch was active",
+        ));
+    }
+
+    let inserted = state
+        .sync_repo
+        .insert_sync_reading(push.id, push.batch_id, &push.fields, recorded_at)
+        .await?;
+
+    if let Some(record) = inserted.as_ref().filter(|_| state.stream.has_subscribers()) {
+        state.stream.publish(StreamEvent::Reading {
+            batch_id: batch.id,
+            tank_id: batch.tank_id,
+            reading: ReadingResponse::from(record.clone()),
+        });
+    }
+
+    match inserted {
+        Some(record) => Ok(SyncPushResult {
+            id: record.id,
+            outcome: SyncOutcome::Created,
+            version: Some(record.sync_version),
+            message: None,
+            server_record: None,
+        }),
+        // Isti id je upisan u međuvremenu (paralelan upload)
+        None => match state.sync_repo.find_sync_reading(push.id).await? {
+            Some(current) => Ok(existing(push, current)),
+            None => Ok(SyncPushResult::rejected(push.id, "Reading was deleted")),
+        },
+    }
+}
+
+fn existing(push: SyncReadingPush, current: SyncReadingRecord) -> SyncPushResult {
+    if current.matches(push.batch_id, &push.fields) {
+        SyncPushResult {
+            id: push.id,
+            outcome: SyncOutcome::Unchanged,
+            version: Some(current.sync_version),
+            message: None,
+            server_record: None,
+        }
+    } else {
+        SyncPushResult {
+            id: push.id,
+            outcome: SyncOutcome::Conflict,
+            version: Some(current.sync_version),
+            message: Some("Reading with this id already exists with different values".to_string()),
+            server_record: Some(current),
+        }
+    }
+}
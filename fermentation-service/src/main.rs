@@ -1,22 +1,38 @@
+mod alerts;
+mod clients;
 mod config;
+mod control;
 mod db;
 mod error;
 mod extractors;
 mod handlers;
 mod middleware;
 mod models;
+mod mqtt;
 mod routes;
 mod pdf;
+mod rollup;
+mod stream;
 
-use std::net::SocketAddr;
+use std::{net::SocketAddr, sync::Arc, time::Duration};
 
 use tower_http::{cors::{Any, CorsLayer}, trace::TraceLayer};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use crate::{
+    alerts::{AlertEngine, Notifier},
+    clients::HarvestClient,
     config::Settings,
-    db::{create_pool, run_migrations, FermentationRepository},
+    control::{ActuatorDriver, MqttActuator, SimulatedActuator, TemperatureController},
+    db::{
+        create_pool, run_migrations, AdditionRepository, AlertRepository, CellarRepository,
+        ControlRepository, CrushRepository, DeviceRepository, FermentationRepository,
+        MaintenanceRepository, RollupRepository, SyncRepository,
+    },
     handlers::AppState,
+    mqtt::{mqtt_connection, spawn_mqtt_subscriber},
+    rollup::spawn_rollup_worker,
+    stream::StreamHub,
 };
 
 #[tokio::main]
@@ -38,13 +54,82 @@ async fn main() -> anyhow::Result<()> {
     run_migrations(&pool).await?;
     tracing::info!("Migrations completed");
 
-    let repo = FermentationRepository::new(pool);
+    let repo = FermentationRepository::new(pool.clone());
+    let sync_repo = SyncRepository::new(pool.clone());
+    let crush_repo = CrushRepository::new(pool.clone());
+    let cellar_repo = CellarRepository::new(pool.clone());
+    let addition_repo = AdditionRepository::new(pool.clone());
+    let alert_repo = AlertRepository::new(pool.clone());
+    let device_repo = DeviceRepository::new(pool.clone());
+    let rollup_repo = RollupRepository::new(pool.clone());
+    let control_repo = ControlRepository::new(pool.clone());
+    let maintenance_repo = MaintenanceRepository::new(pool);
+    let harvest_client = HarvestClient::new(&settings.harvest_service_url)?;
+
+    let notifier = Notifier::new(
+        alert_repo.clone(),
+        settings.smtp_url.as_deref(),
+        &settings.alert_email_from,
+    )?;
+    let stream = StreamHub::new(1024);
+    let alerts = AlertEngine::new(repo.clone(), alert_repo.clone(), notifier, stream.clone());
+    alerts
+        .clone()
+        .spawn_scheduler(Duration::from_secs(settings.alert_check_interval_secs));
+    tracing::info!("Alert engine started");
+
+    spawn_rollup_worker(
+        rollup_repo.clone(),
+        sync_repo.clone(),
+        Duration::from_secs(settings.rollup_interval_secs),
+        settings.raw_reading_retention_days,
+        settings.sync_tombstone_retention_days,
+    );
+    tracing::info!("Reading rollup worker started");
+
+    // MQTT veza se deli između pretplate na merenja i komandi aktuatorima
+    let mqtt = settings
+        .mqtt
+        .clone()
+        .map(|mqtt| (mqtt_connection(&mqtt), mqtt));
+    let actuator: Arc<dyn ActuatorDriver> = match &mqtt {
+        Some(((client, _), mqtt)) => Arc::new(MqttActuator::new(client.clone(), &mqtt.topic_prefix)),
+        None => {
+            tracing::warn!("MQTT_HOST not set, tank actuators are simulated");
+            Arc::new(SimulatedActuator::new())
+        }
+    };
+    let control = TemperatureController::new(repo.clone(), control_repo.clone(), actuator);
+    control
+        .clone()
+        .spawn_loop(Duration::from_secs(settings.control_interval_secs));
+    tracing::info!("Temperature controller started");
 
     let app_state = AppState {
         repo,
+        sync_repo,
+        crush_repo,
+        cellar_repo,
+        addition_repo,
+        alert_repo,
+        device_repo,
+        rollup_repo,
+        control_repo,
+        maintenance_repo,
+        harvest_client,
+        alerts,
+        stream,
+        control,
         settings: settings.clone(),
     };
 
+    match mqtt {
+        Some(((client, eventloop), mqtt)) => {
+            spawn_mqtt_subscriber(app_state.clone(), mqtt, client, eventloop)
+        }
+        None => tracing::info!("MQTT_HOST not set, MQTT ingestion disabled"),
+    }
+
     let app = routes::create_router(app_state, settings.clone())
         .layer(
             CorsLayer::new()
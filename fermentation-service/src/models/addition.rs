@@ -0,0 +1,253 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+// ============== Enums ==============
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "addition_category", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum AdditionCategory {
+    So2,
+    Yeast,
+    Nutrient,
+    Enzyme,
+    FiningAgent,
+    Acid,
+    Tannin,
+    Other,
+}
+
+/// Jedinica u kojoj se proizvod dozira (doza je u toj jedinici po hL)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "addition_unit", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum AdditionUnit {
+    G,
+    Kg,
+    Ml,
+    L,
+}
+
+impl AdditionUnit {
+    /// Koliko grama (ml) ima jedna jedinica
+    pub fn base_factor(&self) -> f64 {
+        match self {
+            AdditionUnit::G | AdditionUnit::Ml => 1.0,
+            AdditionUnit::Kg | AdditionUnit::L => 1000.0,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AdditionUnit::G => "g",
+            AdditionUnit::Kg => "kg",
+            AdditionUnit::Ml => "ml",
+            AdditionUnit::L => "l",
+        }
+    }
+}
+
+// ============== Katalog proizvoda ==============
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct AdditionProduct {
+    pub id: Uuid,
+    pub name: String,
+    pub category: AdditionCategory,
+    pub unit: AdditionUnit,
+    pub so2_factor: Option<f64>, // g SO2 po g/ml proizvoda
+    pub max_dose_per_hl: Option<f64>,
+    pub supplier: Option<String>,
+    pub notes: Option<String>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateAdditionProductRequest {
+    #[validate(length(min = 2, message = "Name must be at least 2 characters"))]
+    pub name: String,
+
+    pub category: AdditionCategory,
+    pub unit: AdditionUnit,
+
+    #[validate(range(min = 0.001, max = 1.0, message = "SO2 factor must be between 0 and 1"))]
+    pub so2_factor: Option<f64>,
+
+    #[validate(range(min = 0.001, message = "Maximum dose must be positive"))]
+    pub max_dose_per_hl: Option<f64>,
+
+    pub supplier: Option<String>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateAdditionProductRequest {
+    #[validate(length(min = 2))]
+    pub name: Option<String>,
+
+    #[validate(range(min = 0.001, max = 1.0))]
+    pub so2_factor: Option<f64>,
+
+    #[validate(range(min = 0.001))]
+    pub max_dose_per_hl: Option<f64>,
+
+    pub supplier: Option<String>,
+    pub notes: Option<String>,
+    pub active: Option<bool>,
+}
+
+// ============== Dodaci po batch-u ==============
+
+/// Upisan dodatak, sa podacima o proizvodu za registar
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct BatchAddition {
+    pub id: Uuid,
+    pub batch_id: Uuid,
+    pub product_id: Uuid,
+    pub product_name: String,
+    pub category: AdditionCategory,
+    pub unit: AdditionUnit,
+    pub dose_per_hl: f64,
+    pub quantity: f64,
+    pub batch_volume_liters: f64,
+    pub so2_added_mg_l: Option<f64>,
+    pub lot_number: Option<String>,
+    pub added_by: Uuid,
+    pub operator_email: String,
+    pub added_at: DateTime<Utc>,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Zadaje se doza po hL ili ukupna količina; drugo se računa iz zapremine batch-a
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateAdditionRequest {
+    pub product_id: Uuid,
+
+    #[validate(range(min = 0.0001, message = "Dose must be positive"))]
+    pub dose_per_hl: Option<f64>,
+
+    #[validate(range(min = 0.0001, message = "Quantity must be positive"))]
+    pub quantity: Option<f64>,
+
+    #[validate(length(max = 100))]
+    pub lot_number: Option<String>,
+
+    pub added_at: Option<DateTime<Utc>>,
+    pub notes: Option<String>,
+}
+
+/// (doza po hL, ukupna količina) za zapreminu batch-a
+pub fn resolve_dose(
+    dose_per_hl: Option<f64>,
+    quantity: Option<f64>,
+    volume_liters: f64,
+) -> Result<(f64, f64), String> {
+    if volume_liters <= 0.0 {
+        return Err("Batch has no volume".to_string());
+    }
+    let hectoliters = volume_liters / 100.0;
+
+    match (dose_per_hl, quantity) {
+        (Some(dose), None) => Ok((dose, dose * hectoliters)),
+        (None, Some(quantity)) => Ok((quantity / hectoliters, quantity)),
+        _ => Err("Specify either dose_per_hl or quantity".to_string()),
+    }
+}
+
+/// Dodatak se upisuje unazad najranije od početka batch-a, a ne unapred
+pub fn check_addition_time(
+    added_at: DateTime<Utc>,
+    batch_started_at: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> Result<(), String> {
+    if added_at > now {
+        return Err("Addition time cannot be in the future".to_string());
+    }
+    if batch_started_at.is_some_and(|started_at| added_at < started_at) {
+        return Err("Addition time cannot be before the batch started".to_string());
+    }
+
+    Ok(())
+}
+
+/// Koliko mg/L SO2 unosi dodatak
+pub fn so2_mg_per_liter(quantity: f64, unit: AdditionUnit, so2_factor: f64, volume_liters: f64) -> f64 {
+    if volume_liters <= 0.0 {
+        return 0.0;
+    }
+    quantity * unit.base_factor() * so2_factor * 1000.0 / volume_liters
+}
+
+/// Očekivani ukupni SO2 posle novog dodatka: poslednje merenje plus sve što je
+/// dodato posle njega; bez merenja zbir svih dodataka
+pub fn projected_total_so2(
+    latest_measurement: Option<(DateTime<Utc>, f64)>,
+    so2_additions: &[(DateTime<Utc>, f64)],
+    new_mg_l: f64,
+) -> f64 {
+    let base = match latest_measurement {
+        Some((measured_at, total_so2)) => {
+            total_so2
+                + so2_additions
+                    .iter()
+                    .filter(|(at, _)| *at > measured_at)
+                    .map(|(_, mg_l)| mg_l)
+                    .sum::<f64>()
+        }
+        None => so2_additions.iter().map(|(_, mg_l)| mg_l).sum(),
+    };
+
+    base + new_mg_l
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_check_addition_time() {
+        let now = Utc::now();
+        let started = now - Duration::days(10);
+
+        assert!(check_addition_time(now - Duration::days(1), Some(started), now).is_ok());
+        assert!(check_addition_time(now + Duration::minutes(5), Some(started), now).is_err());
+        assert!(check_addition_time(started - Duration::days(1), Some(started), now).is_err());
+        assert!(check_addition_time(started - Duration::days(1), None, now).is_ok());
+    }
+
+    #[test]
+    fn test_resolve_dose() {
+        // 20 g/hL u 2500 L = 500 g
+        assert_eq!(resolve_dose(Some(20.0), None, 2500.0), Ok((20.0, 500.0)));
+        assert_eq!(resolve_dose(None, Some(500.0), 2500.0), Ok((20.0, 500.0)));
+        assert!(resolve_dose(Some(20.0), Some(500.0), 2500.0).is_err());
+        assert!(resolve_dose(None, None, 2500.0).is_err());
+    }
+
+    #[test]
+    fn test_so2_from_metabisulfite() {
+        // 100 g K-metabisulfita (57% SO2) u 1000 L ≈ 57 mg/L
+        let mg_l = so2_mg_per_liter(100.0, AdditionUnit::G, 0.57, 1000.0);
+        assert!((mg_l - 57.0).abs() < 1e-9);
+        assert!((so2_mg_per_liter(0.1, AdditionUnit::Kg, 0.57, 1000.0) - 57.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_projected_total_so2() {
+        let now = Utc::now();
+        let additions = vec![(now - Duration::days(10), 30.0), (now - Duration::days(2), 20.0)];
+
+        // Merenje posle prvog dodatka ga već sadrži
+        let projected = projected_total_so2(Some((now - Duration::days(5), 60.0)), &additions, 25.0);
+        assert_eq!(projected, 105.0);
+
+        assert_eq!(projected_total_so2(None, &additions, 25.0), 75.0);
+    }
+}
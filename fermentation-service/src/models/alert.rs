@@ -0,0 +1,430 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::{Validate, ValidateEmail};
+
+// ============== Enums ==============
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "alert_condition", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum AlertCondition {
+    /// °C
+    TemperatureAbove,
+    /// °C
+    TemperatureBelow,
+    /// g/L
+    VolatileAcidityAbove,
+    /// mg/L
+    FreeSo2Below,
+    /// Prag je broj sati bez ijednog merenja
+    MissingReadings,
+}
+
+impl AlertCondition {
+    pub fn unit(&self) -> &'static str {
+        match self {
+            AlertCondition::TemperatureAbove | AlertCondition::TemperatureBelow => "°C",
+            AlertCondition::VolatileAcidityAbove => "g/L",
+            AlertCondition::FreeSo2Below => "mg/L",
+            AlertCondition::MissingReadings => "h",
+        }
+    }
+
+    /// Vrednost koju uslov prati iz merenja
+    pub fn value(&self, point: &AlertPoint) -> Option<f64> {
+        match self {
+            AlertCondition::TemperatureAbove | AlertCondition::TemperatureBelow => point.temperature,
+            AlertCondition::VolatileAcidityAbove => point.volatile_acidity,
+            AlertCondition::FreeSo2Below => point.free_so2,
+            AlertCondition::MissingReadings => None,
+        }
+    }
+
+    pub fn is_breached(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            AlertCondition::TemperatureAbove | AlertCondition::VolatileAcidityAbove => value > threshold,
+            AlertCondition::TemperatureBelow | AlertCondition::FreeSo2Below => value < threshold,
+            AlertCondition::MissingReadings => value >= threshold,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, PartialOrd, Ord)]
+#[sqlx(type_name = "alert_severity", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum AlertSeverity {
+    Info,
+    Warning,
+    Critical,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "alert_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum AlertStatus {
+    Open,
+    Acknowledged,
+    Resolved,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "notification_channel_kind", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationChannelKind {
+    Webhook,
+    Email,
+    Log,
+}
+
+// ============== Pravila ==============
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct AlertRule {
+    pub id: Uuid,
+    pub name: String,
+    pub batch_id: Option<Uuid>,
+    pub tank_id: Option<Uuid>,
+    pub condition: AlertCondition,
+    pub threshold: f64,
+    pub duration_minutes: i32,
+    pub severity: AlertSeverity,
+    pub enabled: bool,
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Pravilo za jedan batch, za tank ili (bez oba) za sve aktivne batch-eve
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateAlertRuleRequest {
+    #[validate(length(min = 2, message = "Name must be at least 2 characters"))]
+    pub name: String,
+
+    pub batch_id: Option<Uuid>,
+    pub tank_id: Option<Uuid>,
+
+    pub condition: AlertCondition,
+    pub threshold: f64,
+
+    #[validate(range(min = 0, max = 1440, message = "Duration must be 0-1440 minutes"))]
+    #[serde(default)]
+    pub duration_minutes: i32,
+
+    pub severity: Option<AlertSeverity>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateAlertRuleRequest {
+    #[validate(length(min = 2))]
+    pub name: Option<String>,
+
+    pub threshold: Option<f64>,
+
+    #[validate(range(min = 0, max = 1440))]
+    pub duration_minutes: Option<i32>,
+
+    pub severity: Option<AlertSeverity>,
+    pub enabled: Option<bool>,
+}
+
+// ============== Alarmi ==============
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Alert {
+    pub id: Uuid,
+    pub rule_id: Uuid,
+    pub rule_name: String,
+    pub condition: AlertCondition,
+    pub batch_id: Uuid,
+    pub batch_name: String,
+    pub tank_id: Uuid,
+    pub tank_name: String,
+    pub status: AlertStatus,
+    pub severity: AlertSeverity,
+    pub message: String,
+    pub value: Option<f64>,
+    pub triggered_at: DateTime<Utc>,
+    pub acknowledged_by: Option<Uuid>,
+    pub acknowledged_at: Option<DateTime<Utc>>,
+    pub resolved_by: Option<Uuid>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Merenje svedeno na vrednosti koje pravila prate
+#[derive(Debug, Clone, FromRow)]
+pub struct AlertPoint {
+    pub recorded_at: DateTime<Utc>,
+    pub temperature: Option<f64>,
+    pub volatile_acidity: Option<f64>,
+    pub free_so2: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RuleEvaluation {
+    Triggered(f64),
+    Clear,
+    /// Nema podataka ili uslov još ne traje dovoljno dugo - stanje alarma se ne menja
+    Unchanged,
+}
+
+/// Uslov praga mora neprekidno važiti `duration_minutes`; tačke su sortirane po vremenu
+pub fn evaluate_threshold(rule: &AlertRule, points: &[AlertPoint]) -> RuleEvaluation {
+    let values: Vec<(DateTime<Utc>, f64)> = points
+        .iter()
+        .filter_map(|p| rule.condition.value(p).map(|v| (p.recorded_at, v)))
+        .collect();
+
+    let Some(&(latest_at, latest)) = values.last() else {
+        return RuleEvaluation::Unchanged;
+    };
+    if !rule.condition.is_breached(latest, rule.threshold) {
+        return RuleEvaluation::Clear;
+    }
+
+    let streak_start = values
+        .iter()
+        .rev()
+        .take_while(|(_, v)| rule.condition.is_breached(*v, rule.threshold))
+        .last()
+        .map(|(at, _)| *at)
+        .unwrap_or(latest_at);
+
+    if latest_at - streak_start >= Duration::minutes(rule.duration_minutes as i64) {
+        RuleEvaluation::Triggered(latest)
+    } else {
+        RuleEvaluation::Unchanged
+    }
+}
+
+/// Sati bez merenja od poslednjeg merenja (ili od početka batch-a)
+/// Alarm se otvara kad merenja nema `threshold` sati plus `duration_minutes`, a
+/// razrešava tek kad razmak padne ispod praga
+pub fn evaluate_missing(
+    rule: &AlertRule,
+    last_reading_at: Option<DateTime<Utc>>,
+    batch_started_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> RuleEvaluation {
+    let since = last_reading_at.unwrap_or(batch_started_at);
+    let hours = (now - since).num_minutes() as f64 / 60.0;
+    let required = rule.threshold + rule.duration_minutes as f64 / 60.0;
+
+    if !rule.condition.is_breached(hours, rule.threshold) {
+        RuleEvaluation::Clear
+    } else if hours >= required {
+        RuleEvaluation::Triggered((hours * 10.0).round() / 10.0)
+    } else {
+        RuleEvaluation::Unchanged
+    }
+}
+
+pub fn alert_message(rule: &AlertRule, batch_name: &str, tank_name: &str, value: f64) -> String {
+    let unit = rule.condition.unit();
+    match rule.condition {
+        AlertCondition::TemperatureAbove => format!(
+            "{} ({}): temperature {:.1}{} above {:.1}{}",
+            batch_name, tank_name, value, unit, rule.threshold, unit
+        ),
+        AlertCondition::TemperatureBelow => format!(
+            "{} ({}): temperature {:.1}{} below {:.1}{}",
+            batch_name, tank_name, value, unit, rule.threshold, unit
+        ),
+        AlertCondition::VolatileAcidityAbove => format!(
+            "{} ({}): volatile acidity {:.2} {} above {:.2} {}",
+            batch_name, tank_name, value, unit, rule.threshold, unit
+        ),
+        AlertCondition::FreeSo2Below => format!(
+            "{} ({}): free SO2 {:.0} {} below {:.0} {}",
+            batch_name, tank_name, value, unit, rule.threshold, unit
+        ),
+        AlertCondition::MissingReadings => format!(
+            "{} ({}): no readings for {:.1} h",
+            batch_name, tank_name, value
+        ),
+    }
+}
+
+// ============== Kanali za obaveštenja ==============
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct NotificationChannel {
+    pub id: Uuid,
+    pub name: String,
+    pub kind: NotificationChannelKind,
+    pub target: Option<String>, // URL webhook-a ili email adresa
+    pub min_severity: AlertSeverity,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateNotificationChannelRequest {
+    #[validate(length(min = 2, message = "Name must be at least 2 characters"))]
+    pub name: String,
+
+    pub kind: NotificationChannelKind,
+
+    #[validate(length(max = 500))]
+    pub target: Option<String>,
+
+    pub min_severity: Option<AlertSeverity>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateNotificationChannelRequest {
+    #[validate(length(min = 2))]
+    pub name: Option<String>,
+
+    #[validate(length(max = 500))]
+    pub target: Option<String>,
+
+    pub min_severity: Option<AlertSeverity>,
+    pub enabled: Option<bool>,
+}
+
+/// Webhook i email moraju imati ispravan cilj
+pub fn validate_channel_target(kind: NotificationChannelKind, target: Option<&str>) -> Result<(), String> {
+    match (kind, target) {
+        (NotificationChannelKind::Log, _) => Ok(()),
+        (NotificationChannelKind::Webhook, Some(url))
+            if url.starts_with("http://") || url.starts_with("https://") =>
+        {
+            Ok(())
+        }
+        (NotificationChannelKind::Webhook, _) => Err("Webhook target must be an http(s) URL".to_string()),
+        (NotificationChannelKind::Email, Some(address)) if address.validate_email() => Ok(()),
+        (NotificationChannelKind::Email, _) => Err("Email target must be a valid address".to_string()),
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertEvent {
+    Triggered,
+    Acknowledged,
+    Resolved,
+    Test,
+}
+
+/// Telo webhook poziva
+#[derive(Debug, Serialize)]
+pub struct AlertNotification<'a> {
+    pub event: AlertEvent,
+    pub alert: &'a Alert,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(condition: AlertCondition, threshold: f64, duration_minutes: i32) -> AlertRule {
+        AlertRule {
+            id: Uuid::new_v4(),
+            name: "Overheat".to_string(),
+            batch_id: None,
+            tank_id: None,
+            condition,
+            threshold,
+            duration_minutes,
+            severity: AlertSeverity::Critical,
+            enabled: true,
+            created_by: Uuid::new_v4(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn temperatures(start: DateTime<Utc>, values: &[f64]) -> Vec<AlertPoint> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, t)| AlertPoint {
+                recorded_at: start + Duration::minutes(5 * i as i64),
+                temperature: Some(*t),
+                volatile_acidity: None,
+                free_so2: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_threshold_must_hold_for_duration() {
+        let start = Utc::now() - Duration::hours(1);
+        let overheat = rule(AlertCondition::TemperatureAbove, 30.0, 10);
+
+        // 31°C tek 5 minuta - još ne
+        let points = temperatures(start, &[28.0, 29.0, 31.0, 31.5]);
+        assert_eq!(evaluate_threshold(&overheat, &points), RuleEvaluation::Unchanged);
+
+        // 10 minuta iznad praga
+        let points = temperatures(start, &[28.0, 31.0, 31.5, 32.0]);
+        assert_eq!(evaluate_threshold(&overheat, &points), RuleEvaluation::Triggered(32.0));
+
+        // Poslednje merenje ispod praga razrešava alarm
+        let points = temperatures(start, &[31.0, 31.5, 32.0, 29.0]);
+        assert_eq!(evaluate_threshold(&overheat, &points), RuleEvaluation::Clear);
+    }
+
+    #[test]
+    fn test_threshold_without_data_keeps_state() {
+        let low_so2 = rule(AlertCondition::FreeSo2Below, 20.0, 0);
+        let points = temperatures(Utc::now(), &[18.0, 19.0]);
+
+        assert_eq!(evaluate_threshold(&low_so2, &points), RuleEvaluation::Unchanged);
+    }
+
+    #[test]
+    fn test_missing_readings() {
+        let now = Utc::now();
+        let missing = rule(AlertCondition::MissingReadings, 6.0, 0);
+
+        assert_eq!(
+            evaluate_missing(&missing, Some(now - Duration::hours(8)), now - Duration::days(3), now),
+            RuleEvaluation::Triggered(8.0)
+        );
+        assert_eq!(
+            evaluate_missing(&missing, Some(now - Duration::hours(2)), now - Duration::days(3), now),
+            RuleEvaluation::Clear
+        );
+        // Bez ijednog merenja računa se od početka batch-a
+        assert_eq!(
+            evaluate_missing(&missing, None, now - Duration::hours(7), now),
+            RuleEvaluation::Triggered(7.0)
+        );
+    }
+
+    #[test]
+    fn test_missing_readings_duration() {
+        let now = Utc::now();
+        let missing = rule(AlertCondition::MissingReadings, 6.0, 90);
+        let started = now - Duration::days(3);
+
+        // Prag je pređen, ali ne još 90 minuta
+        assert_eq!(
+            evaluate_missing(&missing, Some(now - Duration::hours(7)), started, now),
+            RuleEvaluation::Unchanged
+        );
+        assert_eq!(
+            evaluate_missing(&missing, Some(now - Duration::minutes(450)), started, now),
+            RuleEvaluation::Triggered(7.5)
+        );
+        assert_eq!(
+            evaluate_missing(&missing, Some(now - Duration::hours(5)), started, now),
+            RuleEvaluation::Clear
+        );
+    }
+
+    #[test]
+    fn test_channel_target_validation() {
+        assert!(validate_channel_target(NotificationChannelKind::Log, None).is_ok());
+        assert!(validate_channel_target(NotificationChannelKind::Webhook, Some("https://hooks.example.com/x")).is_ok());
+        assert!(validate_channel_target(NotificationChannelKind::Webhook, Some("ftp://x")).is_err());
+        assert!(validate_channel_target(NotificationChannelKind::Email, Some("night@winery.rs")).is_ok());
+        assert!(validate_channel_target(NotificationChannelKind::Email, None).is_err());
+    }
+}
@@ -0,0 +1,334 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::models::{BatchResponse, FermentationBatch};
+
+/// Na ovoj vrednosti i ispod vino se smatra suvim. Alkohol spušta očitavanje, pa
+/// kraj vrenja izgleda kao blag pad oko 1 °Brix (≈ 1.004 g/mL) do ispod nule;
+/// da je prag 0, takva vina bi se prijavljivala kao zastoj.
+pub const DRY_BRIX: f64 = 1.0;
+/// Prozor za računanje brzine fermentacije
+pub const RATE_WINDOW_HOURS: i64 = 48;
+/// Najkraći raspon merenja iz kog se računa brzina
+pub const MIN_RATE_SPAN_HOURS: i64 = 6;
+/// Sporija fermentacija dok ima još dosta šećera je usporena
+pub const SLUGGISH_RATE_BRIX_PER_DAY: f64 = 1.0;
+pub const SLUGGISH_MIN_BRIX: f64 = 5.0;
+/// Praktično bez pada šećera - zastoj
+pub const STUCK_RATE_BRIX_PER_DAY: f64 = 0.25;
+/// Dozvoljeno odstupanje od ciljne temperature
+pub const TEMPERATURE_TOLERANCE: f64 = 2.0;
+pub const TEMPERATURE_WINDOW_DAYS: i64 = 7;
+/// % vol. alkohola po °Brix prevrelog šećera
+pub const ALCOHOL_PER_BRIX: f64 = 0.59;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum FermentationProgress {
+    InsufficientData,
+    Dry,
+    Normal,
+    Sluggish,
+    Stuck,
+}
+
+/// Tačka krive fermentacije (samo kolone potrebne za analizu)
+#[derive(Debug, Clone, FromRow)]
+pub struct CurvePoint {
+    pub batch_id: Uuid,
+    pub recorded_at: DateTime<Utc>,
+    pub brix: Option<f64>,
+    pub density: Option<f64>, // g/mL
+    pub temperature: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FermentationAnalysis {
+    pub progress: FermentationProgress,
+    pub current_brix: Option<f64>,
+    pub brix_recorded_at: Option<DateTime<Utc>>,
+    /// Pad šećera u °Brix/dan (pozitivno dok fermentacija teče)
+    pub rate_brix_per_day: Option<f64>,
+    pub estimated_completion: Option<DateTime<Utc>>,
+    pub potential_alcohol: Option<f64>,
+    pub estimated_alcohol: Option<f64>,
+    pub latest_temperature: Option<f64>,
+    /// Merenja van ciljne temperature ± tolerancija u poslednjih 7 dana
+    pub temperature_excursions: usize,
+    pub max_temperature_deviation: Option<f64>,
+    /// Poslednje merenje temperature je van opsega
+    pub temperature_alert: bool,
+}
+
+impl FermentationAnalysis {
+    pub fn needs_attention(&self) -> bool {
+        self.temperature_alert
+            || matches!(self.progress, FermentationProgress::Sluggish | FermentationProgress::Stuck)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct FermentationDashboard {
+    pub generated_at: DateTime<Utc>,
+    pub active_batches: usize,
+    pub sluggish: usize,
+    pub stuck: usize,
+    pub temperature_alerts: usize,
+    /// Aktivni batch-evi, prvo oni kojima treba pažnja
+    pub batches: Vec<BatchResponse>,
+}
+
+/// °Brix iz specifične težine (polinom ASBC)
+pub fn brix_from_density(sg: f64) -> f64 {
+    ((182.4601 * sg - 775.6821) * sg + 1262.7794) * sg - 669.5622
+}
+
+fn sugar(point: &CurvePoint) -> Option<f64> {
+    point.brix.or_else(|| point.density.map(brix_from_density))
+}
+
+/// Nagib linearne regresije šećera kroz vreme, u °Brix/dan
+fn brix_slope(points: &[(DateTime<Utc>, f64)]) -> Option<f64> {
+    let (first, last) = (points.first()?.0, points.last()?.0);
+    if last - first < Duration::hours(MIN_RATE_SPAN_HOURS) {
+        return None;
+    }
+
+    let xs: Vec<f64> = points
+        .iter()
+        .map(|(at, _)| (*at - first).num_seconds() as f64 / 86_400.0)
+        .collect();
+    let n = points.len() as f64;
+    let mean_x = xs.iter().sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, brix)| brix).sum::<f64>() / n;
+
+    let (mut cov, mut var) = (0.0, 0.0);
+    for (x, (_, y)) in xs.iter().zip(points) {
+        cov += (x - mean_x) * (y - mean_y);
+        var += (x - mean_x) * (x - mean_x);
+    }
+
+    Some(cov / var)
+}
+
+/// Analiza krive fermentacije batch-a; tačke moraju biti sortirane po vremenu
+pub fn analyze_fermentation(
+    batch: &FermentationBatch,
+    points: &[CurvePoint],
+    now: DateTime<Utc>,
+) -> FermentationAnalysis {
+    let sugar_points: Vec<(DateTime<Utc>, f64)> = points
+        .iter()
+        .filter_map(|p| sugar(p).map(|brix| (p.recorded_at, brix)))
+        .collect();
+    let latest = sugar_points.last().copied();
+
+    // Brzina iz poslednjih 48h, a ako tu nema dovoljno merenja - iz poslednja dva
+    let rate = latest.and_then(|(last_at, _)| {
+        let window_start = last_at - Duration::hours(RATE_WINDOW_HOURS);
+        let window: Vec<_> = sugar_points
+            .iter()
+            .copied()
+            .filter(|(at, _)| *at >= window_start)
+            .collect();
+        let window = if window.len() >= 2 {
+            window
+        } else {
+            sugar_points[sugar_points.len().saturating_sub(2)..].to_vec()
+        };
+        brix_slope(&window).map(|slope| -slope)
+    });
+
+    let progress = match (latest, rate) {
+        (Some((_, brix)), _) if brix <= DRY_BRIX => FermentationProgress::Dry,
+        (Some(_), Some(rate)) if rate < STUCK_RATE_BRIX_PER_DAY => FermentationProgress::Stuck,
+        (Some((_, brix)), Some(rate))
+            if rate < SLUGGISH_RATE_BRIX_PER_DAY && brix > SLUGGISH_MIN_BRIX =>
+        {
+            FermentationProgress::Sluggish
+        }
+        (Some(_), Some(_)) => FermentationProgress::Normal,
+        _ => FermentationProgress::InsufficientData,
+    };
+
+    let estimated_completion = match (latest, rate, progress) {
+        (Some((last_at, brix)), Some(rate), FermentationProgress::Normal | FermentationProgress::Sluggish) => {
+            let days = (brix - DRY_BRIX) / rate;
+            Some(last_at + Duration::seconds((days * 86_400.0).round() as i64))
+        }
+        _ => None,
+    };
+
+    let initial_brix = batch
+        .initial_brix
+        .or_else(|| sugar_points.first().map(|(_, brix)| *brix));
+    let potential_alcohol = initial_brix.map(|brix| round2(brix * ALCOHOL_PER_BRIX));
+    let estimated_alcohol = match (initial_brix, latest) {
+        (Some(initial), Some((_, current))) => {
+            Some(round2(((initial - current) * ALCOHOL_PER_BRIX).max(0.0)))
+        }
+        _ => None,
+    };
+
+    // Temperatura u odnosu na cilj
+    let temperature_since = now - Duration::days(TEMPERATURE_WINDOW_DAYS);
+    let temperatures: Vec<f64> = points
+        .iter()
+        .filter(|p| p.recorded_at >= temperature_since)
+        .filter_map(|p| p.temperature)
+        .collect();
+    let latest_temperature = temperatures.last().copied();
+
+    let deviations: Vec<f64> = match batch.target_temperature {
+        Some(target) => temperatures
+            .iter()
+            .map(|t| (t - target).abs())
+            .filter(|d| *d > TEMPERATURE_TOLERANCE)
+            .collect(),
+        None => vec![],
+    };
+    let temperature_alert = match (batch.target_temperature, latest_temperature) {
+        (Some(target), Some(t)) => (t - target).abs() > TEMPERATURE_TOLERANCE,
+        _ => false,
+    };
+
+    FermentationAnalysis {
+        progress,
+        current_brix: latest.map(|(_, brix)| round2(brix)),
+        brix_recorded_at: latest.map(|(at, _)| at),
+        rate_brix_per_day: rate.map(round2),
+        estimated_completion,
+        potential_alcohol,
+        estimated_alcohol,
+        latest_temperature,
+        temperature_excursions: deviations.len(),
+        max_temperature_deviation: deviations.iter().copied().reduce(f64::max).map(round2),
+        temperature_alert,
+    }
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::FermentationStatus;
+
+    fn batch(target_temperature: Option<f64>) -> FermentationBatch {
+        FermentationBatch {
+            id: Uuid::new_v4(),
+            tank_id: Uuid::new_v4(),
+            harvest_id: None,
+            name: "Tank 2 Vranac".to_string(),
+            grape_variety: "Vranac".to_string(),
+            wine_type: None,
+            volume_liters: 2000.0,
+            status: FermentationStatus::Active,
+            target_temperature,
+            yeast_strain: None,
+            initial_brix: Some(24.0),
+            initial_ph: None,
+            start_date: None,
+            end_date: None,
+            expected_end_date: None,
+            notes: None,
+            created_by: Uuid::new_v4(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn point(at: DateTime<Utc>, brix: Option<f64>, temperature: Option<f64>) -> CurvePoint {
+        CurvePoint {
+            batch_id: Uuid::nil(),
+            recorded_at: at,
+            brix,
+            density: None,
+            temperature,
+        }
+    }
+
+    #[test]
+    fn test_brix_from_density() {
+        assert!((brix_from_density(1.000) - 0.0).abs() < 0.05);
+        assert!((brix_from_density(1.100) - 23.8).abs() < 0.2);
+    }
+
+    #[test]
+    fn test_normal_fermentation_rate_and_completion() {
+        let now = Utc::now();
+        let points = vec![
+            point(now - Duration::days(2), Some(16.0), Some(24.0)),
+            point(now - Duration::days(1), Some(12.0), Some(25.0)),
+            point(now, Some(8.0), Some(25.5)),
+        ];
+
+        let analysis = analyze_fermentation(&batch(Some(25.0)), &points, now);
+
+        assert_eq!(analysis.progress, FermentationProgress::Normal);
+        assert_eq!(analysis.rate_brix_per_day, Some(4.0));
+        // (8 - 1) °Brix pri 4 °Brix/dan
+        assert_eq!(analysis.estimated_completion, Some(now + Duration::hours(42)));
+        assert_eq!(analysis.potential_alcohol, Some(14.16));
+        assert_eq!(analysis.estimated_alcohol, Some(9.44));
+        assert!(!analysis.temperature_alert);
+    }
+
+    #[test]
+    fn test_sluggish_and_stuck_detection() {
+        let now = Utc::now();
+        let sluggish = vec![
+            point(now - Duration::days(2), Some(11.0), None),
+            point(now, Some(10.0), None),
+        ];
+        let stuck = vec![
+            point(now - Duration::days(2), Some(6.2), None),
+            point(now, Some(6.0), None),
+        ];
+
+        let b = batch(None);
+        assert_eq!(analyze_fermentation(&b, &sluggish, now).progress, FermentationProgress::Sluggish);
+        let stuck = analyze_fermentation(&b, &stuck, now);
+        assert_eq!(stuck.progress, FermentationProgress::Stuck);
+        assert!(stuck.estimated_completion.is_none());
+        assert!(stuck.needs_attention());
+    }
+
+    #[test]
+    fn test_finished_fermentation_is_dry_not_stuck() {
+        let now = Utc::now();
+        let points = vec![
+            point(now - Duration::days(2), Some(0.9), None),
+            point(now, Some(0.8), None),
+        ];
+
+        let analysis = analyze_fermentation(&batch(None), &points, now);
+
+        assert_eq!(analysis.progress, FermentationProgress::Dry);
+        assert!(analysis.estimated_completion.is_none());
+        assert!(!analysis.needs_attention());
+    }
+
+    #[test]
+    fn test_temperature_excursions() {
+        let now = Utc::now();
+        let points = vec![
+            // Van prozora od 7 dana - ne računa se
+            point(now - Duration::days(10), None, Some(35.0)),
+            point(now - Duration::days(2), None, Some(29.0)),
+            point(now - Duration::days(1), None, Some(25.0)),
+            point(now, None, Some(20.5)),
+        ];
+
+        let analysis = analyze_fermentation(&batch(Some(25.0)), &points, now);
+
+        assert_eq!(analysis.progress, FermentationProgress::InsufficientData);
+        assert_eq!(analysis.temperature_excursions, 2);
+        assert_eq!(analysis.max_temperature_deviation, Some(4.5));
+        assert!(analysis.temperature_alert);
+    }
+}
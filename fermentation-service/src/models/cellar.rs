@@ -0,0 +1,290 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::{BatchResponse, WineType};
+
+// ============== Enums ==============
+
+/// Vrsta podrumske operacije
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "cellar_operation_kind", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum CellarOperationKind {
+    /// Pretakanje sa taloga
+    Racking,
+    /// Premeštanje u drugi sud (npr. u bačve)
+    Transfer,
+    /// Kupaža više batch-eva u novi lot
+    Blend,
+}
+
+// ============== Operacije ==============
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct CellarOperation {
+    pub id: Uuid,
+    pub kind: CellarOperationKind,
+    pub performed_at: DateTime<Utc>,
+    pub loss_liters: f64,
+    pub notes: Option<String>,
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Tok vina u operaciji, sa nazivima batch-eva i tankova
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct CellarMovement {
+    pub id: Uuid,
+    pub operation_id: Uuid,
+    pub source_batch_id: Option<Uuid>,
+    pub source_batch_name: Option<String>,
+    pub from_tank_id: Uuid,
+    pub from_tank_name: String,
+    pub target_batch_id: Option<Uuid>,
+    pub target_batch_name: Option<String>,
+    pub to_tank_id: Uuid,
+    pub to_tank_name: String,
+    pub volume_liters: f64,
+}
+
+/// Boravak batch-a u jednom sudu
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct VesselStay {
+    pub id: Uuid,
+    pub batch_id: Uuid,
+    pub tank_id: Uuid,
+    pub tank_name: String,
+    pub operation_id: Option<Uuid>,
+    pub volume_in_liters: f64,
+    pub volume_out_liters: Option<f64>,
+    pub entered_at: DateTime<Utc>,
+    pub left_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct TransferDestination {
+    pub tank_id: Uuid,
+
+    #[validate(range(min = 1.0, message = "Volume must be at least 1 liter"))]
+    pub volume_liters: f64,
+}
+
+/// Pretakanje ili premeštanje celog batch-a. Prvi odredišni tank nastavlja batch,
+/// ostali dobijaju nove lotove; razlika do zapremine batch-a je gubitak.
+#[derive(Debug, Deserialize, Validate)]
+pub struct TransferRequest {
+    pub batch_id: Uuid,
+
+    /// racking (podrazumevano) ili transfer
+    pub kind: Option<CellarOperationKind>,
+
+    #[validate(length(min = 1, message = "At least one destination tank is required"), nested)]
+    pub destinations: Vec<TransferDestination>,
+
+    pub performed_at: Option<DateTime<Utc>>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct BlendSourceRequest {
+    pub batch_id: Uuid,
+
+    #[validate(range(min = 1.0, message = "Volume must be at least 1 liter"))]
+    pub volume_liters: f64,
+}
+
+/// Kupaža: delovi više batch-eva u novi lot u praznom tanku
+#[derive(Debug, Deserialize, Validate)]
+pub struct BlendRequest {
+    pub tank_id: Uuid,
+
+    #[validate(length(min = 2, message = "Name must be at least 2 characters"))]
+    pub name: String,
+
+    /// Podrazumevano sorte izvornih batch-eva
+    #[validate(length(min = 2, message = "Grape variety is required"))]
+    pub grape_variety: Option<String>,
+
+    /// Podrazumevano tip izvornih batch-eva; obavezan ako se oni ne slažu
+    pub wine_type: Option<WineType>,
+
+    #[validate(length(min = 2, message = "A blend needs at least two source batches"), nested)]
+    pub sources: Vec<BlendSourceRequest>,
+
+    #[validate(range(min = 0.0, message = "Loss cannot be negative"))]
+    #[serde(default)]
+    pub loss_liters: f64,
+
+    #[validate(range(min = 5.0, max = 35.0, message = "Target temperature must be 5-35°C"))]
+    pub target_temperature: Option<f64>,
+
+    pub performed_at: Option<DateTime<Utc>>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CellarOperationResponse {
+    #[serde(flatten)]
+    pub operation: CellarOperation,
+    pub movements: Vec<CellarMovement>,
+}
+
+/// Kupaža: operacija, novi lot i njegov sastav
+#[derive(Debug, Serialize)]
+pub struct BlendResponse {
+    pub operation: CellarOperationResponse,
+    pub batch: BatchResponse,
+    pub composition: BatchComposition,
+}
+
+/// Gubitak pri pretakanju: ono što nije stiglo ni u jedan odredišni tank
+pub fn transfer_loss(batch_volume: f64, destinations: &[TransferDestination]) -> Result<f64, String> {
+    let moved: f64 = destinations.iter().map(|d| d.volume_liters).sum();
+
+    if moved > batch_volume + 1e-6 {
+        return Err(format!(
+            "Transferred volume ({} L) exceeds batch volume ({} L)",
+            moved, batch_volume
+        ));
+    }
+
+    Ok((batch_volume - moved).max(0.0))
+}
+
+/// Tip vina kupaže: zadati, ili zajednički tip svih izvora. Izvor bez tipa ili
+/// izvori različitih tipova traže da se tip navede, jer od njega zavisi SO2 limit.
+pub fn blend_wine_type(
+    requested: Option<WineType>,
+    sources: impl IntoIterator<Item = Option<WineType>>,
+) -> Result<WineType, String> {
+    if let Some(wine_type) = requested {
+        return Ok(wine_type);
+    }
+
+    let missing = || "Wine type is required when source batches do not share one".to_string();
+    let mut sources = sources.into_iter();
+    let first = sources.next().flatten().ok_or_else(missing)?;
+    if sources.all(|wine_type| wine_type == Some(first)) {
+        Ok(first)
+    } else {
+        Err(missing())
+    }
+}
+
+// ============== Sastav ==============
+
+#[derive(Debug, Serialize)]
+pub struct BlendComponent {
+    pub source_batch_id: Option<Uuid>,
+    pub source_batch_name: Option<String>,
+    pub volume_liters: f64,
+    /// Udeo u ukupno unetom vinu, u procentima
+    pub percentage: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchComposition {
+    pub batch_id: Uuid,
+    pub components: Vec<BlendComponent>,
+}
+
+/// Sastav lota iz tokova koji su u njega ušli iz drugih batch-eva;
+/// pretakanje samog batch-a se ne računa
+pub fn blend_composition(batch_id: Uuid, movements: &[CellarMovement]) -> BatchComposition {
+    let mut components: Vec<BlendComponent> = vec![];
+
+    for movement in movements
+        .iter()
+        .filter(|m| m.target_batch_id == Some(batch_id) && m.source_batch_id != Some(batch_id))
+    {
+        match components
+            .iter_mut()
+            .find(|c| c.source_batch_id.is_some() && c.source_batch_id == movement.source_batch_id)
+        {
+            Some(component) => component.volume_liters += movement.volume_liters,
+            None => components.push(BlendComponent {
+                source_batch_id: movement.source_batch_id,
+                source_batch_name: movement.source_batch_name.clone(),
+                volume_liters: movement.volume_liters,
+                percentage: 0.0,
+            }),
+        }
+    }
+
+    let total: f64 = components.iter().map(|c| c.volume_liters).sum();
+    for component in &mut components {
+        component.percentage = if total > 0.0 {
+            (component.volume_liters / total * 10000.0).round() / 100.0
+        } else {
+            0.0
+        };
+    }
+    components.sort_by(|a, b| b.volume_liters.total_cmp(&a.volume_liters));
+
+    BatchComposition { batch_id, components }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn movement(source: Uuid, target: Uuid, liters: f64) -> CellarMovement {
+        CellarMovement {
+            id: Uuid::new_v4(),
+            operation_id: Uuid::new_v4(),
+            source_batch_id: Some(source),
+            source_batch_name: Some("Lot".to_string()),
+            from_tank_id: Uuid::new_v4(),
+            from_tank_name: "T1".to_string(),
+            target_batch_id: Some(target),
+            target_batch_name: Some("Blend".to_string()),
+            to_tank_id: Uuid::new_v4(),
+            to_tank_name: "T2".to_string(),
+            volume_liters: liters,
+        }
+    }
+
+    #[test]
+    fn test_blend_wine_type() {
+        use WineType::*;
+
+        assert_eq!(blend_wine_type(None, [Some(White), Some(White)]), Ok(White));
+        assert_eq!(blend_wine_type(Some(Rose), [Some(Red), Some(White)]), Ok(Rose));
+        assert!(blend_wine_type(None, [Some(Red), Some(White)]).is_err());
+        assert!(blend_wine_type(None, [Some(Red), None]).is_err());
+        assert!(blend_wine_type(None, [None, None]).is_err());
+    }
+
+    #[test]
+    fn test_transfer_loss() {
+        let destinations = vec![
+            TransferDestination { tank_id: Uuid::new_v4(), volume_liters: 900.0 },
+            TransferDestination { tank_id: Uuid::new_v4(), volume_liters: 80.0 },
+        ];
+
+        assert_eq!(transfer_loss(1000.0, &destinations), Ok(20.0));
+        assert!(transfer_loss(950.0, &destinations).is_err());
+    }
+
+    #[test]
+    fn test_blend_composition_percentages() {
+        let (a, b, blend) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let movements = vec![
+            movement(a, blend, 600.0),
+            movement(b, blend, 300.0),
+            movement(a, blend, 100.0),
+            // Kasnije pretakanje kupaže ne menja sastav
+            movement(blend, blend, 950.0),
+        ];
+
+        let composition = blend_composition(blend, &movements);
+
+        assert_eq!(composition.components.len(), 2);
+        assert_eq!(composition.components[0].source_batch_id, Some(a));
+        assert_eq!(composition.components[0].percentage, 70.0);
+        assert_eq!(composition.components[1].percentage, 30.0);
+    }
+}
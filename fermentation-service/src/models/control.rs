@@ -0,0 +1,347 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Starije merenje temperature se ne koristi za regulaciju (aktuator se gasi)
+pub const STALE_TEMPERATURE_MINUTES: i64 = 30;
+pub const MAX_SCHEDULE_STEPS: usize = 20;
+
+// ============== Enums ==============
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "control_action", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ControlAction {
+    /// Ventil glikola zatvoren, grejač isključen
+    Idle,
+    /// Otvoren ventil glikola
+    Cool,
+    Heat,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "control_command_source", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ControlCommandSource {
+    Controller,
+    Manual,
+}
+
+// ============== Podešavanja i raspored ==============
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct TankControl {
+    pub tank_id: Uuid,
+    pub enabled: bool,
+    pub deadband: f64, // °C
+    pub schedule_started_at: Option<DateTime<Utc>>,
+    pub current_action: ControlAction,
+    pub override_action: Option<ControlAction>,
+    pub override_until: Option<DateTime<Utc>>,
+    pub override_reason: Option<String>,
+    pub override_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TankControl {
+    /// Ručna komanda koja još važi
+    pub fn active_override(&self, now: DateTime<Utc>) -> Option<ControlAction> {
+        match (self.override_action, self.override_until) {
+            (Some(action), None) => Some(action),
+            (Some(action), Some(until)) if until > now => Some(action),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct SetpointStep {
+    pub id: Uuid,
+    pub tank_id: Uuid,
+    pub position: i32,
+    pub name: String,
+    pub target_temperature: f64,
+    pub ramp_hours: f64,
+    pub hold_hours: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateTankControlRequest {
+    pub enabled: Option<bool>,
+
+    #[validate(range(min = 0.1, max = 5.0, message = "Deadband must be 0.1-5°C"))]
+    pub deadband: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct SetpointStepInput {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+
+    #[validate(range(min = -5.0, max = 45.0, message = "Target temperature must be -5 to 45°C"))]
+    pub target_temperature: f64,
+
+    #[validate(range(min = 0.0, max = 240.0, message = "Ramp must be 0-240 hours"))]
+    #[serde(default)]
+    pub ramp_hours: f64,
+
+    #[validate(range(min = 0.1, message = "Hold must be positive"))]
+    pub hold_hours: Option<f64>,
+}
+
+/// Novi raspored zamenjuje postojeći; počinje od `start_at` (podrazumevano sada)
+#[derive(Debug, Deserialize, Validate)]
+pub struct SetScheduleRequest {
+    pub start_at: Option<DateTime<Utc>>,
+
+    #[validate(nested)]
+    pub steps: Vec<SetpointStepInput>,
+}
+
+/// Samo poslednji korak može da traje neograničeno
+pub fn check_schedule(steps: &[SetpointStepInput]) -> Result<(), String> {
+    if steps.is_empty() || steps.len() > MAX_SCHEDULE_STEPS {
+        return Err(format!("Schedule must have 1-{} steps", MAX_SCHEDULE_STEPS));
+    }
+    if let Some(open) = steps[..steps.len() - 1].iter().position(|s| s.hold_hours.is_none()) {
+        return Err(format!(
+            "Step #{} needs hold_hours; only the last step can hold indefinitely",
+            open + 1
+        ));
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ControlOverrideRequest {
+    pub action: ControlAction,
+    pub until: Option<DateTime<Utc>>,
+
+    #[validate(length(max = 500))]
+    pub reason: Option<String>,
+}
+
+// ============== Regulacija ==============
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SetpointSource {
+    Schedule,
+    /// `target_temperature` aktivnog batch-a u tanku
+    BatchTarget,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ActiveSetpoint {
+    pub temperature: f64,
+    pub source: SetpointSource,
+    pub step: Option<String>,
+}
+
+/// Zadata temperatura rasporeda u trenutku `now`. Korak prvo linearno prelazi sa
+/// temperature prethodnog koraka (prvi korak nema prelaz), pa je drži `hold_hours`;
+/// posle poslednjeg koraka drži se njegova temperatura.
+pub fn schedule_setpoint(
+    steps: &[SetpointStep],
+    started_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Option<ActiveSetpoint> {
+    if now < started_at {
+        return None;
+    }
+
+    let mut elapsed = (now - started_at).num_seconds() as f64 / 3600.0;
+    let mut previous: Option<f64> = None;
+    let at = |temperature: f64, step: &SetpointStep| ActiveSetpoint {
+        temperature: (temperature * 100.0).round() / 100.0,
+        source: SetpointSource::Schedule,
+        step: Some(step.name.clone()),
+    };
+
+    for step in steps {
+        if let Some(from) = previous {
+            if elapsed < step.ramp_hours {
+                let progress = elapsed / step.ramp_hours;
+                return Some(at(from + (step.target_temperature - from) * progress, step));
+            }
+            elapsed -= step.ramp_hours;
+        }
+
+        match step.hold_hours {
+            Some(hold) if elapsed >= hold => elapsed -= hold,
+            _ => return Some(at(step.target_temperature, step)),
+        }
+        previous = Some(step.target_temperature);
+    }
+
+    steps.last().map(|step| at(step.target_temperature, step))
+}
+
+/// Komanda aktuatoru sa histerezom: uključuje se van opsega zadato ± deadband,
+/// a isključuje tek kad temperatura dođe do zadate
+pub fn decide_action(measured: f64, setpoint: f64, deadband: f64, current: ControlAction) -> ControlAction {
+    if measured > setpoint + deadband {
+        return ControlAction::Cool;
+    }
+    if measured < setpoint - deadband {
+        return ControlAction::Heat;
+    }
+
+    match current {
+        ControlAction::Cool if measured <= setpoint => ControlAction::Idle,
+        ControlAction::Heat if measured >= setpoint => ControlAction::Idle,
+        current => current,
+    }
+}
+
+/// Šta regulator treba da uradi za tank: ručna komanda, regulacija prema zadatoj
+/// temperaturi, ili gašenje kad nema podataka
+pub fn control_decision(
+    control: &TankControl,
+    setpoint: Option<&ActiveSetpoint>,
+    latest_temperature: Option<(f64, DateTime<Utc>)>,
+    now: DateTime<Utc>,
+) -> ControlAction {
+    if let Some(action) = control.active_override(now) {
+        return action;
+    }
+    if !control.enabled {
+        return ControlAction::Idle;
+    }
+
+    let fresh = latest_temperature
+        .filter(|(_, at)| now - *at <= Duration::minutes(STALE_TEMPERATURE_MINUTES));
+    match (setpoint, fresh) {
+        (Some(setpoint), Some((measured, _))) => {
+            decide_action(measured, setpoint.temperature, control.deadband, control.current_action)
+        }
+        _ => ControlAction::Idle,
+    }
+}
+
+/// Poruka aktuatoru (MQTT JSON ili simulator)
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ActuatorCommand {
+    pub tank_id: Uuid,
+    pub action: ControlAction,
+    pub setpoint: Option<f64>,
+    pub issued_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ControlCommand {
+    pub id: Uuid,
+    pub tank_id: Uuid,
+    pub action: ControlAction,
+    pub source: ControlCommandSource,
+    pub setpoint: Option<f64>,
+    pub measured_temperature: Option<f64>,
+    pub driver: String,
+    pub success: bool,
+    pub error: Option<String>,
+    pub issued_by: Option<Uuid>,
+    pub issued_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TankControlStatus {
+    #[serde(flatten)]
+    pub control: TankControl,
+    pub steps: Vec<SetpointStep>,
+    pub setpoint: Option<ActiveSetpoint>,
+    pub latest_temperature: Option<f64>,
+    pub temperature_recorded_at: Option<DateTime<Utc>>,
+    pub override_active: bool,
+    /// Šta bi regulator sada poslao aktuatoru
+    pub desired_action: ControlAction,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(position: i32, target: f64, ramp_hours: f64, hold_hours: Option<f64>) -> SetpointStep {
+        SetpointStep {
+            id: Uuid::new_v4(),
+            tank_id: Uuid::nil(),
+            position,
+            name: format!("step {}", position),
+            target_temperature: target,
+            ramp_hours,
+            hold_hours,
+        }
+    }
+
+    fn control(enabled: bool) -> TankControl {
+        TankControl {
+            tank_id: Uuid::new_v4(),
+            enabled,
+            deadband: 0.5,
+            schedule_started_at: None,
+            current_action: ControlAction::Idle,
+            override_action: None,
+            override_until: None,
+            override_reason: None,
+            override_by: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_schedule_cold_soak_then_ramp() {
+        let start = Utc::now();
+        // Hladna maceracija 8°C 48h, pa za 24h do 24°C i držanje
+        let steps = vec![step(1, 8.0, 0.0, Some(48.0)), step(2, 24.0, 24.0, None)];
+        let at = |hours: i64| schedule_setpoint(&steps, start, start + Duration::hours(hours));
+
+        assert_eq!(at(10).unwrap().temperature, 8.0);
+        assert_eq!(at(10).unwrap().step.as_deref(), Some("step 1"));
+        assert_eq!(at(60).unwrap().temperature, 16.0);
+        assert_eq!(at(72).unwrap().temperature, 24.0);
+        assert_eq!(at(500).unwrap().temperature, 24.0);
+        assert!(schedule_setpoint(&steps, start, start - Duration::hours(1)).is_none());
+    }
+
+    #[test]
+    fn test_decide_action_hysteresis() {
+        assert_eq!(decide_action(25.6, 25.0, 0.5, ControlAction::Idle), ControlAction::Cool);
+        // U opsegu se zadržava trenutno stanje dok se ne dođe do zadate
+        assert_eq!(decide_action(25.3, 25.0, 0.5, ControlAction::Cool), ControlAction::Cool);
+        assert_eq!(decide_action(25.3, 25.0, 0.5, ControlAction::Idle), ControlAction::Idle);
+        assert_eq!(decide_action(24.9, 25.0, 0.5, ControlAction::Cool), ControlAction::Idle);
+        assert_eq!(decide_action(24.4, 25.0, 0.5, ControlAction::Idle), ControlAction::Heat);
+        assert_eq!(decide_action(25.0, 25.0, 0.5, ControlAction::Heat), ControlAction::Idle);
+    }
+
+    #[test]
+    fn test_control_decision_override_and_stale_data() {
+        let now = Utc::now();
+        let setpoint = ActiveSetpoint {
+            temperature: 18.0,
+            source: SetpointSource::BatchTarget,
+            step: None,
+        };
+
+        let enabled = control(true);
+        assert_eq!(
+            control_decision(&enabled, Some(&setpoint), Some((22.0, now)), now),
+            ControlAction::Cool
+        );
+        // Staro merenje - aktuator se gasi
+        let stale = Some((22.0, now - Duration::hours(2)));
+        assert_eq!(control_decision(&enabled, Some(&setpoint), stale, now), ControlAction::Idle);
+        assert_eq!(control_decision(&control(false), Some(&setpoint), Some((22.0, now)), now), ControlAction::Idle);
+
+        let mut manual = control(false);
+        manual.override_action = Some(ControlAction::Heat);
+        manual.override_until = Some(now + Duration::hours(1));
+        assert_eq!(control_decision(&manual, None, None, now), ControlAction::Heat);
+        manual.override_until = Some(now - Duration::minutes(1));
+        assert_eq!(control_decision(&manual, None, None, now), ControlAction::Idle);
+    }
+}
@@ -0,0 +1,515 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::FermentationStatus;
+
+// ============== Enums ==============
+
+/// Frakcija šire: samotok (pre presovanja) ili presovana
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, PartialOrd, Ord)]
+#[sqlx(type_name = "must_fraction", rename_all = "snake_case")]
+pub enum MustFraction {
+    #[serde(rename = "free_run")]
+    FreeRun,
+    #[serde(rename = "press")]
+    Press,
+}
+
+// ============== Crush / press ==============
+
+/// Muljanje i presovanje: kilogrami grožđa iz jedne ili više berbi → litri šire
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct CrushOperation {
+    pub id: Uuid,
+    pub crushed_at: DateTime<Utc>,
+    pub grape_variety: String,
+    pub total_grape_kg: f64,
+    pub free_run_liters: f64,
+    pub press_liters: f64,
+    pub pomace_kg: Option<f64>, // komina
+    pub press_program: Option<String>,
+    pub notes: Option<String>,
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+impl CrushOperation {
+    pub fn total_must_liters(&self) -> f64 {
+        self.free_run_liters + self.press_liters
+    }
+
+    pub fn fraction_liters(&self, fraction: MustFraction) -> f64 {
+        match fraction {
+            MustFraction::FreeRun => self.free_run_liters,
+            MustFraction::Press => self.press_liters,
+        }
+    }
+}
+
+/// Berba koja je ušla u muljanje (podaci o parceli se kopiraju iz harvest-service)
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct CrushInput {
+    pub id: Uuid,
+    pub crush_id: Uuid,
+    pub harvest_id: Uuid,
+    pub weight_kg: f64,
+    pub grape_variety: Option<String>,
+    pub vineyard_name: Option<String>,
+    pub parcel_name: Option<String>,
+}
+
+/// Deo šire iz muljanja koji je završio u batch-u
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct MustAllocation {
+    pub batch_id: Uuid,
+    pub batch_name: String,
+    pub batch_status: FermentationStatus,
+    pub crush_id: Uuid,
+    pub fraction: MustFraction,
+    pub liters: f64,
+}
+
+/// Berba za upis u muljanje, sa podacima proverenim u harvest-service
+#[derive(Debug, Clone)]
+pub struct NewCrushInput {
+    pub harvest_id: Uuid,
+    pub weight_kg: f64,
+    /// Ukupna masa berbe; zbir svih muljanja berbe ne sme da je pređe
+    pub harvest_weight_kg: f64,
+    pub grape_variety: Option<String>,
+    pub vineyard_name: Option<String>,
+    pub parcel_name: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CrushInputRequest {
+    pub harvest_id: Uuid,
+
+    #[validate(range(min = 1.0, message = "Weight must be at least 1 kg"))]
+    pub weight_kg: f64,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateCrushRequest {
+    pub crushed_at: Option<DateTime<Utc>>,
+
+    #[validate(length(min = 1, message = "At least one harvest is required"), nested)]
+    pub inputs: Vec<CrushInputRequest>,
+
+    #[validate(range(min = 0.0, message = "Free-run volume cannot be negative"))]
+    pub free_run_liters: f64,
+
+    #[validate(range(min = 0.0, message = "Press volume cannot be negative"))]
+    #[serde(default)]
+    pub press_liters: f64,
+
+    #[validate(range(min = 0.0, message = "Pomace weight cannot be negative"))]
+    pub pomace_kg: Option<f64>,
+
+    pub press_program: Option<String>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct MustSourceRequest {
+    pub crush_id: Uuid,
+    pub fraction: MustFraction,
+
+    #[validate(range(min = 1.0, message = "Volume must be at least 1 liter"))]
+    pub liters: f64,
+}
+
+/// Novi batch od šire jednog ili više muljanja; zapremina je zbir izvora
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateBatchFromMustRequest {
+    pub tank_id: Uuid,
+
+    #[validate(length(min = 2, message = "Name must be at least 2 characters"))]
+    pub name: String,
+
+    /// Podrazumevano sorta (ili sorte) iz muljanja
+    #[validate(length(min = 2, message = "Grape variety is required"))]
+    pub grape_variety: Option<String>,
+
+    #[validate(length(min = 1, message = "At least one must source is required"), nested)]
+    pub sources: Vec<MustSourceRequest>,
+
+    #[validate(range(min = 5.0, max = 35.0, message = "Target temperature must be 5-35°C"))]
+    pub target_temperature: Option<f64>,
+
+    pub yeast_strain: Option<String>,
+
+    #[validate(range(min = 0.0, max = 50.0, message = "Brix must be 0-50"))]
+    pub initial_brix: Option<f64>,
+
+    #[validate(range(min = 0.0, max = 14.0, message = "pH must be 0-14"))]
+    pub initial_ph: Option<f64>,
+
+    pub expected_end_date: Option<DateTime<Utc>>,
+    pub notes: Option<String>,
+}
+
+/// Zbir traženih litara po (muljanje, frakcija); isti izvor se može navesti više puta
+pub fn group_must_sources(sources: &[MustSourceRequest]) -> BTreeMap<(Uuid, MustFraction), f64> {
+    let mut grouped = BTreeMap::new();
+    for source in sources {
+        *grouped.entry((source.crush_id, source.fraction)).or_insert(0.0) += source.liters;
+    }
+    grouped
+}
+
+// ============== Prinos ==============
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub struct CrushYield {
+    pub total_must_liters: f64,
+    /// Litara šire po toni grožđa
+    pub extraction_yield_l_per_t: f64,
+    /// Udeo samotoka u ukupnoj širi (0-1)
+    pub free_run_share: f64,
+}
+
+pub fn crush_yield(grape_kg: f64, free_run_liters: f64, press_liters: f64) -> CrushYield {
+    let total = free_run_liters + press_liters;
+
+    CrushYield {
+        total_must_liters: total,
+        extraction_yield_l_per_t: if grape_kg > 0.0 {
+            total / (grape_kg / 1000.0)
+        } else {
+            0.0
+        },
+        free_run_share: if total > 0.0 { free_run_liters / total } else { 0.0 },
+    }
+}
+
+/// Sorta muljanja: jedna sorta ili "A / B" za zajedničko muljanje
+pub fn blend_variety_name<'a>(varieties: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    let mut names: Vec<&str> = varieties.into_iter().filter(|v| !v.is_empty()).collect();
+    names.sort_unstable();
+    names.dedup();
+
+    if names.is_empty() {
+        None
+    } else {
+        Some(names.join(" / "))
+    }
+}
+
+// ============== Poreklo (kg → L) ==============
+
+#[derive(Debug, Serialize)]
+pub struct LineageSource {
+    pub crush_id: Uuid,
+    pub crushed_at: DateTime<Utc>,
+    pub fraction: MustFraction,
+    pub liters: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HarvestContribution {
+    pub harvest_id: Uuid,
+    pub vineyard_name: Option<String>,
+    pub parcel_name: Option<String>,
+    pub grape_variety: Option<String>,
+    pub grape_kg: f64,
+    pub must_liters: f64,
+}
+
+/// Poreklo batch-a: iz kojih muljanja i berbi potiče
+#[derive(Debug, Serialize)]
+pub struct BatchLineage {
+    pub batch_id: Uuid,
+    pub volume_liters: f64,
+    pub total_grape_kg: f64,
+    pub sources: Vec<LineageSource>,
+    pub harvests: Vec<HarvestContribution>,
+}
+
+pub fn build_batch_lineage(
+    batch_id: Uuid,
+    volume_liters: f64,
+    allocations: &[MustAllocation],
+    crushes: &[CrushOperation],
+    inputs: &[CrushInput],
+) -> BatchLineage {
+    let mut sources = vec![];
+    let mut harvests: BTreeMap<Uuid, HarvestContribution> = BTreeMap::new();
+
+    for allocation in allocations.iter().filter(|a| a.batch_id == batch_id) {
+        let Some(crush) = crushes.iter().find(|c| c.id == allocation.crush_id) else {
+            continue;
+        };
+        sources.push(LineageSource {
+            crush_id: crush.id,
+            crushed_at: crush.crushed_at,
+            fraction: allocation.fraction,
+            liters: allocation.liters,
+        });
+
+        let total_liters = crush.total_must_liters();
+        for input in inputs.iter().filter(|i| i.crush_id == crush.id) {
+            let entry = harvests.entry(input.harvest_id).or_insert_with(|| HarvestContribution {
+                harvest_id: input.harvest_id,
+                vineyard_name: input.vineyard_name.clone(),
+                parcel_name: input.parcel_name.clone(),
+                grape_variety: input.grape_variety.clone(),
+                grape_kg: 0.0,
+                must_liters: 0.0,
+            });
+            if total_liters > 0.0 {
+                entry.grape_kg += input.weight_kg * allocation.liters / total_liters;
+            }
+            if crush.total_grape_kg > 0.0 {
+                entry.must_liters += allocation.liters * input.weight_kg / crush.total_grape_kg;
+            }
+        }
+    }
+
+    let harvests: Vec<HarvestContribution> = harvests.into_values().collect();
+
+    BatchLineage {
+        batch_id,
+        volume_liters,
+        total_grape_kg: harvests.iter().map(|h| h.grape_kg).sum(),
+        sources,
+        harvests,
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct HarvestCrushShare {
+    pub crush_id: Uuid,
+    pub crushed_at: DateTime<Utc>,
+    pub weight_kg: f64,
+    /// Deo šire muljanja koji potiče od ove berbe
+    pub must_liters: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HarvestBatchShare {
+    pub batch_id: Uuid,
+    pub batch_name: String,
+    pub batch_status: FermentationStatus,
+    pub grape_kg: f64,
+    pub must_liters: f64,
+}
+
+/// Poreklo unazad: gde je završilo grožđe jedne berbe
+#[derive(Debug, Serialize)]
+pub struct HarvestLineage {
+    pub harvest_id: Uuid,
+    pub crushed_kg: f64,
+    pub must_liters: f64,
+    pub crushes: Vec<HarvestCrushShare>,
+    pub batches: Vec<HarvestBatchShare>,
+}
+
+pub fn build_harvest_lineage(
+    harvest_id: Uuid,
+    crushes: &[CrushOperation],
+    inputs: &[CrushInput],
+    allocations: &[MustAllocation],
+) -> HarvestLineage {
+    let mut crush_shares = vec![];
+    let mut batches: BTreeMap<Uuid, HarvestBatchShare> = BTreeMap::new();
+
+    for crush in crushes {
+        let Some(input) = inputs
+            .iter()
+            .find(|i| i.crush_id == crush.id && i.harvest_id == harvest_id)
+        else {
+            continue;
+        };
+        let share = if crush.total_grape_kg > 0.0 {
+            input.weight_kg / crush.total_grape_kg
+        } else {
+            0.0
+        };
+
+        crush_shares.push(HarvestCrushShare {
+            crush_id: crush.id,
+            crushed_at: crush.crushed_at,
+            weight_kg: input.weight_kg,
+            must_liters: crush.total_must_liters() * share,
+        });
+
+        for allocation in allocations.iter().filter(|a| a.crush_id == crush.id) {
+            let entry = batches
+                .entry(allocation.batch_id)
+                .or_insert_with(|| HarvestBatchShare {
+                    batch_id: allocation.batch_id,
+                    batch_name: allocation.batch_name.clone(),
+                    batch_status: allocation.batch_status.clone(),
+                    grape_kg: 0.0,
+                    must_liters: 0.0,
+                });
+            entry.must_liters += allocation.liters * share;
+            if crush.total_must_liters() > 0.0 {
+                entry.grape_kg += input.weight_kg * allocation.liters / crush.total_must_liters();
+            }
+        }
+    }
+
+    HarvestLineage {
+        harvest_id,
+        crushed_kg: crush_shares.iter().map(|c| c.weight_kg).sum(),
+        must_liters: crush_shares.iter().map(|c| c.must_liters).sum(),
+        crushes: crush_shares,
+        batches: batches.into_values().collect(),
+    }
+}
+
+// ============== Response ==============
+
+#[derive(Debug, Serialize)]
+pub struct CrushResponse {
+    #[serde(flatten)]
+    pub crush: CrushOperation,
+    #[serde(rename = "yield")]
+    pub crush_yield: CrushYield,
+    pub inputs: Vec<CrushInput>,
+    pub allocations: Vec<MustAllocation>,
+    pub remaining_free_run_liters: f64,
+    pub remaining_press_liters: f64,
+}
+
+impl CrushResponse {
+    pub fn new(crush: CrushOperation, inputs: Vec<CrushInput>, allocations: Vec<MustAllocation>) -> Self {
+        let allocated = |fraction: MustFraction| -> f64 {
+            allocations
+                .iter()
+                .filter(|a| a.fraction == fraction)
+                .map(|a| a.liters)
+                .sum()
+        };
+
+        CrushResponse {
+            crush_yield: crush_yield(crush.total_grape_kg, crush.free_run_liters, crush.press_liters),
+            remaining_free_run_liters: (crush.free_run_liters - allocated(MustFraction::FreeRun)).max(0.0),
+            remaining_press_liters: (crush.press_liters - allocated(MustFraction::Press)).max(0.0),
+            crush,
+            inputs,
+            allocations,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn crush(kg: f64, free_run: f64, press: f64) -> CrushOperation {
+        CrushOperation {
+            id: Uuid::new_v4(),
+            crushed_at: Utc::now(),
+            grape_variety: "Merlot".to_string(),
+            total_grape_kg: kg,
+            free_run_liters: free_run,
+            press_liters: press,
+            pomace_kg: None,
+            press_program: None,
+            notes: None,
+            created_by: Uuid::new_v4(),
+            created_at: Utc::now(),
+        }
+    }
+
+    fn input(crush: &CrushOperation, harvest_id: Uuid, kg: f64) -> CrushInput {
+        CrushInput {
+            id: Uuid::new_v4(),
+            crush_id: crush.id,
+            harvest_id,
+            weight_kg: kg,
+            grape_variety: Some("Merlot".to_string()),
+            vineyard_name: None,
+            parcel_name: None,
+        }
+    }
+
+    fn allocation(crush: &CrushOperation, batch_id: Uuid, fraction: MustFraction, liters: f64) -> MustAllocation {
+        MustAllocation {
+            batch_id,
+            batch_name: "Merlot 2026".to_string(),
+            batch_status: FermentationStatus::Active,
+            crush_id: crush.id,
+            fraction,
+            liters,
+        }
+    }
+
+    #[test]
+    fn test_crush_yield() {
+        let y = crush_yield(2000.0, 1100.0, 400.0);
+
+        assert_eq!(y.total_must_liters, 1500.0);
+        assert_eq!(y.extraction_yield_l_per_t, 750.0);
+        assert!((y.free_run_share - 0.7333).abs() < 1e-3);
+        assert_eq!(crush_yield(0.0, 0.0, 0.0).extraction_yield_l_per_t, 0.0);
+    }
+
+    #[test]
+    fn test_blend_variety_name() {
+        assert_eq!(blend_variety_name(["Merlot", "Merlot"]), Some("Merlot".to_string()));
+        assert_eq!(
+            blend_variety_name(["Merlot", "Cabernet Sauvignon", ""]),
+            Some("Cabernet Sauvignon / Merlot".to_string())
+        );
+        assert_eq!(blend_variety_name([]), None);
+    }
+
+    #[test]
+    fn test_group_must_sources() {
+        let crush_id = Uuid::new_v4();
+        let source = |fraction, liters| MustSourceRequest { crush_id, fraction, liters };
+        let grouped = group_must_sources(&[
+            source(MustFraction::FreeRun, 300.0),
+            source(MustFraction::Press, 100.0),
+            source(MustFraction::FreeRun, 200.0),
+        ]);
+
+        assert_eq!(grouped.len(), 2);
+        assert_eq!(grouped[&(crush_id, MustFraction::FreeRun)], 500.0);
+    }
+
+    #[test]
+    fn test_batch_lineage_splits_kg_by_liters() {
+        // 3000 kg (2000 + 1000) → 2000 L; batch uzima 500 L samotoka
+        let c = crush(3000.0, 1500.0, 500.0);
+        let (h1, h2, batch_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let inputs = vec![input(&c, h1, 2000.0), input(&c, h2, 1000.0)];
+        let allocations = vec![allocation(&c, batch_id, MustFraction::FreeRun, 500.0)];
+
+        let lineage = build_batch_lineage(batch_id, 500.0, &allocations, std::slice::from_ref(&c), &inputs);
+
+        assert_eq!(lineage.total_grape_kg, 750.0);
+        let first = lineage.harvests.iter().find(|h| h.harvest_id == h1).unwrap();
+        assert_eq!(first.grape_kg, 500.0);
+        assert!((first.must_liters - 333.333).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_harvest_lineage_follows_grapes_into_batches() {
+        let c = crush(3000.0, 1500.0, 500.0);
+        let (h1, h2) = (Uuid::new_v4(), Uuid::new_v4());
+        let (b1, b2) = (Uuid::new_v4(), Uuid::new_v4());
+        let inputs = vec![input(&c, h1, 2000.0), input(&c, h2, 1000.0)];
+        let allocations = vec![
+            allocation(&c, b1, MustFraction::FreeRun, 1500.0),
+            allocation(&c, b2, MustFraction::Press, 300.0),
+        ];
+
+        let lineage = build_harvest_lineage(h2, std::slice::from_ref(&c), &inputs, &allocations);
+
+        assert_eq!(lineage.crushed_kg, 1000.0);
+        assert!((lineage.must_liters - 666.667).abs() < 1e-2);
+        let press = lineage.batches.iter().find(|b| b.batch_id == b2).unwrap();
+        assert_eq!(press.grape_kg, 150.0);
+        assert_eq!(press.must_liters, 100.0);
+    }
+}
@@ -0,0 +1,233 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Bez merenja duže od ovoga uređaj se smatra nedostupnim
+pub const DEVICE_OFFLINE_AFTER_MINUTES: i64 = 15;
+pub const LOW_BATTERY_PERCENT: f64 = 20.0;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "iot_device_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum IotDeviceType {
+    TemperatureProbe,
+    TemperatureHumidity,
+    DensityMeter,
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct IotDevice {
+    pub id: Uuid,
+    pub device_id: String, // hardverski id koji uređaj šalje
+    pub name: String,
+    pub device_type: IotDeviceType,
+    pub firmware_version: Option<String>,
+    pub tank_id: Option<Uuid>,
+    pub calibration_offset: f64, // °C
+    pub battery_percent: Option<f64>,
+    pub last_seen_at: Option<DateTime<Utc>>,
+    pub active: bool,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl IotDevice {
+    /// Izmerena temperatura korigovana kalibracijom uređaja
+    pub fn calibrate(&self, temperature: f64) -> f64 {
+        temperature + self.calibration_offset
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateDeviceRequest {
+    #[validate(length(min = 1, max = 100, message = "Device id must be 1-100 characters"))]
+    pub device_id: String,
+
+    #[validate(length(min = 2, message = "Name must be at least 2 characters"))]
+    pub name: String,
+
+    pub device_type: IotDeviceType,
+
+    #[validate(length(max = 50))]
+    pub firmware_version: Option<String>,
+
+    pub tank_id: Option<Uuid>,
+
+    #[validate(range(min = -10.0, max = 10.0, message = "Calibration offset must be -10 to 10°C"))]
+    #[serde(default)]
+    pub calibration_offset: f64,
+
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateDeviceRequest {
+    #[validate(length(min = 2))]
+    pub name: Option<String>,
+
+    pub device_type: Option<IotDeviceType>,
+
+    #[validate(length(max = 50))]
+    pub firmware_version: Option<String>,
+
+    #[validate(range(min = -10.0, max = 10.0))]
+    pub calibration_offset: Option<f64>,
+
+    pub active: Option<bool>,
+    pub notes: Option<String>,
+}
+
+/// Vezivanje uređaja za tank; `null` ga odvezuje
+#[derive(Debug, Deserialize)]
+pub struct BindDeviceRequest {
+    pub tank_id: Option<Uuid>,
+}
+
+// ============== Stanje uređaja ==============
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceStatus {
+    Offline,
+    NeverSeen,
+    Online,
+    Disabled,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeviceResponse {
+    #[serde(flatten)]
+    pub device: IotDevice,
+    pub tank_name: Option<String>,
+    pub status: DeviceStatus,
+    pub minutes_since_seen: Option<i64>,
+    pub low_battery: bool,
+}
+
+impl DeviceResponse {
+    pub fn new(device: IotDevice, tank_name: Option<String>, now: DateTime<Utc>) -> Self {
+        let status = device_status(&device, now);
+        let minutes_since_seen = device.last_seen_at.map(|at| (now - at).num_minutes());
+        let low_battery = device
+            .battery_percent
+            .is_some_and(|battery| battery < LOW_BATTERY_PERCENT);
+
+        DeviceResponse {
+            device,
+            tank_name,
+            status,
+            minutes_since_seen,
+            low_battery,
+        }
+    }
+
+    pub fn needs_attention(&self) -> bool {
+        self.low_battery || matches!(self.status, DeviceStatus::Offline | DeviceStatus::NeverSeen)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeviceHealthSummary {
+    pub total: usize,
+    pub online: usize,
+    pub offline: usize,
+    pub never_seen: usize,
+    pub low_battery: usize,
+    /// Uređaji kojima treba pažnja su prvi
+    pub devices: Vec<DeviceResponse>,
+}
+
+pub fn device_status(device: &IotDevice, now: DateTime<Utc>) -> DeviceStatus {
+    if !device.active {
+        return DeviceStatus::Disabled;
+    }
+
+    match device.last_seen_at {
+        None => DeviceStatus::NeverSeen,
+        Some(at) if now - at > Duration::minutes(DEVICE_OFFLINE_AFTER_MINUTES) => DeviceStatus::Offline,
+        Some(_) => DeviceStatus::Online,
+    }
+}
+
+pub fn device_health_summary(mut devices: Vec<DeviceResponse>) -> DeviceHealthSummary {
+    devices.sort_by(|a, b| {
+        b.needs_attention()
+            .cmp(&a.needs_attention())
+            .then(a.status.cmp(&b.status))
+            .then_with(|| a.device.name.cmp(&b.device.name))
+    });
+
+    let count = |status: DeviceStatus| devices.iter().filter(|d| d.status == status).count();
+
+    DeviceHealthSummary {
+        total: devices.len(),
+        online: count(DeviceStatus::Online),
+        offline: count(DeviceStatus::Offline),
+        never_seen: count(DeviceStatus::NeverSeen),
+        low_battery: devices.iter().filter(|d| d.low_battery).count(),
+        devices,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device(name: &str, last_seen_at: Option<DateTime<Utc>>, battery: Option<f64>) -> IotDevice {
+        IotDevice {
+            id: Uuid::new_v4(),
+            device_id: format!("esp32-{}", name),
+            name: name.to_string(),
+            device_type: IotDeviceType::TemperatureHumidity,
+            firmware_version: None,
+            tank_id: None,
+            calibration_offset: -0.4,
+            battery_percent: battery,
+            last_seen_at,
+            active: true,
+            notes: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_device_status() {
+        let now = Utc::now();
+
+        assert_eq!(device_status(&device("a", Some(now - Duration::minutes(2)), None), now), DeviceStatus::Online);
+        assert_eq!(device_status(&device("b", Some(now - Duration::hours(1)), None), now), DeviceStatus::Offline);
+        assert_eq!(device_status(&device("c", None, None), now), DeviceStatus::NeverSeen);
+
+        let mut disabled = device("d", None, None);
+        disabled.active = false;
+        assert_eq!(device_status(&disabled, now), DeviceStatus::Disabled);
+    }
+
+    #[test]
+    fn test_calibration() {
+        let probe = device("a", None, None);
+        assert!((probe.calibrate(24.0) - 23.6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_health_summary_orders_problems_first() {
+        let now = Utc::now();
+        let devices = vec![
+            DeviceResponse::new(device("ok", Some(now), Some(90.0)), None, now),
+            DeviceResponse::new(device("weak", Some(now), Some(12.0)), None, now),
+            DeviceResponse::new(device("gone", Some(now - Duration::days(1)), Some(80.0)), None, now),
+        ];
+
+        let summary = device_health_summary(devices);
+
+        assert_eq!((summary.total, summary.online, summary.offline, summary.low_battery), (3, 2, 1, 1));
+        assert_eq!(summary.devices[0].device.name, "gone");
+        assert_eq!(summary.devices[1].device.name, "weak");
+        assert_eq!(summary.devices[2].device.name, "ok");
+    }
+}
@@ -4,6 +4,8 @@ use sqlx::FromRow;
 use uuid::Uuid;
 use validator::Validate;
 
+use crate::models::{FermentationAnalysis, SensorChannel, SensorValue};
+
 // ============== Enums ==============
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
@@ -45,6 +47,52 @@ pub enum FermentationStatus {
     Cancelled,
 }
 
+impl FermentationStatus {
+    /// Aktivan ili pauziran batch drži vino u tanku
+    pub fn occupies_tank(&self) -> bool {
+        matches!(self, FermentationStatus::Active | FermentationStatus::Paused)
+    }
+}
+
+/// Tip vina; određuje zakonski maksimum ukupnog SO2
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "wine_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum WineType {
+    Red,
+    White,
+    Rose,
+    /// Crveno sa ≥ 5 g/L neprevrelog šećera
+    SweetRed,
+    /// Belo/roze sa ≥ 5 g/L neprevrelog šećera
+    SweetWhite,
+    Sparkling,
+}
+
+impl WineType {
+    /// Najveći dozvoljeni ukupni SO2 (mg/L), prema EU pravilniku 2019/934
+    pub fn total_so2_limit_mg_l(&self) -> f64 {
+        match self {
+            WineType::Red => 150.0,
+            WineType::White | WineType::Rose => 200.0,
+            WineType::SweetRed => 200.0,
+            WineType::SweetWhite => 250.0,
+            WineType::Sparkling => 185.0,
+        }
+    }
+}
+
+/// Status tanka prema batch-evima u njemu: zauzet tank je in_use, oslobođen
+/// ide na čišćenje (available postaje tek posle uspešnog čišćenja), a
+/// čišćenje i održavanje ostaju kako jesu
+pub fn derive_tank_status(current: &TankStatus, occupied: bool) -> TankStatus {
+    match (current, occupied) {
+        (_, true) => TankStatus::InUse,
+        (TankStatus::InUse, false) => TankStatus::Cleaning,
+        (status, false) => status.clone(),
+    }
+}
+
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Tank {
@@ -127,6 +175,7 @@ pub struct FermentationBatch {
     pub harvest_id: Option<Uuid>, // reference na harvest service
     pub name: String,
     pub grape_variety: String,
+    pub wine_type: Option<WineType>,
     pub volume_liters: f64,
     pub status: FermentationStatus,
     // Parametri procesa
@@ -155,6 +204,8 @@ pub struct CreateBatchRequest {
     #[validate(length(min = 2, message = "Grape variety is required"))]
     pub grape_variety: String,
 
+    pub wine_type: Option<WineType>,
+
     #[validate(range(min = 1.0, message = "Volume must be at least 1 liter"))]
     pub volume_liters: f64,
 
@@ -183,6 +234,8 @@ pub struct UpdateBatchRequest {
 
     pub status: Option<FermentationStatus>,
 
+    pub wine_type: Option<WineType>,
+
     #[validate(range(min = 5.0, max = 35.0))]
     pub target_temperature: Option<f64>,
 
@@ -200,6 +253,7 @@ pub struct BatchResponse {
     pub harvest_id: Option<Uuid>,
     pub name: String,
     pub grape_variety: String,
+    pub wine_type: Option<WineType>,
     pub volume_liters: f64,
     pub status: FermentationStatus,
     pub target_temperature: Option<f64>,
@@ -212,6 +266,7 @@ pub struct BatchResponse {
     pub notes: Option<String>,
     pub created_by: Uuid,
     pub latest_reading: Option<ReadingResponse>, // poslednje merenje
+    pub analytics: Option<FermentationAnalysis>, // samo za aktivne batch-eve
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -224,6 +279,7 @@ impl From<FermentationBatch> for BatchResponse {
             harvest_id: b.harvest_id,
             name: b.name,
             grape_variety: b.grape_variety,
+            wine_type: b.wine_type,
             volume_liters: b.volume_liters,
             status: b.status,
             target_temperature: b.target_temperature,
@@ -236,6 +292,7 @@ impl From<FermentationBatch> for BatchResponse {
             notes: b.notes,
             created_by: b.created_by,
             latest_reading: None,
+            analytics: None,
             created_at: b.created_at,
             updated_at: b.updated_at,
         }
@@ -264,6 +321,8 @@ pub struct FermentationReading {
     pub aroma_notes: Option<String>,
     // Meta
     pub source: String, // "manual" | "iot"
+    pub device_id: Option<Uuid>, // IoT uređaj koji je poslao merenje
+    pub source_device: Option<String>, // hardverski id uređaja
     pub notes: Option<String>,
     pub recorded_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
@@ -302,16 +361,53 @@ pub struct AddReadingRequest {
     pub recorded_at: Option<DateTime<Utc>>,
 }
 
-// IoT reading iz senzora (jednostavniji format)
-#[derive(Debug, Deserialize)]
+// IoT reading iz senzora (jednostavniji format, HTTP ili MQTT).
+// Registrovan uređaj vezan za tank ne mora da zna batch - uzima se aktivan batch tanka.
+#[derive(Debug, Deserialize, Validate)]
 pub struct IotReadingRequest {
-    pub batch_id: Uuid,
-    pub temperature: f64,
-    pub humidity: Option<f64>, // ako senzor ima i vlažnost
+    pub batch_id: Option<Uuid>,
+    #[validate(length(min = 1, max = 100))]
+    pub device_id: Option<String>,
+    /// Opseg se proverava posle kalibracije uređaja (`IotSampleGroup::calibrate`)
+    pub temperature: Option<f64>,
+    #[validate(range(min = 0.0, max = 100.0, message = "Humidity must be 0-100%"))]
+    pub humidity: Option<f64>,
+    #[validate(range(min = 0.0, max = 10.0, message = "Pressure must be 0-10 bar"))]
+    pub pressure: Option<f64>,
+    #[validate(range(min = 0.0, max = 100000.0, message = "CO2 must be 0-100000 ppm"))]
+    pub co2: Option<f64>,
+    #[validate(range(min = 0.0, max = 20.0, message = "Dissolved oxygen must be 0-20 mg/L"))]
+    pub dissolved_oxygen: Option<f64>,
+    #[validate(range(min = 0.0, max = 100.0, message = "Level must be 0-100%"))]
+    pub level: Option<f64>,
+    #[validate(range(min = 0.0, max = 100.0, message = "Battery must be 0-100%"))]
+    pub battery_percent: Option<f64>,
+    #[validate(length(max = 50))]
+    pub firmware_version: Option<String>,
     pub recorded_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Serialize)]
+impl IotReadingRequest {
+    /// Kanali pored temperature koje je senzor poslao
+    pub fn sensor_values(&self) -> Vec<(SensorChannel, f64)> {
+        [
+            (SensorChannel::Humidity, self.humidity),
+            (SensorChannel::Pressure, self.pressure),
+            (SensorChannel::Co2, self.co2),
+            (SensorChannel::DissolvedOxygen, self.dissolved_oxygen),
+            (SensorChannel::Level, self.level),
+        ]
+        .into_iter()
+        .filter_map(|(channel, value)| value.map(|v| (channel, v)))
+        .collect()
+    }
+
+    pub fn has_measurement(&self) -> bool {
+        self.temperature.is_some() || !self.sensor_values().is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct ReadingResponse {
     pub id: Uuid,
     pub batch_id: Uuid,
@@ -327,6 +423,10 @@ pub struct ReadingResponse {
     pub clarity: Option<String>,
     pub aroma_notes: Option<String>,
     pub source: String,
+    pub device_id: Option<Uuid>,
+    pub source_device: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub sensor_values: Vec<SensorValue>,
     pub notes: Option<String>,
     pub recorded_at: DateTime<Utc>,
 }
@@ -348,6 +448,9 @@ impl From<FermentationReading> for ReadingResponse {
             clarity: r.clarity,
             aroma_notes: r.aroma_notes,
             source: r.source,
+            device_id: r.device_id,
+            source_device: r.source_device,
+            sensor_values: vec![],
             notes: r.notes,
             recorded_at: r.recorded_at,
         }
@@ -364,4 +467,26 @@ pub struct BatchStats {
     pub latest_brix: Option<f64>,
     pub latest_ph: Option<f64>,
     pub latest_alcohol: Option<f64>,
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paused_batch_keeps_tank_occupied() {
+        assert!(FermentationStatus::Active.occupies_tank());
+        assert!(FermentationStatus::Paused.occupies_tank());
+        assert!(!FermentationStatus::Completed.occupies_tank());
+        assert!(!FermentationStatus::Cancelled.occupies_tank());
+    }
+
+    #[test]
+    fn test_derive_tank_status() {
+        assert_eq!(derive_tank_status(&TankStatus::Available, true), TankStatus::InUse);
+        assert_eq!(derive_tank_status(&TankStatus::InUse, false), TankStatus::Cleaning);
+        assert_eq!(derive_tank_status(&TankStatus::Available, false), TankStatus::Available);
+        assert_eq!(derive_tank_status(&TankStatus::Cleaning, false), TankStatus::Cleaning);
+        assert_eq!(derive_tank_status(&TankStatus::InUse, true), TankStatus::InUse);
+    }
+}
@@ -0,0 +1,284 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::TankStatus;
+
+/// Najveći ATP bris (RLU) koji se smatra čistom površinom za kontakt sa vinom
+pub const ATP_PASS_MAX_RLU: f64 = 150.0;
+pub const DEFAULT_DUE_WITHIN_DAYS: i64 = 14;
+
+// ============== Enums ==============
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "cleaning_kind", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum CleaningKind {
+    /// Alkalno/kiselo pranje (uklanjanje vinskog kamena i taloga)
+    Cleaning,
+    /// Dezinfekcija (persirćetna kiselina, para, ozon...)
+    Sanitation,
+    CleaningAndSanitation,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "cleaning_verification", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum CleaningVerification {
+    Passed,
+    Failed,
+    NotVerified,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "maintenance_kind", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum MaintenanceKind {
+    ValveSeals,
+    CoolingJacket,
+    Gaskets,
+    PressureTest,
+    Calibration,
+    Other,
+}
+
+// ============== Čišćenje i sanitacija ==============
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct TankCleaning {
+    pub id: Uuid,
+    pub tank_id: Uuid,
+    pub kind: CleaningKind,
+    pub procedure: String,
+    pub chemicals: Vec<String>,
+    pub water_temperature: Option<f64>,
+    pub duration_minutes: Option<i32>,
+    pub atp_rlu: Option<f64>,
+    pub visual_check: Option<bool>,
+    pub verification: CleaningVerification,
+    pub operator_id: Uuid,
+    pub operator_email: String,
+    pub performed_at: DateTime<Utc>,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateCleaningRequest {
+    pub kind: CleaningKind,
+
+    #[validate(length(min = 3, message = "Procedure must be at least 3 characters"))]
+    pub procedure: String,
+
+    /// Sredstva sa koncentracijom, npr. "NaOH 2%"
+    #[serde(default)]
+    pub chemicals: Vec<String>,
+
+    #[validate(range(min = 0.0, max = 100.0, message = "Water temperature must be 0-100°C"))]
+    pub water_temperature: Option<f64>,
+
+    #[validate(range(min = 1, message = "Duration must be positive"))]
+    pub duration_minutes: Option<i32>,
+
+    #[validate(range(min = 0.0, message = "ATP result cannot be negative"))]
+    pub atp_rlu: Option<f64>,
+
+    pub visual_check: Option<bool>,
+    pub performed_at: Option<DateTime<Utc>>,
+    pub notes: Option<String>,
+}
+
+/// Upisano čišćenje i status tanka posle njega
+#[derive(Debug, Serialize)]
+pub struct CleaningResponse {
+    #[serde(flatten)]
+    pub cleaning: TankCleaning,
+    pub tank_status: TankStatus,
+}
+
+/// Pao vizuelni pregled ili ATP bris obara čišćenje; bez ijedne provere
+/// čišćenje ostaje neprovereno i ne oslobađa tank
+pub fn cleaning_verification(atp_rlu: Option<f64>, visual_check: Option<bool>) -> CleaningVerification {
+    match (atp_rlu, visual_check) {
+        (_, Some(false)) => CleaningVerification::Failed,
+        (Some(rlu), _) if rlu > ATP_PASS_MAX_RLU => CleaningVerification::Failed,
+        (None, None) => CleaningVerification::NotVerified,
+        _ => CleaningVerification::Passed,
+    }
+}
+
+// ============== Održavanje ==============
+
+/// Planirano održavanje; sa `interval_days` se ponavlja
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct MaintenanceTask {
+    pub id: Uuid,
+    pub tank_id: Uuid,
+    pub kind: MaintenanceKind,
+    pub title: String,
+    pub due_date: NaiveDate,
+    pub interval_days: Option<i32>,
+    pub active: bool,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateMaintenanceTaskRequest {
+    pub kind: MaintenanceKind,
+
+    #[validate(length(min = 2, max = 255, message = "Title must be 2-255 characters"))]
+    pub title: String,
+
+    pub due_date: NaiveDate,
+
+    #[validate(range(min = 1, max = 3650, message = "Interval must be 1-3650 days"))]
+    pub interval_days: Option<i32>,
+
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateMaintenanceTaskRequest {
+    pub kind: Option<MaintenanceKind>,
+
+    #[validate(length(min = 2, max = 255))]
+    pub title: Option<String>,
+
+    pub due_date: Option<NaiveDate>,
+
+    #[validate(range(min = 1, max = 3650))]
+    pub interval_days: Option<i32>,
+
+    pub active: Option<bool>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct MaintenanceRecord {
+    pub id: Uuid,
+    pub tank_id: Uuid,
+    pub task_id: Option<Uuid>,
+    pub kind: MaintenanceKind,
+    pub description: String,
+    pub operator_id: Uuid,
+    pub operator_email: String,
+    pub performed_at: DateTime<Utc>,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Izvršeno održavanje; uz `task_id` vrsta se preuzima iz zadatka
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateMaintenanceRecordRequest {
+    pub task_id: Option<Uuid>,
+    pub kind: Option<MaintenanceKind>,
+
+    #[validate(length(min = 3, message = "Description must be at least 3 characters"))]
+    pub description: String,
+
+    pub performed_at: Option<DateTime<Utc>>,
+    pub notes: Option<String>,
+}
+
+/// Upisano održavanje i zadatak posle njega (novi rok ili zatvoren)
+#[derive(Debug, Serialize)]
+pub struct MaintenanceRecordResponse {
+    #[serde(flatten)]
+    pub record: MaintenanceRecord,
+    pub task: Option<MaintenanceTask>,
+}
+
+/// Sledeći rok ponavljajućeg zadatka računa se od dana izvršenja;
+/// jednokratni zadatak nema sledeći rok
+pub fn next_due_date(task: &MaintenanceTask, performed_on: NaiveDate) -> Option<NaiveDate> {
+    task.interval_days
+        .map(|days| performed_on + Duration::days(days as i64))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DueMaintenanceQuery {
+    pub within_days: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DueMaintenance {
+    #[serde(flatten)]
+    pub task: MaintenanceTask,
+    pub tank_name: String,
+    /// Negativno za zakasnele zadatke
+    pub days_until_due: i64,
+    pub overdue: bool,
+}
+
+impl DueMaintenance {
+    pub fn new(task: MaintenanceTask, tank_name: String, today: NaiveDate) -> Self {
+        let days_until_due = (task.due_date - today).num_days();
+
+        Self {
+            task,
+            tank_name,
+            days_until_due,
+            overdue: days_until_due < 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(interval_days: Option<i32>, due_date: NaiveDate) -> MaintenanceTask {
+        let now = Utc::now();
+        MaintenanceTask {
+            id: Uuid::new_v4(),
+            tank_id: Uuid::new_v4(),
+            kind: MaintenanceKind::ValveSeals,
+            title: "Zamena zaptivki ventila".to_string(),
+            due_date,
+            interval_days,
+            active: true,
+            notes: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_cleaning_verification() {
+        assert_eq!(cleaning_verification(Some(40.0), Some(true)), CleaningVerification::Passed);
+        assert_eq!(cleaning_verification(Some(40.0), None), CleaningVerification::Passed);
+        assert_eq!(cleaning_verification(None, Some(true)), CleaningVerification::Passed);
+        assert_eq!(cleaning_verification(Some(420.0), Some(true)), CleaningVerification::Failed);
+        assert_eq!(cleaning_verification(Some(40.0), Some(false)), CleaningVerification::Failed);
+        assert_eq!(cleaning_verification(None, None), CleaningVerification::NotVerified);
+    }
+
+    #[test]
+    fn test_next_due_date() {
+        let due = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        let performed = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+
+        assert_eq!(
+            next_due_date(&task(Some(180), due), performed),
+            NaiveDate::from_ymd_opt(2026, 9, 6)
+        );
+        assert_eq!(next_due_date(&task(None, due), performed), None);
+    }
+
+    #[test]
+    fn test_due_maintenance_overdue() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+
+        let late = DueMaintenance::new(task(None, today - Duration::days(3)), "T1".to_string(), today);
+        assert_eq!(late.days_until_due, -3);
+        assert!(late.overdue);
+
+        let upcoming = DueMaintenance::new(task(None, today + Duration::days(5)), "T1".to_string(), today);
+        assert_eq!(upcoming.days_until_due, 5);
+        assert!(!upcoming.overdue);
+    }
+}
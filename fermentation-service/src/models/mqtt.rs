@@ -0,0 +1,191 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::models::IotReadingRequest;
+
+/// Na šta se MQTT poruka odnosi, prema topic-u
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MqttTarget {
+    /// Merenje ide aktivnom batch-u u tanku
+    Tank(Uuid),
+    Batch(Uuid),
+    /// Registrovan uređaj; tank se uzima iz registra
+    Device,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MqttTopic {
+    pub target: MqttTarget,
+    pub device_id: String,
+}
+
+/// Topic filteri na koje se servis pretplaćuje
+pub fn mqtt_subscriptions(prefix: &str) -> [String; 3] {
+    [
+        format!("{}/devices/+/readings", prefix),
+        format!("{}/tanks/+/+/readings", prefix),
+        format!("{}/batches/+/+/readings", prefix),
+    ]
+}
+
+/// `{prefix}/devices/{device_id}/readings`,
+/// `{prefix}/tanks/{tank_id}/{device_id}/readings` ili
+/// `{prefix}/batches/{batch_id}/{device_id}/readings`
+pub fn parse_topic(prefix: &str, topic: &str) -> Result<MqttTopic, String> {
+    let rest = topic
+        .strip_prefix(prefix)
+        .and_then(|rest| rest.strip_prefix('/'))
+        .ok_or_else(|| format!("Unexpected topic '{}'", topic))?;
+
+    let parts: Vec<&str> = rest.split('/').collect();
+    if let ["devices", device_id, "readings"] = parts[..] {
+        if device_id.is_empty() {
+            return Err("Device id is missing from topic".to_string());
+        }
+        return Ok(MqttTopic {
+            target: MqttTarget::Device,
+            device_id: device_id.to_string(),
+        });
+    }
+
+    let [scope, id, device_id, "readings"] = parts[..] else {
+        return Err(format!("Unexpected topic '{}'", topic));
+    };
+
+    let id = Uuid::parse_str(id).map_err(|_| format!("Invalid id '{}' in topic", id))?;
+    let target = match scope {
+        "tanks" => MqttTarget::Tank(id),
+        "batches" => MqttTarget::Batch(id),
+        _ => return Err(format!("Unexpected topic '{}'", topic)),
+    };
+    if device_id.is_empty() {
+        return Err("Device id is missing from topic".to_string());
+    }
+
+    Ok(MqttTopic {
+        target,
+        device_id: device_id.to_string(),
+    })
+}
+
+/// JSON koji senzor šalje (npr. ESP32 sa DHT11)
+#[derive(Debug, Deserialize)]
+pub struct MqttReadingPayload {
+    pub temperature: Option<f64>,
+    pub humidity: Option<f64>,
+    pub pressure: Option<f64>,
+    pub co2: Option<f64>,
+    pub dissolved_oxygen: Option<f64>,
+    pub level: Option<f64>,
+    pub battery_percent: Option<f64>,
+    pub firmware_version: Option<String>,
+    pub recorded_at: Option<DateTime<Utc>>,
+}
+
+impl MqttReadingPayload {
+    pub fn into_request(self, batch_id: Option<Uuid>, device_id: String) -> IotReadingRequest {
+        IotReadingRequest {
+            batch_id,
+            device_id: Some(device_id),
+            temperature: self.temperature,
+            humidity: self.humidity,
+            pressure: self.pressure,
+            co2: self.co2,
+            dissolved_oxygen: self.dissolved_oxygen,
+            level: self.level,
+            battery_percent: self.battery_percent,
+            firmware_version: self.firmware_version,
+            recorded_at: self.recorded_at,
+        }
+    }
+}
+
+/// Poruka nosi jedno merenje ili niz merenja (uređaj prazni bafer posle prekida veze)
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum MqttPayload {
+    Batch(Vec<MqttReadingPayload>),
+    Single(MqttReadingPayload),
+}
+
+impl MqttPayload {
+    pub fn into_requests(self, batch_id: Option<Uuid>, device_id: &str) -> Vec<IotReadingRequest> {
+        let payloads = match self {
+            MqttPayload::Batch(payloads) => payloads,
+            MqttPayload::Single(payload) => vec![payload],
+        };
+
+        payloads
+            .into_iter()
+            .map(|p| p.into_request(batch_id, device_id.to_string()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_topic() {
+        let tank_id = Uuid::new_v4();
+        let topic = format!("vinomonitor/tanks/{}/esp32-04/readings", tank_id);
+
+        assert_eq!(
+            parse_topic("vinomonitor", &topic),
+            Ok(MqttTopic {
+                target: MqttTarget::Tank(tank_id),
+                device_id: "esp32-04".to_string(),
+            })
+        );
+
+        let topic = format!("vinomonitor/batches/{}/probe/readings", tank_id);
+        assert_eq!(parse_topic("vinomonitor", &topic).unwrap().target, MqttTarget::Batch(tank_id));
+
+        let parsed = parse_topic("vinomonitor", "vinomonitor/devices/esp32-04/readings").unwrap();
+        assert_eq!(parsed.target, MqttTarget::Device);
+        assert_eq!(parsed.device_id, "esp32-04");
+    }
+
+    #[test]
+    fn test_parse_topic_rejects_unknown() {
+        let id = Uuid::new_v4();
+
+        assert!(parse_topic("vinomonitor", &format!("other/tanks/{}/d/readings", id)).is_err());
+        assert!(parse_topic("vinomonitor", &format!("vinomonitor/cellars/{}/d/readings", id)).is_err());
+        assert!(parse_topic("vinomonitor", "vinomonitor/tanks/not-a-uuid/d/readings").is_err());
+        assert!(parse_topic("vinomonitor", &format!("vinomonitor/tanks/{}/d/status", id)).is_err());
+    }
+
+    #[test]
+    fn test_payload_to_request() {
+        let payload: MqttReadingPayload =
+            serde_json::from_str(r#"{"temperature": 23.4, "humidity": 61.0}"#).unwrap();
+        let batch_id = Uuid::new_v4();
+
+        let req = payload.into_request(Some(batch_id), "esp32-04".to_string());
+        assert_eq!(req.batch_id, Some(batch_id));
+        assert_eq!(req.device_id.as_deref(), Some("esp32-04"));
+        assert_eq!(req.temperature, Some(23.4));
+        assert_eq!(req.humidity, Some(61.0));
+        assert!(req.recorded_at.is_none());
+    }
+
+    #[test]
+    fn test_payload_array() {
+        let payload: MqttPayload = serde_json::from_str(
+            r#"[{"temperature": 18.1, "recorded_at": "2026-10-18T10:00:00Z"},
+                {"co2": 1800, "recorded_at": "2026-10-18T10:01:00Z"}]"#,
+        )
+        .unwrap();
+
+        let requests = payload.into_requests(None, "esp32-04");
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].co2, Some(1800.0));
+        assert!(requests.iter().all(|r| r.device_id.as_deref() == Some("esp32-04")));
+
+        let single: MqttPayload = serde_json::from_str(r#"{"temperature": 18.1}"#).unwrap();
+        assert_eq!(single.into_requests(None, "esp32-04").len(), 1);
+    }
+}
@@ -0,0 +1,239 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::{IotDevice, IotReadingRequest, ReadingMetric, ReadingResponse};
+
+/// Najviše merenja u jednom IoT upload-u
+pub const IOT_BATCH_MAX: usize = 1000;
+/// Opseg temperature (°C) koju senzor sme da prijavi, posle kalibracije
+pub const IOT_TEMPERATURE_MIN: f64 = -5.0;
+pub const IOT_TEMPERATURE_MAX: f64 = 45.0;
+
+/// Kanali pored temperature: vlažnost (%RH), pritisak (bar), CO2 (ppm),
+/// rastvoreni kiseonik (mg/L) i nivo u tanku (%)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "sensor_channel", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SensorChannel {
+    Humidity,
+    Pressure,
+    Co2,
+    DissolvedOxygen,
+    Level,
+}
+
+impl SensorChannel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SensorChannel::Humidity => "humidity",
+            SensorChannel::Pressure => "pressure",
+            SensorChannel::Co2 => "co2",
+            SensorChannel::DissolvedOxygen => "dissolved_oxygen",
+            SensorChannel::Level => "level",
+        }
+    }
+
+    pub fn metric(&self) -> ReadingMetric {
+        match self {
+            SensorChannel::Humidity => ReadingMetric::Humidity,
+            SensorChannel::Pressure => ReadingMetric::Pressure,
+            SensorChannel::Co2 => ReadingMetric::Co2,
+            SensorChannel::DissolvedOxygen => ReadingMetric::DissolvedOxygen,
+            SensorChannel::Level => ReadingMetric::Level,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct SensorValue {
+    #[serde(skip)]
+    pub reading_id: Uuid,
+    pub channel: SensorChannel,
+    pub value: f64,
+}
+
+/// Više merenja u jednom zahtevu (uređaj koji šalje u serijama ili prazni bafer)
+#[derive(Debug, Deserialize, Validate)]
+pub struct IotReadingBatchRequest {
+    #[validate(nested)]
+    pub readings: Vec<IotReadingRequest>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct IotIngestSummary {
+    pub received: usize,
+    pub stored: usize,
+    /// Već upisana merenja (isti uređaj i vreme) - preskočena
+    pub duplicates: usize,
+    #[serde(skip)]
+    pub readings: Vec<ReadingResponse>,
+}
+
+/// Merenja iz jednog izvora (batch, uređaj) koja se upisuju zajedno
+#[derive(Debug)]
+pub struct IotSampleGroup {
+    pub batch_id: Option<Uuid>,
+    pub device_id: Option<String>,
+    pub samples: Vec<IotReadingRequest>,
+}
+
+impl IotSampleGroup {
+    /// Koriguje temperature kalibracijom uređaja, pa tek onda proverava opseg
+    pub fn calibrate(&mut self, device: Option<&IotDevice>) -> Result<(), String> {
+        for sample in &mut self.samples {
+            let Some(raw) = sample.temperature else {
+                continue;
+            };
+            let temperature = device.map_or(raw, |d| d.calibrate(raw));
+            if !(IOT_TEMPERATURE_MIN..=IOT_TEMPERATURE_MAX).contains(&temperature) {
+                return Err(format!(
+                    "Temperature must be {} to {}°C, got {:.1}°C",
+                    IOT_TEMPERATURE_MIN, IOT_TEMPERATURE_MAX, temperature
+                ));
+            }
+            sample.temperature = Some(temperature);
+        }
+
+        Ok(())
+    }
+}
+
+/// Grupa sa razrešenim batch-om i uređajem, spremna za upis
+#[derive(Debug)]
+pub struct IotIngestTarget {
+    pub batch_id: Uuid,
+    pub device: Option<IotDevice>,
+    pub group: IotSampleGroup,
+}
+
+/// Grupisanje po izvoru uz očuvan redosled, da se batch i uređaj razreše jednom po grupi
+pub fn group_iot_samples(samples: Vec<IotReadingRequest>) -> Vec<IotSampleGroup> {
+    let mut groups: Vec<IotSampleGroup> = Vec::new();
+
+    for sample in samples {
+        match groups
+            .iter_mut()
+            .find(|g| g.batch_id == sample.batch_id && g.device_id == sample.device_id)
+        {
+            Some(group) => group.samples.push(sample),
+            None => groups.push(IotSampleGroup {
+                batch_id: sample.batch_id,
+                device_id: sample.device_id.clone(),
+                samples: vec![sample],
+            }),
+        }
+    }
+
+    groups
+}
+
+/// Bez vremena merenja duplikati se ne mogu prepoznati, pa serija mora da ga ima
+pub fn check_iot_batch(samples: &[IotReadingRequest]) -> Result<(), String> {
+    if samples.len() > 1 && samples.iter().any(|s| s.recorded_at.is_none()) {
+        return Err("Every reading in a batch upload needs recorded_at".to_string());
+    }
+    if let Some(empty) = samples.iter().position(|s| !s.has_measurement()) {
+        return Err(format!("Reading #{} has no measurements", empty + 1));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    fn sample(device_id: Option<&str>, temperature: Option<f64>) -> IotReadingRequest {
+        IotReadingRequest {
+            batch_id: None,
+            device_id: device_id.map(str::to_string),
+            temperature,
+            humidity: None,
+            pressure: None,
+            co2: None,
+            dissolved_oxygen: None,
+            level: None,
+            battery_percent: None,
+            firmware_version: None,
+            recorded_at: Some(Utc::now()),
+        }
+    }
+
+    #[test]
+    fn test_sensor_values() {
+        let mut reading = sample(Some("esp32-01"), Some(18.5));
+        reading.humidity = Some(72.0);
+        reading.co2 = Some(1450.0);
+
+        assert_eq!(
+            reading.sensor_values(),
+            vec![(SensorChannel::Humidity, 72.0), (SensorChannel::Co2, 1450.0)]
+        );
+        assert!(sample(None, None).sensor_values().is_empty());
+    }
+
+    #[test]
+    fn test_group_iot_samples_keeps_order() {
+        let samples = vec![
+            sample(Some("a"), Some(20.0)),
+            sample(Some("b"), Some(21.0)),
+            sample(Some("a"), Some(20.5)),
+        ];
+
+        let groups = group_iot_samples(samples);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].device_id.as_deref(), Some("a"));
+        assert_eq!(groups[0].samples.len(), 2);
+        assert_eq!(groups[0].samples[1].temperature, Some(20.5));
+        assert_eq!(groups[1].device_id.as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn test_check_iot_batch() {
+        let mut untimed = sample(Some("a"), Some(20.0));
+        untimed.recorded_at = None;
+        assert!(check_iot_batch(std::slice::from_ref(&untimed)).is_ok());
+
+        let mut timed = sample(Some("a"), Some(20.0));
+        timed.recorded_at = Some(Utc::now() - Duration::minutes(1));
+        assert!(check_iot_batch(&[timed, untimed]).is_err());
+
+        let mut level_only = sample(Some("a"), None);
+        assert!(check_iot_batch(std::slice::from_ref(&level_only)).is_err());
+        level_only.level = Some(84.0);
+        assert!(check_iot_batch(&[level_only]).is_ok());
+    }
+
+    #[test]
+    fn test_calibrate_checks_corrected_temperature() {
+        let mut probe = IotDevice {
+            id: Uuid::new_v4(),
+            device_id: "a".to_string(),
+            name: "a".to_string(),
+            device_type: crate::models::IotDeviceType::TemperatureHumidity,
+            firmware_version: None,
+            tank_id: None,
+            calibration_offset: -1.0,
+            battery_percent: None,
+            last_seen_at: None,
+            active: true,
+            notes: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        // 45.5°C sa senzora je posle kalibracije 44.5°C
+        let mut group = group_iot_samples(vec![sample(Some("a"), Some(45.5))]).remove(0);
+        assert!(group.calibrate(Some(&probe)).is_ok());
+        assert_eq!(group.samples[0].temperature, Some(44.5));
+
+        probe.calibration_offset = 2.0;
+        let mut group = group_iot_samples(vec![sample(Some("a"), Some(44.0))]).remove(0);
+        assert!(group.calibrate(Some(&probe)).is_err());
+        assert!(group.calibrate(None).is_ok());
+    }
+}
@@ -0,0 +1,336 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::models::{FermentationReading, SensorValue};
+
+/// Najviše tačaka po seriji u jednom odgovoru
+pub const MAX_SERIES_BUCKETS: i64 = 5000;
+/// Najviše sirovih merenja u jednom odgovoru
+pub const MAX_RAW_POINTS: i64 = 10000;
+
+/// Rezolucija agregata koji se čuvaju u bazi
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "rollup_resolution", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum RollupResolution {
+    FiveMinutes,
+    Hour,
+    Day,
+}
+
+impl RollupResolution {
+    pub const ALL: [RollupResolution; 3] = [
+        RollupResolution::FiveMinutes,
+        RollupResolution::Hour,
+        RollupResolution::Day,
+    ];
+
+    /// Interval za `date_bin` u SQL-u
+    pub fn interval(&self) -> &'static str {
+        match self {
+            RollupResolution::FiveMinutes => "5 minutes",
+            RollupResolution::Hour => "1 hour",
+            RollupResolution::Day => "1 day",
+        }
+    }
+
+    pub fn duration(&self) -> Duration {
+        match self {
+            RollupResolution::FiveMinutes => Duration::minutes(5),
+            RollupResolution::Hour => Duration::hours(1),
+            RollupResolution::Day => Duration::days(1),
+        }
+    }
+}
+
+/// Rezolucija koju traži klijent; `raw` vraća pojedinačna merenja
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SeriesResolution {
+    Raw,
+    FiveMinutes,
+    Hour,
+    Day,
+}
+
+impl SeriesResolution {
+    pub fn rollup(&self) -> Option<RollupResolution> {
+        match self {
+            SeriesResolution::Raw => None,
+            SeriesResolution::FiveMinutes => Some(RollupResolution::FiveMinutes),
+            SeriesResolution::Hour => Some(RollupResolution::Hour),
+            SeriesResolution::Day => Some(RollupResolution::Day),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, PartialOrd, Ord)]
+#[sqlx(type_name = "reading_metric", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ReadingMetric {
+    Temperature,
+    Brix,
+    Density,
+    Ph,
+    Humidity,
+    Pressure,
+    Co2,
+    DissolvedOxygen,
+    Level,
+}
+
+impl ReadingMetric {
+    pub const ALL: [ReadingMetric; 9] = [
+        ReadingMetric::Temperature,
+        ReadingMetric::Brix,
+        ReadingMetric::Density,
+        ReadingMetric::Ph,
+        ReadingMetric::Humidity,
+        ReadingMetric::Pressure,
+        ReadingMetric::Co2,
+        ReadingMetric::DissolvedOxygen,
+        ReadingMetric::Level,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReadingMetric::Temperature => "temperature",
+            ReadingMetric::Brix => "brix",
+            ReadingMetric::Density => "density",
+            ReadingMetric::Ph => "ph",
+            ReadingMetric::Humidity => "humidity",
+            ReadingMetric::Pressure => "pressure",
+            ReadingMetric::Co2 => "co2",
+            ReadingMetric::DissolvedOxygen => "dissolved_oxygen",
+            ReadingMetric::Level => "level",
+        }
+    }
+
+    pub fn unit(&self) -> &'static str {
+        match self {
+            ReadingMetric::Temperature => "°C",
+            ReadingMetric::Brix => "°Bx",
+            ReadingMetric::Density => "g/mL",
+            ReadingMetric::Ph => "pH",
+            ReadingMetric::Humidity => "%RH",
+            ReadingMetric::Pressure => "bar",
+            ReadingMetric::Co2 => "ppm",
+            ReadingMetric::DissolvedOxygen => "mg/L",
+            ReadingMetric::Level => "%",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SeriesQuery {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// Bez rezolucije bira se prema dužini perioda
+    pub resolution: Option<SeriesResolution>,
+    /// Lista metrika odvojenih zarezom, npr. `temperature,brix`; podrazumevano sve
+    pub metrics: Option<String>,
+}
+
+/// Jedan interval jedne metrike (iz agregata ili sirovog merenja)
+#[derive(Debug, Clone, FromRow)]
+pub struct SeriesRow {
+    pub metric: ReadingMetric,
+    pub bucket_start: DateTime<Utc>,
+    pub min_value: f64,
+    pub max_value: f64,
+    pub avg_value: f64,
+    pub sample_count: i32,
+}
+
+/// Tačka grafika; `t` je početak intervala (ili vreme merenja za `raw`)
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SeriesPoint {
+    pub t: DateTime<Utc>,
+    pub avg: f64,
+    pub min: f64,
+    pub max: f64,
+    pub count: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MetricSeries {
+    pub metric: ReadingMetric,
+    pub unit: &'static str,
+    pub points: Vec<SeriesPoint>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReadingSeries {
+    pub batch_id: Uuid,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub resolution: SeriesResolution,
+    /// Agregati su ažurirani do ovog trenutka; novija merenja stižu preko live stream-a
+    pub rolled_up_at: Option<DateTime<Utc>>,
+    pub series: Vec<MetricSeries>,
+}
+
+pub fn parse_metrics(value: Option<&str>) -> Result<Vec<ReadingMetric>, String> {
+    let Some(value) = value.filter(|v| !v.trim().is_empty()) else {
+        return Ok(ReadingMetric::ALL.to_vec());
+    };
+
+    let mut metrics = Vec::new();
+    for name in value.split(',').map(str::trim) {
+        let metric = ReadingMetric::ALL
+            .into_iter()
+            .find(|m| m.as_str() == name)
+            .ok_or_else(|| format!("Unknown metric '{}'", name))?;
+        if !metrics.contains(&metric) {
+            metrics.push(metric);
+        }
+    }
+
+    Ok(metrics)
+}
+
+/// Rezolucija tako da grafik ima najviše oko 1500 tačaka
+pub fn auto_resolution(from: DateTime<Utc>, to: DateTime<Utc>) -> SeriesResolution {
+    let span = to - from;
+    if span <= Duration::days(5) {
+        SeriesResolution::FiveMinutes
+    } else if span <= Duration::days(60) {
+        SeriesResolution::Hour
+    } else {
+        SeriesResolution::Day
+    }
+}
+
+/// Sirova merenja kao redovi serije (svako merenje je "interval" sa jednim uzorkom)
+pub fn raw_series_rows(readings: &[FermentationReading], sensor_values: &[SensorValue]) -> Vec<SeriesRow> {
+    let row = |metric, at, value| SeriesRow {
+        metric,
+        bucket_start: at,
+        min_value: value,
+        max_value: value,
+        avg_value: value,
+        sample_count: 1,
+    };
+
+    let mut rows = Vec::new();
+    for reading in readings {
+        let fields = [
+            (ReadingMetric::Temperature, reading.temperature),
+            (ReadingMetric::Brix, reading.brix),
+            (ReadingMetric::Density, reading.density),
+            (ReadingMetric::Ph, reading.ph),
+        ];
+        for (metric, value) in fields {
+            if let Some(value) = value {
+                rows.push(row(metric, reading.recorded_at, value));
+            }
+        }
+        for value in sensor_values.iter().filter(|v| v.reading_id == reading.id) {
+            rows.push(row(value.channel.metric(), reading.recorded_at, value.value));
+        }
+    }
+
+    rows
+}
+
+/// Serije po traženim metrikama (redosledom iz upita), tačke sortirane po vremenu
+pub fn build_series(metrics: &[ReadingMetric], rows: Vec<SeriesRow>) -> Vec<MetricSeries> {
+    let mut series: Vec<MetricSeries> = metrics
+        .iter()
+        .map(|metric| MetricSeries {
+            metric: *metric,
+            unit: metric.unit(),
+            points: Vec::new(),
+        })
+        .collect();
+
+    for row in rows {
+        if let Some(s) = series.iter_mut().find(|s| s.metric == row.metric) {
+            s.points.push(SeriesPoint {
+                t: row.bucket_start,
+                avg: row.avg_value,
+                min: row.min_value,
+                max: row.max_value,
+                count: row.sample_count,
+            });
+        }
+    }
+
+    for s in &mut series {
+        s.points.sort_by_key(|p| p.t);
+    }
+
+    series
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SensorChannel;
+
+    fn reading(at: DateTime<Utc>, temperature: Option<f64>, brix: Option<f64>) -> FermentationReading {
+        FermentationReading {
+            id: Uuid::new_v4(),
+            batch_id: Uuid::nil(),
+            temperature,
+            brix,
+            ph: None,
+            density: None,
+            alcohol_percent: None,
+            volatile_acidity: None,
+            free_so2: None,
+            total_so2: None,
+            color: None,
+            clarity: None,
+            aroma_notes: None,
+            source: "iot".to_string(),
+            device_id: None,
+            source_device: None,
+            notes: None,
+            recorded_at: at,
+            created_at: at,
+        }
+    }
+
+    #[test]
+    fn test_parse_metrics() {
+        assert_eq!(parse_metrics(None).unwrap().len(), ReadingMetric::ALL.len());
+        assert_eq!(
+            parse_metrics(Some("brix, temperature,brix")).unwrap(),
+            vec![ReadingMetric::Brix, ReadingMetric::Temperature]
+        );
+        assert!(parse_metrics(Some("temperature,sugar")).is_err());
+    }
+
+    #[test]
+    fn test_auto_resolution() {
+        let now = Utc::now();
+
+        assert_eq!(auto_resolution(now - Duration::days(1), now), SeriesResolution::FiveMinutes);
+        assert_eq!(auto_resolution(now - Duration::days(30), now), SeriesResolution::Hour);
+        assert_eq!(auto_resolution(now - Duration::days(365), now), SeriesResolution::Day);
+    }
+
+    #[test]
+    fn test_raw_rows_build_sorted_series() {
+        let now = Utc::now();
+        let later = reading(now, Some(24.5), None);
+        let earlier = reading(now - Duration::minutes(10), Some(24.0), Some(18.2));
+        let humidity = SensorValue {
+            reading_id: later.id,
+            channel: SensorChannel::Humidity,
+            value: 64.0,
+        };
+
+        let rows = raw_series_rows(&[later, earlier], &[humidity]);
+        let series = build_series(&[ReadingMetric::Temperature, ReadingMetric::Humidity], rows);
+
+        assert_eq!(series.len(), 2);
+        let temperatures: Vec<f64> = series[0].points.iter().map(|p| p.avg).collect();
+        assert_eq!(temperatures, vec![24.0, 24.5]);
+        assert_eq!(series[1].unit, "%RH");
+        assert_eq!(series[1].points[0].max, 64.0);
+    }
+}
@@ -0,0 +1,128 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::{Alert, AlertEvent, ReadingResponse};
+
+/// Događaj koji se šalje preko WebSocket-a i SSE-a
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamEvent {
+    Reading {
+        batch_id: Uuid,
+        tank_id: Uuid,
+        reading: ReadingResponse,
+    },
+    Alert {
+        event: AlertEvent,
+        alert: Alert,
+    },
+    /// Klijent je bio prespor pa je propustio `skipped` događaja
+    Lagged { skipped: u64 },
+}
+
+impl StreamEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            StreamEvent::Reading { .. } => "reading",
+            StreamEvent::Alert { .. } => "alert",
+            StreamEvent::Lagged { .. } => "lagged",
+        }
+    }
+
+    fn scope(&self) -> Option<(Uuid, Uuid)> {
+        match self {
+            StreamEvent::Reading { batch_id, tank_id, .. } => Some((*batch_id, *tank_id)),
+            StreamEvent::Alert { alert, .. } => Some((alert.batch_id, alert.tank_id)),
+            StreamEvent::Lagged { .. } => None,
+        }
+    }
+}
+
+/// Filter pretplate: jedan batch, jedan tank ili (bez oba) svi aktivni batch-evi
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct StreamQuery {
+    pub batch_id: Option<Uuid>,
+    pub tank_id: Option<Uuid>,
+}
+
+impl StreamQuery {
+    pub fn matches(&self, event: &StreamEvent) -> bool {
+        match event.scope() {
+            Some((batch_id, tank_id)) => {
+                self.batch_id.is_none_or(|id| id == batch_id)
+                    && self.tank_id.is_none_or(|id| id == tank_id)
+            }
+            None => true,
+        }
+    }
+}
+
+/// Jednokratna karta za otvaranje WebSocket/SSE veze
+#[derive(Debug, Serialize)]
+pub struct StreamTicketResponse {
+    pub ticket: Uuid,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StreamTicketQuery {
+    pub ticket: Uuid,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reading_event(batch_id: Uuid, tank_id: Uuid) -> StreamEvent {
+        StreamEvent::Reading {
+            batch_id,
+            tank_id,
+            reading: ReadingResponse {
+                id: Uuid::new_v4(),
+                batch_id,
+                temperature: Some(24.5),
+                brix: None,
+                ph: None,
+                density: None,
+                alcohol_percent: None,
+                volatile_acidity: None,
+                free_so2: None,
+                total_so2: None,
+                color: None,
+                clarity: None,
+                aroma_notes: None,
+                source: "iot".to_string(),
+                device_id: None,
+                source_device: Some("esp32-01".to_string()),
+                sensor_values: vec![],
+                notes: None,
+                recorded_at: Utc::now(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_stream_filter() {
+        let (batch, tank) = (Uuid::new_v4(), Uuid::new_v4());
+        let event = reading_event(batch, tank);
+
+        assert!(StreamQuery::default().matches(&event));
+        assert!(StreamQuery { batch_id: Some(batch), tank_id: None }.matches(&event));
+        assert!(StreamQuery { batch_id: None, tank_id: Some(tank) }.matches(&event));
+        assert!(!StreamQuery { batch_id: Some(Uuid::new_v4()), tank_id: None }.matches(&event));
+        assert!(!StreamQuery { batch_id: None, tank_id: Some(Uuid::new_v4()) }.matches(&event));
+
+        // Obaveštenje o propuštenim događajima ide svima
+        let lagged = StreamEvent::Lagged { skipped: 3 };
+        assert!(StreamQuery { batch_id: Some(batch), tank_id: None }.matches(&lagged));
+    }
+
+    #[test]
+    fn test_stream_event_json() {
+        let json = serde_json::to_value(reading_event(Uuid::new_v4(), Uuid::new_v4())).unwrap();
+
+        assert_eq!(json["type"], "reading");
+        assert_eq!(json["reading"]["temperature"], 24.5);
+    }
+}
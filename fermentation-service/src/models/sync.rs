@@ -0,0 +1,195 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+pub use sync_common::{
+    sync_page_cursor, SyncCursor, SyncOutcome, SyncResult, SyncTombstone, SYNC_PAGE_MAX,
+};
+
+use crate::models::{AddReadingRequest, FermentationBatch, FermentationStatus, ReadingResponse};
+
+// ============== Zapisi u feed-u promena ==============
+
+/// Merenje kako ga vidi mobilni klijent, sa serverskom verzijom
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct SyncReadingRecord {
+    pub id: Uuid,
+    pub batch_id: Uuid,
+    pub temperature: Option<f64>,
+    pub brix: Option<f64>,
+    pub ph: Option<f64>,
+    pub density: Option<f64>,
+    pub alcohol_percent: Option<f64>,
+    pub volatile_acidity: Option<f64>,
+    pub free_so2: Option<f64>,
+    pub total_so2: Option<f64>,
+    pub color: Option<String>,
+    pub clarity: Option<String>,
+    pub aroma_notes: Option<String>,
+    pub source: String,
+    pub notes: Option<String>,
+    pub recorded_at: DateTime<Utc>,
+    pub sync_version: i64,
+}
+
+impl SyncReadingRecord {
+    pub fn cursor(&self) -> SyncCursor {
+        SyncCursor::new(self.sync_version, self.id)
+    }
+
+    /// Da li zapis na serveru već ima poslate vrednosti (ponovljen upload)
+    pub fn matches(&self, batch_id: Uuid, req: &AddReadingRequest) -> bool {
+        self.batch_id == batch_id
+            && self.temperature == req.temperature
+            && self.brix == req.brix
+            && self.ph == req.ph
+            && self.density == req.density
+            && self.alcohol_percent == req.alcohol_percent
+            && self.volatile_acidity == req.volatile_acidity
+            && self.free_so2 == req.free_so2
+            && self.total_so2 == req.total_so2
+            && self.color == req.color
+            && self.clarity == req.clarity
+            && self.aroma_notes == req.aroma_notes
+            && self.notes == req.notes
+            && req.recorded_at.is_none_or(|t| t == self.recorded_at)
+    }
+}
+
+impl From<SyncReadingRecord> for ReadingResponse {
+    fn from(r: SyncReadingRecord) -> Self {
+        ReadingResponse {
+            id: r.id,
+            batch_id: r.batch_id,
+            temperature: r.temperature,
+            brix: r.brix,
+            ph: r.ph,
+            density: r.density,
+            alcohol_percent: r.alcohol_percent,
+            volatile_acidity: r.volatile_acidity,
+            free_so2: r.free_so2,
+            total_so2: r.total_so2,
+            color: r.color,
+            clarity: r.clarity,
+            aroma_notes: r.aroma_notes,
+            source: r.source,
+            device_id: None,
+            source_device: None,
+            sensor_values: vec![],
+            notes: r.notes,
+            recorded_at: r.recorded_at,
+        }
+    }
+}
+
+/// Merenje sa uređaja se prihvata za aktivan batch, ili ako je izmereno
+/// dok je batch još trajao (batch je u međuvremenu završen ili pauziran)
+pub fn accepts_offline_reading(batch: &FermentationBatch, recorded_at: DateTime<Utc>) -> bool {
+    match batch.status {
+        FermentationStatus::Active => true,
+        FermentationStatus::Cancelled => false,
+        FermentationStatus::Completed | FermentationStatus::Paused => {
+            batch.start_date.is_some_and(|start| recorded_at >= start)
+                && batch.end_date.is_none_or(|end| recorded_at <= end)
+        }
+    }
+}
+
+// ============== Request structs ==============
+
+/// Merenje napravljeno na uređaju (id generiše klijent)
+#[derive(Debug, Deserialize)]
+pub struct SyncReadingPush {
+    pub id: Uuid,
+    pub batch_id: Uuid,
+    #[serde(flatten)]
+    pub fields: AddReadingRequest,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SyncPushRequest {
+    #[serde(default)]
+    pub readings: Vec<SyncReadingPush>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SyncChangesQuery {
+    #[serde(default)]
+    pub since: SyncCursor,
+    pub limit: Option<i64>,
+    pub batch_id: Option<Uuid>,
+}
+
+// ============== Response structs ==============
+
+/// Kod konflikta klijent dobija serversku verziju merenja
+pub type SyncPushResult = SyncResult<SyncReadingRecord>;
+
+#[derive(Debug, Serialize)]
+pub struct SyncPushResponse {
+    pub readings: Vec<SyncPushResult>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncChangesResponse {
+    pub readings: Vec<SyncReadingRecord>,
+    pub deleted: Vec<SyncTombstone>,
+    /// Sledeći `since`; klijent nastavlja dok je `has_more`
+    pub next_since: SyncCursor,
+    pub has_more: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn batch(
+        status: FermentationStatus,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> FermentationBatch {
+        FermentationBatch {
+            id: Uuid::new_v4(),
+            tank_id: Uuid::new_v4(),
+            harvest_id: None,
+            name: "Tank 3 Merlot".to_string(),
+            grape_variety: "Merlot".to_string(),
+            wine_type: None,
+            volume_liters: 1000.0,
+            status,
+            target_temperature: None,
+            yeast_strain: None,
+            initial_brix: None,
+            initial_ph: None,
+            start_date: start,
+            end_date: end,
+            expected_end_date: None,
+            notes: None,
+            created_by: Uuid::new_v4(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn day(d: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 9, d, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_accepts_reading_taken_before_batch_completed() {
+        let completed = batch(FermentationStatus::Completed, Some(day(1)), Some(day(20)));
+
+        assert!(accepts_offline_reading(&completed, day(19)));
+        assert!(!accepts_offline_reading(&completed, day(21)));
+        assert!(!accepts_offline_reading(&completed, Utc.with_ymd_and_hms(2026, 8, 30, 0, 0, 0).unwrap()));
+    }
+
+    #[test]
+    fn test_accepts_reading_by_status() {
+        assert!(accepts_offline_reading(&batch(FermentationStatus::Active, None, None), day(5)));
+        assert!(accepts_offline_reading(&batch(FermentationStatus::Paused, Some(day(1)), None), day(5)));
+        assert!(!accepts_offline_reading(&batch(FermentationStatus::Cancelled, Some(day(1)), None), day(5)));
+    }
+}
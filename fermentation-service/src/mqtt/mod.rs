@@ -0,0 +1,3 @@
+pub mod subscriber;
+
+pub use subscriber::*;
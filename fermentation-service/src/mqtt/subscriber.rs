@@ -0,0 +1,169 @@
+use std::time::Duration;
+
+use rumqttc::{AsyncClient, Event, EventLoop, MqttOptions, Packet, Publish, QoS};
+use tokio::sync::mpsc;
+
+use crate::{
+    config::MqttSettings,
+    error::AppError,
+    handlers::{ingest_iot_readings, AppState},
+    models::{mqtt_subscriptions, parse_topic, MqttPayload, MqttTarget},
+};
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+/// Poruke primljene a još neobrađene; broker ionako ne šalje više nepotvrđenih
+/// QoS 1 poruka od svog `max_inflight_messages` (podrazumevano 20). Pun red
+/// zaustavlja čitanje sa brokera dok obrada ne sustigne.
+const WORKER_QUEUE: usize = 256;
+/// Pauza posle greške baze ili drugog servisa; udvostručuje se do maksimuma
+const RETRY_DELAY_MIN: Duration = Duration::from_secs(1);
+const RETRY_DELAY_MAX: Duration = Duration::from_secs(60);
+
+/// Veza sa brokerom. Klijent se deli (npr. za komande aktuatorima), a event loop
+/// pokreće pretplata.
+pub fn mqtt_connection(settings: &MqttSettings) -> (AsyncClient, EventLoop) {
+    let mut options = MqttOptions::new(&settings.client_id, &settings.host, settings.port);
+    options.set_keep_alive(Duration::from_secs(30));
+    options.set_clean_session(false);
+    options.set_manual_acks(true);
+    if let (Some(username), Some(password)) = (&settings.username, &settings.password) {
+        options.set_credentials(username, password);
+    }
+
+    AsyncClient::new(options, 100)
+}
+
+/// Pretplata na merenja senzora. QoS 1 sa trajnom sesijom: broker čuva poruke dok
+/// je servis nedostupan, a potvrda se šalje tek kada je poruka obrađena. Obrada je
+/// u posebnom zadatku, da event loop i dalje odgovara na keepalive i šalje komande
+/// aktuatorima.
+pub fn spawn_mqtt_subscriber(
+    state: AppState,
+    settings: MqttSettings,
+    client: AsyncClient,
+    mut eventloop: EventLoop,
+) {
+    let (tx, rx) = mpsc::channel(WORKER_QUEUE);
+    spawn_message_worker(state, settings.topic_prefix.clone(), client.clone(), rx);
+
+    tokio::spawn(async move {
+        loop {
+            match eventloop.poll().await {
+                // Pretplata se obnavlja posle svakog (ponovnog) povezivanja
+                Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                    tracing::info!("Connected to MQTT broker {}:{}", settings.host, settings.port);
+                    for topic in mqtt_subscriptions(&settings.topic_prefix) {
+                        if let Err(e) = client.subscribe(&topic, QoS::AtLeastOnce).await {
+                            tracing::error!("MQTT subscribe to '{}' failed: {}", topic, e);
+                        }
+                    }
+                }
+                Ok(Event::Incoming(Packet::Publish(publish))) => {
+                    if tx.send(publish).await.is_err() {
+                        tracing::error!("MQTT worker stopped");
+                        break;
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::warn!("MQTT connection error: {}; retrying", e);
+                    tokio::time::sleep(RECONNECT_DELAY).await;
+                }
+            }
+        }
+    });
+}
+
+/// Potvrda posle uspeha ili trajne greške (neispravan topic/sadržaj, nepoznat
+/// batch); takva poruka bi se inače vraćala zauvek. Kod greške baze ili drugog
+/// servisa poruka ostaje nepotvrđena: posle pauze se veza prekida, a broker je
+/// ponovo šalje iz trajne sesije.
+fn spawn_message_worker(
+    state: AppState,
+    prefix: String,
+    client: AsyncClient,
+    mut rx: mpsc::Receiver<Publish>,
+) {
+    tokio::spawn(async move {
+        let mut retry_delay = RETRY_DELAY_MIN;
+
+        while let Some(publish) = rx.recv().await {
+            match handle_message(&state, &prefix, &publish).await {
+                Ok(()) => retry_delay = RETRY_DELAY_MIN,
+                Err(e) if is_permanent(&e) => {
+                    tracing::warn!("MQTT message on '{}' rejected: {}", publish.topic, e);
+                }
+                Err(e) => {
+                    tracing::error!(
+                        "MQTT message on '{}' failed: {}; retrying in {:?}",
+                        publish.topic,
+                        e,
+                        retry_delay
+                    );
+                    tokio::time::sleep(retry_delay).await;
+                    retry_delay = (retry_delay * 2).min(RETRY_DELAY_MAX);
+
+                    // Primljene poruke iza ove broker takođe šalje ponovo
+                    while rx.try_recv().is_ok() {}
+                    if let Err(e) = client.disconnect().await {
+                        tracing::error!("MQTT disconnect failed: {}", e);
+                    }
+                    continue;
+                }
+            }
+
+            if let Err(e) = client.ack(&publish).await {
+                tracing::error!("MQTT ack failed: {}", e);
+            }
+        }
+    });
+}
+
+fn is_permanent(error: &AppError) -> bool {
+    matches!(
+        error,
+        AppError::ValidationError(_)
+            | AppError::NotFound(_)
+            | AppError::Conflict(_)
+            | AppError::Forbidden(_)
+    )
+}
+
+async fn handle_message(state: &AppState, prefix: &str, publish: &Publish) -> Result<(), AppError> {
+    let topic = parse_topic(prefix, &publish.topic).map_err(AppError::ValidationError)?;
+    let payload: MqttPayload = serde_json::from_slice(&publish.payload)
+        .map_err(|e| AppError::ValidationError(format!("Invalid payload: {}", e)))?;
+
+    // Na topic-e tankova i batch-eva smeju da pišu samo uređaji iz registra
+    if topic.target != MqttTarget::Device
+        && state.device_repo.find_device_by_hardware_id(&topic.device_id).await?.is_none()
+    {
+        return Err(AppError::ValidationError(format!(
+            "Device '{}' is not registered",
+            topic.device_id
+        )));
+    }
+
+    let batch_id = match topic.target {
+        MqttTarget::Batch(batch_id) => Some(batch_id),
+        MqttTarget::Tank(tank_id) => Some(
+            state
+                .repo
+                .find_active_batch_in_tank(tank_id)
+                .await?
+                .map(|b| b.id)
+                .ok_or_else(|| AppError::Conflict(format!("Tank {} has no active batch", tank_id)))?,
+        ),
+        MqttTarget::Device => None,
+    };
+
+    let summary = ingest_iot_readings(state, payload.into_requests(batch_id, &topic.device_id)).await?;
+    tracing::debug!(
+        "MQTT message from device '{}': {} stored, {} duplicates",
+        topic.device_id,
+        summary.stored,
+        summary.duplicates
+    );
+
+    Ok(())
+}
@@ -2,12 +2,13 @@
 use chrono::Utc;
 use std::io::BufWriter;
 
-use crate::models::{FermentationBatch, FermentationReading, BatchStats};
+use crate::models::{BatchAddition, FermentationBatch, FermentationReading, BatchStats};
 
 pub fn generate_batch_report(
     batch: &FermentationBatch,
     readings: &[FermentationReading],
     stats: &BatchStats,
+    additions: &[BatchAddition],
     tank_name: &str,
 ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
     // Create PDF document
@@ -39,7 +40,7 @@ pub fn generate_batch_report(
 
     // Date
     current_layer.use_text(
-        &format!("Generated: {}", Utc::now().format("%Y-%m-%d %H:%M")),
+        format!("Generated: {}", Utc::now().format("%Y-%m-%d %H:%M")),
         10.0,
         Mm(20.0),
         Mm(y_position),
@@ -63,6 +64,13 @@ pub fn generate_batch_report(
     let info_items: Vec<(&str, String)> = vec![
         ("Batch Name:", batch.name.clone()),
         ("Grape Variety:", batch.grape_variety.clone()),
+        (
+            "Wine Type:",
+            batch
+                .wine_type
+                .map(|t| format!("{:?}", t))
+                .unwrap_or_else(|| "N/A".to_string()),
+        ),
         ("Tank:", tank_name.to_string()),
         ("Volume:", format!("{} L", batch.volume_liters)),
         (
@@ -179,10 +187,75 @@ pub fn generate_batch_report(
 
     y_position -= 10.0;
 
+    // Additions register
+    current_layer.use_text(
+        format!("ADDITIONS REGISTER ({})", additions.len()),
+        16.0,
+        Mm(20.0),
+        Mm(y_position),
+        &font_bold,
+    );
+    y_position -= 8.0;
+
+    draw_line(&current_layer, y_position);
+    y_position -= 5.0;
+
+    if additions.is_empty() {
+        current_layer.use_text("No additions recorded", 11.0, Mm(20.0), Mm(y_position), &font);
+        y_position -= 6.0;
+    } else {
+        let columns = [
+            ("Date", 20.0),
+            ("Product", 47.0),
+            ("Dose/hL", 92.0),
+            ("Quantity", 112.0),
+            ("Lot", 134.0),
+            ("Operator", 157.0),
+        ];
+        for (label, x) in columns {
+            current_layer.use_text(label, 9.0, Mm(x), Mm(y_position), &font_bold);
+        }
+        y_position -= 5.0;
+
+        for addition in additions {
+            if y_position < 20.0 {
+                let (page_num, layer_num) = doc.add_page(Mm(210.0), Mm(297.0), "Layer 1");
+                current_layer = doc.get_page(page_num).get_layer(layer_num);
+                y_position = 270.0;
+            }
+
+            let unit = addition.unit.as_str();
+            let mut product: String = addition.product_name.chars().take(26).collect();
+            if let Some(so2) = addition.so2_added_mg_l {
+                product = format!("{} (+{:.0} mg/L SO2)", product, so2);
+            }
+            let values = [
+                (addition.added_at.format("%Y-%m-%d %H:%M").to_string(), 20.0),
+                (product, 47.0),
+                (format!("{:.2} {}", addition.dose_per_hl, unit), 92.0),
+                (format!("{:.2} {}", addition.quantity, unit), 112.0),
+                (addition.lot_number.clone().unwrap_or_else(|| "-".to_string()), 134.0),
+                (addition.operator_email.chars().take(24).collect(), 157.0),
+            ];
+            for (value, x) in values {
+                current_layer.use_text(&value, 8.0, Mm(x), Mm(y_position), &font);
+            }
+            y_position -= 5.0;
+        }
+    }
+
+    y_position -= 10.0;
+
+    if y_position < 60.0 {
+        let (page_num, layer_num) = doc.add_page(Mm(210.0), Mm(297.0), "Layer 1");
+        current_layer = doc.get_page(page_num).get_layer(layer_num);
+        y_position = 270.0;
+    }
+
     // Readings
     if !readings.is_empty() {
         current_layer.use_text(
-            &format!("FERMENTATION READINGS ({})", readings.len()),
+            format!("FERMENTATION READINGS ({})", readings.len()),
             16.0,
             Mm(20.0),
             Mm(y_position),
@@ -203,7 +276,7 @@ pub fn generate_batch_report(
             }
 
             current_layer.use_text(
-                &format!("Reading {}", i + 1),
+                format!("Reading {}", i + 1),
                 12.0,
                 Mm(20.0),
                 Mm(y_position),
@@ -0,0 +1,52 @@
+use std::time::Duration;
+
+use chrono::Utc;
+
+use crate::db::{RollupRepository, SyncRepository};
+
+/// Koliko sirovih merenja se briše u jednoj transakciji
+const PURGE_CHUNK: i64 = 5000;
+
+/// Periodično osvežavanje agregata merenja, brisanje starih sirovih IoT merenja i
+/// starih tombstone-a. Bez `retention_days` sirova merenja se čuvaju trajno, a bez
+/// `tombstone_retention_days` tombstone-i.
+pub fn spawn_rollup_worker(
+    rollup_repo: RollupRepository,
+    sync_repo: SyncRepository,
+    interval: Duration,
+    retention_days: Option<i64>,
+    tombstone_retention_days: Option<i64>,
+) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+
+            let cutoff = retention_days.map(|days| Utc::now() - chrono::Duration::days(days));
+            match rollup_repo.refresh_rollups(cutoff).await {
+                Ok(0) => {}
+                Ok(updated) => tracing::debug!("Refreshed {} reading rollups", updated),
+                Err(e) => {
+                    tracing::error!("Reading rollup refresh failed: {}", e);
+                    continue;
+                }
+            }
+
+            if let Some(cutoff) = cutoff {
+                match rollup_repo.purge_raw_iot_readings(cutoff, PURGE_CHUNK).await {
+                    Ok(0) => {}
+                    Ok(deleted) => tracing::info!("Purged {} raw IoT readings older than {}", deleted, cutoff),
+                    Err(e) => tracing::error!("Raw reading retention failed: {}", e),
+                }
+            }
+
+            if let Some(days) = tombstone_retention_days {
+                match sync_repo.prune_sync_tombstones(Utc::now() - chrono::Duration::days(days)).await {
+                    Ok(0) => {}
+                    Ok(pruned) => tracing::info!("Pruned {} sync tombstones", pruned),
+                    Err(e) => tracing::error!("Sync tombstone pruning failed: {}", e),
+                }
+            }
+        }
+    });
+}
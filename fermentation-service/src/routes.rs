@@ -1,6 +1,6 @@
 ﻿use axum::{
     middleware,
-    routing::{delete, get, patch, post, put},
+    routing::{delete, get, post, put},
     Router,
 };
 
@@ -10,7 +10,14 @@ pub fn create_router(state: AppState, settings: Settings) -> Router {
     // Public routes (health check + IoT endpoint bez JWT)
     let public_routes = Router::new()
         .route("/health", get(handlers::health_check))
-        .route("/iot/readings", post(handlers::iot_reading));
+        .route("/iot/readings", post(handlers::iot_reading))
+        .route("/iot/readings/batch", post(handlers::iot_readings_batch));
+
+    // Live stream: browser ne može da pošalje header, pa se veza otvara
+    // jednokratnom kartom (?ticket=) dobijenom preko POST /stream/tickets
+    let stream_routes = Router::new()
+        .route("/stream/ws", get(handlers::stream_ws))
+        .route("/stream/sse", get(handlers::stream_sse));
 
     // Protected routes
     let protected_routes = Router::new()
@@ -22,25 +29,99 @@ pub fn create_router(state: AppState, settings: Settings) -> Router {
         .route("/tanks/:tank_id", put(handlers::update_tank))
         .route("/tanks/:tank_id", delete(handlers::delete_tank))
         .route("/tanks/:tank_id/batches", get(handlers::list_batches_by_tank))
+        // Temperature control
+        .route("/tanks/:tank_id/control", get(handlers::get_tank_control))
+        .route("/tanks/:tank_id/control", put(handlers::update_tank_control))
+        .route("/tanks/:tank_id/control/schedule", put(handlers::set_setpoint_schedule))
+        .route("/tanks/:tank_id/control/schedule", delete(handlers::delete_setpoint_schedule))
+        .route("/tanks/:tank_id/control/override", post(handlers::set_control_override))
+        .route("/tanks/:tank_id/control/override", delete(handlers::clear_control_override))
+        .route("/tanks/:tank_id/control/commands", get(handlers::list_control_commands))
+        // Tank cleaning & maintenance
+        .route("/tanks/:tank_id/cleanings", post(handlers::create_tank_cleaning))
+        .route("/tanks/:tank_id/cleanings", get(handlers::list_tank_cleanings))
+        .route("/tanks/:tank_id/maintenance-tasks", post(handlers::create_maintenance_task))
+        .route("/tanks/:tank_id/maintenance-tasks", get(handlers::list_maintenance_tasks))
+        .route("/tanks/:tank_id/maintenance", post(handlers::create_maintenance_record))
+        .route("/tanks/:tank_id/maintenance", get(handlers::list_maintenance_records))
+        .route("/maintenance-tasks/due", get(handlers::list_due_maintenance))
+        .route("/maintenance-tasks/:task_id", put(handlers::update_maintenance_task))
+        .route("/maintenance-tasks/:task_id", delete(handlers::delete_maintenance_task))
         // Batches
         .route("/batches", post(handlers::create_batch))
         .route("/batches", get(handlers::list_batches))
         .route("/batches/active", get(handlers::list_active_batches))
+        .route("/batches/dashboard", get(handlers::get_fermentation_dashboard))
+        .route("/batches/from-must", post(handlers::create_batch_from_must))
         .route("/batches/:batch_id", get(handlers::get_batch))
         .route("/batches/:batch_id", put(handlers::update_batch))
         .route("/batches/:batch_id", delete(handlers::delete_batch))
         .route("/batches/:batch_id/stats", get(handlers::get_batch_stats))
+        .route("/batches/:batch_id/lineage", get(handlers::get_batch_lineage))
+        .route("/batches/:batch_id/vessels", get(handlers::list_batch_vessels))
+        .route("/batches/:batch_id/composition", get(handlers::get_batch_composition))
+        // Additions
+        .route("/batches/:batch_id/additions", post(handlers::add_batch_addition))
+        .route("/batches/:batch_id/additions", get(handlers::list_batch_additions))
+        .route("/batches/:batch_id/additions/:addition_id", delete(handlers::delete_batch_addition))
+        .route("/addition-products", post(handlers::create_addition_product))
+        .route("/addition-products", get(handlers::list_addition_products))
+        .route("/addition-products/:product_id", get(handlers::get_addition_product))
+        .route("/addition-products/:product_id", put(handlers::update_addition_product))
+        .route("/addition-products/:product_id", delete(handlers::delete_addition_product))
         // Readings
         .route("/batches/:batch_id/readings", post(handlers::add_reading))
         .route("/batches/:batch_id/readings", get(handlers::list_readings))
+        .route("/batches/:batch_id/readings/series", get(handlers::get_reading_series))
         .route("/batches/:batch_id/readings/:reading_id", delete(handlers::delete_reading))
         .route("/batches/:id/pdf", get(handlers::export_batch_pdf))
+        // Transfers / blending
+        .route("/transfers", post(handlers::transfer_batch))
+        .route("/blends", post(handlers::blend_batches))
+        .route("/cellar-operations", get(handlers::list_cellar_operations))
+        .route("/cellar-operations/:operation_id", get(handlers::get_cellar_operation))
+        // Crush / press
+        .route("/crushes", post(handlers::create_crush))
+        .route("/crushes", get(handlers::list_crushes))
+        .route("/crushes/:crush_id", get(handlers::get_crush))
+        .route("/crushes/:crush_id", delete(handlers::delete_crush))
+        .route("/harvests/:harvest_id/lineage", get(handlers::get_harvest_lineage))
+        // Alerts
+        .route("/alert-rules", post(handlers::create_alert_rule))
+        .route("/alert-rules", get(handlers::list_alert_rules))
+        .route("/alert-rules/:rule_id", get(handlers::get_alert_rule))
+        .route("/alert-rules/:rule_id", put(handlers::update_alert_rule))
+        .route("/alert-rules/:rule_id", delete(handlers::delete_alert_rule))
+        .route("/alerts", get(handlers::list_alerts))
+        .route("/alerts/:alert_id", get(handlers::get_alert))
+        .route("/alerts/:alert_id/acknowledge", post(handlers::acknowledge_alert))
+        .route("/alerts/:alert_id/resolve", post(handlers::resolve_alert))
+        .route("/notification-channels", post(handlers::create_notification_channel))
+        .route("/notification-channels", get(handlers::list_notification_channels))
+        .route("/notification-channels/:channel_id", put(handlers::update_notification_channel))
+        .route("/notification-channels/:channel_id", delete(handlers::delete_notification_channel))
+        .route("/notification-channels/:channel_id/test", post(handlers::test_notification_channel))
+        // IoT devices
+        .route("/devices", post(handlers::create_device))
+        .route("/devices", get(handlers::list_devices))
+        .route("/devices/health", get(handlers::get_device_health))
+        .route("/devices/:device_id", get(handlers::get_device))
+        .route("/devices/:device_id", put(handlers::update_device))
+        .route("/devices/:device_id", delete(handlers::delete_device))
+        .route("/devices/:device_id/tank", put(handlers::bind_device))
+        .route("/tanks/:tank_id/devices", get(handlers::list_tank_devices))
+        // Offline sync (mobile)
+        .route("/sync/push", post(handlers::push_sync_changes))
+        .route("/sync/changes", get(handlers::get_sync_changes))
+        // Live stream
+        .route("/stream/tickets", post(handlers::create_stream_ticket))
         .layer(middleware::from_fn(move |req, next| {
             crate::middleware::add_settings(settings.clone(), req, next)
         }));
 
     Router::new()
         .nest("/api/v1", public_routes)
+        .nest("/api/v1", stream_routes)
         .nest("/api/v1", protected_routes)
         .with_state(state)
 }
\ No newline at end of file
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Duration, Utc};
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::models::{Claims, StreamEvent, StreamTicketResponse};
+
+/// Koliko dugo važi karta za otvaranje stream-a
+pub const STREAM_TICKET_TTL_SECS: i64 = 30;
+
+struct StreamTicket {
+    claims: Claims,
+    expires_at: DateTime<Utc>,
+}
+
+/// Raspodela novih merenja i alarma svim otvorenim WebSocket/SSE vezama
+#[derive(Clone)]
+pub struct StreamHub {
+    tx: broadcast::Sender<StreamEvent>,
+    /// Izdate karte; kao i događaji, važe samo na ovoj instanci servisa
+    tickets: Arc<Mutex<HashMap<Uuid, StreamTicket>>>,
+}
+
+impl StreamHub {
+    /// `capacity` događaja se čuva za sporije klijente pre nego što počnu da ih gube
+    pub fn new(capacity: usize) -> Self {
+        let (tx, _) = broadcast::channel(capacity);
+        Self {
+            tx,
+            tickets: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<StreamEvent> {
+        self.tx.subscribe()
+    }
+
+    pub fn has_subscribers(&self) -> bool {
+        self.tx.receiver_count() > 0
+    }
+
+    /// Bez pretplatnika događaj se jednostavno odbacuje
+    pub fn publish(&self, event: StreamEvent) {
+        let _ = self.tx.send(event);
+    }
+
+    /// Karta kratkog veka umesto JWT-a u URL-u; ne traje duže od samog tokena
+    pub fn issue_ticket(&self, claims: Claims) -> StreamTicketResponse {
+        let now = Utc::now();
+        let token_expires_at = DateTime::from_timestamp(claims.exp, 0).unwrap_or(now);
+        let expires_at = (now + Duration::seconds(STREAM_TICKET_TTL_SECS)).min(token_expires_at);
+        let ticket = Uuid::new_v4();
+
+        let mut tickets = self.tickets.lock().unwrap();
+        tickets.retain(|_, t| t.expires_at > now);
+        tickets.insert(ticket, StreamTicket { claims, expires_at });
+
+        StreamTicketResponse { ticket, expires_at }
+    }
+
+    /// Karta se troši pri prvom korišćenju, i kad je istekla
+    pub fn redeem_ticket(&self, ticket: Uuid) -> Option<Claims> {
+        let ticket = self.tickets.lock().unwrap().remove(&ticket)?;
+
+        (ticket.expires_at > Utc::now()).then_some(ticket.claims)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::UserRole;
+
+    fn claims(expires_in: Duration) -> Claims {
+        let now = Utc::now();
+        Claims {
+            sub: Uuid::new_v4().to_string(),
+            email: "podrum@example.com".to_string(),
+            role: UserRole::Worker,
+            exp: (now + expires_in).timestamp(),
+            iat: now.timestamp(),
+        }
+    }
+
+    #[test]
+    fn test_ticket_is_single_use() {
+        let hub = StreamHub::new(8);
+        let issued = hub.issue_ticket(claims(Duration::hours(1)));
+
+        assert!(hub.redeem_ticket(issued.ticket).is_some());
+        assert!(hub.redeem_ticket(issued.ticket).is_none());
+        assert!(hub.redeem_ticket(Uuid::new_v4()).is_none());
+    }
+
+    #[test]
+    fn test_ticket_does_not_outlive_token() {
+        let hub = StreamHub::new(8);
+        let issued = hub.issue_ticket(claims(Duration::seconds(-5)));
+
+        assert!(issued.expires_at <= Utc::now());
+        assert!(hub.redeem_ticket(issued.ticket).is_none());
+    }
+}
@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS idx_harvests_grape_variety;
+
+ALTER TABLE harvests
+    DROP COLUMN IF EXISTS parcel_area_m2,
+    DROP COLUMN IF EXISTS grape_variety,
+    DROP COLUMN IF EXISTS parcel_name,
+    DROP COLUMN IF EXISTS vineyard_name;
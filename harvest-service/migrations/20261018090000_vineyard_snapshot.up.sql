@@ -0,0 +1,8 @@
+-- Podaci o vinogradu i parceli preuzeti iz vineyard-service (keš za izveštaje)
+ALTER TABLE harvests
+    ADD COLUMN vineyard_name  VARCHAR(255),
+    ADD COLUMN parcel_name    VARCHAR(255),
+    ADD COLUMN grape_variety  VARCHAR(255),
+    ADD COLUMN parcel_area_m2 DOUBLE PRECISION CHECK (parcel_area_m2 > 0);
+
+CREATE INDEX idx_harvests_grape_variety ON harvests(grape_variety);
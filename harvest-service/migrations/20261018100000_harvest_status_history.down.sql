@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_harvest_status_history_harvest_id;
+DROP TABLE IF EXISTS harvest_status_history;
@@ -0,0 +1,16 @@
+-- Istorija promena statusa berbe
+CREATE TABLE harvest_status_history (
+                                        id          UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                        harvest_id  UUID NOT NULL REFERENCES harvests(id) ON DELETE CASCADE,
+                                        from_status harvest_status,          -- NULL = kreiranje berbe
+                                        to_status   harvest_status NOT NULL,
+                                        changed_by  UUID NOT NULL,
+                                        reason      TEXT,
+                                        changed_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_harvest_status_history_harvest_id ON harvest_status_history(harvest_id, changed_at);
+
+-- Postojeće berbe dobijaju početni zapis
+INSERT INTO harvest_status_history (harvest_id, from_status, to_status, changed_by, changed_at)
+SELECT id, NULL, status, created_by, created_at FROM harvests;
@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS idx_harvest_loads_arrived_at;
+DROP INDEX IF EXISTS idx_harvest_loads_harvest_id;
+
+DROP TABLE IF EXISTS harvest_loads;
+DROP SEQUENCE IF EXISTS weighbridge_ticket_seq;
+DROP TYPE IF EXISTS load_container_type;
@@ -0,0 +1,28 @@
+-- Ture grožđa (kontejneri / prikolice) sa kolske vage
+CREATE TYPE load_container_type AS ENUM ('bin', 'trailer');
+
+CREATE SEQUENCE weighbridge_ticket_seq;
+
+CREATE TABLE harvest_loads (
+                               id               UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                               harvest_id       UUID NOT NULL REFERENCES harvests(id) ON DELETE CASCADE,
+                               ticket_number    VARCHAR(50) NOT NULL UNIQUE,
+                               container_type   load_container_type NOT NULL,
+                               container_code   VARCHAR(100) NOT NULL,
+                               picker_crew      VARCHAR(255),
+    -- Vaga
+                               gross_weight_kg  DOUBLE PRECISION NOT NULL CHECK (gross_weight_kg > 0),
+                               tare_weight_kg   DOUBLE PRECISION NOT NULL CHECK (tare_weight_kg >= 0),
+                               net_weight_kg    DOUBLE PRECISION GENERATED ALWAYS AS (gross_weight_kg - tare_weight_kg) STORED,
+    -- Kvalitet na prijemu
+                               brix             DOUBLE PRECISION CHECK (brix BETWEEN 0 AND 50),
+    -- Meta
+                               arrived_at       TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                               received_by      UUID NOT NULL,
+                               notes            TEXT,
+                               created_at       TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                               CHECK (gross_weight_kg > tare_weight_kg)
+);
+
+CREATE INDEX idx_harvest_loads_harvest_id ON harvest_loads(harvest_id);
+CREATE INDEX idx_harvest_loads_arrived_at ON harvest_loads(arrived_at DESC);
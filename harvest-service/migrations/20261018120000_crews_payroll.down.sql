@@ -0,0 +1,11 @@
+DROP INDEX IF EXISTS idx_picker_work_work_date;
+DROP INDEX IF EXISTS idx_picker_work_picker_date;
+DROP INDEX IF EXISTS idx_picker_work_harvest_id;
+DROP INDEX IF EXISTS idx_pickers_crew_id;
+
+DROP TABLE IF EXISTS picker_work_entries;
+DROP TABLE IF EXISTS harvest_crews;
+DROP TABLE IF EXISTS pickers;
+DROP TABLE IF EXISTS picker_crews;
+
+DROP TYPE IF EXISTS pay_basis;
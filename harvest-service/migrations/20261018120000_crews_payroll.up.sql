@@ -0,0 +1,56 @@
+-- Ekipe berača, berači i evidencija rada za obračun zarade
+CREATE TYPE pay_basis AS ENUM ('hourly', 'per_kg', 'per_bin');
+
+CREATE TABLE picker_crews (
+                              id           UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                              name         VARCHAR(255) NOT NULL UNIQUE,
+                              leader_name  VARCHAR(255),
+                              notes        TEXT,
+                              created_by   UUID NOT NULL,
+                              created_at   TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                              updated_at   TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE pickers (
+                         id             UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                         crew_id        UUID REFERENCES picker_crews(id) ON DELETE SET NULL,
+                         full_name      VARCHAR(255) NOT NULL,
+                         employee_code  VARCHAR(50) UNIQUE,
+    -- Tarife
+                         hourly_rate    DOUBLE PRECISION CHECK (hourly_rate >= 0),
+                         rate_per_kg    DOUBLE PRECISION CHECK (rate_per_kg >= 0),
+                         rate_per_bin   DOUBLE PRECISION CHECK (rate_per_bin >= 0),
+                         active         BOOLEAN NOT NULL DEFAULT TRUE,
+                         created_at     TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                         updated_at     TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Ekipe raspoređene na berbu
+CREATE TABLE harvest_crews (
+                               harvest_id   UUID NOT NULL REFERENCES harvests(id) ON DELETE CASCADE,
+                               crew_id      UUID NOT NULL REFERENCES picker_crews(id) ON DELETE CASCADE,
+                               assigned_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                               PRIMARY KEY (harvest_id, crew_id)
+);
+
+-- Rad berača po danu berbe
+CREATE TABLE picker_work_entries (
+                                     id            UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                     harvest_id    UUID NOT NULL REFERENCES harvests(id) ON DELETE CASCADE,
+                                     picker_id     UUID NOT NULL REFERENCES pickers(id),
+                                     work_date     DATE NOT NULL,
+                                     hours_worked  DOUBLE PRECISION NOT NULL CHECK (hours_worked BETWEEN 0 AND 24),
+                                     kg_picked     DOUBLE PRECISION NOT NULL DEFAULT 0 CHECK (kg_picked >= 0),
+                                     bins_picked   INTEGER NOT NULL DEFAULT 0 CHECK (bins_picked >= 0),
+                                     pay_basis     pay_basis NOT NULL,
+                                     rate_applied  DOUBLE PRECISION NOT NULL CHECK (rate_applied >= 0),
+                                     amount        DOUBLE PRECISION NOT NULL CHECK (amount >= 0),
+                                     notes         TEXT,
+                                     recorded_by   UUID NOT NULL,
+                                     created_at    TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_pickers_crew_id              ON pickers(crew_id);
+CREATE INDEX idx_picker_work_harvest_id       ON picker_work_entries(harvest_id);
+CREATE INDEX idx_picker_work_picker_date      ON picker_work_entries(picker_id, work_date);
+CREATE INDEX idx_picker_work_work_date        ON picker_work_entries(work_date);
@@ -0,0 +1,9 @@
+DROP INDEX IF EXISTS idx_harvest_plan_items_plan_id;
+DROP INDEX IF EXISTS idx_harvest_plans_season;
+
+DROP TABLE IF EXISTS harvest_plan_items;
+DROP TABLE IF EXISTS harvest_plans;
+DROP TYPE IF EXISTS harvest_plan_status;
+DROP TABLE IF EXISTS ripeness_targets;
+
+ALTER TABLE picker_crews DROP COLUMN IF EXISTS daily_capacity_kg;
@@ -0,0 +1,59 @@
+-- Planiranje berbe: ciljna zrelost po sorti, kapacitet ekipa i predlozi kalendara berbe
+ALTER TABLE picker_crews
+    ADD COLUMN daily_capacity_kg DOUBLE PRECISION CHECK (daily_capacity_kg > 0);
+
+-- Ciljna zrelost po sorti
+CREATE TABLE ripeness_targets (
+                                  id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                  grape_variety   VARCHAR(255) NOT NULL UNIQUE,
+                                  target_brix     DOUBLE PRECISION NOT NULL CHECK (target_brix BETWEEN 0 AND 50),
+                                  max_ph          DOUBLE PRECISION CHECK (max_ph BETWEEN 0 AND 14),
+                                  target_acidity  DOUBLE PRECISION CHECK (target_acidity BETWEEN 0 AND 30),
+                                  updated_by      UUID NOT NULL,
+                                  created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                                  updated_at      TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TYPE harvest_plan_status AS ENUM ('proposed', 'accepted', 'discarded');
+
+-- Predlog kalendara berbe
+CREATE TABLE harvest_plans (
+                               id                        UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                               season                    INTEGER NOT NULL,
+                               vineyard_id               UUID,
+                               start_date                DATE NOT NULL,
+                               status                    harvest_plan_status NOT NULL DEFAULT 'proposed',
+    -- Kapacitet (kg dnevno)
+                               crew_capacity_kg_per_day  DOUBLE PRECISION NOT NULL CHECK (crew_capacity_kg_per_day > 0),
+                               press_capacity_kg_per_day DOUBLE PRECISION NOT NULL CHECK (press_capacity_kg_per_day > 0),
+    -- Meta
+                               created_by                UUID NOT NULL,
+                               accepted_by               UUID,
+                               accepted_at               TIMESTAMPTZ,
+                               created_at                TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Stavka plana: jedna parcela sa prognozom zrelosti i predloženim danima berbe
+CREATE TABLE harvest_plan_items (
+                                    id                   UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                    plan_id              UUID NOT NULL REFERENCES harvest_plans(id) ON DELETE CASCADE,
+                                    parcel_id            UUID NOT NULL,
+                                    vineyard_id          UUID NOT NULL,
+                                    vineyard_name        VARCHAR(255),
+                                    parcel_name          VARCHAR(255),
+                                    grape_variety        VARCHAR(255),
+    -- Prognoza
+                                    sample_count         INTEGER NOT NULL,
+                                    latest_brix          DOUBLE PRECISION,
+                                    brix_per_day         DOUBLE PRECISION,
+                                    predicted_ripe_date  DATE,
+    -- Raspored
+                                    estimated_kg         DOUBLE PRECISION CHECK (estimated_kg >= 0),
+                                    scheduled_date       DATE,
+                                    finish_date          DATE,
+                                    notes                TEXT,
+                                    harvest_id           UUID REFERENCES harvests(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_harvest_plans_season       ON harvest_plans(season);
+CREATE INDEX idx_harvest_plan_items_plan_id ON harvest_plan_items(plan_id);
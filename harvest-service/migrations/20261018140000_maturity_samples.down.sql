@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS idx_maturity_samples_harvest_id;
+DROP INDEX IF EXISTS idx_maturity_samples_parcel_season;
+
+DROP TABLE IF EXISTS maturity_samples;
+DROP TYPE IF EXISTS seed_ripeness;
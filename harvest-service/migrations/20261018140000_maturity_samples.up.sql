@@ -0,0 +1,33 @@
+-- Uzorci zrelosti pre berbe, vezani za parcelu i sezonu (berba se povezuje naknadno)
+CREATE TYPE seed_ripeness AS ENUM ('green', 'partially_brown', 'brown');
+
+CREATE TABLE maturity_samples (
+                                  id                  UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                  parcel_id           UUID NOT NULL,
+                                  vineyard_id         UUID NOT NULL,
+                                  season              INTEGER NOT NULL,
+                                  sampled_at          TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- Hemijska merenja
+                                  brix                DOUBLE PRECISION CHECK (brix BETWEEN 0 AND 50),
+                                  ph                  DOUBLE PRECISION CHECK (ph BETWEEN 0 AND 14),
+                                  titratable_acidity  DOUBLE PRECISION CHECK (titratable_acidity BETWEEN 0 AND 30),
+                                  yan_mg_l            DOUBLE PRECISION CHECK (yan_mg_l BETWEEN 0 AND 1000),
+    -- Bobica i semenka
+                                  berry_weight_g      DOUBLE PRECISION CHECK (berry_weight_g > 0 AND berry_weight_g <= 20),
+                                  seed_ripeness       seed_ripeness,
+                                  sample_size         INTEGER CHECK (sample_size > 0), -- broj bobica u uzorku
+    -- Keš iz vineyard-service
+                                  vineyard_name       VARCHAR(255),
+                                  parcel_name         VARCHAR(255),
+                                  grape_variety       VARCHAR(255),
+                                  parcel_area_m2      DOUBLE PRECISION CHECK (parcel_area_m2 > 0),
+    -- Povezivanje sa berbom
+                                  harvest_id          UUID REFERENCES harvests(id) ON DELETE SET NULL,
+    -- Meta
+                                  notes               TEXT,
+                                  sampled_by          UUID NOT NULL,
+                                  created_at          TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_maturity_samples_parcel_season ON maturity_samples(parcel_id, season, sampled_at);
+CREATE INDEX idx_maturity_samples_harvest_id    ON maturity_samples(harvest_id);
@@ -0,0 +1,17 @@
+DROP INDEX IF EXISTS idx_harvests_quality_grade;
+
+ALTER TABLE harvests
+    DROP COLUMN IF EXISTS quality_grade,
+    DROP COLUMN IF EXISTS quality_score;
+
+DROP TABLE IF EXISTS quality_score_profiles;
+
+ALTER TABLE harvest_quality
+    ALTER COLUMN berry_size   TYPE VARCHAR(50)  USING berry_size::TEXT,
+    ALTER COLUMN berry_color  TYPE VARCHAR(255) USING berry_color::TEXT,
+    ALTER COLUMN grape_health TYPE VARCHAR(50)  USING grape_health::TEXT;
+
+DROP TYPE IF EXISTS quality_grade;
+DROP TYPE IF EXISTS grape_health;
+DROP TYPE IF EXISTS berry_color;
+DROP TYPE IF EXISTS berry_size;
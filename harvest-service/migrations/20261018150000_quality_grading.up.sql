@@ -0,0 +1,123 @@
+-- Zatvoreni rečnici za vizuelnu procenu, profili ocenjivanja po sorti i klasa kvaliteta berbe
+CREATE TYPE berry_size    AS ENUM ('small', 'medium', 'large');
+CREATE TYPE berry_color   AS ENUM ('green', 'yellow_green', 'golden', 'pink', 'red', 'purple', 'blue_black');
+CREATE TYPE grape_health  AS ENUM ('excellent', 'good', 'fair', 'poor');
+CREATE TYPE quality_grade AS ENUM ('A', 'B', 'C');
+
+-- Mapiranje postojećih slobodnih unosa (engleski i srpski nazivi); nepoznato → NULL
+CREATE FUNCTION pg_temp.map_berry_size(value TEXT) RETURNS TEXT AS $$
+SELECT CASE LOWER(TRIM(value))
+           WHEN 'small'   THEN 'small'
+           WHEN 'mala'    THEN 'small'
+           WHEN 'mali'    THEN 'small'
+           WHEN 'sitna'   THEN 'small'
+           WHEN 'medium'  THEN 'medium'
+           WHEN 'srednja' THEN 'medium'
+           WHEN 'srednji' THEN 'medium'
+           WHEN 'large'   THEN 'large'
+           WHEN 'velika'  THEN 'large'
+           WHEN 'veliki'  THEN 'large'
+           WHEN 'krupna'  THEN 'large'
+           END
+$$ LANGUAGE SQL IMMUTABLE;
+
+CREATE FUNCTION pg_temp.map_berry_color(value TEXT) RETURNS TEXT AS $$
+SELECT CASE REPLACE(REPLACE(LOWER(TRIM(value)), '-', ' '), '_', ' ')
+           WHEN 'green'        THEN 'green'
+           WHEN 'zelena'       THEN 'green'
+           WHEN 'yellow green' THEN 'yellow_green'
+           WHEN 'žuto zelena'  THEN 'yellow_green'
+           WHEN 'zuto zelena'  THEN 'yellow_green'
+           WHEN 'golden'       THEN 'golden'
+           WHEN 'gold'         THEN 'golden'
+           WHEN 'zlatna'       THEN 'golden'
+           WHEN 'zlatno žuta'  THEN 'golden'
+           WHEN 'pink'         THEN 'pink'
+           WHEN 'roze'         THEN 'pink'
+           WHEN 'ružičasta'    THEN 'pink'
+           WHEN 'red'          THEN 'red'
+           WHEN 'crvena'       THEN 'red'
+           WHEN 'purple'       THEN 'purple'
+           WHEN 'violet'       THEN 'purple'
+           WHEN 'ljubičasta'   THEN 'purple'
+           WHEN 'blue black'   THEN 'blue_black'
+           WHEN 'black'        THEN 'blue_black'
+           WHEN 'dark blue'    THEN 'blue_black'
+           WHEN 'plavo crna'   THEN 'blue_black'
+           WHEN 'crna'         THEN 'blue_black'
+           WHEN 'tamno plava'  THEN 'blue_black'
+           END
+$$ LANGUAGE SQL IMMUTABLE;
+
+CREATE FUNCTION pg_temp.map_grape_health(value TEXT) RETURNS TEXT AS $$
+SELECT CASE LOWER(TRIM(value))
+           WHEN 'excellent' THEN 'excellent'
+           WHEN 'odlično'   THEN 'excellent'
+           WHEN 'odlicno'   THEN 'excellent'
+           WHEN 'good'      THEN 'good'
+           WHEN 'dobro'     THEN 'good'
+           WHEN 'dobar'     THEN 'good'
+           WHEN 'fair'      THEN 'fair'
+           WHEN 'srednje'   THEN 'fair'
+           WHEN 'prosečno'  THEN 'fair'
+           WHEN 'prosecno'  THEN 'fair'
+           WHEN 'poor'      THEN 'poor'
+           WHEN 'loše'      THEN 'poor'
+           WHEN 'lose'      THEN 'poor'
+           WHEN 'loš'       THEN 'poor'
+           END
+$$ LANGUAGE SQL IMMUTABLE;
+
+-- Vrednosti koje ne mogu da se mapiraju ostaju zapisane u napomeni
+UPDATE harvest_quality
+SET notes = CONCAT_WS(E'\n', notes,
+                      CASE WHEN berry_size IS NOT NULL AND pg_temp.map_berry_size(berry_size) IS NULL
+                               THEN 'berry_size: ' || berry_size END,
+                      CASE WHEN berry_color IS NOT NULL AND pg_temp.map_berry_color(berry_color) IS NULL
+                               THEN 'berry_color: ' || berry_color END,
+                      CASE WHEN grape_health IS NOT NULL AND pg_temp.map_grape_health(grape_health) IS NULL
+                               THEN 'grape_health: ' || grape_health END)
+WHERE (berry_size IS NOT NULL AND pg_temp.map_berry_size(berry_size) IS NULL)
+   OR (berry_color IS NOT NULL AND pg_temp.map_berry_color(berry_color) IS NULL)
+   OR (grape_health IS NOT NULL AND pg_temp.map_grape_health(grape_health) IS NULL);
+
+ALTER TABLE harvest_quality
+    ALTER COLUMN berry_size   TYPE berry_size   USING pg_temp.map_berry_size(berry_size)::berry_size,
+    ALTER COLUMN berry_color  TYPE berry_color  USING pg_temp.map_berry_color(berry_color)::berry_color,
+    ALTER COLUMN grape_health TYPE grape_health USING pg_temp.map_grape_health(grape_health)::grape_health;
+
+-- Profil ocenjivanja po sorti (sorte bez profila koriste podrazumevana pravila iz servisa)
+CREATE TABLE quality_score_profiles (
+                                        id                 UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                        grape_variety      VARCHAR(255) NOT NULL UNIQUE,
+    -- Optimalni opsezi i tolerancija van opsega
+                                        brix_min           DOUBLE PRECISION NOT NULL,
+                                        brix_max           DOUBLE PRECISION NOT NULL,
+                                        brix_tolerance     DOUBLE PRECISION NOT NULL CHECK (brix_tolerance > 0),
+                                        ph_min             DOUBLE PRECISION NOT NULL,
+                                        ph_max             DOUBLE PRECISION NOT NULL,
+                                        ph_tolerance       DOUBLE PRECISION NOT NULL CHECK (ph_tolerance > 0),
+                                        acidity_min        DOUBLE PRECISION NOT NULL,
+                                        acidity_max        DOUBLE PRECISION NOT NULL,
+                                        acidity_tolerance  DOUBLE PRECISION NOT NULL CHECK (acidity_tolerance > 0),
+    -- Težine komponenti
+                                        weight_brix        DOUBLE PRECISION NOT NULL CHECK (weight_brix >= 0),
+                                        weight_ph          DOUBLE PRECISION NOT NULL CHECK (weight_ph >= 0),
+                                        weight_acidity     DOUBLE PRECISION NOT NULL CHECK (weight_acidity >= 0),
+                                        weight_health      DOUBLE PRECISION NOT NULL CHECK (weight_health >= 0),
+    -- Pragovi klasa
+                                        grade_a_min        DOUBLE PRECISION NOT NULL CHECK (grade_a_min BETWEEN 0 AND 100),
+                                        grade_b_min        DOUBLE PRECISION NOT NULL CHECK (grade_b_min BETWEEN 0 AND 100),
+    -- Meta
+                                        updated_by         UUID NOT NULL,
+                                        created_at         TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                                        updated_at         TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                                        CHECK (brix_min <= brix_max AND ph_min <= ph_max AND acidity_min <= acidity_max),
+                                        CHECK (grade_b_min <= grade_a_min)
+);
+
+ALTER TABLE harvests
+    ADD COLUMN quality_score DOUBLE PRECISION CHECK (quality_score BETWEEN 0 AND 100),
+    ADD COLUMN quality_grade quality_grade;
+
+CREATE INDEX idx_harvests_quality_grade ON harvests(quality_grade);
@@ -0,0 +1,11 @@
+DROP INDEX IF EXISTS idx_harvests_contract_id;
+DROP INDEX IF EXISTS idx_grower_payments_grower_season;
+DROP INDEX IF EXISTS idx_grape_contracts_grower_season;
+
+ALTER TABLE harvests DROP COLUMN IF EXISTS contract_id;
+
+DROP TABLE IF EXISTS grower_payments;
+DROP TABLE IF EXISTS grape_contracts;
+DROP TABLE IF EXISTS growers;
+
+DROP TYPE IF EXISTS contract_status;
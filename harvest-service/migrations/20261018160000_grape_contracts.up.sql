@@ -0,0 +1,64 @@
+-- Otkup grožđa: proizvođači, ugovori, isplate i veza berbi sa ugovorom
+CREATE TYPE contract_status AS ENUM ('active', 'closed');
+
+CREATE TABLE growers (
+                         id            UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                         name          VARCHAR(255) NOT NULL,
+                         tax_id        VARCHAR(50) UNIQUE,
+                         address       TEXT,
+                         phone         VARCHAR(50),
+                         email         VARCHAR(255),
+                         bank_account  VARCHAR(100),
+                         notes         TEXT,
+                         created_by    UUID NOT NULL,
+                         created_at    TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                         updated_at    TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE grape_contracts (
+                                 id                      UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                 grower_id               UUID NOT NULL REFERENCES growers(id),
+                                 contract_number         VARCHAR(50) NOT NULL UNIQUE,
+                                 season                  INTEGER NOT NULL,
+                                 grape_variety           VARCHAR(255),
+                                 status                  contract_status NOT NULL DEFAULT 'active',
+    -- Cena
+                                 base_price_per_kg       DOUBLE PRECISION NOT NULL CHECK (base_price_per_kg >= 0),
+    -- Korekcija po Brix-u
+                                 target_brix             DOUBLE PRECISION CHECK (target_brix BETWEEN 0 AND 50),
+                                 brix_bonus_per_point    DOUBLE PRECISION CHECK (brix_bonus_per_point >= 0),
+                                 brix_penalty_per_point  DOUBLE PRECISION CHECK (brix_penalty_per_point >= 0),
+    -- Korekcija po klasi (% osnovne cene)
+                                 grade_a_adjustment_pct  DOUBLE PRECISION NOT NULL DEFAULT 0 CHECK (grade_a_adjustment_pct BETWEEN -100 AND 100),
+                                 grade_b_adjustment_pct  DOUBLE PRECISION NOT NULL DEFAULT 0 CHECK (grade_b_adjustment_pct BETWEEN -100 AND 100),
+                                 grade_c_adjustment_pct  DOUBLE PRECISION NOT NULL DEFAULT 0 CHECK (grade_c_adjustment_pct BETWEEN -100 AND 100),
+    -- Ugovorena količina
+                                 max_tonnage_kg          DOUBLE PRECISION CHECK (max_tonnage_kg > 0),
+                                 over_cap_price_per_kg   DOUBLE PRECISION CHECK (over_cap_price_per_kg >= 0),
+    -- Meta
+                                 signed_on               DATE,
+                                 notes                   TEXT,
+                                 created_by              UUID NOT NULL,
+                                 created_at              TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                                 updated_at              TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE grower_payments (
+                                 id           UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                                 grower_id    UUID NOT NULL REFERENCES growers(id),
+                                 season       INTEGER NOT NULL,
+                                 amount       DOUBLE PRECISION NOT NULL CHECK (amount > 0),
+                                 paid_on      DATE NOT NULL,
+                                 reference    VARCHAR(100),
+                                 notes        TEXT,
+                                 recorded_by  UUID NOT NULL,
+                                 created_at   TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Berba otkupljenog grožđa pripada ugovoru (ture nasleđuju ugovor od berbe)
+ALTER TABLE harvests
+    ADD COLUMN contract_id UUID REFERENCES grape_contracts(id);
+
+CREATE INDEX idx_grape_contracts_grower_season ON grape_contracts(grower_id, season);
+CREATE INDEX idx_grower_payments_grower_season ON grower_payments(grower_id, season);
+CREATE INDEX idx_harvests_contract_id          ON harvests(contract_id);
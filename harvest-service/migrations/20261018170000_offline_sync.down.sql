@@ -0,0 +1,15 @@
+DROP INDEX IF EXISTS idx_sync_tombstones_version;
+DROP INDEX IF EXISTS idx_harvest_quality_sync_version;
+DROP INDEX IF EXISTS idx_harvests_sync_version;
+
+DROP TRIGGER IF EXISTS trg_harvest_quality_tombstone ON harvest_quality;
+DROP TRIGGER IF EXISTS trg_harvests_tombstone ON harvests;
+DROP FUNCTION IF EXISTS record_sync_tombstone();
+DROP TABLE IF EXISTS sync_tombstones;
+
+DROP TRIGGER IF EXISTS trg_harvest_quality_sync_version ON harvest_quality;
+DROP TRIGGER IF EXISTS trg_harvests_sync_version ON harvests;
+DROP FUNCTION IF EXISTS bump_sync_version();
+
+ALTER TABLE harvest_quality DROP COLUMN IF EXISTS sync_version;
+ALTER TABLE harvests DROP COLUMN IF EXISTS sync_version;
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_attachments_entity;
+DROP TABLE IF EXISTS attachments;
+DROP TYPE IF EXISTS attachment_entity;
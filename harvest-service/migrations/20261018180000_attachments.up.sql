@@ -0,0 +1,22 @@
+-- Prilozi (fotografije, sertifikati, čeklisti) uz berbe, merenja, parcele, batch-eve i tankove
+CREATE TYPE attachment_entity AS ENUM ('harvest', 'quality_measurement', 'parcel', 'batch', 'tank');
+
+CREATE TABLE attachments (
+                             id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                             entity_type     attachment_entity NOT NULL,
+                             entity_id       UUID NOT NULL,
+                             vineyard_id     UUID,                  -- samo za parcele
+                             file_name       VARCHAR(255) NOT NULL,
+                             content_type    VARCHAR(100) NOT NULL,
+                             size_bytes      BIGINT NOT NULL CHECK (size_bytes > 0),
+    -- Lokacija u storage-u (lokalni disk ili S3)
+                             storage_key     VARCHAR(500) NOT NULL UNIQUE,
+                             thumbnail_key   VARCHAR(500),
+                             width           INTEGER,
+                             height          INTEGER,
+                             description     TEXT,
+                             uploaded_by     UUID NOT NULL,
+                             created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_attachments_entity ON attachments(entity_type, entity_id);
@@ -0,0 +1,118 @@
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use serde::{de::IgnoredAny, Deserialize};
+use uuid::Uuid;
+
+use crate::{
+    error::AppError,
+    models::{LineageBatchShare, LineageCrushShare},
+};
+
+/// Poreklo berbe kako ga vraća fermentation-service (muljanja i batch-evi se prosleđuju)
+#[derive(Debug, Clone, Deserialize)]
+pub struct RemoteHarvestLineage {
+    pub crushed_kg: f64,
+    pub must_liters: f64,
+    pub crushes: Vec<LineageCrushShare>,
+    pub batches: Vec<LineageBatchShare>,
+}
+
+/// HTTP klijent za fermentation-service
+#[derive(Clone)]
+pub struct FermentationClient {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+impl FermentationClient {
+    pub fn new(base_url: &str) -> anyhow::Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()?;
+
+        Ok(Self {
+            http,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        })
+    }
+
+    /// Proveri da batch postoji u fermentation-service
+    pub async fn check_batch(&self, token: &str, batch_id: Uuid) -> Result<(), AppError> {
+        let url = format!("{}/api/v1/batches/{}", self.base_url, batch_id);
+        self.get_json::<IgnoredAny>(&url, token, "Batch not found").await?;
+        Ok(())
+    }
+
+    /// Proveri da tank postoji u fermentation-service
+    pub async fn check_tank(&self, token: &str, tank_id: Uuid) -> Result<(), AppError> {
+        let url = format!("{}/api/v1/tanks/{}", self.base_url, tank_id);
+        self.get_json::<IgnoredAny>(&url, token, "Tank not found").await?;
+        Ok(())
+    }
+
+    /// Da li batch ili tank (`entity` = "batches" | "tanks") još postoji
+    pub async fn exists(&self, token: &str, entity: &str, id: Uuid) -> Result<bool, AppError> {
+        let url = format!("{}/api/v1/{}/{}", self.base_url, entity, id);
+
+        let response = self
+            .http
+            .get(&url)
+            .bearer_auth(token)
+            .send()
+            .await
+            .map_err(|e| AppError::UpstreamError(format!("fermentation-service: {}", e)))?;
+
+        match response.status() {
+            s if s.is_success() => Ok(true),
+            StatusCode::NOT_FOUND => Ok(false),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(AppError::Forbidden(
+                "Access to fermentation data denied".to_string(),
+            )),
+            s => Err(AppError::UpstreamError(format!(
+                "fermentation-service returned {} for {}",
+                s, url
+            ))),
+        }
+    }
+
+    /// Muljanja i batch-evi u koje je ušlo grožđe berbe
+    pub async fn get_harvest_lineage(
+        &self,
+        token: &str,
+        harvest_id: Uuid,
+    ) -> Result<RemoteHarvestLineage, AppError> {
+        let url = format!("{}/api/v1/harvests/{}/lineage", self.base_url, harvest_id);
+        self.get_json(&url, token, "Harvest lineage not found").await
+    }
+
+    async fn get_json<T: for<'de> Deserialize<'de>>(
+        &self,
+        url: &str,
+        token: &str,
+        not_found_message: &str,
+    ) -> Result<T, AppError> {
+        let response = self
+            .http
+            .get(url)
+            .bearer_auth(token)
+            .send()
+            .await
+            .map_err(|e| AppError::UpstreamError(format!("fermentation-service: {}", e)))?;
+
+        match response.status() {
+            s if s.is_success() => response
+                .json::<T>()
+                .await
+                .map_err(|e| AppError::UpstreamError(format!("fermentation-service: {}", e))),
+            StatusCode::NOT_FOUND => Err(AppError::ValidationError(not_found_message.to_string())),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(AppError::Forbidden(
+                "Access to fermentation data denied".to_string(),
+            )),
+            s => Err(AppError::UpstreamError(format!(
+                "fermentation-service returned {} for {}",
+                s, url
+            ))),
+        }
+    }
+}
@@ -0,0 +1,5 @@
+pub mod fermentation_client;
+pub mod vineyard_client;
+
+pub use fermentation_client::*;
+pub use vineyard_client::*;
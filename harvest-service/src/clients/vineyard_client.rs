@@ -0,0 +1,138 @@
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{error::AppError, extractors::AuthenticatedUser, models::UserRole};
+
+/// Vinograd kako ga vraća vineyard-service (samo polja koja nam trebaju)
+#[derive(Debug, Clone, Deserialize)]
+pub struct RemoteVineyard {
+    pub id: Uuid,
+    pub name: String,
+    pub owner_id: Uuid,
+}
+
+/// Parcela kako je vraća vineyard-service
+#[derive(Debug, Clone, Deserialize)]
+pub struct RemoteParcel {
+    pub vineyard_id: Uuid,
+    pub name: String,
+    pub area: f64, // m²
+    pub grape_variety: String,
+}
+
+/// Proverena parcela - podaci koji se keširaju uz berbu
+#[derive(Debug, Clone)]
+pub struct ParcelSnapshot {
+    pub vineyard_name: String,
+    pub parcel_name: String,
+    pub grape_variety: String,
+    pub parcel_area_m2: f64,
+}
+
+/// HTTP klijent za vineyard-service
+#[derive(Clone)]
+pub struct VineyardClient {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+impl VineyardClient {
+    pub fn new(base_url: &str) -> anyhow::Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()?;
+
+        Ok(Self {
+            http,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        })
+    }
+
+    pub async fn get_vineyard(
+        &self,
+        token: &str,
+        vineyard_id: Uuid,
+    ) -> Result<RemoteVineyard, AppError> {
+        let url = format!("{}/api/v1/vineyards/{}", self.base_url, vineyard_id);
+        self.get_json(&url, token, "Vineyard not found").await
+    }
+
+    pub async fn get_parcel(
+        &self,
+        token: &str,
+        vineyard_id: Uuid,
+        parcel_id: Uuid,
+    ) -> Result<RemoteParcel, AppError> {
+        let url = format!(
+            "{}/api/v1/vineyards/{}/parcels/{}",
+            self.base_url, vineyard_id, parcel_id
+        );
+        self.get_json(&url, token, "Parcel not found in this vineyard")
+            .await
+    }
+
+    /// Proveri da vinograd postoji i pripada korisniku, i da je parcela deo tog vinograda
+    pub async fn resolve_parcel(
+        &self,
+        auth: &AuthenticatedUser,
+        vineyard_id: Uuid,
+        parcel_id: Uuid,
+    ) -> Result<ParcelSnapshot, AppError> {
+        let vineyard = self.get_vineyard(&auth.token, vineyard_id).await?;
+
+        if auth.claims.role != UserRole::Admin && vineyard.owner_id != auth.claims.user_id()? {
+            return Err(AppError::Forbidden(
+                "Vineyard belongs to another user".to_string(),
+            ));
+        }
+
+        let parcel = self.get_parcel(&auth.token, vineyard_id, parcel_id).await?;
+
+        // vineyard-service već vraća 404 za parcelu iz drugog vinograda, ali ne verujemo slepo
+        if parcel.vineyard_id != vineyard.id {
+            return Err(AppError::ValidationError(
+                "Parcel does not belong to the given vineyard".to_string(),
+            ));
+        }
+
+        Ok(ParcelSnapshot {
+            vineyard_name: vineyard.name,
+            parcel_name: parcel.name,
+            grape_variety: parcel.grape_variety,
+            parcel_area_m2: parcel.area,
+        })
+    }
+
+    async fn get_json<T: for<'de> Deserialize<'de>>(
+        &self,
+        url: &str,
+        token: &str,
+        not_found_message: &str,
+    ) -> Result<T, AppError> {
+        let response = self
+            .http
+            .get(url)
+            .bearer_auth(token)
+            .send()
+            .await
+            .map_err(|e| AppError::UpstreamError(format!("vineyard-service: {}", e)))?;
+
+        match response.status() {
+            s if s.is_success() => response
+                .json::<T>()
+                .await
+                .map_err(|e| AppError::UpstreamError(format!("vineyard-service: {}", e))),
+            StatusCode::NOT_FOUND => Err(AppError::ValidationError(not_found_message.to_string())),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(AppError::Forbidden(
+                "Access to vineyard denied".to_string(),
+            )),
+            s => Err(AppError::UpstreamError(format!(
+                "vineyard-service returned {} for {}",
+                s, url
+            ))),
+        }
+    }
+}
@@ -6,6 +6,13 @@ pub struct Settings {
     pub host: String,
     pub port: u16,
     pub jwt_secret: String,
+    pub vineyard_service_url: String,
+    pub fermentation_service_url: String,
+    pub attachment_storage: String,
+    pub attachment_local_dir: String,
+    pub attachment_s3_bucket: Option<String>,
+    pub attachment_s3_endpoint: Option<String>,
+    pub attachment_max_bytes: usize,
     pub allowed_origins: Vec<String>,
 }
 
@@ -26,6 +33,19 @@ impl Settings {
                 .unwrap_or_else(|_| "8003".to_string())
                 .parse()?,
             jwt_secret: env::var("JWT_SECRET")?,
+            vineyard_service_url: env::var("VINEYARD_SERVICE_URL")
+                .unwrap_or_else(|_| "http://localhost:8002".to_string()),
+            fermentation_service_url: env::var("FERMENTATION_SERVICE_URL")
+                .unwrap_or_else(|_| "http://localhost:8004".to_string()),
+            attachment_storage: env::var("ATTACHMENT_STORAGE")
+                .unwrap_or_else(|_| "local".to_string()),
+            attachment_local_dir: env::var("ATTACHMENT_LOCAL_DIR")
+                .unwrap_or_else(|_| "./data/attachments".to_string()),
+            attachment_s3_bucket: env::var("ATTACHMENT_S3_BUCKET").ok(),
+            attachment_s3_endpoint: env::var("ATTACHMENT_S3_ENDPOINT").ok(),
+            attachment_max_bytes: env::var("ATTACHMENT_MAX_BYTES")
+                .unwrap_or_else(|_| "10485760".to_string())
+                .parse()?,
             allowed_origins,
         })
     }
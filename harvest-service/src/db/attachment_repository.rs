@@ -0,0 +1,112 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{Attachment, AttachmentEntity, NewAttachment};
+
+#[derive(Clone)]
+pub struct AttachmentRepository {
+    pool: PgPool,
+}
+
+impl AttachmentRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    // ============== Attachments ==============
+
+    pub async fn create(
+        &self,
+        uploaded_by: Uuid,
+        attachment: NewAttachment,
+    ) -> Result<Attachment, AppError> {
+        let attachment = sqlx::query_as::<_, Attachment>(
+            r#"
+            INSERT INTO attachments (
+                id, entity_type, entity_id, vineyard_id, file_name, content_type, size_bytes,
+                storage_key, thumbnail_key, width, height, description, uploaded_by
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            RETURNING *
+            "#,
+        )
+            .bind(attachment.id)
+            .bind(attachment.entity_type)
+            .bind(attachment.entity_id)
+            .bind(attachment.vineyard_id)
+            .bind(attachment.file_name)
+            .bind(attachment.content_type)
+            .bind(attachment.size_bytes)
+            .bind(attachment.storage_key)
+            .bind(attachment.thumbnail_key)
+            .bind(attachment.width)
+            .bind(attachment.height)
+            .bind(attachment.description)
+            .bind(uploaded_by)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(attachment)
+    }
+
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Attachment, AppError> {
+        sqlx::query_as::<_, Attachment>("SELECT * FROM attachments WHERE id = $1")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::NotFound("Attachment not found".to_string()),
+                _ => AppError::DatabaseError(e),
+            })
+    }
+
+    pub async fn list_by_entity(
+        &self,
+        entity_type: AttachmentEntity,
+        entity_id: Uuid,
+    ) -> Result<Vec<Attachment>, AppError> {
+        let attachments = sqlx::query_as::<_, Attachment>(
+            r#"
+            SELECT * FROM attachments
+            WHERE entity_type = $1 AND entity_id = $2
+            ORDER BY created_at DESC
+            "#,
+        )
+            .bind(entity_type)
+            .bind(entity_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(attachments)
+    }
+
+    pub async fn delete(&self, id: Uuid) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM attachments WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Briše priloge obrisanih zapisa i vraća ih, da bi se uklonili i fajlovi
+    pub async fn delete_by_entities(
+        &self,
+        entity_type: AttachmentEntity,
+        entity_ids: &[Uuid],
+    ) -> Result<Vec<Attachment>, AppError> {
+        let attachments = sqlx::query_as::<_, Attachment>(
+            r#"
+            DELETE FROM attachments
+            WHERE entity_type = $1 AND entity_id = ANY($2)
+            RETURNING *
+            "#,
+        )
+            .bind(entity_type)
+            .bind(entity_ids)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(attachments)
+    }
+}
@@ -0,0 +1,344 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{
+    ContractDelivery, CreateContractRequest, CreateGrowerRequest, GrapeContract, Grower,
+    GrowerPayment, RecordGrowerPaymentRequest, UpdateGrowerRequest,
+};
+
+#[derive(Clone)]
+pub struct ContractRepository {
+    pool: PgPool,
+}
+
+impl ContractRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    // ============== Growers ==============
+
+    pub async fn create_grower(
+        &self,
+        created_by: Uuid,
+        req: CreateGrowerRequest,
+    ) -> Result<Grower, AppError> {
+        sqlx::query_as::<_, Grower>(
+            r#"
+            INSERT INTO growers (name, tax_id, address, phone, email, bank_account, notes, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING *
+            "#,
+        )
+            .bind(&req.name)
+            .bind(&req.tax_id)
+            .bind(&req.address)
+            .bind(&req.phone)
+            .bind(&req.email)
+            .bind(&req.bank_account)
+            .bind(&req.notes)
+            .bind(created_by)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(ref db) if db.is_unique_violation() => {
+                    AppError::Conflict("A grower with this tax ID already exists".to_string())
+                }
+                _ => AppError::DatabaseError(e),
+            })
+    }
+
+    pub async fn find_grower(&self, id: Uuid) -> Result<Grower, AppError> {
+        sqlx::query_as::<_, Grower>("SELECT * FROM growers WHERE id = $1")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::NotFound("Grower not found".to_string()),
+                _ => AppError::DatabaseError(e),
+            })
+    }
+
+    pub async fn list_growers(&self) -> Result<Vec<Grower>, AppError> {
+        let growers = sqlx::query_as::<_, Grower>("SELECT * FROM growers ORDER BY name ASC")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(growers)
+    }
+
+    pub async fn update_grower(
+        &self,
+        id: Uuid,
+        req: UpdateGrowerRequest,
+    ) -> Result<Grower, AppError> {
+        sqlx::query_as::<_, Grower>(
+            r#"
+            UPDATE growers SET
+                name          = COALESCE($2, name),
+                tax_id        = COALESCE($3, tax_id),
+                address       = COALESCE($4, address),
+                phone         = COALESCE($5, phone),
+                email         = COALESCE($6, email),
+                bank_account  = COALESCE($7, bank_account),
+                notes         = COALESCE($8, notes),
+                updated_at    = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+            .bind(id)
+            .bind(req.name)
+            .bind(req.tax_id)
+            .bind(req.address)
+            .bind(req.phone)
+            .bind(req.email)
+            .bind(req.bank_account)
+            .bind(req.notes)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::NotFound("Grower not found".to_string()),
+                sqlx::Error::Database(ref db) if db.is_unique_violation() => {
+                    AppError::Conflict("A grower with this tax ID already exists".to_string())
+                }
+                _ => AppError::DatabaseError(e),
+            })
+    }
+
+    // ============== Contracts ==============
+
+    pub async fn create_contract(
+        &self,
+        created_by: Uuid,
+        req: CreateContractRequest,
+    ) -> Result<GrapeContract, AppError> {
+        sqlx::query_as::<_, GrapeContract>(
+            r#"
+            INSERT INTO grape_contracts (
+                grower_id, contract_number, season, grape_variety, base_price_per_kg,
+                target_brix, brix_bonus_per_point, brix_penalty_per_point,
+                grade_a_adjustment_pct, grade_b_adjustment_pct, grade_c_adjustment_pct,
+                max_tonnage_kg, over_cap_price_per_kg, signed_on, notes, created_by
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
+            RETURNING *
+            "#,
+        )
+            .bind(req.grower_id)
+            .bind(&req.contract_number)
+            .bind(req.season)
+            .bind(&req.grape_variety)
+            .bind(req.base_price_per_kg)
+            .bind(req.target_brix)
+            .bind(req.brix_bonus_per_point)
+            .bind(req.brix_penalty_per_point)
+            .bind(req.grade_a_adjustment_pct)
+            .bind(req.grade_b_adjustment_pct)
+            .bind(req.grade_c_adjustment_pct)
+            .bind(req.max_tonnage_kg)
+            .bind(req.over_cap_price_per_kg)
+            .bind(req.signed_on)
+            .bind(&req.notes)
+            .bind(created_by)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(ref db) if db.is_unique_violation() => AppError::Conflict(
+                    format!("Contract '{}' already exists", req.contract_number),
+                ),
+                sqlx::Error::Database(ref db) if db.is_foreign_key_violation() => {
+                    AppError::ValidationError("Grower does not exist".to_string())
+                }
+                _ => AppError::DatabaseError(e),
+            })
+    }
+
+    pub async fn find_contract(&self, id: Uuid) -> Result<GrapeContract, AppError> {
+        sqlx::query_as::<_, GrapeContract>("SELECT * FROM grape_contracts WHERE id = $1")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::NotFound("Contract not found".to_string()),
+                _ => AppError::DatabaseError(e),
+            })
+    }
+
+    pub async fn list_contracts(
+        &self,
+        grower_id: Option<Uuid>,
+        season: Option<i32>,
+    ) -> Result<Vec<GrapeContract>, AppError> {
+        let contracts = sqlx::query_as::<_, GrapeContract>(
+            r#"
+            SELECT * FROM grape_contracts
+            WHERE ($1::UUID IS NULL OR grower_id = $1)
+              AND ($2::INT IS NULL OR season = $2)
+            ORDER BY season DESC, contract_number ASC
+            "#,
+        )
+            .bind(grower_id)
+            .bind(season)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(contracts)
+    }
+
+    /// Upis spojenog ugovora; ako ga je neko izmenio u međuvremenu, izmena se odbija
+    pub async fn update_contract(&self, contract: &GrapeContract) -> Result<GrapeContract, AppError> {
+        sqlx::query_as::<_, GrapeContract>(
+            r#"
+            UPDATE grape_contracts SET
+                status                 = $2,
+                base_price_per_kg      = $3,
+                target_brix            = $4,
+                brix_bonus_per_point   = $5,
+                brix_penalty_per_point = $6,
+                grade_a_adjustment_pct = $7,
+                grade_b_adjustment_pct = $8,
+                grade_c_adjustment_pct = $9,
+                max_tonnage_kg         = $10,
+                over_cap_price_per_kg  = $11,
+                notes                  = $12,
+                updated_at             = NOW()
+            WHERE id = $1 AND updated_at = $13
+            RETURNING *
+            "#,
+        )
+            .bind(contract.id)
+            .bind(&contract.status)
+            .bind(contract.base_price_per_kg)
+            .bind(contract.target_brix)
+            .bind(contract.brix_bonus_per_point)
+            .bind(contract.brix_penalty_per_point)
+            .bind(contract.grade_a_adjustment_pct)
+            .bind(contract.grade_b_adjustment_pct)
+            .bind(contract.grade_c_adjustment_pct)
+            .bind(contract.max_tonnage_kg)
+            .bind(contract.over_cap_price_per_kg)
+            .bind(&contract.notes)
+            .bind(contract.updated_at)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::Conflict(
+                    "Contract was modified concurrently, please retry".to_string(),
+                ),
+                _ => AppError::DatabaseError(e),
+            })
+    }
+
+    /// Veži berbu za ugovor (None = ukloni vezu)
+    pub async fn link_harvest(
+        &self,
+        harvest_id: Uuid,
+        contract_id: Option<Uuid>,
+    ) -> Result<(), AppError> {
+        sqlx::query("UPDATE harvests SET contract_id = $2, updated_at = NOW() WHERE id = $1")
+            .bind(harvest_id)
+            .bind(contract_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    // ============== Deliveries ==============
+
+    /// Isporuke po ugovoru redom prijema: ture sa vage, a za berbe bez tura ukupna težina
+    pub async fn list_deliveries(&self, contract_id: Uuid) -> Result<Vec<ContractDelivery>, AppError> {
+        let deliveries = sqlx::query_as::<_, ContractDelivery>(
+            r#"
+            SELECT * FROM (
+                SELECT
+                    h.contract_id,
+                    h.id                AS harvest_id,
+                    l.id                AS load_id,
+                    l.ticket_number,
+                    l.arrived_at        AS delivered_at,
+                    l.net_weight_kg     AS weight_kg,
+                    COALESCE(l.brix, (SELECT AVG(brix) FROM harvest_quality q WHERE q.harvest_id = h.id)) AS brix,
+                    h.quality_grade
+                FROM harvest_loads l
+                JOIN harvests h ON h.id = l.harvest_id
+                WHERE h.contract_id = $1
+                  AND h.status <> 'cancelled'
+
+                UNION ALL
+
+                SELECT
+                    h.contract_id,
+                    h.id,
+                    NULL::UUID,
+                    NULL::VARCHAR,
+                    h.harvest_date::TIMESTAMPTZ,
+                    h.total_weight_kg,
+                    (SELECT AVG(brix) FROM harvest_quality q WHERE q.harvest_id = h.id),
+                    h.quality_grade
+                FROM harvests h
+                WHERE h.contract_id = $1
+                  AND h.status <> 'cancelled'
+                  AND h.total_weight_kg > 0
+                  AND NOT EXISTS (SELECT 1 FROM harvest_loads l WHERE l.harvest_id = h.id)
+            ) d
+            ORDER BY d.delivered_at ASC, d.ticket_number ASC NULLS LAST
+            "#,
+        )
+            .bind(contract_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(deliveries)
+    }
+
+    // ============== Payments ==============
+
+    pub async fn record_payment(
+        &self,
+        grower_id: Uuid,
+        recorded_by: Uuid,
+        req: RecordGrowerPaymentRequest,
+    ) -> Result<GrowerPayment, AppError> {
+        let payment = sqlx::query_as::<_, GrowerPayment>(
+            r#"
+            INSERT INTO grower_payments (grower_id, season, amount, paid_on, reference, notes, recorded_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#,
+        )
+            .bind(grower_id)
+            .bind(req.season)
+            .bind(req.amount)
+            .bind(req.paid_on)
+            .bind(req.reference)
+            .bind(req.notes)
+            .bind(recorded_by)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(payment)
+    }
+
+    pub async fn list_payments(
+        &self,
+        grower_id: Uuid,
+        season: Option<i32>,
+    ) -> Result<Vec<GrowerPayment>, AppError> {
+        let payments = sqlx::query_as::<_, GrowerPayment>(
+            r#"
+            SELECT * FROM grower_payments
+            WHERE grower_id = $1
+              AND ($2::INT IS NULL OR season = $2)
+            ORDER BY paid_on ASC, created_at ASC
+            "#,
+        )
+            .bind(grower_id)
+            .bind(season)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(payments)
+    }
+}
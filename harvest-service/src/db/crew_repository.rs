@@ -0,0 +1,374 @@
+use chrono::NaiveDate;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{
+    CreateCrewRequest, CreatePickerRequest, PayrollRow, Picker, PickerCrew,
+    PickerWorkEntry, RecordWorkRequest, UpdateCrewRequest, UpdatePickerRequest,
+};
+
+#[derive(Clone)]
+pub struct CrewRepository {
+    pool: PgPool,
+}
+
+impl CrewRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    // ============== Crews ==============
+
+    pub async fn create_crew(
+        &self,
+        created_by: Uuid,
+        req: CreateCrewRequest,
+    ) -> Result<PickerCrew, AppError> {
+        sqlx::query_as::<_, PickerCrew>(
+            r#"
+            INSERT INTO picker_crews (name, leader_name, notes, daily_capacity_kg, created_by)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+        )
+            .bind(&req.name)
+            .bind(&req.leader_name)
+            .bind(&req.notes)
+            .bind(req.daily_capacity_kg)
+            .bind(created_by)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(ref db) if db.is_unique_violation() => {
+                    AppError::Conflict(format!("Crew '{}' already exists", req.name))
+                }
+                _ => AppError::DatabaseError(e),
+            })
+    }
+
+    pub async fn find_crew(&self, id: Uuid) -> Result<PickerCrew, AppError> {
+        sqlx::query_as::<_, PickerCrew>("SELECT * FROM picker_crews WHERE id = $1")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::NotFound("Crew not found".to_string()),
+                _ => AppError::DatabaseError(e),
+            })
+    }
+
+    pub async fn update_crew(
+        &self,
+        id: Uuid,
+        req: UpdateCrewRequest,
+    ) -> Result<PickerCrew, AppError> {
+        sqlx::query_as::<_, PickerCrew>(
+            r#"
+            UPDATE picker_crews SET
+                name              = COALESCE($2, name),
+                leader_name       = COALESCE($3, leader_name),
+                notes             = COALESCE($4, notes),
+                daily_capacity_kg = COALESCE($5, daily_capacity_kg),
+                updated_at        = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+            .bind(id)
+            .bind(req.name)
+            .bind(req.leader_name)
+            .bind(req.notes)
+            .bind(req.daily_capacity_kg)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::NotFound("Crew not found".to_string()),
+                sqlx::Error::Database(ref db) if db.is_unique_violation() => {
+                    AppError::Conflict("Crew name already in use".to_string())
+                }
+                _ => AppError::DatabaseError(e),
+            })
+    }
+
+    pub async fn list_crews(&self) -> Result<Vec<PickerCrew>, AppError> {
+        let crews = sqlx::query_as::<_, PickerCrew>("SELECT * FROM picker_crews ORDER BY name ASC")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(crews)
+    }
+
+    // ============== Pickers ==============
+
+    pub async fn create_picker(&self, req: CreatePickerRequest) -> Result<Picker, AppError> {
+        sqlx::query_as::<_, Picker>(
+            r#"
+            INSERT INTO pickers (
+                crew_id, full_name, employee_code, hourly_rate, rate_per_kg, rate_per_bin
+            )
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#,
+        )
+            .bind(req.crew_id)
+            .bind(&req.full_name)
+            .bind(&req.employee_code)
+            .bind(req.hourly_rate)
+            .bind(req.rate_per_kg)
+            .bind(req.rate_per_bin)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(ref db) if db.is_unique_violation() => {
+                    AppError::Conflict("Employee code already in use".to_string())
+                }
+                sqlx::Error::Database(ref db) if db.is_foreign_key_violation() => {
+                    AppError::ValidationError("Crew does not exist".to_string())
+                }
+                _ => AppError::DatabaseError(e),
+            })
+    }
+
+    pub async fn find_picker(&self, id: Uuid) -> Result<Picker, AppError> {
+        sqlx::query_as::<_, Picker>("SELECT * FROM pickers WHERE id = $1")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::NotFound("Picker not found".to_string()),
+                _ => AppError::DatabaseError(e),
+            })
+    }
+
+    pub async fn list_pickers(&self, crew_id: Option<Uuid>) -> Result<Vec<Picker>, AppError> {
+        let pickers = sqlx::query_as::<_, Picker>(
+            r#"
+            SELECT * FROM pickers
+            WHERE ($1::UUID IS NULL OR crew_id = $1)
+            ORDER BY full_name ASC
+            "#,
+        )
+            .bind(crew_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(pickers)
+    }
+
+    pub async fn update_picker(
+        &self,
+        id: Uuid,
+        req: UpdatePickerRequest,
+    ) -> Result<Picker, AppError> {
+        sqlx::query_as::<_, Picker>(
+            r#"
+            UPDATE pickers SET
+                crew_id       = COALESCE($2, crew_id),
+                full_name     = COALESCE($3, full_name),
+                hourly_rate   = COALESCE($4, hourly_rate),
+                rate_per_kg   = COALESCE($5, rate_per_kg),
+                rate_per_bin  = COALESCE($6, rate_per_bin),
+                active        = COALESCE($7, active),
+                updated_at    = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+            .bind(id)
+            .bind(req.crew_id)
+            .bind(req.full_name)
+            .bind(req.hourly_rate)
+            .bind(req.rate_per_kg)
+            .bind(req.rate_per_bin)
+            .bind(req.active)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::NotFound("Picker not found".to_string()),
+                sqlx::Error::Database(ref db) if db.is_foreign_key_violation() => {
+                    AppError::ValidationError("Crew does not exist".to_string())
+                }
+                _ => AppError::DatabaseError(e),
+            })
+    }
+
+    // ============== Harvest assignments ==============
+
+    pub async fn assign_crew(&self, harvest_id: Uuid, crew_id: Uuid) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO harvest_crews (harvest_id, crew_id)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+            .bind(harvest_id)
+            .bind(crew_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn unassign_crew(&self, harvest_id: Uuid, crew_id: Uuid) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM harvest_crews WHERE harvest_id = $1 AND crew_id = $2")
+            .bind(harvest_id)
+            .bind(crew_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn list_harvest_crews(&self, harvest_id: Uuid) -> Result<Vec<PickerCrew>, AppError> {
+        let crews = sqlx::query_as::<_, PickerCrew>(
+            r#"
+            SELECT c.* FROM picker_crews c
+            JOIN harvest_crews hc ON hc.crew_id = c.id
+            WHERE hc.harvest_id = $1
+            ORDER BY c.name ASC
+            "#,
+        )
+            .bind(harvest_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(crews)
+    }
+
+    // ============== Work entries ==============
+
+    /// Rad se upisuje samo beraču čija je ekipa raspoređena na berbu
+    /// (provera je u istom upitu, pa je ne može preteći uklanjanje ekipe)
+    pub async fn record_work(
+        &self,
+        harvest_id: Uuid,
+        recorded_by: Uuid,
+        work_date: NaiveDate,
+        rate_applied: f64,
+        amount: f64,
+        req: RecordWorkRequest,
+    ) -> Result<PickerWorkEntry, AppError> {
+        let entry = sqlx::query_as::<_, PickerWorkEntry>(
+            r#"
+            INSERT INTO picker_work_entries (
+                harvest_id, picker_id, work_date, hours_worked, kg_picked, bins_picked,
+                pay_basis, rate_applied, amount, notes, recorded_by
+            )
+            SELECT $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11
+            WHERE EXISTS (
+                SELECT 1 FROM pickers p
+                JOIN harvest_crews hc ON hc.crew_id = p.crew_id
+                WHERE p.id = $2 AND hc.harvest_id = $1
+            )
+            RETURNING *
+            "#,
+        )
+            .bind(harvest_id)
+            .bind(req.picker_id)
+            .bind(work_date)
+            .bind(req.hours_worked)
+            .bind(req.kg_picked)
+            .bind(req.bins_picked)
+            .bind(req.pay_basis)
+            .bind(rate_applied)
+            .bind(amount)
+            .bind(&req.notes)
+            .bind(recorded_by)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| {
+                AppError::Conflict("Picker's crew is not assigned to this harvest".to_string())
+            })?;
+
+        Ok(entry)
+    }
+
+    pub async fn list_work_by_harvest(
+        &self,
+        harvest_id: Uuid,
+    ) -> Result<Vec<PickerWorkEntry>, AppError> {
+        let entries = sqlx::query_as::<_, PickerWorkEntry>(
+            r#"
+            SELECT * FROM picker_work_entries
+            WHERE harvest_id = $1
+            ORDER BY work_date ASC, created_at ASC
+            "#,
+        )
+            .bind(harvest_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(entries)
+    }
+
+    pub async fn find_work_entry(&self, id: Uuid) -> Result<PickerWorkEntry, AppError> {
+        sqlx::query_as::<_, PickerWorkEntry>("SELECT * FROM picker_work_entries WHERE id = $1")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::NotFound("Work entry not found".to_string()),
+                _ => AppError::DatabaseError(e),
+            })
+    }
+
+    pub async fn delete_work_entry(&self, id: Uuid) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM picker_work_entries WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    // ============== Payroll ==============
+
+    pub async fn payroll_for_harvest(&self, harvest_id: Uuid) -> Result<Vec<PayrollRow>, AppError> {
+        let rows = sqlx::query_as::<_, PayrollRow>(&payroll_query("w.harvest_id = $1"))
+            .bind(harvest_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows)
+    }
+
+    pub async fn payroll_for_period(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<PayrollRow>, AppError> {
+        let rows = sqlx::query_as::<_, PayrollRow>(&payroll_query("w.work_date BETWEEN $1 AND $2"))
+            .bind(from)
+            .bind(to)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows)
+    }
+}
+
+/// Zbir po beraču za dati filter nad picker_work_entries (alias w)
+fn payroll_query(filter: &str) -> String {
+    format!(
+        r#"
+        SELECT
+            p.id                                AS picker_id,
+            p.full_name,
+            p.employee_code,
+            c.name                              AS crew_name,
+            COUNT(DISTINCT w.work_date)::BIGINT AS days_worked,
+            SUM(w.hours_worked)                 AS hours_worked,
+            SUM(w.kg_picked)                    AS kg_picked,
+            SUM(w.bins_picked)::BIGINT          AS bins_picked,
+            SUM(w.amount)                       AS amount
+        FROM picker_work_entries w
+        JOIN pickers p ON p.id = w.picker_id
+        LEFT JOIN picker_crews c ON c.id = p.crew_id
+        WHERE {}
+        GROUP BY p.id, c.name
+        ORDER BY c.name ASC NULLS LAST, p.full_name ASC
+        "#,
+        filter
+    )
+}
@@ -0,0 +1,126 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::db::HarvestRepository;
+use crate::error::AppError;
+use crate::models::{QualityScoreProfile, UpsertQualityProfileRequest};
+
+#[derive(Clone)]
+pub struct GradingRepository {
+    pool: PgPool,
+}
+
+impl GradingRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    // ============== Quality score profiles ==============
+
+    /// Upis profila i nova ocena svih berbi te sorte, u jednoj transakciji
+    pub async fn upsert_profile(
+        &self,
+        updated_by: Uuid,
+        req: UpsertQualityProfileRequest,
+    ) -> Result<QualityScoreProfile, AppError> {
+        let r = &req.rules;
+        let mut tx = self.pool.begin().await?;
+
+        let profile = sqlx::query_as::<_, QualityScoreProfile>(
+            r#"
+            INSERT INTO quality_score_profiles (
+                grape_variety,
+                brix_min, brix_max, brix_tolerance,
+                ph_min, ph_max, ph_tolerance,
+                acidity_min, acidity_max, acidity_tolerance,
+                weight_brix, weight_ph, weight_acidity, weight_health,
+                grade_a_min, grade_b_min, updated_by
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
+            ON CONFLICT (grape_variety) DO UPDATE SET
+                brix_min          = EXCLUDED.brix_min,
+                brix_max          = EXCLUDED.brix_max,
+                brix_tolerance    = EXCLUDED.brix_tolerance,
+                ph_min            = EXCLUDED.ph_min,
+                ph_max            = EXCLUDED.ph_max,
+                ph_tolerance      = EXCLUDED.ph_tolerance,
+                acidity_min       = EXCLUDED.acidity_min,
+                acidity_max       = EXCLUDED.acidity_max,
+                acidity_tolerance = EXCLUDED.acidity_tolerance,
+                weight_brix       = EXCLUDED.weight_brix,
+                weight_ph         = EXCLUDED.weight_ph,
+                weight_acidity    = EXCLUDED.weight_acidity,
+                weight_health     = EXCLUDED.weight_health,
+                grade_a_min       = EXCLUDED.grade_a_min,
+                grade_b_min       = EXCLUDED.grade_b_min,
+                updated_by        = EXCLUDED.updated_by,
+                updated_at        = NOW()
+            RETURNING *
+            "#,
+        )
+            .bind(req.grape_variety.trim())
+            .bind(r.brix_min)
+            .bind(r.brix_max)
+            .bind(r.brix_tolerance)
+            .bind(r.ph_min)
+            .bind(r.ph_max)
+            .bind(r.ph_tolerance)
+            .bind(r.acidity_min)
+            .bind(r.acidity_max)
+            .bind(r.acidity_tolerance)
+            .bind(r.weight_brix)
+            .bind(r.weight_ph)
+            .bind(r.weight_acidity)
+            .bind(r.weight_health)
+            .bind(r.grade_a_min)
+            .bind(r.grade_b_min)
+            .bind(updated_by)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        HarvestRepository::regrade_variety(&mut tx, &profile.grape_variety).await?;
+
+        tx.commit().await?;
+
+        Ok(profile)
+    }
+
+    pub async fn list_profiles(&self) -> Result<Vec<QualityScoreProfile>, AppError> {
+        let profiles = sqlx::query_as::<_, QualityScoreProfile>(
+            "SELECT * FROM quality_score_profiles ORDER BY grape_variety ASC",
+        )
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(profiles)
+    }
+
+    pub async fn find_profile_by_id(&self, id: Uuid) -> Result<QualityScoreProfile, AppError> {
+        sqlx::query_as::<_, QualityScoreProfile>("SELECT * FROM quality_score_profiles WHERE id = $1")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => {
+                    AppError::NotFound("Quality profile not found".to_string())
+                }
+                _ => AppError::DatabaseError(e),
+            })
+    }
+
+    /// Brisanje profila vraća berbe sorte na podrazumevana pravila
+    pub async fn delete_profile(&self, profile: &QualityScoreProfile) -> Result<(), AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM quality_score_profiles WHERE id = $1")
+            .bind(profile.id)
+            .execute(&mut *tx)
+            .await?;
+
+        HarvestRepository::regrade_variety(&mut tx, &profile.grape_variety).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
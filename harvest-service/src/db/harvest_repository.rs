@@ -1,10 +1,14 @@
-﻿use sqlx::PgPool;
+﻿use std::collections::HashMap;
+
+use sqlx::{PgPool, Postgres, Transaction};
 use uuid::Uuid;
 
+use crate::clients::ParcelSnapshot;
 use crate::error::AppError;
 use crate::models::{
-    AddQualityMeasurementRequest, CreateHarvestRequest, Harvest, HarvestQuality,
-    HarvestStatus, UpdateHarvestRequest,
+    compute_yield_per_hectare, AddQualityMeasurementRequest, CreateHarvestRequest, Harvest,
+    HarvestQuality, HarvestStatsQuery, HarvestStatsSample, HarvestStatus, HarvestStatusChange,
+    ImportedHarvest, QualityScoreProfile, ScoringRules, UpdateHarvestRequest,
 };
 
 #[derive(Clone)]
@@ -23,16 +27,42 @@ impl HarvestRepository {
         &self,
         created_by: Uuid,
         req: CreateHarvestRequest,
+        parcel: &ParcelSnapshot,
+    ) -> Result<Harvest, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let harvest = Self::insert_harvest(&mut tx, None, created_by, req, parcel).await?;
+
+        Self::record_status_change(&mut tx, harvest.id, None, &harvest.status, created_by, None)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(harvest)
+    }
+
+    /// Upis berbe u okviru postojeće transakcije (istoriju statusa upisuje pozivalac).
+    /// `id` zadaje klijent kod offline sinhronizacije, inače ga generiše baza.
+    pub(crate) async fn insert_harvest(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Option<Uuid>,
+        created_by: Uuid,
+        req: CreateHarvestRequest,
+        parcel: &ParcelSnapshot,
     ) -> Result<Harvest, AppError> {
+        let yield_per_hectare =
+            compute_yield_per_hectare(req.total_weight_kg, Some(parcel.parcel_area_m2));
+
         let harvest = sqlx::query_as::<_, Harvest>(
             r#"
             INSERT INTO harvests (
                 parcel_id, vineyard_id, harvest_date,
                 total_weight_kg, yield_per_hectare,
                 weather_condition, temperature_celsius, humidity_percent,
-                notes, created_by
+                notes, created_by,
+                vineyard_name, parcel_name, grape_variety, parcel_area_m2, id
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, COALESCE($15, gen_random_uuid()))
             RETURNING *
             "#,
         )
@@ -40,13 +70,18 @@ impl HarvestRepository {
             .bind(req.vineyard_id)
             .bind(req.harvest_date)
             .bind(req.total_weight_kg)
-            .bind(req.yield_per_hectare)
+            .bind(yield_per_hectare)
             .bind(req.weather_condition)
             .bind(req.temperature_celsius)
             .bind(req.humidity_percent)
             .bind(req.notes)
             .bind(created_by)
-            .fetch_one(&self.pool)
+            .bind(&parcel.vineyard_name)
+            .bind(&parcel.parcel_name)
+            .bind(&parcel.grape_variety)
+            .bind(parcel.parcel_area_m2)
+            .bind(id)
+            .fetch_one(&mut **tx)
             .await?;
 
         Ok(harvest)
@@ -78,6 +113,28 @@ impl HarvestRepository {
         Ok(harvests)
     }
 
+    /// Berbe vinograda za izvoz u tabelu, hronološki
+    pub async fn list_for_export(
+        &self,
+        vineyard_id: Uuid,
+        season: Option<i32>,
+    ) -> Result<Vec<Harvest>, AppError> {
+        let harvests = sqlx::query_as::<_, Harvest>(
+            r#"
+            SELECT * FROM harvests
+            WHERE vineyard_id = $1
+              AND ($2::INT IS NULL OR EXTRACT(YEAR FROM harvest_date) = $2)
+            ORDER BY harvest_date ASC, created_at ASC
+            "#,
+        )
+            .bind(vineyard_id)
+            .bind(season)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(harvests)
+    }
+
     pub async fn list_by_parcel(&self, parcel_id: Uuid) -> Result<Vec<Harvest>, AppError> {
         let harvests = sqlx::query_as::<_, Harvest>(
             r#"
@@ -107,40 +164,134 @@ impl HarvestRepository {
         &self,
         id: Uuid,
         req: UpdateHarvestRequest,
+        parcel: &ParcelSnapshot,
     ) -> Result<Harvest, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        // Zaključaj red da prijem ture ne prođe između provere i upisa težine
+        let current = sqlx::query_as::<_, Harvest>("SELECT * FROM harvests WHERE id = $1 FOR UPDATE")
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::NotFound("Harvest not found".to_string()),
+                _ => AppError::DatabaseError(e),
+            })?;
+
+        // Kad postoje ture, težina se računa iz njih
+        if req.total_weight_kg.is_some() {
+            let (has_loads,): (bool,) =
+                sqlx::query_as("SELECT EXISTS (SELECT 1 FROM harvest_loads WHERE harvest_id = $1)")
+                    .bind(id)
+                    .fetch_one(&mut *tx)
+                    .await?;
+            if has_loads {
+                return Err(AppError::Conflict(
+                    "Total weight is derived from recorded loads".to_string(),
+                ));
+            }
+        }
+
+        let yield_per_hectare = compute_yield_per_hectare(
+            req.total_weight_kg.or(current.total_weight_kg),
+            Some(parcel.parcel_area_m2),
+        );
+
         let harvest = sqlx::query_as::<_, Harvest>(
             r#"
             UPDATE harvests SET
-                harvest_date       = COALESCE($2, harvest_date),
-                status             = COALESCE($3, status),
-                total_weight_kg    = COALESCE($4, total_weight_kg),
-                yield_per_hectare  = COALESCE($5, yield_per_hectare),
-                weather_condition  = COALESCE($6, weather_condition),
-                temperature_celsius = COALESCE($7, temperature_celsius),
-                humidity_percent   = COALESCE($8, humidity_percent),
-                notes              = COALESCE($9, notes),
+                parcel_id          = COALESCE($2, parcel_id),
+                vineyard_id        = COALESCE($3, vineyard_id),
+                harvest_date       = COALESCE($4, harvest_date),
+                total_weight_kg    = COALESCE($5, total_weight_kg),
+                yield_per_hectare  = $6,
+                weather_condition  = COALESCE($7, weather_condition),
+                temperature_celsius = COALESCE($8, temperature_celsius),
+                humidity_percent   = COALESCE($9, humidity_percent),
+                notes              = COALESCE($10, notes),
+                vineyard_name      = $11,
+                parcel_name        = $12,
+                grape_variety      = $13,
+                parcel_area_m2     = $14,
                 updated_at         = NOW()
             WHERE id = $1
             RETURNING *
             "#,
         )
             .bind(id)
+            .bind(req.parcel_id)
+            .bind(req.vineyard_id)
             .bind(req.harvest_date)
-            .bind(req.status)
             .bind(req.total_weight_kg)
-            .bind(req.yield_per_hectare)
+            .bind(yield_per_hectare)
             .bind(req.weather_condition)
             .bind(req.temperature_celsius)
             .bind(req.humidity_percent)
             .bind(req.notes)
-            .fetch_one(&self.pool)
+            .bind(&parcel.vineyard_name)
+            .bind(&parcel.parcel_name)
+            .bind(&parcel.grape_variety)
+            .bind(parcel.parcel_area_m2)
+            .fetch_one(&mut *tx)
             .await?;
 
+        // Sorta je mogla da se promeni sa parcelom - ocena ide po profilu nove sorte
+        let harvest = Self::refresh_quality_grade(&mut tx, harvest.id).await?;
+
+        tx.commit().await?;
+
         Ok(harvest)
     }
 
-    pub async fn update_status(&self, id: Uuid, status: HarvestStatus) -> Result<Harvest, AppError> {
-        let harvest = sqlx::query_as::<_, Harvest>(
+    /// Promena statusa po grafu prelaza, uz zapis u istoriju (u jednoj transakciji)
+    pub async fn change_status(
+        &self,
+        id: Uuid,
+        to: HarvestStatus,
+        changed_by: Uuid,
+        reason: Option<String>,
+    ) -> Result<Harvest, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        // Zaključaj red da dve istovremene promene ne prođu obe
+        let harvest = sqlx::query_as::<_, Harvest>("SELECT * FROM harvests WHERE id = $1 FOR UPDATE")
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::NotFound("Harvest not found".to_string()),
+                _ => AppError::DatabaseError(e),
+            })?;
+
+        if !harvest.status.can_transition_to(&to) {
+            return Err(AppError::Conflict(format!(
+                "Cannot change harvest status from '{}' to '{}'",
+                harvest.status, to
+            )));
+        }
+
+        if to == HarvestStatus::Completed {
+            if !harvest.total_weight_kg.is_some_and(|w| w > 0.0) {
+                return Err(AppError::ValidationError(
+                    "Total weight must be recorded before completing a harvest".to_string(),
+                ));
+            }
+
+            let (measurements,): (i64,) =
+                sqlx::query_as("SELECT COUNT(*) FROM harvest_quality WHERE harvest_id = $1")
+                    .bind(id)
+                    .fetch_one(&mut *tx)
+                    .await?;
+
+            if measurements == 0 {
+                return Err(AppError::ValidationError(
+                    "At least one quality measurement is required before completing a harvest"
+                        .to_string(),
+                ));
+            }
+        }
+
+        let updated = sqlx::query_as::<_, Harvest>(
             r#"
             UPDATE harvests
             SET status = $2, updated_at = NOW()
@@ -149,11 +300,59 @@ impl HarvestRepository {
             "#,
         )
             .bind(id)
-            .bind(status)
-            .fetch_one(&self.pool)
+            .bind(&to)
+            .fetch_one(&mut *tx)
             .await?;
 
-        Ok(harvest)
+        Self::record_status_change(&mut tx, id, Some(&harvest.status), &to, changed_by, reason)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(updated)
+    }
+
+    pub(crate) async fn record_status_change(
+        tx: &mut Transaction<'_, Postgres>,
+        harvest_id: Uuid,
+        from: Option<&HarvestStatus>,
+        to: &HarvestStatus,
+        changed_by: Uuid,
+        reason: Option<String>,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO harvest_status_history (harvest_id, from_status, to_status, changed_by, reason)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+            .bind(harvest_id)
+            .bind(from)
+            .bind(to)
+            .bind(changed_by)
+            .bind(reason)
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn list_status_history(
+        &self,
+        harvest_id: Uuid,
+    ) -> Result<Vec<HarvestStatusChange>, AppError> {
+        let history = sqlx::query_as::<_, HarvestStatusChange>(
+            r#"
+            SELECT * FROM harvest_status_history
+            WHERE harvest_id = $1
+            ORDER BY changed_at ASC
+            "#,
+        )
+            .bind(harvest_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(history)
     }
 
     pub async fn delete_harvest(&self, id: Uuid) -> Result<(), AppError> {
@@ -166,11 +365,14 @@ impl HarvestRepository {
 
     // ============== Quality measurements ==============
 
+    /// Upis merenja i nova ocena berbe u istoj transakciji
     pub async fn add_quality_measurement(
         &self,
         harvest_id: Uuid,
         req: AddQualityMeasurementRequest,
     ) -> Result<HarvestQuality, AppError> {
+        let mut tx = self.pool.begin().await?;
+
         let quality = sqlx::query_as::<_, HarvestQuality>(
             r#"
             INSERT INTO harvest_quality (
@@ -189,12 +391,35 @@ impl HarvestRepository {
             .bind(req.berry_color)
             .bind(req.grape_health)
             .bind(req.notes)
-            .fetch_one(&self.pool)
+            .fetch_one(&mut *tx)
             .await?;
 
+        Self::refresh_quality_grade(&mut tx, harvest_id).await?;
+
+        tx.commit().await?;
+
         Ok(quality)
     }
 
+    /// Merenja za više berbi odjednom (izvoz)
+    pub async fn list_quality_by_harvests(
+        &self,
+        harvest_ids: &[Uuid],
+    ) -> Result<Vec<HarvestQuality>, AppError> {
+        let measurements = sqlx::query_as::<_, HarvestQuality>(
+            r#"
+            SELECT * FROM harvest_quality
+            WHERE harvest_id = ANY($1)
+            ORDER BY measured_at ASC
+            "#,
+        )
+            .bind(harvest_ids)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(measurements)
+    }
+
     pub async fn list_quality_measurements(
         &self,
         harvest_id: Uuid,
@@ -230,13 +455,227 @@ impl HarvestRepository {
     }
 
     pub async fn delete_quality_measurement(&self, id: Uuid) -> Result<(), AppError> {
-        sqlx::query("DELETE FROM harvest_quality WHERE id = $1")
-            .bind(id)
-            .execute(&self.pool)
+        let mut tx = self.pool.begin().await?;
+
+        let deleted: Option<(Uuid,)> =
+            sqlx::query_as("DELETE FROM harvest_quality WHERE id = $1 RETURNING harvest_id")
+                .bind(id)
+                .fetch_optional(&mut *tx)
+                .await?;
+
+        if let Some((harvest_id,)) = deleted {
+            Self::refresh_quality_grade(&mut tx, harvest_id).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    // ============== Quality grade ==============
+
+    /// Ponovo izračunaj ocenu i klasu berbe iz njenih merenja, po profilu njene sorte
+    pub(crate) async fn refresh_quality_grade(
+        tx: &mut Transaction<'_, Postgres>,
+        harvest_id: Uuid,
+    ) -> Result<Harvest, AppError> {
+        let (grape_variety,): (Option<String>,) =
+            sqlx::query_as("SELECT grape_variety FROM harvests WHERE id = $1")
+                .bind(harvest_id)
+                .fetch_one(&mut **tx)
+                .await?;
+        let rules = Self::scoring_rules(tx, grape_variety.as_deref()).await?;
+
+        let measurements = sqlx::query_as::<_, HarvestQuality>(
+            "SELECT * FROM harvest_quality WHERE harvest_id = $1 ORDER BY measured_at ASC",
+        )
+            .bind(harvest_id)
+            .fetch_all(&mut **tx)
+            .await?;
+
+        let score = rules.score(&measurements);
+        let grade = score.map(|s| rules.grade(s));
+
+        let harvest = sqlx::query_as::<_, Harvest>(
+            "UPDATE harvests SET quality_score = $2, quality_grade = $3 WHERE id = $1 RETURNING *",
+        )
+            .bind(harvest_id)
+            .bind(score)
+            .bind(grade)
+            .fetch_one(&mut **tx)
+            .await?;
+
+        Ok(harvest)
+    }
+
+    /// Oceni berbe koje imaju merenja a nemaju ocenu (berbe unete pre uvođenja
+    /// klasa kvaliteta). Poziva se pri pokretanju; vraća broj ocenjenih berbi.
+    pub async fn regrade_ungraded(&self) -> Result<u64, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let harvest_ids: Vec<(Uuid,)> = sqlx::query_as(
+            r#"
+            SELECT h.id FROM harvests h
+            WHERE h.quality_score IS NULL
+              AND EXISTS (
+                  SELECT 1 FROM harvest_quality q
+                  WHERE q.harvest_id = h.id
+                    AND (q.brix IS NOT NULL OR q.ph IS NOT NULL
+                         OR q.acidity IS NOT NULL OR q.grape_health IS NOT NULL)
+              )
+            FOR UPDATE OF h
+            "#,
+        )
+            .fetch_all(&mut *tx)
+            .await?;
+
+        let mut graded = 0;
+        for (id,) in harvest_ids {
+            if Self::refresh_quality_grade(&mut tx, id).await?.quality_grade.is_some() {
+                graded += 1;
+            }
+        }
+
+        tx.commit().await?;
+
+        Ok(graded)
+    }
+
+    /// Ponovo oceni sve berbe sorte (posle izmene profila) sa dva čitanja i jednim upisom
+    pub(crate) async fn regrade_variety(
+        tx: &mut Transaction<'_, Postgres>,
+        grape_variety: &str,
+    ) -> Result<(), AppError> {
+        let rules = Self::scoring_rules(tx, Some(grape_variety)).await?;
+
+        let harvest_ids: Vec<(Uuid,)> =
+            sqlx::query_as("SELECT id FROM harvests WHERE LOWER(grape_variety) = LOWER($1)")
+                .bind(grape_variety)
+                .fetch_all(&mut **tx)
+                .await?;
+
+        let measurements = sqlx::query_as::<_, HarvestQuality>(
+            r#"
+            SELECT q.* FROM harvest_quality q
+            JOIN harvests h ON h.id = q.harvest_id
+            WHERE LOWER(h.grape_variety) = LOWER($1)
+            ORDER BY q.measured_at ASC
+            "#,
+        )
+            .bind(grape_variety)
+            .fetch_all(&mut **tx)
             .await?;
+
+        let mut by_harvest: HashMap<Uuid, Vec<HarvestQuality>> = HashMap::new();
+        for measurement in measurements {
+            by_harvest.entry(measurement.harvest_id).or_default().push(measurement);
+        }
+
+        let mut ids = Vec::with_capacity(harvest_ids.len());
+        let mut scores = Vec::with_capacity(harvest_ids.len());
+        let mut grades = Vec::with_capacity(harvest_ids.len());
+        for (id,) in harvest_ids {
+            let score = rules.score(by_harvest.get(&id).map_or(&[][..], Vec::as_slice));
+            ids.push(id);
+            scores.push(score);
+            grades.push(score.map(|s| rules.grade(s).to_string()));
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE harvests h
+            SET quality_score = u.score, quality_grade = u.grade::quality_grade
+            FROM UNNEST($1::UUID[], $2::FLOAT8[], $3::TEXT[]) AS u(id, score, grade)
+            WHERE h.id = u.id
+            "#,
+        )
+            .bind(&ids)
+            .bind(&scores)
+            .bind(&grades)
+            .execute(&mut **tx)
+            .await?;
+
         Ok(())
     }
 
+    /// Profil sorte ili podrazumevana pravila
+    async fn scoring_rules(
+        tx: &mut Transaction<'_, Postgres>,
+        grape_variety: Option<&str>,
+    ) -> Result<ScoringRules, AppError> {
+        let Some(grape_variety) = grape_variety else {
+            return Ok(ScoringRules::default());
+        };
+
+        let profile = sqlx::query_as::<_, QualityScoreProfile>(
+            "SELECT * FROM quality_score_profiles WHERE LOWER(grape_variety) = LOWER($1)",
+        )
+            .bind(grape_variety)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        Ok(profile.map(|p| p.rules).unwrap_or_default())
+    }
+
+    // ============== Import ==============
+
+    /// Upis uvezenih berbi sa merenjima - sve ili ništa
+    pub async fn import_harvests(
+        &self,
+        created_by: Uuid,
+        harvests: Vec<(ImportedHarvest, ParcelSnapshot)>,
+    ) -> Result<Vec<Harvest>, AppError> {
+        let mut tx = self.pool.begin().await?;
+        let mut created = Vec::with_capacity(harvests.len());
+
+        for (imported, parcel) in harvests {
+            let harvest = Self::insert_harvest(&mut tx, None, created_by, imported.request, &parcel).await?;
+
+            Self::record_status_change(
+                &mut tx,
+                harvest.id,
+                None,
+                &harvest.status,
+                created_by,
+                Some("Imported from spreadsheet".to_string()),
+            )
+                .await?;
+
+            for measurement in imported.measurements {
+                let req = measurement.request;
+                sqlx::query(
+                    r#"
+                    INSERT INTO harvest_quality (
+                        harvest_id, brix, ph, acidity,
+                        berry_size, berry_color, grape_health, notes, measured_at
+                    )
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, COALESCE($9, NOW()))
+                    "#,
+                )
+                    .bind(harvest.id)
+                    .bind(req.brix)
+                    .bind(req.ph)
+                    .bind(req.acidity)
+                    .bind(req.berry_size)
+                    .bind(req.berry_color)
+                    .bind(req.grape_health)
+                    .bind(req.notes)
+                    .bind(measurement.measured_at)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+
+            // Ocena iz uvezenih merenja, u istoj transakciji sa uvozom
+            let harvest = Self::refresh_quality_grade(&mut tx, harvest.id).await?;
+
+            created.push(harvest);
+        }
+
+        tx.commit().await?;
+
+        Ok(created)
+    }
+
     // ============== Statistics ==============
 
     pub async fn get_vineyard_stats(&self, vineyard_id: Uuid) -> Result<VineyardHarvestStats, AppError> {
@@ -246,13 +685,26 @@ impl HarvestRepository {
                 COUNT(*)::BIGINT                        AS total_harvests,
                 COALESCE(SUM(total_weight_kg), 0)       AS total_weight_kg,
                 COALESCE(AVG(yield_per_hectare), 0)     AS avg_yield_per_hectare,
-                COALESCE(AVG(
-                    (SELECT AVG(brix) FROM harvest_quality q WHERE q.harvest_id = h.id)
-                ), 0)                                   AS avg_brix,
-                COALESCE(AVG(
-                    (SELECT AVG(ph) FROM harvest_quality q WHERE q.harvest_id = h.id)
-                ), 0)                                   AS avg_ph
+                COALESCE(
+                    SUM(m.brix * total_weight_kg) / NULLIF(SUM(total_weight_kg) FILTER (WHERE m.brix IS NOT NULL), 0),
+                    AVG(m.brix),
+                    0
+                )                                       AS avg_brix,
+                COALESCE(
+                    SUM(m.ph * total_weight_kg) / NULLIF(SUM(total_weight_kg) FILTER (WHERE m.ph IS NOT NULL), 0),
+                    AVG(m.ph),
+                    0
+                )                                       AS avg_ph,
+                COALESCE(AVG(quality_score), 0)         AS avg_quality_score,
+                COUNT(*) FILTER (WHERE quality_grade = 'A')::BIGINT AS grade_a_count,
+                COUNT(*) FILTER (WHERE quality_grade = 'B')::BIGINT AS grade_b_count,
+                COUNT(*) FILTER (WHERE quality_grade = 'C')::BIGINT AS grade_c_count
             FROM harvests h
+            LEFT JOIN LATERAL (
+                SELECT AVG(brix) AS brix, AVG(ph) AS ph
+                FROM harvest_quality q
+                WHERE q.harvest_id = h.id
+            ) m ON TRUE
             WHERE vineyard_id = $1
               AND status = 'completed'
             "#,
@@ -263,6 +715,51 @@ impl HarvestRepository {
 
         Ok(stats)
     }
+
+    /// Završene berbe sa prosekom merenja, za statistiku po sezoni/parceli/sorti
+    pub async fn list_stats_samples(
+        &self,
+        query: &HarvestStatsQuery,
+        season_from: Option<i32>,
+    ) -> Result<Vec<HarvestStatsSample>, AppError> {
+        let samples = sqlx::query_as::<_, HarvestStatsSample>(
+            r#"
+            SELECT
+                EXTRACT(YEAR FROM h.harvest_date)::INT  AS season,
+                h.parcel_id,
+                h.parcel_name,
+                h.grape_variety,
+                h.total_weight_kg                       AS weight_kg,
+                h.yield_per_hectare,
+                m.brix,
+                m.ph,
+                m.acidity,
+                h.quality_score
+            FROM harvests h
+            LEFT JOIN LATERAL (
+                SELECT AVG(brix) AS brix, AVG(ph) AS ph, AVG(acidity) AS acidity
+                FROM harvest_quality q
+                WHERE q.harvest_id = h.id
+            ) m ON TRUE
+            WHERE h.status = 'completed'
+              AND ($1::UUID IS NULL OR h.vineyard_id = $1)
+              AND ($2::UUID IS NULL OR h.parcel_id = $2)
+              AND ($3::VARCHAR IS NULL OR LOWER(h.grape_variety) = LOWER($3))
+              AND ($4::INT IS NULL OR EXTRACT(YEAR FROM h.harvest_date) >= $4)
+              AND ($5::INT IS NULL OR EXTRACT(YEAR FROM h.harvest_date) <= $5)
+            ORDER BY h.harvest_date ASC
+            "#,
+        )
+            .bind(query.vineyard_id)
+            .bind(query.parcel_id)
+            .bind(&query.grape_variety)
+            .bind(season_from)
+            .bind(query.season_to)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(samples)
+    }
 }
 
 #[derive(Debug, Serialize, sqlx::FromRow)]
@@ -272,6 +769,10 @@ pub struct VineyardHarvestStats {
     pub avg_yield_per_hectare: f64,
     pub avg_brix: f64,
     pub avg_ph: f64,
+    pub avg_quality_score: f64,
+    pub grade_a_count: i64,
+    pub grade_b_count: i64,
+    pub grade_c_count: i64,
 }
 
 use serde::Serialize;
\ No newline at end of file
@@ -0,0 +1,230 @@
+use chrono::NaiveDate;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::db::HarvestRepository;
+use crate::error::AppError;
+use crate::models::{
+    compute_yield_per_hectare, status_on_load_receipt, Harvest, HarvestLoad, HarvestStatus,
+    ReceiveLoadRequest,
+};
+
+#[derive(Clone)]
+pub struct LoadRepository {
+    pool: PgPool,
+}
+
+impl LoadRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Prijem ture na vagi: upis ture, preračun ukupne težine berbe i
+    /// automatski prelaz planned → in_progress kod prve ture
+    pub async fn receive_load(
+        &self,
+        received_by: Uuid,
+        req: ReceiveLoadRequest,
+    ) -> Result<HarvestLoad, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let harvest = Self::lock_harvest(&mut tx, req.harvest_id).await?;
+
+        if let Some(next) = status_on_load_receipt(&harvest.status).map_err(AppError::Conflict)? {
+            sqlx::query("UPDATE harvests SET status = $2, updated_at = NOW() WHERE id = $1")
+                .bind(harvest.id)
+                .bind(&next)
+                .execute(&mut *tx)
+                .await?;
+
+            HarvestRepository::record_status_change(
+                &mut tx,
+                harvest.id,
+                Some(&harvest.status),
+                &next,
+                received_by,
+                Some("First load received".to_string()),
+            )
+                .await?;
+        }
+
+        let ticket_number = match req.ticket_number {
+            Some(ticket) => ticket,
+            None => {
+                let (ticket,): (String,) = sqlx::query_as(
+                    r#"
+                    SELECT 'WB-' || to_char(NOW(), 'YYYY') || '-'
+                        || lpad(nextval('weighbridge_ticket_seq')::TEXT, 6, '0')
+                    "#,
+                )
+                    .fetch_one(&mut *tx)
+                    .await?;
+                ticket
+            }
+        };
+
+        let load = sqlx::query_as::<_, HarvestLoad>(
+            r#"
+            INSERT INTO harvest_loads (
+                harvest_id, ticket_number, container_type, container_code, picker_crew,
+                gross_weight_kg, tare_weight_kg, brix, arrived_at, received_by, notes
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, COALESCE($9, NOW()), $10, $11)
+            RETURNING *
+            "#,
+        )
+            .bind(req.harvest_id)
+            .bind(&ticket_number)
+            .bind(&req.container_type)
+            .bind(&req.container_code)
+            .bind(&req.picker_crew)
+            .bind(req.gross_weight_kg)
+            .bind(req.tare_weight_kg)
+            .bind(req.brix)
+            .bind(req.arrived_at)
+            .bind(received_by)
+            .bind(&req.notes)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(ref db) if db.is_unique_violation() => AppError::Conflict(
+                    format!("Weighbridge ticket '{}' already recorded", ticket_number),
+                ),
+                _ => AppError::DatabaseError(e),
+            })?;
+
+        Self::refresh_harvest_weight(&mut tx, &harvest).await?;
+
+        tx.commit().await?;
+
+        Ok(load)
+    }
+
+    pub async fn find_by_id(&self, id: Uuid) -> Result<HarvestLoad, AppError> {
+        sqlx::query_as::<_, HarvestLoad>("SELECT * FROM harvest_loads WHERE id = $1")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::NotFound("Load not found".to_string()),
+                _ => AppError::DatabaseError(e),
+            })
+    }
+
+    pub async fn find_by_ticket(&self, ticket_number: &str) -> Result<HarvestLoad, AppError> {
+        sqlx::query_as::<_, HarvestLoad>("SELECT * FROM harvest_loads WHERE ticket_number = $1")
+            .bind(ticket_number)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => {
+                    AppError::NotFound("Weighbridge ticket not found".to_string())
+                }
+                _ => AppError::DatabaseError(e),
+            })
+    }
+
+    pub async fn list_by_harvest(&self, harvest_id: Uuid) -> Result<Vec<HarvestLoad>, AppError> {
+        let loads = sqlx::query_as::<_, HarvestLoad>(
+            r#"
+            SELECT * FROM harvest_loads
+            WHERE harvest_id = $1
+            ORDER BY arrived_at ASC
+            "#,
+        )
+            .bind(harvest_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(loads)
+    }
+
+    /// Sve ture primljene određenog dana (pregled za prijemno mesto)
+    pub async fn list_received_on(&self, date: NaiveDate) -> Result<Vec<HarvestLoad>, AppError> {
+        let loads = sqlx::query_as::<_, HarvestLoad>(
+            r#"
+            SELECT * FROM harvest_loads
+            WHERE arrived_at::DATE = $1
+            ORDER BY arrived_at DESC
+            "#,
+        )
+            .bind(date)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(loads)
+    }
+
+    pub async fn count_by_harvest(&self, harvest_id: Uuid) -> Result<i64, AppError> {
+        let (count,): (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM harvest_loads WHERE harvest_id = $1")
+                .bind(harvest_id)
+                .fetch_one(&self.pool)
+                .await?;
+
+        Ok(count)
+    }
+
+    pub async fn delete_load(&self, load: &HarvestLoad) -> Result<(), AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let harvest = Self::lock_harvest(&mut tx, load.harvest_id).await?;
+        if harvest.status == HarvestStatus::Completed {
+            return Err(AppError::Conflict(
+                "Cannot delete loads of a completed harvest".to_string(),
+            ));
+        }
+
+        sqlx::query("DELETE FROM harvest_loads WHERE id = $1")
+            .bind(load.id)
+            .execute(&mut *tx)
+            .await?;
+
+        Self::refresh_harvest_weight(&mut tx, &harvest).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn lock_harvest(
+        tx: &mut Transaction<'_, Postgres>,
+        harvest_id: Uuid,
+    ) -> Result<Harvest, AppError> {
+        sqlx::query_as::<_, Harvest>("SELECT * FROM harvests WHERE id = $1 FOR UPDATE")
+            .bind(harvest_id)
+            .fetch_one(&mut **tx)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::NotFound("Harvest not found".to_string()),
+                _ => AppError::DatabaseError(e),
+            })
+    }
+
+    /// total_weight_kg berbe = zbir neto težina tura (NULL ako tura nema)
+    async fn refresh_harvest_weight(
+        tx: &mut Transaction<'_, Postgres>,
+        harvest: &Harvest,
+    ) -> Result<(), AppError> {
+        let (total,): (Option<f64>,) =
+            sqlx::query_as("SELECT SUM(net_weight_kg) FROM harvest_loads WHERE harvest_id = $1")
+                .bind(harvest.id)
+                .fetch_one(&mut **tx)
+                .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE harvests
+            SET total_weight_kg = $2, yield_per_hectare = $3, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+            .bind(harvest.id)
+            .bind(total)
+            .bind(compute_yield_per_hectare(total, harvest.parcel_area_m2))
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+}
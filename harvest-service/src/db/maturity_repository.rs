@@ -0,0 +1,235 @@
+use chrono::{DateTime, Datelike, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::clients::ParcelSnapshot;
+use crate::error::AppError;
+use crate::models::{CreateMaturitySampleRequest, Harvest, MaturitySample, MaturityTrendPoint};
+
+#[derive(Clone)]
+pub struct MaturityRepository {
+    pool: PgPool,
+}
+
+impl MaturityRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    // ============== Samples ==============
+
+    pub async fn create_sample(
+        &self,
+        parcel_id: Uuid,
+        sampled_by: Uuid,
+        sampled_at: DateTime<Utc>,
+        req: CreateMaturitySampleRequest,
+        parcel: &ParcelSnapshot,
+    ) -> Result<MaturitySample, AppError> {
+        let sample = sqlx::query_as::<_, MaturitySample>(
+            r#"
+            INSERT INTO maturity_samples (
+                parcel_id, vineyard_id, season, sampled_at,
+                brix, ph, titratable_acidity, yan_mg_l,
+                berry_weight_g, seed_ripeness, sample_size,
+                vineyard_name, parcel_name, grape_variety, parcel_area_m2,
+                notes, sampled_by
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
+            RETURNING *
+            "#,
+        )
+            .bind(parcel_id)
+            .bind(req.vineyard_id)
+            .bind(sampled_at.year())
+            .bind(sampled_at)
+            .bind(req.brix)
+            .bind(req.ph)
+            .bind(req.titratable_acidity)
+            .bind(req.yan_mg_l)
+            .bind(req.berry_weight_g)
+            .bind(req.seed_ripeness)
+            .bind(req.sample_size)
+            .bind(&parcel.vineyard_name)
+            .bind(&parcel.parcel_name)
+            .bind(&parcel.grape_variety)
+            .bind(parcel.parcel_area_m2)
+            .bind(req.notes)
+            .bind(sampled_by)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(sample)
+    }
+
+    pub async fn find_sample(&self, id: Uuid) -> Result<MaturitySample, AppError> {
+        sqlx::query_as::<_, MaturitySample>("SELECT * FROM maturity_samples WHERE id = $1")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => {
+                    AppError::NotFound("Maturity sample not found".to_string())
+                }
+                _ => AppError::DatabaseError(e),
+            })
+    }
+
+    pub async fn list_by_parcel(
+        &self,
+        parcel_id: Uuid,
+        season: i32,
+    ) -> Result<Vec<MaturitySample>, AppError> {
+        let samples = sqlx::query_as::<_, MaturitySample>(
+            r#"
+            SELECT * FROM maturity_samples
+            WHERE parcel_id = $1 AND season = $2
+            ORDER BY sampled_at ASC
+            "#,
+        )
+            .bind(parcel_id)
+            .bind(season)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(samples)
+    }
+
+    pub async fn list_by_harvest(&self, harvest_id: Uuid) -> Result<Vec<MaturitySample>, AppError> {
+        let samples = sqlx::query_as::<_, MaturitySample>(
+            r#"
+            SELECT * FROM maturity_samples
+            WHERE harvest_id = $1
+            ORDER BY sampled_at ASC
+            "#,
+        )
+            .bind(harvest_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(samples)
+    }
+
+    pub async fn delete_sample(&self, id: Uuid) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM maturity_samples WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    // ============== Trend ==============
+
+    /// Dnevni proseci za parcelu u sezoni
+    pub async fn trend_by_parcel(
+        &self,
+        parcel_id: Uuid,
+        season: i32,
+    ) -> Result<Vec<MaturityTrendPoint>, AppError> {
+        let points = sqlx::query_as::<_, MaturityTrendPoint>(
+            r#"
+            SELECT
+                (sampled_at AT TIME ZONE 'UTC')::DATE AS date,
+                COUNT(*)::BIGINT                     AS samples,
+                AVG(brix)                            AS brix,
+                AVG(ph)                              AS ph,
+                AVG(titratable_acidity)              AS titratable_acidity,
+                AVG(yan_mg_l)                        AS yan_mg_l,
+                AVG(berry_weight_g)                  AS berry_weight_g
+            FROM maturity_samples
+            WHERE parcel_id = $1 AND season = $2
+            GROUP BY 1
+            ORDER BY 1 ASC
+            "#,
+        )
+            .bind(parcel_id)
+            .bind(season)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(points)
+    }
+
+    // ============== Harvest linking ==============
+
+    /// Poveži izabrane uzorke sa berbom; uzorci moraju biti sa iste parcele
+    /// i ne smeju već pripadati drugoj berbi
+    pub async fn link_samples(
+        &self,
+        harvest: &Harvest,
+        sample_ids: &[Uuid],
+    ) -> Result<Vec<MaturitySample>, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let linked = sqlx::query_as::<_, MaturitySample>(
+            r#"
+            UPDATE maturity_samples SET harvest_id = $1
+            WHERE id = ANY($2)
+              AND parcel_id = $3
+              AND (harvest_id IS NULL OR harvest_id = $1)
+            RETURNING *
+            "#,
+        )
+            .bind(harvest.id)
+            .bind(sample_ids)
+            .bind(harvest.parcel_id)
+            .fetch_all(&mut *tx)
+            .await?;
+
+        if linked.len() != sample_ids.len() {
+            return Err(AppError::ValidationError(
+                "Samples must exist, belong to the harvest's parcel and not be linked to another harvest"
+                    .to_string(),
+            ));
+        }
+
+        tx.commit().await?;
+
+        Ok(linked)
+    }
+
+    /// Poveži nepovezane uzorke sa poslednjeg dana uzorkovanja pre (ili na dan) berbe
+    pub async fn link_final_samples(&self, harvest: &Harvest) -> Result<Vec<MaturitySample>, AppError> {
+        let linked = sqlx::query_as::<_, MaturitySample>(
+            r#"
+            UPDATE maturity_samples SET harvest_id = $1
+            WHERE parcel_id = $2
+              AND harvest_id IS NULL
+              AND (sampled_at AT TIME ZONE 'UTC')::DATE = (
+                  SELECT MAX((sampled_at AT TIME ZONE 'UTC')::DATE)
+                  FROM maturity_samples
+                  WHERE parcel_id = $2
+                    AND season = $3
+                    AND (sampled_at AT TIME ZONE 'UTC')::DATE <= $4
+              )
+            RETURNING *
+            "#,
+        )
+            .bind(harvest.id)
+            .bind(harvest.parcel_id)
+            .bind(harvest.harvest_date.year())
+            .bind(harvest.harvest_date)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(linked)
+    }
+
+    pub async fn unlink_sample(&self, harvest_id: Uuid, sample_id: Uuid) -> Result<(), AppError> {
+        let result = sqlx::query(
+            "UPDATE maturity_samples SET harvest_id = NULL WHERE id = $1 AND harvest_id = $2",
+        )
+            .bind(sample_id)
+            .bind(harvest_id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(
+                "Maturity sample is not linked to this harvest".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -1,5 +1,21 @@
-﻿pub mod harvest_repository;
+﻿pub mod attachment_repository;
+pub mod contract_repository;
+pub mod crew_repository;
+pub mod grading_repository;
+pub mod harvest_repository;
+pub mod load_repository;
+pub mod maturity_repository;
+pub mod planning_repository;
 pub mod pool;
+pub mod sync_repository;
 
+pub use attachment_repository::*;
+pub use contract_repository::*;
+pub use crew_repository::*;
+pub use grading_repository::*;
 pub use harvest_repository::*;
-pub use pool::*;
\ No newline at end of file
+pub use load_repository::*;
+pub use maturity_repository::*;
+pub use planning_repository::*;
+pub use pool::*;
+pub use sync_repository::*;
\ No newline at end of file
@@ -0,0 +1,404 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::clients::ParcelSnapshot;
+use crate::db::HarvestRepository;
+use crate::error::AppError;
+use crate::models::{
+    CreateHarvestRequest, Harvest, HarvestPlan, HarvestPlanItem, HarvestPlanStatus,
+    HarvestStatus, NewHarvestPlan, NewHarvestPlanItem, ParcelRipenessSample, RipenessTarget,
+    UpsertRipenessTargetRequest,
+};
+
+#[derive(Clone)]
+pub struct PlanningRepository {
+    pool: PgPool,
+}
+
+impl PlanningRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    // ============== Ripeness targets ==============
+
+    pub async fn upsert_ripeness_target(
+        &self,
+        updated_by: Uuid,
+        req: UpsertRipenessTargetRequest,
+    ) -> Result<RipenessTarget, AppError> {
+        let target = sqlx::query_as::<_, RipenessTarget>(
+            r#"
+            INSERT INTO ripeness_targets (grape_variety, target_brix, max_ph, target_acidity, updated_by)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (grape_variety) DO UPDATE SET
+                target_brix    = EXCLUDED.target_brix,
+                max_ph         = EXCLUDED.max_ph,
+                target_acidity = EXCLUDED.target_acidity,
+                updated_by     = EXCLUDED.updated_by,
+                updated_at     = NOW()
+            RETURNING *
+            "#,
+        )
+            .bind(req.grape_variety.trim())
+            .bind(req.target_brix)
+            .bind(req.max_ph)
+            .bind(req.target_acidity)
+            .bind(updated_by)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(target)
+    }
+
+    pub async fn list_ripeness_targets(&self) -> Result<Vec<RipenessTarget>, AppError> {
+        let targets = sqlx::query_as::<_, RipenessTarget>(
+            "SELECT * FROM ripeness_targets ORDER BY grape_variety ASC",
+        )
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(targets)
+    }
+
+    pub async fn find_ripeness_target(
+        &self,
+        grape_variety: &str,
+    ) -> Result<Option<RipenessTarget>, AppError> {
+        let target = sqlx::query_as::<_, RipenessTarget>(
+            "SELECT * FROM ripeness_targets WHERE LOWER(grape_variety) = LOWER($1)",
+        )
+            .bind(grape_variety)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(target)
+    }
+
+    // ============== Forecast inputs ==============
+
+    /// Uzorci zrelosti i merenja kvaliteta u sezoni, za parcele koje još nisu brane
+    /// (nema berbe u toku ili završene)
+    pub async fn list_ripeness_samples(
+        &self,
+        season: i32,
+        vineyard_id: Option<Uuid>,
+        created_by: Option<Uuid>,
+    ) -> Result<Vec<ParcelRipenessSample>, AppError> {
+        let samples = sqlx::query_as::<_, ParcelRipenessSample>(
+            r#"
+            SELECT * FROM (
+                SELECT
+                    m.parcel_id, m.vineyard_id, m.vineyard_name, m.parcel_name,
+                    m.grape_variety, m.parcel_area_m2,
+                    m.sampled_at AS measured_at, m.brix, m.ph, m.titratable_acidity AS acidity
+                FROM maturity_samples m
+                WHERE m.season = $1
+                  AND ($2::UUID IS NULL OR m.vineyard_id = $2)
+                  AND ($3::UUID IS NULL OR m.sampled_by = $3)
+
+                UNION ALL
+
+                SELECT
+                    h.parcel_id, h.vineyard_id, h.vineyard_name, h.parcel_name,
+                    h.grape_variety, h.parcel_area_m2,
+                    q.measured_at, q.brix, q.ph, q.acidity
+                FROM harvest_quality q
+                JOIN harvests h ON h.id = q.harvest_id
+                WHERE EXTRACT(YEAR FROM q.measured_at)::INT = $1
+                  AND ($2::UUID IS NULL OR h.vineyard_id = $2)
+                  AND ($3::UUID IS NULL OR h.created_by = $3)
+                  AND h.status <> 'cancelled'
+            ) s
+            WHERE NOT EXISTS (
+                SELECT 1 FROM harvests d
+                WHERE d.parcel_id = s.parcel_id
+                  AND d.status IN ('in_progress', 'completed')
+                  AND EXTRACT(YEAR FROM d.harvest_date)::INT = $1
+            )
+            ORDER BY s.parcel_id, s.measured_at ASC
+            "#,
+        )
+            .bind(season)
+            .bind(vineyard_id)
+            .bind(created_by)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(samples)
+    }
+
+    /// Prosečan prinos (kg/ha) ranijih završenih berbi po parceli
+    pub async fn average_yields(&self, parcel_ids: &[Uuid]) -> Result<Vec<(Uuid, f64)>, AppError> {
+        let yields = sqlx::query_as::<_, (Uuid, f64)>(
+            r#"
+            SELECT parcel_id, AVG(yield_per_hectare)
+            FROM harvests
+            WHERE parcel_id = ANY($1)
+              AND status = 'completed'
+              AND yield_per_hectare IS NOT NULL
+            GROUP BY parcel_id
+            "#,
+        )
+            .bind(parcel_ids)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(yields)
+    }
+
+    /// Ukupan dnevni kapacitet ekipa (sve ekipe sa unetim kapacitetom ako lista nije data)
+    pub async fn crew_capacity(&self, crew_ids: Option<&[Uuid]>) -> Result<f64, AppError> {
+        let (capacity,): (f64,) = sqlx::query_as(
+            r#"
+            SELECT COALESCE(SUM(daily_capacity_kg), 0)
+            FROM picker_crews
+            WHERE ($1::UUID[] IS NULL OR id = ANY($1))
+            "#,
+        )
+            .bind(crew_ids)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(capacity)
+    }
+
+    // ============== Plans ==============
+
+    pub async fn create_plan(
+        &self,
+        plan: NewHarvestPlan,
+        items: Vec<NewHarvestPlanItem>,
+    ) -> Result<HarvestPlan, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let created = sqlx::query_as::<_, HarvestPlan>(
+            r#"
+            INSERT INTO harvest_plans (
+                season, vineyard_id, start_date,
+                crew_capacity_kg_per_day, press_capacity_kg_per_day, created_by
+            )
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#,
+        )
+            .bind(plan.season)
+            .bind(plan.vineyard_id)
+            .bind(plan.start_date)
+            .bind(plan.crew_capacity_kg_per_day)
+            .bind(plan.press_capacity_kg_per_day)
+            .bind(plan.created_by)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        for item in items {
+            sqlx::query(
+                r#"
+                INSERT INTO harvest_plan_items (
+                    plan_id, parcel_id, vineyard_id, vineyard_name, parcel_name, grape_variety,
+                    sample_count, latest_brix, brix_per_day, predicted_ripe_date,
+                    estimated_kg, scheduled_date, finish_date, notes
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+                "#,
+            )
+                .bind(created.id)
+                .bind(item.parcel_id)
+                .bind(item.vineyard_id)
+                .bind(item.vineyard_name)
+                .bind(item.parcel_name)
+                .bind(item.grape_variety)
+                .bind(item.sample_count)
+                .bind(item.latest_brix)
+                .bind(item.brix_per_day)
+                .bind(item.predicted_ripe_date)
+                .bind(item.estimated_kg)
+                .bind(item.scheduled_date)
+                .bind(item.finish_date)
+                .bind(item.notes)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(created)
+    }
+
+    pub async fn find_plan(&self, id: Uuid) -> Result<HarvestPlan, AppError> {
+        sqlx::query_as::<_, HarvestPlan>("SELECT * FROM harvest_plans WHERE id = $1")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::NotFound("Harvest plan not found".to_string()),
+                _ => AppError::DatabaseError(e),
+            })
+    }
+
+    pub async fn list_plans(&self, created_by: Option<Uuid>) -> Result<Vec<HarvestPlan>, AppError> {
+        let plans = sqlx::query_as::<_, HarvestPlan>(
+            r#"
+            SELECT * FROM harvest_plans
+            WHERE ($1::UUID IS NULL OR created_by = $1)
+            ORDER BY created_at DESC
+            "#,
+        )
+            .bind(created_by)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(plans)
+    }
+
+    pub async fn list_plan_items(&self, plan_id: Uuid) -> Result<Vec<HarvestPlanItem>, AppError> {
+        let items = sqlx::query_as::<_, HarvestPlanItem>(
+            r#"
+            SELECT * FROM harvest_plan_items
+            WHERE plan_id = $1
+            ORDER BY scheduled_date ASC NULLS LAST, parcel_name ASC
+            "#,
+        )
+            .bind(plan_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(items)
+    }
+
+    /// Prihvatanje plana: za svaku stavku kreira planiranu berbu, ili pomera datum
+    /// postojeće planirane berbe iste parcele u sezoni
+    pub async fn accept_plan(
+        &self,
+        plan_id: Uuid,
+        accepted_by: Uuid,
+        items: Vec<(HarvestPlanItem, ParcelSnapshot)>,
+    ) -> Result<HarvestPlan, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let plan = sqlx::query_as::<_, HarvestPlan>(
+            "SELECT * FROM harvest_plans WHERE id = $1 FOR UPDATE",
+        )
+            .bind(plan_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::NotFound("Harvest plan not found".to_string()),
+                _ => AppError::DatabaseError(e),
+            })?;
+
+        if plan.status != HarvestPlanStatus::Proposed {
+            return Err(AppError::Conflict(
+                "Only proposed plans can be accepted".to_string(),
+            ));
+        }
+
+        for (item, parcel) in items {
+            let Some(scheduled_date) = item.scheduled_date else {
+                continue;
+            };
+
+            let existing = sqlx::query_as::<_, Harvest>(
+                r#"
+                SELECT * FROM harvests
+                WHERE parcel_id = $1
+                  AND status = 'planned'
+                  AND EXTRACT(YEAR FROM harvest_date)::INT = $2
+                ORDER BY harvest_date ASC
+                LIMIT 1
+                FOR UPDATE
+                "#,
+            )
+                .bind(item.parcel_id)
+                .bind(plan.season)
+                .fetch_optional(&mut *tx)
+                .await?;
+
+            let harvest_id = match existing {
+                Some(harvest) => {
+                    sqlx::query(
+                        "UPDATE harvests SET harvest_date = $2, updated_at = NOW() WHERE id = $1",
+                    )
+                        .bind(harvest.id)
+                        .bind(scheduled_date)
+                        .execute(&mut *tx)
+                        .await?;
+                    harvest.id
+                }
+                None => {
+                    let req = CreateHarvestRequest {
+                        parcel_id: item.parcel_id,
+                        vineyard_id: item.vineyard_id,
+                        harvest_date: scheduled_date,
+                        total_weight_kg: None,
+                        weather_condition: None,
+                        temperature_celsius: None,
+                        humidity_percent: None,
+                        notes: item
+                            .estimated_kg
+                            .map(|kg| format!("Planned from harvest plan, estimated {:.0} kg", kg)),
+                    };
+                    let harvest =
+                        HarvestRepository::insert_harvest(&mut tx, None, accepted_by, req, &parcel)
+                            .await?;
+                    HarvestRepository::record_status_change(
+                        &mut tx,
+                        harvest.id,
+                        None,
+                        &HarvestStatus::Planned,
+                        accepted_by,
+                        Some("Created from harvest plan".to_string()),
+                    )
+                        .await?;
+                    harvest.id
+                }
+            };
+
+            sqlx::query("UPDATE harvest_plan_items SET harvest_id = $2 WHERE id = $1")
+                .bind(item.id)
+                .bind(harvest_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        let accepted = sqlx::query_as::<_, HarvestPlan>(
+            r#"
+            UPDATE harvest_plans
+            SET status = 'accepted', accepted_by = $2, accepted_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+            .bind(plan_id)
+            .bind(accepted_by)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(accepted)
+    }
+
+    pub async fn discard_plan(&self, plan_id: Uuid) -> Result<HarvestPlan, AppError> {
+        let plan = self.find_plan(plan_id).await?;
+        if plan.status != HarvestPlanStatus::Proposed {
+            return Err(AppError::Conflict(
+                "Only proposed plans can be discarded".to_string(),
+            ));
+        }
+
+        let discarded = sqlx::query_as::<_, HarvestPlan>(
+            r#"
+            UPDATE harvest_plans SET status = 'discarded'
+            WHERE id = $1 AND status = 'proposed'
+            RETURNING *
+            "#,
+        )
+            .bind(plan_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::Conflict("Plan was changed concurrently".to_string()))?;
+
+        Ok(discarded)
+    }
+}
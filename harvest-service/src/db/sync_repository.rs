@@ -0,0 +1,330 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::clients::ParcelSnapshot;
+use crate::db::HarvestRepository;
+use crate::error::AppError;
+use crate::models::{
+    compute_yield_per_hectare, AddQualityMeasurementRequest, CreateHarvestRequest,
+    SyncCursor, SyncHarvestRecord, SyncQualityRecord, SyncTombstone,
+};
+
+const HARVEST_COLUMNS: &str = r#"
+    id, parcel_id, vineyard_id, harvest_date, status,
+    total_weight_kg, yield_per_hectare,
+    weather_condition, temperature_celsius, humidity_percent, notes,
+    vineyard_name, parcel_name, grape_variety,
+    quality_score, quality_grade, created_by, updated_at, sync_version
+"#;
+
+const QUALITY_COLUMNS: &str = r#"
+    q.id, q.harvest_id, q.brix, q.ph, q.acidity,
+    q.berry_size, q.berry_color, q.grape_health, q.notes, q.measured_at, q.sync_version
+"#;
+
+#[derive(Clone)]
+pub struct SyncRepository {
+    pool: PgPool,
+}
+
+impl SyncRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    // ============== Harvests ==============
+
+    pub async fn find_harvest(&self, id: Uuid) -> Result<Option<SyncHarvestRecord>, AppError> {
+        let record = sqlx::query_as::<_, SyncHarvestRecord>(&format!(
+            "SELECT {} FROM harvests WHERE id = $1",
+            HARVEST_COLUMNS
+        ))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(record)
+    }
+
+    /// Nova berba sa id-jem koji je generisao klijent
+    pub async fn insert_harvest(
+        &self,
+        id: Uuid,
+        created_by: Uuid,
+        req: CreateHarvestRequest,
+        parcel: &ParcelSnapshot,
+    ) -> Result<SyncHarvestRecord, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let harvest = HarvestRepository::insert_harvest(&mut tx, Some(id), created_by, req, parcel)
+            .await
+            .map_err(|e| match e {
+                AppError::DatabaseError(sqlx::Error::Database(ref db)) if db.is_unique_violation() => {
+                    AppError::Conflict("Harvest already exists".to_string())
+                }
+                e => e,
+            })?;
+
+        HarvestRepository::record_status_change(
+            &mut tx,
+            harvest.id,
+            None,
+            &harvest.status,
+            created_by,
+            Some("Synced from mobile device".to_string()),
+        )
+            .await?;
+
+        tx.commit().await?;
+
+        self.find_harvest(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Harvest not found".to_string()))
+    }
+
+    /// Izmena samo ako je verzija na serveru još uvek `base_version`;
+    /// None znači da je neko u međuvremenu promenio berbu. Ocena se preračunava
+    /// u istoj transakciji (sorta je mogla da se promeni sa parcelom).
+    pub async fn update_harvest(
+        &self,
+        id: Uuid,
+        base_version: i64,
+        req: CreateHarvestRequest,
+        parcel: &ParcelSnapshot,
+    ) -> Result<Option<SyncHarvestRecord>, AppError> {
+        let yield_per_hectare =
+            compute_yield_per_hectare(req.total_weight_kg, Some(parcel.parcel_area_m2));
+
+        let mut tx = self.pool.begin().await?;
+
+        let updated: Option<(Uuid,)> = sqlx::query_as(
+            r#"
+            UPDATE harvests SET
+                parcel_id           = $3,
+                vineyard_id         = $4,
+                harvest_date        = $5,
+                total_weight_kg     = $6,
+                yield_per_hectare   = $7,
+                weather_condition   = $8,
+                temperature_celsius = $9,
+                humidity_percent    = $10,
+                notes               = $11,
+                vineyard_name       = $12,
+                parcel_name         = $13,
+                grape_variety       = $14,
+                parcel_area_m2      = $15,
+                updated_at          = NOW()
+            WHERE id = $1 AND sync_version = $2
+            RETURNING id
+            "#,
+        )
+            .bind(id)
+            .bind(base_version)
+            .bind(req.parcel_id)
+            .bind(req.vineyard_id)
+            .bind(req.harvest_date)
+            .bind(req.total_weight_kg)
+            .bind(yield_per_hectare)
+            .bind(req.weather_condition)
+            .bind(req.temperature_celsius)
+            .bind(req.humidity_percent)
+            .bind(req.notes)
+            .bind(&parcel.vineyard_name)
+            .bind(&parcel.parcel_name)
+            .bind(&parcel.grape_variety)
+            .bind(parcel.parcel_area_m2)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        if updated.is_none() {
+            return Ok(None);
+        }
+
+        HarvestRepository::refresh_quality_grade(&mut tx, id).await?;
+
+        let record = sqlx::query_as::<_, SyncHarvestRecord>(&format!(
+            "SELECT {} FROM harvests WHERE id = $1",
+            HARVEST_COLUMNS
+        ))
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(record))
+    }
+
+    // ============== Quality measurements ==============
+
+    pub async fn find_measurement(&self, id: Uuid) -> Result<Option<SyncQualityRecord>, AppError> {
+        let record = sqlx::query_as::<_, SyncQualityRecord>(&format!(
+            "SELECT {} FROM harvest_quality q WHERE q.id = $1",
+            QUALITY_COLUMNS
+        ))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(record)
+    }
+
+    /// Upis merenja i nova ocena berbe; ako isti id stigne istovremeno dva puta,
+    /// drugi upis ne radi ništa
+    pub async fn insert_measurement(
+        &self,
+        id: Uuid,
+        harvest_id: Uuid,
+        req: AddQualityMeasurementRequest,
+        measured_at: Option<DateTime<Utc>>,
+    ) -> Result<Option<SyncQualityRecord>, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let record = sqlx::query_as::<_, SyncQualityRecord>(&format!(
+            r#"
+            INSERT INTO harvest_quality AS q (
+                id, harvest_id, brix, ph, acidity,
+                berry_size, berry_color, grape_health, notes, measured_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, COALESCE($10, NOW()))
+            ON CONFLICT (id) DO NOTHING
+            RETURNING {}
+            "#,
+            QUALITY_COLUMNS
+        ))
+            .bind(id)
+            .bind(harvest_id)
+            .bind(req.brix)
+            .bind(req.ph)
+            .bind(req.acidity)
+            .bind(req.berry_size)
+            .bind(req.berry_color)
+            .bind(req.grape_health)
+            .bind(req.notes)
+            .bind(measured_at)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        if record.is_some() {
+            HarvestRepository::refresh_quality_grade(&mut tx, harvest_id).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(record)
+    }
+
+    // ============== Change feed ==============
+
+    pub async fn watermark(&self) -> Result<i64, AppError> {
+        Ok(sync_common::sync_watermark(&self.pool).await?)
+    }
+
+    /// Promene posle `since`, samo iz završenih transakcija (ispod `watermark`);
+    /// uz `owner` samo berbe tog korisnika
+    pub async fn harvest_changes(
+        &self,
+        since: SyncCursor,
+        watermark: i64,
+        limit: i64,
+        vineyard_id: Option<Uuid>,
+        owner: Option<Uuid>,
+    ) -> Result<Vec<SyncHarvestRecord>, AppError> {
+        let records = sqlx::query_as::<_, SyncHarvestRecord>(&format!(
+            r#"
+            SELECT {} FROM harvests
+            WHERE (sync_version, id) > ($1, $2)
+              AND sync_version < $3
+              AND ($5::UUID IS NULL OR vineyard_id = $5)
+              AND ($6::UUID IS NULL OR created_by = $6)
+            ORDER BY sync_version ASC, id ASC
+            LIMIT $4
+            "#,
+            HARVEST_COLUMNS
+        ))
+            .bind(since.version)
+            .bind(since.id)
+            .bind(watermark)
+            .bind(limit)
+            .bind(vineyard_id)
+            .bind(owner)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(records)
+    }
+
+    pub async fn quality_changes(
+        &self,
+        since: SyncCursor,
+        watermark: i64,
+        limit: i64,
+        vineyard_id: Option<Uuid>,
+        owner: Option<Uuid>,
+    ) -> Result<Vec<SyncQualityRecord>, AppError> {
+        let records = sqlx::query_as::<_, SyncQualityRecord>(&format!(
+            r#"
+            SELECT {} FROM harvest_quality q
+            JOIN harvests h ON h.id = q.harvest_id
+            WHERE (q.sync_version, q.id) > ($1, $2)
+              AND q.sync_version < $3
+              AND ($5::UUID IS NULL OR h.vineyard_id = $5)
+              AND ($6::UUID IS NULL OR h.created_by = $6)
+            ORDER BY q.sync_version ASC, q.id ASC
+            LIMIT $4
+            "#,
+            QUALITY_COLUMNS
+        ))
+            .bind(since.version)
+            .bind(since.id)
+            .bind(watermark)
+            .bind(limit)
+            .bind(vineyard_id)
+            .bind(owner)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(records)
+    }
+
+    /// Brisanja u istom opsegu. Vinograd i autor merenja se traže preko berbe:
+    /// postojeće, ili njenog tombstone-a ako je obrisana zajedno sa merenjem.
+    pub async fn tombstones(
+        &self,
+        since: SyncCursor,
+        watermark: i64,
+        limit: i64,
+        vineyard_id: Option<Uuid>,
+        owner: Option<Uuid>,
+    ) -> Result<Vec<SyncTombstone>, AppError> {
+        let records = sqlx::query_as::<_, SyncTombstone>(
+            r#"
+            SELECT t.entity, t.entity_id, t.sync_version, t.deleted_at
+            FROM sync_tombstones t
+            LEFT JOIN harvests h ON h.id = (t.scope->>'harvest_id')::UUID
+            LEFT JOIN sync_tombstones ht
+                   ON ht.entity = 'harvest'
+                  AND ht.entity_id = (t.scope->>'harvest_id')::UUID
+            WHERE (t.sync_version, t.entity_id) > ($1, $2)
+              AND t.sync_version < $3
+              AND ($5::UUID IS NULL OR $5::TEXT = COALESCE(
+                      t.scope->>'vineyard_id', h.vineyard_id::TEXT, ht.scope->>'vineyard_id'))
+              AND ($6::UUID IS NULL OR $6::TEXT = COALESCE(
+                      t.scope->>'created_by', h.created_by::TEXT, ht.scope->>'created_by'))
+            ORDER BY t.sync_version ASC, t.entity_id ASC
+            LIMIT $4
+            "#,
+        )
+            .bind(since.version)
+            .bind(since.id)
+            .bind(watermark)
+            .bind(limit)
+            .bind(vineyard_id)
+            .bind(owner)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(records)
+    }
+}
@@ -14,6 +14,7 @@ pub enum AppError {
     #[error("Validation error: {0}")]
     ValidationError(String),
 
+    #[allow(dead_code)]
     #[error("Authentication failed: {0}")]
     AuthenticationError(String),
 
@@ -34,6 +35,9 @@ pub enum AppError {
 
     #[error("Forbidden: {0}")]
     Forbidden(String),
+
+    #[error("Upstream service error: {0}")]
+    UpstreamError(String),
 }
 
 impl IntoResponse for AppError {
@@ -54,6 +58,10 @@ impl IntoResponse for AppError {
             }
             AppError::Unauthorized(m) => (StatusCode::UNAUTHORIZED, m.as_str()),
             AppError::Forbidden(m) => (StatusCode::FORBIDDEN, m.as_str()),
+            AppError::UpstreamError(m) => {
+                tracing::error!("Upstream error: {}", m);
+                (StatusCode::BAD_GATEWAY, "Upstream service unavailable")
+            }
         };
 
         (status, Json(json!({ "error": message, "status": status.as_u16() }))).into_response()
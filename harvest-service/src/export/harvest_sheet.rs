@@ -0,0 +1,44 @@
+use rust_xlsxwriter::{Format, Workbook};
+
+use crate::models::{HARVEST_SHEET_COLUMNS, HARVEST_SHEET_NUMERIC_COLUMNS};
+
+/// Berbe i merenja u CSV formatu koji prihvata uvoz
+pub fn generate_harvest_sheet_csv(rows: &[Vec<String>]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+
+    writer.write_record(HARVEST_SHEET_COLUMNS)?;
+    for row in rows {
+        writer.write_record(row)?;
+    }
+
+    Ok(writer.into_inner()?)
+}
+
+/// Isti format kao CSV, sa brojevima kao numeričkim ćelijama
+pub fn generate_harvest_sheet_xlsx(rows: &[Vec<String>]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    worksheet.set_name("harvests")?;
+
+    let bold = Format::new().set_bold();
+    for (col, name) in HARVEST_SHEET_COLUMNS.iter().enumerate() {
+        worksheet.write_string_with_format(0, col as u16, *name, &bold)?;
+    }
+
+    for (index, row) in rows.iter().enumerate() {
+        let row_number = index as u32 + 1;
+        for (col, value) in row.iter().enumerate() {
+            if value.is_empty() {
+                continue;
+            }
+            let numeric = HARVEST_SHEET_NUMERIC_COLUMNS.contains(&HARVEST_SHEET_COLUMNS[col]);
+            match value.parse::<f64>() {
+                Ok(number) if numeric => worksheet.write_number(row_number, col as u16, number)?,
+                _ => worksheet.write_string(row_number, col as u16, value)?,
+            };
+        }
+    }
+    worksheet.set_freeze_panes(1, 0)?;
+
+    Ok(workbook.save_to_buffer()?)
+}
@@ -0,0 +1,7 @@
+pub mod harvest_sheet;
+pub mod payroll_csv;
+pub mod settlement_csv;
+
+pub use harvest_sheet::{generate_harvest_sheet_csv, generate_harvest_sheet_xlsx};
+pub use payroll_csv::generate_payroll_csv;
+pub use settlement_csv::generate_settlement_csv;
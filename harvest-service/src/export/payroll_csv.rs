@@ -0,0 +1,37 @@
+use crate::models::PayrollRow;
+
+/// CSV obračun zarade po beraču
+pub fn generate_payroll_csv(rows: &[PayrollRow]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+
+    writer.write_record([
+        "employee_code",
+        "full_name",
+        "crew",
+        "days_worked",
+        "hours_worked",
+        "kg_picked",
+        "bins_picked",
+        "amount",
+    ])?;
+
+    let mut total_amount = 0.0;
+
+    for row in rows {
+        writer.write_record([
+            row.employee_code.clone().unwrap_or_default(),
+            row.full_name.clone(),
+            row.crew_name.clone().unwrap_or_default(),
+            row.days_worked.to_string(),
+            format!("{:.2}", row.hours_worked),
+            format!("{:.2}", row.kg_picked),
+            row.bins_picked.to_string(),
+            format!("{:.2}", row.amount),
+        ])?;
+        total_amount += row.amount;
+    }
+
+    writer.write_record(["", "TOTAL", "", "", "", "", "", &format!("{:.2}", total_amount)])?;
+
+    Ok(writer.into_inner()?)
+}
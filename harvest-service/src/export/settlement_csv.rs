@@ -0,0 +1,81 @@
+use crate::models::GrowerSettlement;
+
+/// CSV konačnog obračuna proizvođača: isporuke po ugovoru, isplate i saldo
+pub fn generate_settlement_csv(
+    settlement: &GrowerSettlement,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+
+    writer.write_record([
+        "contract",
+        "ticket",
+        "delivered_on",
+        "weight_kg",
+        "brix",
+        "grade",
+        "price_per_kg",
+        "kg_within_cap",
+        "kg_over_cap",
+        "amount",
+    ])?;
+
+    for contract in &settlement.contracts {
+        for d in &contract.deliveries {
+            writer.write_record([
+                contract.contract.contract_number.clone(),
+                d.ticket_number.clone().unwrap_or_default(),
+                d.delivered_at.format("%Y-%m-%d").to_string(),
+                format!("{:.2}", d.weight_kg),
+                d.brix.map(|b| format!("{:.1}", b)).unwrap_or_default(),
+                d.quality_grade.map(|g| g.to_string()).unwrap_or_default(),
+                format!("{:.2}", d.price_per_kg),
+                format!("{:.2}", d.kg_within_cap),
+                format!("{:.2}", d.kg_over_cap),
+                format!("{:.2}", d.amount),
+            ])?;
+        }
+    }
+
+    writer.write_record([
+        "",
+        "TOTAL PAYABLE",
+        "",
+        &format!("{:.2}", settlement.total_kg),
+        "",
+        "",
+        "",
+        "",
+        "",
+        &format!("{:.2}", settlement.total_payable),
+    ])?;
+
+    for payment in &settlement.payments {
+        writer.write_record([
+            "",
+            format!("PAYMENT {}", payment.reference.as_deref().unwrap_or("")).trim_end(),
+            &payment.paid_on.format("%Y-%m-%d").to_string(),
+            "",
+            "",
+            "",
+            "",
+            "",
+            "",
+            &format!("{:.2}", -payment.amount),
+        ])?;
+    }
+
+    writer.write_record([
+        "",
+        "BALANCE",
+        "",
+        "",
+        "",
+        "",
+        "",
+        "",
+        "",
+        &format!("{:.2}", settlement.balance),
+    ])?;
+
+    Ok(writer.into_inner()?)
+}
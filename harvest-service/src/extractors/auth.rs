@@ -9,6 +9,7 @@ use crate::{config::Settings, error::AppError, models::Claims};
 #[derive(Debug, Clone)]
 pub struct AuthenticatedUser {
     pub claims: Claims,
+    pub token: String, // prosleđuje se drugim servisima
 }
 
 #[async_trait]
@@ -31,6 +32,9 @@ where
 
         let claims = Claims::decode(bearer.token(), &settings.jwt_secret)?;
 
-        Ok(AuthenticatedUser { claims })
+        Ok(AuthenticatedUser {
+            claims,
+            token: bearer.token().to_string(),
+        })
     }
 }
\ No newline at end of file
@@ -0,0 +1,422 @@
+use axum::{
+    extract::{Multipart, Path, Query, State},
+    http::{header, HeaderValue, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use bytes::Bytes;
+use uuid::Uuid;
+
+use crate::{
+    error::AppError,
+    extractors::AuthenticatedUser,
+    handlers::AppState,
+    models::{
+        content_disposition, detect_content_type, is_image_type, sanitize_file_name, Attachment,
+        AttachmentEntity, AttachmentQuery, AttachmentResponse, NewAttachment, UserRole,
+        ATTACHMENT_TYPES,
+    },
+    storage::make_thumbnail,
+};
+
+/// Polja multipart upload-a
+#[derive(Default)]
+struct AttachmentUpload {
+    entity_type: Option<AttachmentEntity>,
+    entity_id: Option<Uuid>,
+    vineyard_id: Option<Uuid>,
+    description: Option<String>,
+    file: Option<(String, Bytes)>,
+}
+
+/// Upload priloga (multipart: entity_type, entity_id, vineyard_id za parcele,
+/// description, file). Tip fajla se proverava po sadržaju; slike dobijaju sličicu.
+pub async fn upload_attachment(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    mut multipart: Multipart,
+) -> Result<(StatusCode, Json<AttachmentResponse>), AppError> {
+    let mut upload = AttachmentUpload::default();
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::ValidationError(format!("Invalid upload: {}", e)))?
+    {
+        let name = field.name().unwrap_or_default().to_string();
+        if name == "file" {
+            let file_name = field.file_name().unwrap_or_default().to_string();
+            let bytes = field
+                .bytes()
+                .await
+                .map_err(|e| AppError::ValidationError(format!("Invalid upload: {}", e)))?;
+            upload.file = Some((file_name, bytes));
+            continue;
+        }
+
+        let value = field
+            .text()
+            .await
+            .map_err(|e| AppError::ValidationError(format!("Invalid upload: {}", e)))?;
+        let value = value.trim();
+        match name.as_str() {
+            "entity_type" => {
+                upload.entity_type = Some(AttachmentEntity::parse(value).ok_or_else(|| {
+                    AppError::ValidationError(format!("Unknown entity_type '{}'", value))
+                })?)
+            }
+            "entity_id" => upload.entity_id = Some(parse_uuid("entity_id", value)?),
+            "vineyard_id" if !value.is_empty() => {
+                upload.vineyard_id = Some(parse_uuid("vineyard_id", value)?)
+            }
+            "description" if !value.is_empty() => upload.description = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    let entity_type = upload
+        .entity_type
+        .ok_or_else(|| AppError::ValidationError("Missing 'entity_type' field".to_string()))?;
+    let entity_id = upload
+        .entity_id
+        .ok_or_else(|| AppError::ValidationError("Missing 'entity_id' field".to_string()))?;
+    let (file_name, bytes) = upload
+        .file
+        .ok_or_else(|| AppError::ValidationError("Missing 'file' field".to_string()))?;
+
+    if bytes.is_empty() {
+        return Err(AppError::ValidationError("Uploaded file is empty".to_string()));
+    }
+    if bytes.len() > state.storage.max_bytes() {
+        return Err(AppError::ValidationError(format!(
+            "File is larger than {} bytes",
+            state.storage.max_bytes()
+        )));
+    }
+
+    let content_type = detect_content_type(&bytes).ok_or_else(|| {
+        AppError::ValidationError(format!(
+            "Unsupported file type, allowed: {}",
+            ATTACHMENT_TYPES
+                .iter()
+                .map(|(t, _)| *t)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))
+    })?;
+
+    // Samo parcele žive u vinogradu; za ostale se vineyard_id ne čuva
+    let vineyard_id = entity_type
+        .vineyard_scope(upload.vineyard_id)
+        .map_err(AppError::ValidationError)?;
+    check_entity(&state, &auth, entity_type, entity_id, vineyard_id).await?;
+
+    let thumbnail = if is_image_type(content_type) {
+        let image = bytes.clone();
+        let thumbnail = tokio::task::spawn_blocking(move || make_thumbnail(&image))
+            .await
+            .map_err(|e| AppError::InternalError(format!("Thumbnail task failed: {}", e)))?
+            .map_err(|e| AppError::ValidationError(format!("Image cannot be decoded: {}", e)))?;
+        Some(thumbnail)
+    } else {
+        None
+    };
+
+    let id = Uuid::new_v4();
+    let storage_key = format!("{}/{}/{}", entity_type.as_str(), entity_id, id);
+    let thumbnail_key = thumbnail.as_ref().map(|_| format!("{}.thumb.jpg", storage_key));
+
+    let new_attachment = NewAttachment {
+        id,
+        entity_type,
+        entity_id,
+        vineyard_id,
+        file_name: sanitize_file_name(&file_name, content_type),
+        content_type: content_type.to_string(),
+        size_bytes: bytes.len() as i64,
+        storage_key: storage_key.clone(),
+        thumbnail_key: thumbnail_key.clone(),
+        width: thumbnail.as_ref().map(|t| t.width as i32),
+        height: thumbnail.as_ref().map(|t| t.height as i32),
+        description: upload.description,
+    };
+
+    state.storage.put(&storage_key, content_type, bytes).await?;
+    if let (Some(key), Some(thumbnail)) = (&thumbnail_key, thumbnail) {
+        if let Err(e) = state.storage.put(key, "image/jpeg", thumbnail.jpeg.into()).await {
+            remove_files(&state, &storage_key, None).await;
+            return Err(e);
+        }
+    }
+
+    let user_id = auth.claims.user_id()?;
+    let attachment = match state.attachment_repo.create(user_id, new_attachment).await {
+        Ok(attachment) => attachment,
+        Err(e) => {
+            remove_files(&state, &storage_key, thumbnail_key.as_deref()).await;
+            return Err(e);
+        }
+    };
+
+    Ok((StatusCode::CREATED, Json(AttachmentResponse::from(attachment))))
+}
+
+/// Prilozi jednog zapisa (?entity_type=harvest&entity_id=..., za parcele i &vineyard_id=...)
+pub async fn list_attachments(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Query(query): Query<AttachmentQuery>,
+) -> Result<Json<Vec<AttachmentResponse>>, AppError> {
+    let vineyard_id = query
+        .entity_type
+        .vineyard_scope(query.vineyard_id)
+        .map_err(AppError::ValidationError)?;
+    check_owner(&state, &auth, query.entity_type, query.entity_id, vineyard_id).await?;
+
+    let attachments = state
+        .attachment_repo
+        .list_by_entity(query.entity_type, query.entity_id)
+        .await?;
+
+    Ok(Json(
+        attachments.into_iter().map(AttachmentResponse::from).collect(),
+    ))
+}
+
+pub async fn get_attachment(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(attachment_id): Path<Uuid>,
+) -> Result<Json<AttachmentResponse>, AppError> {
+    let attachment = find_readable(&state, &auth, attachment_id).await?;
+
+    Ok(Json(AttachmentResponse::from(attachment)))
+}
+
+/// Sadržaj fajla
+pub async fn download_attachment(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(attachment_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let attachment = find_readable(&state, &auth, attachment_id).await?;
+    let bytes = state.storage.get(&attachment.storage_key).await?;
+
+    file_response(&attachment.content_type, &attachment.file_name, bytes)
+}
+
+/// JPEG sličica (samo za slike)
+pub async fn download_attachment_thumbnail(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(attachment_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let attachment = find_readable(&state, &auth, attachment_id).await?;
+    let key = attachment
+        .thumbnail_key
+        .ok_or_else(|| AppError::NotFound("Attachment has no thumbnail".to_string()))?;
+    let bytes = state.storage.get(&key).await?;
+
+    file_response("image/jpeg", &format!("thumb_{}.jpg", attachment.id), bytes)
+}
+
+/// Brisanje priloga - onaj ko ga je postavio ili admin
+pub async fn delete_attachment(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(attachment_id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    let attachment = state.attachment_repo.find_by_id(attachment_id).await?;
+    let user_id = auth.claims.user_id()?;
+
+    if auth.claims.role != UserRole::Admin && attachment.uploaded_by != user_id {
+        return Err(AppError::Forbidden("Access denied".to_string()));
+    }
+
+    state.attachment_repo.delete(attachment_id).await?;
+    remove_files(&state, &attachment.storage_key, attachment.thumbnail_key.as_deref()).await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Prilozi obrisanog batch-a ili tanka (?entity_type=batch&entity_id=...).
+/// Poziva ga fermentation-service posle brisanja; zapis ne sme više postojati.
+pub async fn purge_entity_attachments(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Query(query): Query<AttachmentQuery>,
+) -> Result<StatusCode, AppError> {
+    if auth.claims.role == UserRole::Worker {
+        return Err(AppError::Forbidden(
+            "Workers cannot purge attachments".to_string(),
+        ));
+    }
+
+    let entity = match query.entity_type {
+        AttachmentEntity::Batch => "batches",
+        AttachmentEntity::Tank => "tanks",
+        _ => {
+            return Err(AppError::ValidationError(
+                "Only batch and tank attachments can be purged".to_string(),
+            ))
+        }
+    };
+    if state
+        .fermentation_client
+        .exists(&auth.token, entity, query.entity_id)
+        .await?
+    {
+        return Err(AppError::Conflict(format!(
+            "{} still exists",
+            query.entity_type.as_str()
+        )));
+    }
+
+    purge_attachments(&state, query.entity_type, &[query.entity_id]).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Ukloni priloge obrisanih zapisa (zapisi i fajlovi)
+pub(crate) async fn purge_attachments(
+    state: &AppState,
+    entity_type: AttachmentEntity,
+    entity_ids: &[Uuid],
+) -> Result<(), AppError> {
+    let removed: Vec<Attachment> = state
+        .attachment_repo
+        .delete_by_entities(entity_type, entity_ids)
+        .await?;
+
+    for attachment in removed {
+        remove_files(state, &attachment.storage_key, attachment.thumbnail_key.as_deref()).await;
+    }
+
+    Ok(())
+}
+
+/// Proveri da zapis postoji (i da ga korisnik sme menjati, za berbe i merenja)
+async fn check_entity(
+    state: &AppState,
+    auth: &AuthenticatedUser,
+    entity_type: AttachmentEntity,
+    entity_id: Uuid,
+    vineyard_id: Option<Uuid>,
+) -> Result<(), AppError> {
+    match entity_type {
+        AttachmentEntity::Harvest
+        | AttachmentEntity::QualityMeasurement
+        | AttachmentEntity::Parcel => {
+            check_owner(state, auth, entity_type, entity_id, vineyard_id).await
+        }
+        AttachmentEntity::Batch => {
+            state
+                .fermentation_client
+                .check_batch(&auth.token, entity_id)
+                .await?;
+            Ok(())
+        }
+        AttachmentEntity::Tank => {
+            state
+                .fermentation_client
+                .check_tank(&auth.token, entity_id)
+                .await?;
+            Ok(())
+        }
+    }
+}
+
+/// Radnik vidi i menja samo priloge svojih berbi i njihovih merenja, i parcela
+/// čiji vinograd sme da čita (proverava vineyard-service njegovim tokenom)
+async fn check_owner(
+    state: &AppState,
+    auth: &AuthenticatedUser,
+    entity_type: AttachmentEntity,
+    entity_id: Uuid,
+    vineyard_id: Option<Uuid>,
+) -> Result<(), AppError> {
+    let harvest_id = match entity_type {
+        AttachmentEntity::Harvest => entity_id,
+        AttachmentEntity::QualityMeasurement => {
+            state
+                .harvest_repo
+                .get_quality_measurement(entity_id)
+                .await?
+                .harvest_id
+        }
+        AttachmentEntity::Parcel => {
+            let vineyard_id = vineyard_id.unwrap_or_default();
+            let parcel = state
+                .vineyard_client
+                .get_parcel(&auth.token, vineyard_id, entity_id)
+                .await?;
+            if parcel.vineyard_id != vineyard_id {
+                return Err(AppError::ValidationError(
+                    "Parcel does not belong to the given vineyard".to_string(),
+                ));
+            }
+            return Ok(());
+        }
+        _ => return Ok(()),
+    };
+
+    let harvest = state.harvest_repo.find_by_id(harvest_id).await?;
+    if auth.claims.role == UserRole::Worker && harvest.created_by != auth.claims.user_id()? {
+        return Err(AppError::Forbidden("Access denied".to_string()));
+    }
+
+    Ok(())
+}
+
+async fn find_readable(
+    state: &AppState,
+    auth: &AuthenticatedUser,
+    attachment_id: Uuid,
+) -> Result<Attachment, AppError> {
+    let attachment = state.attachment_repo.find_by_id(attachment_id).await?;
+    check_owner(
+        state,
+        auth,
+        attachment.entity_type,
+        attachment.entity_id,
+        attachment.vineyard_id,
+    )
+    .await?;
+
+    Ok(attachment)
+}
+
+/// Brisanje fajlova posle brisanja zapisa; greška se samo loguje (fajl ostaje kao siroče)
+async fn remove_files(state: &AppState, storage_key: &str, thumbnail_key: Option<&str>) {
+    for key in std::iter::once(storage_key).chain(thumbnail_key) {
+        if let Err(e) = state.storage.delete(key).await {
+            tracing::warn!("Failed to remove attachment file {}: {}", key, e);
+        }
+    }
+}
+
+fn parse_uuid(field: &str, value: &str) -> Result<Uuid, AppError> {
+    Uuid::parse_str(value)
+        .map_err(|_| AppError::ValidationError(format!("'{}' must be a UUID", field)))
+}
+
+fn file_response(
+    content_type: &str,
+    file_name: &str,
+    bytes: Bytes,
+) -> Result<impl IntoResponse, AppError> {
+    let content_type = HeaderValue::from_str(content_type)
+        .map_err(|e| AppError::InternalError(format!("Invalid content type: {}", e)))?;
+    let disposition = HeaderValue::from_str(&content_disposition(file_name))
+        .map_err(|e| AppError::InternalError(format!("Invalid file name: {}", e)))?;
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, content_type),
+            (header::CONTENT_DISPOSITION, disposition),
+            (header::X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff")),
+        ],
+        bytes,
+    ))
+}
@@ -0,0 +1,316 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderValue, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use chrono::Datelike;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    error::AppError,
+    extractors::AuthenticatedUser,
+    handlers::AppState,
+    models::{
+        ContractQuery, ContractSettlement, ContractStatus, CreateContractRequest,
+        CreateGrowerRequest, DeliveryPayable, GrapeContract, Grower, GrowerPayment,
+        GrowerSettlement, HarvestResponse, LinkContractRequest, RecordGrowerPaymentRequest,
+        SettlementQuery, UpdateContractRequest, UpdateGrowerRequest, UserRole,
+    },
+};
+
+/// Otkup i isplate vode vinar / admin
+fn require_office_role(auth: &AuthenticatedUser) -> Result<(), AppError> {
+    if auth.claims.role == UserRole::Worker {
+        return Err(AppError::Forbidden(
+            "Workers cannot manage grape purchasing".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+// ============== Growers ==============
+
+pub async fn create_grower(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(req): Json<CreateGrowerRequest>,
+) -> Result<(StatusCode, Json<Grower>), AppError> {
+    require_office_role(&auth)?;
+    req.validate()?;
+
+    let user_id = auth.claims.user_id()?;
+    let grower = state.contract_repo.create_grower(user_id, req).await?;
+
+    Ok((StatusCode::CREATED, Json(grower)))
+}
+
+pub async fn list_growers(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<Grower>>, AppError> {
+    require_office_role(&auth)?;
+
+    let growers = state.contract_repo.list_growers().await?;
+
+    Ok(Json(growers))
+}
+
+pub async fn get_grower(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(grower_id): Path<Uuid>,
+) -> Result<Json<Grower>, AppError> {
+    require_office_role(&auth)?;
+
+    let grower = state.contract_repo.find_grower(grower_id).await?;
+
+    Ok(Json(grower))
+}
+
+pub async fn update_grower(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(grower_id): Path<Uuid>,
+    Json(req): Json<UpdateGrowerRequest>,
+) -> Result<Json<Grower>, AppError> {
+    require_office_role(&auth)?;
+    req.validate()?;
+
+    let grower = state.contract_repo.update_grower(grower_id, req).await?;
+
+    Ok(Json(grower))
+}
+
+// ============== Contracts ==============
+
+pub async fn create_contract(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(req): Json<CreateContractRequest>,
+) -> Result<(StatusCode, Json<GrapeContract>), AppError> {
+    require_office_role(&auth)?;
+    req.validate()?;
+
+    let user_id = auth.claims.user_id()?;
+    let contract = state.contract_repo.create_contract(user_id, req).await?;
+
+    Ok((StatusCode::CREATED, Json(contract)))
+}
+
+pub async fn list_contracts(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Query(query): Query<ContractQuery>,
+) -> Result<Json<Vec<GrapeContract>>, AppError> {
+    require_office_role(&auth)?;
+
+    let contracts = state
+        .contract_repo
+        .list_contracts(query.grower_id, query.season)
+        .await?;
+
+    Ok(Json(contracts))
+}
+
+pub async fn get_contract(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(contract_id): Path<Uuid>,
+) -> Result<Json<GrapeContract>, AppError> {
+    require_office_role(&auth)?;
+
+    let contract = state.contract_repo.find_contract(contract_id).await?;
+
+    Ok(Json(contract))
+}
+
+pub async fn update_contract(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(contract_id): Path<Uuid>,
+    Json(req): Json<UpdateContractRequest>,
+) -> Result<Json<GrapeContract>, AppError> {
+    require_office_role(&auth)?;
+    req.validate()?;
+
+    let contract = state.contract_repo.find_contract(contract_id).await?;
+    let merged = contract
+        .with_update(req)
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+    let updated = state.contract_repo.update_contract(&merged).await?;
+
+    Ok(Json(updated))
+}
+
+/// Isporuke po ugovoru sa obračunatim iznosom za svaku
+pub async fn list_contract_deliveries(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(contract_id): Path<Uuid>,
+) -> Result<Json<Vec<DeliveryPayable>>, AppError> {
+    require_office_role(&auth)?;
+
+    let contract = state.contract_repo.find_contract(contract_id).await?;
+    let deliveries = state.contract_repo.list_deliveries(contract_id).await?;
+
+    Ok(Json(contract.price_deliveries(&deliveries)))
+}
+
+/// Veži berbu (i njene ture) za ugovor o otkupu
+pub async fn link_harvest_contract(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(harvest_id): Path<Uuid>,
+    Json(req): Json<LinkContractRequest>,
+) -> Result<Json<HarvestResponse>, AppError> {
+    require_office_role(&auth)?;
+
+    let harvest = state.harvest_repo.find_by_id(harvest_id).await?;
+    let user_id = auth.claims.user_id()?;
+
+    if auth.claims.role != UserRole::Admin && harvest.created_by != user_id {
+        return Err(AppError::Forbidden("Access denied".to_string()));
+    }
+
+    if let Some(contract_id) = req.contract_id {
+        let contract = state.contract_repo.find_contract(contract_id).await?;
+
+        if contract.status != ContractStatus::Active {
+            return Err(AppError::Conflict("Contract is closed".to_string()));
+        }
+        if contract.season != harvest.harvest_date.year() {
+            return Err(AppError::ValidationError(format!(
+                "Contract is for season {}, harvest is in {}",
+                contract.season,
+                harvest.harvest_date.year()
+            )));
+        }
+        if let (Some(contracted), Some(actual)) = (&contract.grape_variety, &harvest.grape_variety) {
+            if !contracted.eq_ignore_ascii_case(actual) {
+                return Err(AppError::ValidationError(format!(
+                    "Contract covers '{}', harvest is '{}'",
+                    contracted, actual
+                )));
+            }
+        }
+    }
+
+    state
+        .contract_repo
+        .link_harvest(harvest_id, req.contract_id)
+        .await?;
+
+    let updated = state.harvest_repo.find_by_id(harvest_id).await?;
+
+    Ok(Json(HarvestResponse::from(updated)))
+}
+
+// ============== Payments & settlement ==============
+
+pub async fn record_grower_payment(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(grower_id): Path<Uuid>,
+    Json(req): Json<RecordGrowerPaymentRequest>,
+) -> Result<(StatusCode, Json<GrowerPayment>), AppError> {
+    require_office_role(&auth)?;
+    req.validate()?;
+
+    state.contract_repo.find_grower(grower_id).await?;
+
+    let user_id = auth.claims.user_id()?;
+    let payment = state
+        .contract_repo
+        .record_payment(grower_id, user_id, req)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(payment)))
+}
+
+pub async fn list_grower_payments(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(grower_id): Path<Uuid>,
+) -> Result<Json<Vec<GrowerPayment>>, AppError> {
+    require_office_role(&auth)?;
+
+    state.contract_repo.find_grower(grower_id).await?;
+
+    let payments = state.contract_repo.list_payments(grower_id, None).await?;
+
+    Ok(Json(payments))
+}
+
+/// Konačni obračun proizvođača za sezonu
+pub async fn get_grower_settlement(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(grower_id): Path<Uuid>,
+    Query(query): Query<SettlementQuery>,
+) -> Result<Json<GrowerSettlement>, AppError> {
+    require_office_role(&auth)?;
+
+    let settlement = build_settlement(&state, grower_id, query.season).await?;
+
+    Ok(Json(settlement))
+}
+
+pub async fn export_grower_settlement(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(grower_id): Path<Uuid>,
+    Query(query): Query<SettlementQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    require_office_role(&auth)?;
+
+    let settlement = build_settlement(&state, grower_id, query.season).await?;
+
+    let csv_bytes = crate::export::generate_settlement_csv(&settlement)
+        .map_err(|e| AppError::InternalError(format!("Failed to generate CSV: {}", e)))?;
+
+    let content_disposition = format!(
+        "attachment; filename=\"settlement_{}_{}.csv\"",
+        settlement.season, grower_id
+    );
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, HeaderValue::from_static("text/csv; charset=utf-8")),
+            (
+                header::CONTENT_DISPOSITION,
+                HeaderValue::from_str(&content_disposition).unwrap(),
+            ),
+        ],
+        csv_bytes,
+    ))
+}
+
+async fn build_settlement(
+    state: &AppState,
+    grower_id: Uuid,
+    season: i32,
+) -> Result<GrowerSettlement, AppError> {
+    let grower = state.contract_repo.find_grower(grower_id).await?;
+    let contracts = state
+        .contract_repo
+        .list_contracts(Some(grower_id), Some(season))
+        .await?;
+
+    let mut settlements = Vec::with_capacity(contracts.len());
+    for contract in contracts {
+        let deliveries = state.contract_repo.list_deliveries(contract.id).await?;
+        let payables = contract.price_deliveries(&deliveries);
+        settlements.push(ContractSettlement::new(contract, payables));
+    }
+
+    let payments = state
+        .contract_repo
+        .list_payments(grower_id, Some(season))
+        .await?;
+
+    Ok(GrowerSettlement::new(grower, season, settlements, payments))
+}
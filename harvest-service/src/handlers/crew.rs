@@ -0,0 +1,309 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderValue, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    error::AppError,
+    extractors::AuthenticatedUser,
+    handlers::AppState,
+    models::{
+        calculate_pay, AssignCrewRequest, CreateCrewRequest, CreatePickerRequest, PayrollQuery,
+        Picker, PickerCrew, PickerWorkEntry, RecordWorkRequest, UpdateCrewRequest,
+        UpdatePickerRequest, UserRole,
+    },
+};
+
+#[derive(Debug, Deserialize)]
+pub struct PickersQuery {
+    pub crew_id: Option<Uuid>,
+}
+
+/// Podaci o ekipama i zaradama nisu za radnike
+fn require_office_role(auth: &AuthenticatedUser) -> Result<(), AppError> {
+    if auth.claims.role == UserRole::Worker {
+        return Err(AppError::Forbidden(
+            "Workers cannot manage crews or payroll".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+// ============== Crews ==============
+
+pub async fn create_crew(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(req): Json<CreateCrewRequest>,
+) -> Result<(StatusCode, Json<PickerCrew>), AppError> {
+    require_office_role(&auth)?;
+    req.validate()?;
+
+    let user_id = auth.claims.user_id()?;
+    let crew = state.crew_repo.create_crew(user_id, req).await?;
+
+    Ok((StatusCode::CREATED, Json(crew)))
+}
+
+pub async fn update_crew(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(crew_id): Path<Uuid>,
+    Json(req): Json<UpdateCrewRequest>,
+) -> Result<Json<PickerCrew>, AppError> {
+    require_office_role(&auth)?;
+    req.validate()?;
+
+    let crew = state.crew_repo.update_crew(crew_id, req).await?;
+
+    Ok(Json(crew))
+}
+
+pub async fn list_crews(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<PickerCrew>>, AppError> {
+    let crews = state.crew_repo.list_crews().await?;
+
+    Ok(Json(crews))
+}
+
+// ============== Pickers ==============
+
+pub async fn create_picker(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(req): Json<CreatePickerRequest>,
+) -> Result<(StatusCode, Json<Picker>), AppError> {
+    require_office_role(&auth)?;
+    req.validate()?;
+
+    let picker = state.crew_repo.create_picker(req).await?;
+
+    Ok((StatusCode::CREATED, Json(picker)))
+}
+
+pub async fn list_pickers(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Query(query): Query<PickersQuery>,
+) -> Result<Json<Vec<Picker>>, AppError> {
+    require_office_role(&auth)?;
+
+    let pickers = state.crew_repo.list_pickers(query.crew_id).await?;
+
+    Ok(Json(pickers))
+}
+
+pub async fn update_picker(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(picker_id): Path<Uuid>,
+    Json(req): Json<UpdatePickerRequest>,
+) -> Result<Json<Picker>, AppError> {
+    require_office_role(&auth)?;
+    req.validate()?;
+
+    let picker = state.crew_repo.update_picker(picker_id, req).await?;
+
+    Ok(Json(picker))
+}
+
+// ============== Crew assignment ==============
+
+pub async fn assign_crew_to_harvest(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(harvest_id): Path<Uuid>,
+    Json(req): Json<AssignCrewRequest>,
+) -> Result<Json<Vec<PickerCrew>>, AppError> {
+    require_office_role(&auth)?;
+
+    state.harvest_repo.find_by_id(harvest_id).await?;
+    state.crew_repo.find_crew(req.crew_id).await?;
+
+    state.crew_repo.assign_crew(harvest_id, req.crew_id).await?;
+
+    let crews = state.crew_repo.list_harvest_crews(harvest_id).await?;
+
+    Ok(Json(crews))
+}
+
+pub async fn list_harvest_crews(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(harvest_id): Path<Uuid>,
+) -> Result<Json<Vec<PickerCrew>>, AppError> {
+    state.harvest_repo.find_by_id(harvest_id).await?;
+
+    let crews = state.crew_repo.list_harvest_crews(harvest_id).await?;
+
+    Ok(Json(crews))
+}
+
+pub async fn unassign_crew_from_harvest(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path((harvest_id, crew_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, AppError> {
+    require_office_role(&auth)?;
+
+    state.crew_repo.unassign_crew(harvest_id, crew_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============== Work entries ==============
+
+/// Upiši rad berača; zarada se računa po važećoj tarifi berača
+pub async fn record_picker_work(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(harvest_id): Path<Uuid>,
+    Json(req): Json<RecordWorkRequest>,
+) -> Result<(StatusCode, Json<PickerWorkEntry>), AppError> {
+    require_office_role(&auth)?;
+    req.validate()?;
+
+    let harvest = state.harvest_repo.find_by_id(harvest_id).await?;
+    let picker = state.crew_repo.find_picker(req.picker_id).await?;
+
+    if !picker.active {
+        return Err(AppError::ValidationError(format!(
+            "Picker '{}' is not active",
+            picker.full_name
+        )));
+    }
+    if picker.crew_id.is_none() {
+        return Err(AppError::ValidationError(format!(
+            "Picker '{}' is not in a crew",
+            picker.full_name
+        )));
+    }
+
+    let rate = picker.rate_for(req.pay_basis).ok_or_else(|| {
+        AppError::ValidationError(format!(
+            "Picker '{}' has no {} rate",
+            picker.full_name, req.pay_basis
+        ))
+    })?;
+
+    let amount = calculate_pay(
+        req.pay_basis,
+        rate,
+        req.hours_worked,
+        req.kg_picked,
+        req.bins_picked,
+    );
+    let work_date = req.work_date.unwrap_or(harvest.harvest_date);
+
+    let user_id = auth.claims.user_id()?;
+    let entry = state
+        .crew_repo
+        .record_work(harvest_id, user_id, work_date, rate, amount, req)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(entry)))
+}
+
+pub async fn list_picker_work(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(harvest_id): Path<Uuid>,
+) -> Result<Json<Vec<PickerWorkEntry>>, AppError> {
+    require_office_role(&auth)?;
+
+    state.harvest_repo.find_by_id(harvest_id).await?;
+
+    let entries = state.crew_repo.list_work_by_harvest(harvest_id).await?;
+
+    Ok(Json(entries))
+}
+
+pub async fn delete_picker_work(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path((harvest_id, entry_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, AppError> {
+    require_office_role(&auth)?;
+
+    let entry = state.crew_repo.find_work_entry(entry_id).await?;
+    if entry.harvest_id != harvest_id {
+        return Err(AppError::NotFound("Work entry not found in this harvest".to_string()));
+    }
+
+    state.crew_repo.delete_work_entry(entry_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============== Payroll export ==============
+
+pub async fn export_harvest_payroll(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(harvest_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    require_office_role(&auth)?;
+
+    let harvest = state.harvest_repo.find_by_id(harvest_id).await?;
+    let rows = state.crew_repo.payroll_for_harvest(harvest_id).await?;
+
+    let filename = format!("payroll_harvest_{}.csv", harvest.harvest_date.format("%Y%m%d"));
+
+    csv_response(&rows, &filename)
+}
+
+pub async fn export_period_payroll(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Query(query): Query<PayrollQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    require_office_role(&auth)?;
+
+    if query.from > query.to {
+        return Err(AppError::ValidationError(
+            "'from' must not be after 'to'".to_string(),
+        ));
+    }
+
+    let rows = state
+        .crew_repo
+        .payroll_for_period(query.from, query.to)
+        .await?;
+
+    let filename = format!(
+        "payroll_{}_{}.csv",
+        query.from.format("%Y%m%d"),
+        query.to.format("%Y%m%d")
+    );
+
+    csv_response(&rows, &filename)
+}
+
+fn csv_response(
+    rows: &[crate::models::PayrollRow],
+    filename: &str,
+) -> Result<impl IntoResponse, AppError> {
+    let csv_bytes = crate::export::generate_payroll_csv(rows)
+        .map_err(|e| AppError::InternalError(format!("Failed to generate CSV: {}", e)))?;
+
+    let content_disposition = format!("attachment; filename=\"{}\"", filename);
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, HeaderValue::from_static("text/csv; charset=utf-8")),
+            (
+                header::CONTENT_DISPOSITION,
+                HeaderValue::from_str(&content_disposition).unwrap(),
+            ),
+        ],
+        csv_bytes,
+    ))
+}
@@ -0,0 +1,68 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    error::AppError,
+    extractors::AuthenticatedUser,
+    handlers::AppState,
+    models::{QualityScoreProfile, ScoringRules, UpsertQualityProfileRequest, UserRole},
+};
+
+// ============== Quality score profiles ==============
+
+/// Podrazumevana pravila ocenjivanja (za sorte bez profila)
+pub async fn get_default_scoring_rules(_auth: AuthenticatedUser) -> Json<ScoringRules> {
+    Json(ScoringRules::default())
+}
+
+pub async fn list_quality_profiles(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<QualityScoreProfile>>, AppError> {
+    let profiles = state.grading_repo.list_profiles().await?;
+
+    Ok(Json(profiles))
+}
+
+/// Postavi profil ocenjivanja za sortu (samo vinar / admin)
+pub async fn upsert_quality_profile(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(req): Json<UpsertQualityProfileRequest>,
+) -> Result<Json<QualityScoreProfile>, AppError> {
+    if auth.claims.role == UserRole::Worker {
+        return Err(AppError::Forbidden(
+            "Workers cannot change quality profiles".to_string(),
+        ));
+    }
+
+    req.validate()?;
+
+    let user_id = auth.claims.user_id()?;
+    let profile = state.grading_repo.upsert_profile(user_id, req).await?;
+
+    Ok(Json(profile))
+}
+
+pub async fn delete_quality_profile(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(profile_id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    if auth.claims.role == UserRole::Worker {
+        return Err(AppError::Forbidden(
+            "Workers cannot change quality profiles".to_string(),
+        ));
+    }
+
+    let profile = state.grading_repo.find_profile_by_id(profile_id).await?;
+
+    state.grading_repo.delete_profile(&profile).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,5 +1,5 @@
 ﻿use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     Json,
     response::IntoResponse,
@@ -10,19 +10,39 @@ use axum::http::header;
 use axum::http::HeaderValue;
 
 use crate::{
+    clients::{FermentationClient, VineyardClient},
     config::Settings,
-    db::{HarvestRepository, VineyardHarvestStats},
+    db::{
+        AttachmentRepository, ContractRepository, CrewRepository, GradingRepository,
+        HarvestRepository, LoadRepository, MaturityRepository, PlanningRepository, SyncRepository,
+        VineyardHarvestStats,
+    },
     error::AppError,
     extractors::AuthenticatedUser,
     models::{
-        AddQualityMeasurementRequest, CreateHarvestRequest, HarvestQualityResponse,
-        HarvestResponse, HarvestStatus, UpdateHarvestRequest, UserRole,
+        summarize_harvests, AddQualityMeasurementRequest, AttachmentEntity, CreateHarvestRequest,
+        HarvestLineageResponse, HarvestLoadResponse, HarvestQualityResponse, HarvestResponse,
+        HarvestStatsGroup, HarvestStatsQuery, HarvestStatus, StatsGrouping, UpdateHarvestRequest,
+        UpdateHarvestStatusRequest, UserRole,
     },
+    storage::AttachmentStorage,
 };
 
 #[derive(Clone)]
 pub struct AppState {
     pub harvest_repo: HarvestRepository,
+    pub load_repo: LoadRepository,
+    pub crew_repo: CrewRepository,
+    pub grading_repo: GradingRepository,
+    pub maturity_repo: MaturityRepository,
+    pub planning_repo: PlanningRepository,
+    pub contract_repo: ContractRepository,
+    pub sync_repo: SyncRepository,
+    pub attachment_repo: AttachmentRepository,
+    pub vineyard_client: VineyardClient,
+    pub fermentation_client: FermentationClient,
+    pub storage: AttachmentStorage,
+    #[allow(dead_code)]
     pub settings: Settings,
 }
 
@@ -43,11 +63,23 @@ pub async fn create_harvest(
 
     req.validate()?;
 
+    // Parcela mora postojati i pripadati vinogradu korisnika
+    let parcel = state
+        .vineyard_client
+        .resolve_parcel(&auth, req.vineyard_id, req.parcel_id)
+        .await?;
+
     let user_id = auth.claims.user_id()?;
-    let harvest = state.harvest_repo.create_harvest(user_id, req).await?;
+    let harvest = state
+        .harvest_repo
+        .create_harvest(user_id, req, &parcel)
+        .await?;
+
+    let history = state.harvest_repo.list_status_history(harvest.id).await?;
 
     let mut response = HarvestResponse::from(harvest);
     response.quality_measurements = vec![];
+    response.status_history = history;
 
     Ok((StatusCode::CREATED, Json(response)))
 }
@@ -71,18 +103,23 @@ pub async fn get_harvest(
         .list_quality_measurements(harvest_id)
         .await?;
 
+    let history = state.harvest_repo.list_status_history(harvest_id).await?;
+    let loads = state.load_repo.list_by_harvest(harvest_id).await?;
+
     let mut response = HarvestResponse::from(harvest);
     response.quality_measurements = quality
         .into_iter()
         .map(HarvestQualityResponse::from)
         .collect();
+    response.status_history = history;
+    response.loads = loads.into_iter().map(HarvestLoadResponse::from).collect();
 
     Ok(Json(response))
 }
 
 /// Lista berbi za vinograd
 pub async fn list_harvests_by_vineyard(
-    auth: AuthenticatedUser,
+    _auth: AuthenticatedUser,
     State(state): State<AppState>,
     Path(vineyard_id): Path<Uuid>,
 ) -> Result<Json<Vec<HarvestResponse>>, AppError> {
@@ -95,7 +132,7 @@ pub async fn list_harvests_by_vineyard(
 
 /// Lista berbi za parcelu
 pub async fn list_harvests_by_parcel(
-    auth: AuthenticatedUser,
+    _auth: AuthenticatedUser,
     State(state): State<AppState>,
     Path(parcel_id): Path<Uuid>,
 ) -> Result<Json<Vec<HarvestResponse>>, AppError> {
@@ -144,17 +181,30 @@ pub async fn update_harvest(
         return Err(AppError::Forbidden("Access denied".to_string()));
     }
 
-    let updated = state.harvest_repo.update_harvest(harvest_id, req).await?;
+    // Ponovo proveri parcelu (i osveži keširane nazive / površinu)
+    let parcel = state
+        .vineyard_client
+        .resolve_parcel(
+            &auth,
+            req.vineyard_id.unwrap_or(harvest.vineyard_id),
+            req.parcel_id.unwrap_or(harvest.parcel_id),
+        )
+        .await?;
+
+    let updated = state
+        .harvest_repo
+        .update_harvest(harvest_id, req, &parcel)
+        .await?;
 
     Ok(Json(HarvestResponse::from(updated)))
 }
 
-/// Promeni status berbe
+/// Promeni status berbe (samo dozvoljeni prelazi, svaka promena ide u istoriju)
 pub async fn update_harvest_status(
     auth: AuthenticatedUser,
     State(state): State<AppState>,
     Path(harvest_id): Path<Uuid>,
-    Json(body): Json<serde_json::Value>,
+    Json(req): Json<UpdateHarvestStatusRequest>,
 ) -> Result<Json<HarvestResponse>, AppError> {
     if auth.claims.role == UserRole::Worker {
         return Err(AppError::Forbidden(
@@ -162,19 +212,15 @@ pub async fn update_harvest_status(
         ));
     }
 
-    let status_str = body["status"]
-        .as_str()
-        .ok_or_else(|| AppError::ValidationError("status field is required".to_string()))?;
-
-    let status = match status_str {
-        "planned" => HarvestStatus::Planned,
-        "in_progress" => HarvestStatus::InProgress,
-        "completed" => HarvestStatus::Completed,
-        "cancelled" => HarvestStatus::Cancelled,
-        _ => return Err(AppError::ValidationError(
-            "Invalid status. Use: planned, in_progress, completed, cancelled".to_string(),
-        )),
-    };
+    req.validate()?;
+
+    if req.status == HarvestStatus::Cancelled
+        && req.reason.as_deref().is_none_or(|r| r.trim().is_empty())
+    {
+        return Err(AppError::ValidationError(
+            "A reason is required to cancel a harvest".to_string(),
+        ));
+    }
 
     let harvest = state.harvest_repo.find_by_id(harvest_id).await?;
     let user_id = auth.claims.user_id()?;
@@ -183,9 +229,17 @@ pub async fn update_harvest_status(
         return Err(AppError::Forbidden("Access denied".to_string()));
     }
 
-    let updated = state.harvest_repo.update_status(harvest_id, status).await?;
+    let updated = state
+        .harvest_repo
+        .change_status(harvest_id, req.status, user_id, req.reason)
+        .await?;
+
+    let history = state.harvest_repo.list_status_history(harvest_id).await?;
 
-    Ok(Json(HarvestResponse::from(updated)))
+    let mut response = HarvestResponse::from(updated);
+    response.status_history = history;
+
+    Ok(Json(response))
 }
 
 /// Obriši berbu
@@ -207,8 +261,19 @@ pub async fn delete_harvest(
         return Err(AppError::Forbidden("Access denied".to_string()));
     }
 
+    let measurement_ids: Vec<Uuid> = state
+        .harvest_repo
+        .list_quality_measurements(harvest_id)
+        .await?
+        .iter()
+        .map(|m| m.id)
+        .collect();
+
     state.harvest_repo.delete_harvest(harvest_id).await?;
 
+    super::purge_attachments(&state, AttachmentEntity::Harvest, &[harvest_id]).await?;
+    super::purge_attachments(&state, AttachmentEntity::QualityMeasurement, &measurement_ids).await?;
+
     Ok(StatusCode::NO_CONTENT)
 }
 
@@ -241,7 +306,7 @@ pub async fn add_quality_measurement(
 
 /// Lista merenja kvaliteta za berbu
 pub async fn list_quality_measurements(
-    auth: AuthenticatedUser,
+    _auth: AuthenticatedUser,
     State(state): State<AppState>,
     Path(harvest_id): Path<Uuid>,
 ) -> Result<Json<Vec<HarvestQualityResponse>>, AppError> {
@@ -280,15 +345,47 @@ pub async fn delete_quality_measurement(
         .harvest_repo
         .delete_quality_measurement(measurement_id)
         .await?;
+    super::purge_attachments(&state, AttachmentEntity::QualityMeasurement, &[measurement_id]).await?;
 
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// Poreklo vina unazad od berbe: muljanja, šira i batch-evi iz fermentation-service
+pub async fn get_harvest_lineage(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(harvest_id): Path<Uuid>,
+) -> Result<Json<HarvestLineageResponse>, AppError> {
+    let harvest = state.harvest_repo.find_by_id(harvest_id).await?;
+
+    let user_id = auth.claims.user_id()?;
+    if auth.claims.role == UserRole::Worker && harvest.created_by != user_id {
+        return Err(AppError::Forbidden("Access denied".to_string()));
+    }
+
+    let lineage = state
+        .fermentation_client
+        .get_harvest_lineage(&auth.token, harvest_id)
+        .await?;
+
+    Ok(Json(HarvestLineageResponse {
+        harvest_id,
+        total_weight_kg: harvest.total_weight_kg,
+        crushed_kg: lineage.crushed_kg,
+        uncrushed_kg: harvest
+            .total_weight_kg
+            .map(|total| (total - lineage.crushed_kg).max(0.0)),
+        must_liters: lineage.must_liters,
+        crushes: lineage.crushes,
+        batches: lineage.batches,
+    }))
+}
+
 // ============== Stats handler ==============
 
 /// Statistike berbi za vinograd
 pub async fn get_vineyard_harvest_stats(
-    auth: AuthenticatedUser,
+    _auth: AuthenticatedUser,
     State(state): State<AppState>,
     Path(vineyard_id): Path<Uuid>,
 ) -> Result<Json<VineyardHarvestStats>, AppError> {
@@ -296,6 +393,40 @@ pub async fn get_vineyard_harvest_stats(
 
     Ok(Json(stats))
 }
+
+/// Statistike po sezoni, parceli i sorti sa poređenjem sa prethodnom sezonom
+pub async fn get_harvest_stats_breakdown(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Query(query): Query<HarvestStatsQuery>,
+) -> Result<Json<Vec<HarvestStatsGroup>>, AppError> {
+    let grouping = match query.group_by.as_deref() {
+        Some(value) => StatsGrouping::parse(value).map_err(AppError::ValidationError)?,
+        None => StatsGrouping::default(),
+    };
+
+    if let (Some(from), Some(to)) = (query.season_from, query.season_to) {
+        if from > to {
+            return Err(AppError::ValidationError(
+                "season_from must not be after season_to".to_string(),
+            ));
+        }
+    }
+
+    // Prethodna sezona se učitava samo radi poređenja
+    let samples = state
+        .harvest_repo
+        .list_stats_samples(&query, query.season_from.map(|s| s - 1))
+        .await?;
+
+    let mut groups = summarize_harvests(&samples, grouping);
+    if let Some(from) = query.season_from {
+        groups.retain(|g| g.season.is_none_or(|s| s >= from));
+    }
+
+    Ok(Json(groups))
+}
+
 pub async fn export_harvest_pdf(
     auth: AuthenticatedUser,
     State(state): State<AppState>,
@@ -318,16 +449,28 @@ pub async fn export_harvest_pdf(
         .await
         .unwrap_or_default();
 
-    // Get vineyard and parcel names (simplified - you may need to call vineyard service)
-    let vineyard_name = "Vineyard"; // TODO: Fetch from vineyard service via HTTP
-    let parcel_name = "Parcel"; // TODO: Fetch from vineyard service via HTTP
+    // Nazivi su keširani uz berbu; za stare zapise pokušaj vineyard-service
+    let (vineyard_name, parcel_name) = match (&harvest.vineyard_name, &harvest.parcel_name) {
+        (Some(v), Some(p)) => (v.clone(), p.clone()),
+        _ => match state
+            .vineyard_client
+            .resolve_parcel(&auth, harvest.vineyard_id, harvest.parcel_id)
+            .await
+        {
+            Ok(parcel) => (parcel.vineyard_name, parcel.parcel_name),
+            Err(e) => {
+                tracing::warn!("Could not fetch vineyard/parcel names: {}", e);
+                ("N/A".to_string(), "N/A".to_string())
+            }
+        },
+    };
 
     // Generate PDF
     let pdf_bytes = crate::pdf::generate_harvest_report(
         &harvest,
         &quality_measurements,
-        vineyard_name,
-        parcel_name,
+        &vineyard_name,
+        &parcel_name,
     )
         .map_err(|e| AppError::InternalError(format!("Failed to generate PDF: {}", e)))?;
 
@@ -0,0 +1,221 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Multipart, Query, State},
+    http::{header, HeaderValue, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use uuid::Uuid;
+
+use crate::{
+    clients::ParcelSnapshot,
+    error::AppError,
+    extractors::AuthenticatedUser,
+    handlers::AppState,
+    models::{
+        harvest_sheet_rows, parse_harvest_sheet, HarvestExportQuery, HarvestImportQuery,
+        HarvestImportReport, ImportRowError, ImportedHarvestRef, UserRole,
+    },
+};
+
+const XLSX_CONTENT_TYPE: &str = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+
+/// Uvoz berbi i merenja iz CSV/XLSX tabele (polje "file").
+/// Podrazumevano je provera bez upisa; sa `dry_run=false` upisuje sve ili ništa.
+pub async fn import_harvest_sheet(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Query(query): Query<HarvestImportQuery>,
+    mut multipart: Multipart,
+) -> Result<(StatusCode, Json<HarvestImportReport>), AppError> {
+    if auth.claims.role == UserRole::Worker {
+        return Err(AppError::Forbidden(
+            "Workers cannot import harvests".to_string(),
+        ));
+    }
+
+    let mut upload = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::ValidationError(format!("Invalid upload: {}", e)))?
+    {
+        if field.name() == Some("file") {
+            let file_name = field.file_name().unwrap_or_default().to_lowercase();
+            let content_type = field.content_type().unwrap_or_default().to_string();
+            let bytes = field
+                .bytes()
+                .await
+                .map_err(|e| AppError::ValidationError(format!("Invalid upload: {}", e)))?;
+            upload = Some((file_name, content_type, bytes));
+        }
+    }
+
+    let (file_name, content_type, bytes) =
+        upload.ok_or_else(|| AppError::ValidationError("Missing 'file' field".to_string()))?;
+
+    let table = if file_name.ends_with(".xlsx") || content_type == XLSX_CONTENT_TYPE {
+        crate::import::read_xlsx_table(&bytes)
+    } else if file_name.ends_with(".csv") || content_type.starts_with("text/csv") {
+        crate::import::read_csv_table(&bytes)
+    } else {
+        return Err(AppError::ValidationError(
+            "Upload must be a .csv or .xlsx file".to_string(),
+        ));
+    };
+    let (headers, rows) =
+        table.map_err(|e| AppError::ValidationError(format!("Cannot read spreadsheet: {}", e)))?;
+
+    let mut sheet = parse_harvest_sheet(&headers, &rows).map_err(AppError::ValidationError)?;
+
+    // Svaka parcela se proverava jednom u vineyard-service; greške idu u izveštaj
+    let mut parcels: HashMap<(Uuid, Uuid), Result<ParcelSnapshot, String>> = HashMap::new();
+    for harvest in &sheet.harvests {
+        let key = (harvest.request.vineyard_id, harvest.request.parcel_id);
+        if parcels.contains_key(&key) {
+            continue;
+        }
+        let resolved = match state.vineyard_client.resolve_parcel(&auth, key.0, key.1).await {
+            Ok(parcel) => Ok(parcel),
+            Err(AppError::NotFound(m)) | Err(AppError::Forbidden(m)) | Err(AppError::ValidationError(m)) => {
+                Err(m)
+            }
+            Err(e) => return Err(e),
+        };
+        parcels.insert(key, resolved);
+    }
+
+    let mut resolved = Vec::with_capacity(sheet.harvests.len());
+    for harvest in sheet.harvests.drain(..) {
+        match &parcels[&(harvest.request.vineyard_id, harvest.request.parcel_id)] {
+            Ok(parcel) => resolved.push((harvest, parcel.clone())),
+            Err(message) => sheet.errors.push(ImportRowError {
+                row: harvest.row,
+                column: Some("parcel_id".to_string()),
+                message: message.clone(),
+            }),
+        }
+    }
+    sheet.errors.sort_by_key(|e| e.row);
+
+    let mut report = HarvestImportReport {
+        dry_run: query.dry_run,
+        committed: false,
+        rows_read: sheet.rows_read,
+        harvests: resolved.len(),
+        measurements: resolved.iter().map(|(h, _)| h.measurements.len()).sum(),
+        errors: sheet.errors,
+        created: vec![],
+    };
+
+    if !report.errors.is_empty() {
+        let status = if query.dry_run {
+            StatusCode::OK
+        } else {
+            StatusCode::UNPROCESSABLE_ENTITY
+        };
+        return Ok((status, Json(report)));
+    }
+    if query.dry_run {
+        return Ok((StatusCode::OK, Json(report)));
+    }
+
+    let refs: Vec<String> = resolved.iter().map(|(h, _)| h.harvest_ref.clone()).collect();
+
+    let user_id = auth.claims.user_id()?;
+    let created = state.harvest_repo.import_harvests(user_id, resolved).await?;
+
+    report.committed = true;
+    report.created = refs
+        .into_iter()
+        .zip(&created)
+        .map(|(harvest_ref, harvest)| ImportedHarvestRef {
+            harvest_ref,
+            harvest_id: harvest.id,
+        })
+        .collect();
+
+    Ok((StatusCode::CREATED, Json(report)))
+}
+
+/// Izvoz berbi vinograda u istom formatu koji prihvata uvoz
+pub async fn export_harvest_sheet(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Query(query): Query<HarvestExportQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let vineyard = state
+        .vineyard_client
+        .get_vineyard(&auth.token, query.vineyard_id)
+        .await?;
+
+    if auth.claims.role != UserRole::Admin && vineyard.owner_id != auth.claims.user_id()? {
+        return Err(AppError::Forbidden(
+            "Vineyard belongs to another user".to_string(),
+        ));
+    }
+
+    let harvests = state
+        .harvest_repo
+        .list_for_export(query.vineyard_id, query.season)
+        .await?;
+    let ids: Vec<Uuid> = harvests.iter().map(|h| h.id).collect();
+    let measurements = state.harvest_repo.list_quality_by_harvests(&ids).await?;
+
+    let rows: Vec<Vec<String>> = harvests
+        .iter()
+        .flat_map(|harvest| {
+            let own: Vec<_> = measurements
+                .iter()
+                .filter(|m| m.harvest_id == harvest.id)
+                .cloned()
+                .collect();
+            harvest_sheet_rows(harvest, &own)
+        })
+        .collect();
+
+    let (bytes, content_type, extension) = match query.format.as_deref().unwrap_or("csv") {
+        "csv" => (
+            crate::export::generate_harvest_sheet_csv(&rows),
+            "text/csv; charset=utf-8",
+            "csv",
+        ),
+        "xlsx" => (
+            crate::export::generate_harvest_sheet_xlsx(&rows),
+            XLSX_CONTENT_TYPE,
+            "xlsx",
+        ),
+        other => {
+            return Err(AppError::ValidationError(format!(
+                "Unknown format '{}', expected csv or xlsx",
+                other
+            )))
+        }
+    };
+    let bytes = bytes
+        .map_err(|e| AppError::InternalError(format!("Failed to generate spreadsheet: {}", e)))?;
+
+    let content_disposition = match query.season {
+        Some(season) => format!(
+            "attachment; filename=\"harvests_{}_{}.{}\"",
+            query.vineyard_id, season, extension
+        ),
+        None => format!(
+            "attachment; filename=\"harvests_{}.{}\"",
+            query.vineyard_id, extension
+        ),
+    };
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, HeaderValue::from_static(content_type)),
+            (
+                header::CONTENT_DISPOSITION,
+                HeaderValue::from_str(&content_disposition).unwrap(),
+            ),
+        ],
+        bytes,
+    ))
+}
@@ -0,0 +1,108 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    error::AppError,
+    extractors::AuthenticatedUser,
+    handlers::AppState,
+    models::{HarvestLoadResponse, ReceiveLoadRequest, ReceivingQuery, UserRole},
+};
+
+// ============== Prijem grožđa (vaga / prijemno mesto) ==============
+
+/// Prijem ture - dostupno i radnicima na prijemnom mestu, ali samo za njihove berbe
+pub async fn receive_load(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(req): Json<ReceiveLoadRequest>,
+) -> Result<(StatusCode, Json<HarvestLoadResponse>), AppError> {
+    req.validate()?;
+
+    let harvest = state.harvest_repo.find_by_id(req.harvest_id).await?;
+    let user_id = auth.claims.user_id()?;
+
+    if auth.claims.role == UserRole::Worker && harvest.created_by != user_id {
+        return Err(AppError::Forbidden("Access denied".to_string()));
+    }
+
+    let load = state.load_repo.receive_load(user_id, req).await?;
+
+    Ok((StatusCode::CREATED, Json(HarvestLoadResponse::from(load))))
+}
+
+/// Ture primljene tog dana (podrazumevano danas)
+pub async fn list_received_loads(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Query(query): Query<ReceivingQuery>,
+) -> Result<Json<Vec<HarvestLoadResponse>>, AppError> {
+    let date = query
+        .date
+        .unwrap_or_else(|| chrono::Utc::now().date_naive());
+
+    let loads = state.load_repo.list_received_on(date).await?;
+
+    let responses = loads.into_iter().map(HarvestLoadResponse::from).collect();
+
+    Ok(Json(responses))
+}
+
+/// Pronađi turu po broju vagarske potvrde
+pub async fn get_load_by_ticket(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(ticket_number): Path<String>,
+) -> Result<Json<HarvestLoadResponse>, AppError> {
+    let load = state.load_repo.find_by_ticket(&ticket_number).await?;
+
+    Ok(Json(HarvestLoadResponse::from(load)))
+}
+
+/// Lista tura za berbu
+pub async fn list_harvest_loads(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(harvest_id): Path<Uuid>,
+) -> Result<Json<Vec<HarvestLoadResponse>>, AppError> {
+    state.harvest_repo.find_by_id(harvest_id).await?;
+
+    let loads = state.load_repo.list_by_harvest(harvest_id).await?;
+
+    let responses = loads.into_iter().map(HarvestLoadResponse::from).collect();
+
+    Ok(Json(responses))
+}
+
+/// Obriši pogrešno unetu turu
+pub async fn delete_harvest_load(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path((harvest_id, load_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, AppError> {
+    if auth.claims.role == UserRole::Worker {
+        return Err(AppError::Forbidden(
+            "Workers cannot delete loads".to_string(),
+        ));
+    }
+
+    let harvest = state.harvest_repo.find_by_id(harvest_id).await?;
+    let user_id = auth.claims.user_id()?;
+
+    if auth.claims.role != UserRole::Admin && harvest.created_by != user_id {
+        return Err(AppError::Forbidden("Access denied".to_string()));
+    }
+
+    let load = state.load_repo.find_by_id(load_id).await?;
+    if load.harvest_id != harvest_id {
+        return Err(AppError::NotFound("Load not found in this harvest".to_string()));
+    }
+
+    state.load_repo.delete_load(&load).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,190 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{Datelike, Utc};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    error::AppError,
+    extractors::AuthenticatedUser,
+    handlers::AppState,
+    models::{
+        resolve_sampled_at, CreateMaturitySampleRequest, Harvest, LinkMaturitySamplesRequest,
+        MaturityQuery, MaturitySample, MaturityTrendResponse, UserRole,
+    },
+};
+
+// ============== Maturity samples (po parceli) ==============
+
+/// Upiši uzorak zrelosti za parcelu - bez berbe
+pub async fn create_maturity_sample(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(parcel_id): Path<Uuid>,
+    Json(req): Json<CreateMaturitySampleRequest>,
+) -> Result<(StatusCode, Json<MaturitySample>), AppError> {
+    req.validate()?;
+    let sampled_at =
+        resolve_sampled_at(req.sampled_at, Utc::now()).map_err(AppError::ValidationError)?;
+
+    let parcel = state
+        .vineyard_client
+        .resolve_parcel(&auth, req.vineyard_id, parcel_id)
+        .await?;
+
+    let user_id = auth.claims.user_id()?;
+    let sample = state
+        .maturity_repo
+        .create_sample(parcel_id, user_id, sampled_at, req, &parcel)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(sample)))
+}
+
+/// Uzorci parcele u sezoni
+pub async fn list_maturity_samples(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(parcel_id): Path<Uuid>,
+    Query(query): Query<MaturityQuery>,
+) -> Result<Json<Vec<MaturitySample>>, AppError> {
+    let season = query.season.unwrap_or_else(|| Utc::now().year());
+
+    let samples = state.maturity_repo.list_by_parcel(parcel_id, season).await?;
+
+    Ok(Json(samples))
+}
+
+/// Podaci za grafik zrelosti (dnevni proseci) sa ciljnim Brix-om sorte
+pub async fn get_maturity_trend(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(parcel_id): Path<Uuid>,
+    Query(query): Query<MaturityQuery>,
+) -> Result<Json<MaturityTrendResponse>, AppError> {
+    let season = query.season.unwrap_or_else(|| Utc::now().year());
+
+    let samples = state.maturity_repo.list_by_parcel(parcel_id, season).await?;
+    let grape_variety = samples.iter().rev().find_map(|s| s.grape_variety.clone());
+
+    let target_brix = match &grape_variety {
+        Some(variety) => state
+            .planning_repo
+            .find_ripeness_target(variety)
+            .await?
+            .map(|t| t.target_brix),
+        None => None,
+    };
+
+    let points = state.maturity_repo.trend_by_parcel(parcel_id, season).await?;
+
+    Ok(Json(MaturityTrendResponse {
+        parcel_id,
+        season,
+        grape_variety,
+        target_brix,
+        points,
+    }))
+}
+
+/// Obriši uzorak (radnik samo svoj)
+pub async fn delete_maturity_sample(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(sample_id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    let sample = state.maturity_repo.find_sample(sample_id).await?;
+    let user_id = auth.claims.user_id()?;
+
+    if auth.claims.role == UserRole::Worker && sample.sampled_by != user_id {
+        return Err(AppError::Forbidden("Access denied".to_string()));
+    }
+
+    state.maturity_repo.delete_sample(sample_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============== Povezivanje sa berbom ==============
+
+/// Poveži završne uzorke sa berbom
+pub async fn link_maturity_samples(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(harvest_id): Path<Uuid>,
+    Json(req): Json<LinkMaturitySamplesRequest>,
+) -> Result<Json<Vec<MaturitySample>>, AppError> {
+    let harvest = find_editable_harvest(&state, &auth, harvest_id).await?;
+
+    match req.sample_ids {
+        Some(mut ids) => {
+            ids.sort();
+            ids.dedup();
+            state.maturity_repo.link_samples(&harvest, &ids).await?;
+        }
+        None => {
+            let linked = state.maturity_repo.link_final_samples(&harvest).await?;
+            if linked.is_empty() {
+                return Err(AppError::ValidationError(
+                    "No unlinked maturity samples for this parcel before the harvest date"
+                        .to_string(),
+                ));
+            }
+        }
+    }
+
+    let samples = state.maturity_repo.list_by_harvest(harvest_id).await?;
+
+    Ok(Json(samples))
+}
+
+pub async fn list_harvest_maturity_samples(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(harvest_id): Path<Uuid>,
+) -> Result<Json<Vec<MaturitySample>>, AppError> {
+    state.harvest_repo.find_by_id(harvest_id).await?;
+
+    let samples = state.maturity_repo.list_by_harvest(harvest_id).await?;
+
+    Ok(Json(samples))
+}
+
+pub async fn unlink_maturity_sample(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path((harvest_id, sample_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, AppError> {
+    find_editable_harvest(&state, &auth, harvest_id).await?;
+
+    state
+        .maturity_repo
+        .unlink_sample(harvest_id, sample_id)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn find_editable_harvest(
+    state: &AppState,
+    auth: &AuthenticatedUser,
+    harvest_id: Uuid,
+) -> Result<Harvest, AppError> {
+    if auth.claims.role == UserRole::Worker {
+        return Err(AppError::Forbidden(
+            "Workers cannot link maturity samples".to_string(),
+        ));
+    }
+
+    let harvest = state.harvest_repo.find_by_id(harvest_id).await?;
+    let user_id = auth.claims.user_id()?;
+
+    if auth.claims.role != UserRole::Admin && harvest.created_by != user_id {
+        return Err(AppError::Forbidden("Access denied".to_string()));
+    }
+
+    Ok(harvest)
+}
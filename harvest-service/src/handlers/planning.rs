@@ -0,0 +1,356 @@
+use std::collections::{BTreeMap, HashMap};
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{Datelike, Utc};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    error::AppError,
+    extractors::AuthenticatedUser,
+    handlers::AppState,
+    models::{
+        forecast_ripeness, schedule_harvests, AcceptHarvestPlanRequest, CreateHarvestPlanRequest,
+        HarvestPlan, HarvestPlanResponse, NewHarvestPlan, NewHarvestPlanItem,
+        ParcelRipenessResponse, ParcelRipenessSample, RipenessQuery, RipenessTarget, UserRole,
+        UpsertRipenessTargetRequest,
+    },
+};
+
+/// Planiranje je posao vinara / administratora
+fn require_planner_role(auth: &AuthenticatedUser) -> Result<(), AppError> {
+    if auth.claims.role == UserRole::Worker {
+        return Err(AppError::Forbidden(
+            "Workers cannot plan harvests".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Ne-admin korisnici vide samo parcele i planove koje su sami uneli
+fn owner_filter(auth: &AuthenticatedUser) -> Result<Option<Uuid>, AppError> {
+    if auth.claims.role == UserRole::Admin {
+        Ok(None)
+    } else {
+        Ok(Some(auth.claims.user_id()?))
+    }
+}
+
+// ============== Ripeness targets ==============
+
+pub async fn upsert_ripeness_target(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(req): Json<UpsertRipenessTargetRequest>,
+) -> Result<Json<RipenessTarget>, AppError> {
+    require_planner_role(&auth)?;
+    req.validate()?;
+
+    let user_id = auth.claims.user_id()?;
+    let target = state.planning_repo.upsert_ripeness_target(user_id, req).await?;
+
+    Ok(Json(target))
+}
+
+pub async fn list_ripeness_targets(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<RipenessTarget>>, AppError> {
+    let targets = state.planning_repo.list_ripeness_targets().await?;
+
+    Ok(Json(targets))
+}
+
+// ============== Ripeness forecast ==============
+
+/// Prognoza zrelosti po parceli iz merenja kvaliteta u sezoni
+pub async fn get_ripeness_forecast(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Query(query): Query<RipenessQuery>,
+) -> Result<Json<Vec<ParcelRipenessResponse>>, AppError> {
+    require_planner_role(&auth)?;
+
+    let season = query.season.unwrap_or_else(|| Utc::now().year());
+    let forecasts = parcel_forecasts(&state, &auth, season, query.vineyard_id).await?;
+
+    Ok(Json(forecasts.into_iter().map(|f| f.response).collect()))
+}
+
+struct ParcelForecast {
+    response: ParcelRipenessResponse,
+    parcel_area_m2: Option<f64>,
+}
+
+async fn parcel_forecasts(
+    state: &AppState,
+    auth: &AuthenticatedUser,
+    season: i32,
+    vineyard_id: Option<Uuid>,
+) -> Result<Vec<ParcelForecast>, AppError> {
+    let samples = state
+        .planning_repo
+        .list_ripeness_samples(season, vineyard_id, owner_filter(auth)?)
+        .await?;
+
+    let targets: HashMap<String, RipenessTarget> = state
+        .planning_repo
+        .list_ripeness_targets()
+        .await?
+        .into_iter()
+        .map(|t| (t.grape_variety.to_lowercase(), t))
+        .collect();
+
+    let mut by_parcel: BTreeMap<Uuid, Vec<ParcelRipenessSample>> = BTreeMap::new();
+    for sample in samples {
+        by_parcel.entry(sample.parcel_id).or_default().push(sample);
+    }
+
+    let forecasts = by_parcel
+        .into_values()
+        .map(|samples| {
+            // Podaci o parceli iz najnovijeg merenja
+            let latest = samples.last().unwrap();
+            let target = latest
+                .grape_variety
+                .as_ref()
+                .and_then(|v| targets.get(&v.to_lowercase()));
+
+            ParcelForecast {
+                parcel_area_m2: latest.parcel_area_m2,
+                response: ParcelRipenessResponse {
+                    parcel_id: latest.parcel_id,
+                    vineyard_id: latest.vineyard_id,
+                    vineyard_name: latest.vineyard_name.clone(),
+                    parcel_name: latest.parcel_name.clone(),
+                    grape_variety: latest.grape_variety.clone(),
+                    target_brix: target.map(|t| t.target_brix),
+                    forecast: target.map(|t| {
+                        forecast_ripeness(&samples, t.target_brix, t.max_ph, t.target_acidity)
+                    }),
+                },
+            }
+        })
+        .collect();
+
+    Ok(forecasts)
+}
+
+// ============== Harvest plans ==============
+
+/// Predloži kalendar berbe: parcele po prognozi zrelosti, uz dnevni kapacitet ekipa i prese
+pub async fn create_harvest_plan(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(req): Json<CreateHarvestPlanRequest>,
+) -> Result<(StatusCode, Json<HarvestPlanResponse>), AppError> {
+    require_planner_role(&auth)?;
+    req.validate()?;
+
+    let crew_capacity = state
+        .planning_repo
+        .crew_capacity(req.crew_ids.as_deref())
+        .await?;
+    if crew_capacity <= 0.0 {
+        return Err(AppError::ValidationError(
+            "No crew capacity available; set daily_capacity_kg on picker crews".to_string(),
+        ));
+    }
+    let daily_capacity = crew_capacity.min(req.press_capacity_kg_per_day);
+
+    let season = req.season.unwrap_or_else(|| Utc::now().year());
+    let start_date = req.start_date.unwrap_or_else(|| Utc::now().date_naive());
+
+    let forecasts = parcel_forecasts(&state, &auth, season, req.vineyard_id).await?;
+
+    let parcel_ids: Vec<Uuid> = forecasts.iter().map(|f| f.response.parcel_id).collect();
+    let yields: HashMap<Uuid, f64> = state
+        .planning_repo
+        .average_yields(&parcel_ids)
+        .await?
+        .into_iter()
+        .collect();
+
+    // Procena količine: površina × prosečan prinos parcele (ili podrazumevani)
+    let estimates: Vec<Option<f64>> = forecasts
+        .iter()
+        .map(|f| {
+            let yield_per_ha = yields
+                .get(&f.response.parcel_id)
+                .copied()
+                .unwrap_or(req.default_yield_kg_per_ha);
+            f.parcel_area_m2.map(|area| area / 10_000.0 * yield_per_ha)
+        })
+        .collect();
+
+    let mut candidate_index = Vec::new();
+    let mut candidates = Vec::new();
+    for (i, f) in forecasts.iter().enumerate() {
+        let ripe = f.response.forecast.as_ref().and_then(|fc| fc.predicted_ripe_date);
+        if let (Some(ripe), Some(kg)) = (ripe, estimates[i]) {
+            candidate_index.push(i);
+            candidates.push((ripe, kg));
+        }
+    }
+
+    let slots = schedule_harvests(&candidates, start_date, daily_capacity, req.horizon_days);
+    let mut slot_by_forecast = HashMap::new();
+    for (slot, i) in slots.into_iter().zip(candidate_index) {
+        slot_by_forecast.insert(i, slot);
+    }
+
+    let items = forecasts
+        .into_iter()
+        .enumerate()
+        .map(|(i, f)| {
+            let r = f.response;
+            let slot = slot_by_forecast.get(&i).copied().flatten();
+
+            let notes = if r.target_brix.is_none() {
+                Some(format!(
+                    "No ripeness target for variety '{}'",
+                    r.grape_variety.as_deref().unwrap_or("unknown")
+                ))
+            } else if r.forecast.as_ref().is_some_and(|fc| fc.predicted_ripe_date.is_none()) {
+                r.forecast.as_ref().and_then(|fc| fc.note.clone())
+            } else if estimates[i].is_none() {
+                Some("Parcel area unknown; cannot estimate quantity".to_string())
+            } else if slot.is_none() {
+                Some("Does not fit in the planning horizon with the available capacity".to_string())
+            } else {
+                r.forecast.as_ref().and_then(|fc| fc.note.clone())
+            };
+
+            NewHarvestPlanItem {
+                parcel_id: r.parcel_id,
+                vineyard_id: r.vineyard_id,
+                vineyard_name: r.vineyard_name,
+                parcel_name: r.parcel_name,
+                grape_variety: r.grape_variety,
+                sample_count: r.forecast.as_ref().map_or(0, |fc| fc.sample_count as i32),
+                latest_brix: r.forecast.as_ref().and_then(|fc| fc.latest_brix),
+                brix_per_day: r.forecast.as_ref().and_then(|fc| fc.brix_per_day),
+                predicted_ripe_date: r.forecast.as_ref().and_then(|fc| fc.predicted_ripe_date),
+                estimated_kg: estimates[i],
+                scheduled_date: slot.map(|s| s.start),
+                finish_date: slot.map(|s| s.finish),
+                notes,
+            }
+        })
+        .collect();
+
+    let plan = NewHarvestPlan {
+        season,
+        vineyard_id: req.vineyard_id,
+        start_date,
+        crew_capacity_kg_per_day: crew_capacity,
+        press_capacity_kg_per_day: req.press_capacity_kg_per_day,
+        created_by: auth.claims.user_id()?,
+    };
+
+    let plan = state.planning_repo.create_plan(plan, items).await?;
+    let items = state.planning_repo.list_plan_items(plan.id).await?;
+
+    Ok((StatusCode::CREATED, Json(HarvestPlanResponse { plan, items })))
+}
+
+pub async fn list_harvest_plans(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<HarvestPlan>>, AppError> {
+    require_planner_role(&auth)?;
+
+    let plans = state.planning_repo.list_plans(owner_filter(&auth)?).await?;
+
+    Ok(Json(plans))
+}
+
+pub async fn get_harvest_plan(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(plan_id): Path<Uuid>,
+) -> Result<Json<HarvestPlanResponse>, AppError> {
+    let plan = find_own_plan(&state, &auth, plan_id).await?;
+    let items = state.planning_repo.list_plan_items(plan_id).await?;
+
+    Ok(Json(HarvestPlanResponse { plan, items }))
+}
+
+/// Prihvati plan - raspoređene stavke postaju planirane berbe
+pub async fn accept_harvest_plan(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(plan_id): Path<Uuid>,
+    Json(req): Json<AcceptHarvestPlanRequest>,
+) -> Result<Json<HarvestPlanResponse>, AppError> {
+    find_own_plan(&state, &auth, plan_id).await?;
+
+    let items: Vec<_> = state
+        .planning_repo
+        .list_plan_items(plan_id)
+        .await?
+        .into_iter()
+        .filter(|item| item.scheduled_date.is_some())
+        .filter(|item| {
+            req.item_ids
+                .as_ref()
+                .is_none_or(|ids| ids.contains(&item.id))
+        })
+        .collect();
+
+    if items.is_empty() {
+        return Err(AppError::ValidationError(
+            "Plan has no scheduled items to accept".to_string(),
+        ));
+    }
+
+    // Parcele se proveravaju u vineyard-service pre upisa berbi
+    let mut accepted = Vec::with_capacity(items.len());
+    for item in items {
+        let parcel = state
+            .vineyard_client
+            .resolve_parcel(&auth, item.vineyard_id, item.parcel_id)
+            .await?;
+        accepted.push((item, parcel));
+    }
+
+    let user_id = auth.claims.user_id()?;
+    let plan = state
+        .planning_repo
+        .accept_plan(plan_id, user_id, accepted)
+        .await?;
+    let items = state.planning_repo.list_plan_items(plan_id).await?;
+
+    Ok(Json(HarvestPlanResponse { plan, items }))
+}
+
+pub async fn discard_harvest_plan(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(plan_id): Path<Uuid>,
+) -> Result<Json<HarvestPlan>, AppError> {
+    find_own_plan(&state, &auth, plan_id).await?;
+
+    let plan = state.planning_repo.discard_plan(plan_id).await?;
+
+    Ok(Json(plan))
+}
+
+async fn find_own_plan(
+    state: &AppState,
+    auth: &AuthenticatedUser,
+    plan_id: Uuid,
+) -> Result<HarvestPlan, AppError> {
+    require_planner_role(auth)?;
+
+    let plan = state.planning_repo.find_plan(plan_id).await?;
+    if owner_filter(auth)?.is_some_and(|user_id| plan.created_by != user_id) {
+        return Err(AppError::Forbidden("Access denied".to_string()));
+    }
+
+    Ok(plan)
+}
@@ -0,0 +1,278 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use chrono::Utc;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    error::AppError,
+    extractors::AuthenticatedUser,
+    handlers::AppState,
+    models::{
+        decide_sync, sync_page_cursor, SyncChangesQuery, SyncChangesResponse, SyncDecision,
+        SyncHarvestPush, SyncOutcome, SyncPushRequest, SyncPushResponse, SyncPushResult,
+        SyncQualityPush, UserRole, SYNC_PAGE_MAX,
+    },
+};
+
+/// Najviše zapisa u jednom upload-u
+const SYNC_PUSH_MAX: usize = 1000;
+
+/// Batch upload sa uređaja. Svaki zapis dobija svoj ishod; ponovljen upload
+/// istog sadržaja ne pravi duplikate.
+pub async fn push_sync_changes(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(req): Json<SyncPushRequest>,
+) -> Result<Json<SyncPushResponse>, AppError> {
+    if req.harvests.len() + req.quality_measurements.len() > SYNC_PUSH_MAX {
+        return Err(AppError::ValidationError(format!(
+            "At most {} records per sync upload",
+            SYNC_PUSH_MAX
+        )));
+    }
+
+    // Berbe prve, da bi merenja iz istog upload-a našla svoju berbu
+    let mut harvests = Vec::with_capacity(req.harvests.len());
+    for push in req.harvests {
+        harvests.push(sync_harvest(&state, &auth, push).await?);
+    }
+
+    let mut quality_measurements = Vec::with_capacity(req.quality_measurements.len());
+    for push in req.quality_measurements {
+        quality_measurements.push(sync_measurement(&state, &auth, push).await?);
+    }
+
+    Ok(Json(SyncPushResponse {
+        harvests,
+        quality_measurements,
+    }))
+}
+
+/// Feed promena posle kursora `since` (uključujući brisanja). Kao i liste berbi,
+/// radnik vidi samo svoje berbe i njihova merenja.
+pub async fn get_sync_changes(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Query(query): Query<SyncChangesQuery>,
+) -> Result<Json<SyncChangesResponse>, AppError> {
+    let limit = query.limit.unwrap_or(SYNC_PAGE_MAX).clamp(1, SYNC_PAGE_MAX);
+    let owner = match auth.claims.role {
+        UserRole::Worker => Some(auth.claims.user_id()?),
+        _ => None,
+    };
+
+    let watermark = state.sync_repo.watermark().await?;
+    let mut harvests = state
+        .sync_repo
+        .harvest_changes(query.since, watermark, limit + 1, query.vineyard_id, owner)
+        .await?;
+    let mut quality_measurements = state
+        .sync_repo
+        .quality_changes(query.since, watermark, limit + 1, query.vineyard_id, owner)
+        .await?;
+    let mut deleted = state
+        .sync_repo
+        .tombstones(query.since, watermark, limit + 1, query.vineyard_id, owner)
+        .await?;
+
+    let (next_since, has_more) = sync_page_cursor(
+        query.since,
+        limit as usize,
+        &[
+            &harvests.iter().map(|r| r.cursor()).collect::<Vec<_>>(),
+            &quality_measurements.iter().map(|r| r.cursor()).collect::<Vec<_>>(),
+            &deleted.iter().map(|r| r.cursor()).collect::<Vec<_>>(),
+        ],
+    );
+
+    harvests.retain(|r| r.cursor() <= next_since);
+    quality_measurements.retain(|r| r.cursor() <= next_since);
+    deleted.retain(|r| r.cursor() <= next_since);
+
+    Ok(Json(SyncChangesResponse {
+        harvests,
+        quality_measurements,
+        deleted,
+        next_since,
+        has_more,
+    }))
+}
+
+async fn sync_harvest(
+    state: &AppState,
+    auth: &AuthenticatedUser,
+    push: SyncHarvestPush,
+) -> Result<SyncPushResult, AppError> {
+    if let Err(e) = push.fields.validate() {
+        return Ok(SyncPushResult::rejected(push.id, e.to_string()));
+    }
+
+    let user_id = auth.claims.user_id()?;
+    let current = state.sync_repo.find_harvest(push.id).await?;
+    let same = current.as_ref().is_some_and(|c| c.matches(&push.fields));
+
+    let decision = decide_sync(
+        current.as_ref().map(|c| c.sync_version),
+        same,
+        push.base_version,
+        true,
+    );
+
+    let current = match (decision, current) {
+        (SyncDecision::Insert, _) => {
+            if auth.claims.role == UserRole::Worker {
+                return Ok(SyncPushResult::rejected(push.id, "Workers cannot create harvests"));
+            }
+
+            let parcel = match state
+                .vineyard_client
+                .resolve_parcel(auth, push.fields.vineyard_id, push.fields.parcel_id)
+                .await
+            {
+                Ok(parcel) => parcel,
+                Err(e) => return rejection(push.id, e),
+            };
+
+            return match state
+                .sync_repo
+                .insert_harvest(push.id, user_id, push.fields, &parcel)
+                .await
+            {
+                Ok(record) => Ok(outcome(push.id, SyncOutcome::Created, record.sync_version)),
+                // Isti id je upisan u međuvremenu (paralelan upload)
+                Err(AppError::Conflict(_)) => {
+                    let record = state.sync_repo.find_harvest(push.id).await?;
+                    Ok(conflict(push.id, record))
+                }
+                Err(e) => Err(e),
+            };
+        }
+        (SyncDecision::Unchanged, Some(current)) => {
+            return Ok(outcome(push.id, SyncOutcome::Unchanged, current.sync_version));
+        }
+        (SyncDecision::Update, Some(current)) => current,
+        (_, current) => return Ok(conflict(push.id, current)),
+    };
+
+    if auth.claims.role == UserRole::Worker {
+        return Ok(SyncPushResult::rejected(push.id, "Workers cannot update harvests"));
+    }
+    if auth.claims.role != UserRole::Admin && current.created_by != user_id {
+        return Ok(SyncPushResult::rejected(push.id, "Access denied"));
+    }
+    if push.fields.total_weight_kg != current.total_weight_kg
+        && state.load_repo.count_by_harvest(push.id).await? > 0
+    {
+        return Ok(SyncPushResult::rejected(
+            push.id,
+            "Total weight is derived from recorded loads",
+        ));
+    }
+
+    let parcel = match state
+        .vineyard_client
+        .resolve_parcel(auth, push.fields.vineyard_id, push.fields.parcel_id)
+        .await
+    {
+        Ok(parcel) => parcel,
+        Err(e) => return rejection(push.id, e),
+    };
+
+    let updated = state
+        .sync_repo
+        .update_harvest(push.id, current.sync_version, push.fields, &parcel)
+        .await?;
+
+    let Some(updated) = updated else {
+        let record = state.sync_repo.find_harvest(push.id).await?;
+        return Ok(conflict(push.id, record));
+    };
+
+    Ok(outcome(push.id, SyncOutcome::Updated, updated.sync_version))
+}
+
+async fn sync_measurement(
+    state: &AppState,
+    auth: &AuthenticatedUser,
+    push: SyncQualityPush,
+) -> Result<SyncPushResult, AppError> {
+    if let Err(e) = push.fields.validate() {
+        return Ok(SyncPushResult::rejected(push.id, e.to_string()));
+    }
+    if push.measured_at.is_some_and(|t| t > Utc::now()) {
+        return Ok(SyncPushResult::rejected(
+            push.id,
+            "Measurement time cannot be in the future",
+        ));
+    }
+
+    let current = state.sync_repo.find_measurement(push.id).await?;
+    let same = current
+        .as_ref()
+        .is_some_and(|c| c.matches(push.harvest_id, &push.fields, push.measured_at));
+
+    match (decide_sync(current.as_ref().map(|c| c.sync_version), same, None, false), current) {
+        (SyncDecision::Insert, _) => {}
+        (SyncDecision::Unchanged, Some(current)) => {
+            return Ok(outcome(push.id, SyncOutcome::Unchanged, current.sync_version));
+        }
+        (_, current) => return Ok(conflict(push.id, current)),
+    }
+
+    let harvest = match state.harvest_repo.find_by_id(push.harvest_id).await {
+        Ok(harvest) => harvest,
+        Err(e) => return rejection(push.id, e),
+    };
+
+    let user_id = auth.claims.user_id()?;
+    if auth.claims.role == UserRole::Worker && harvest.created_by != user_id {
+        return Ok(SyncPushResult::rejected(push.id, "Access denied"));
+    }
+
+    let inserted = state
+        .sync_repo
+        .insert_measurement(push.id, push.harvest_id, push.fields, push.measured_at)
+        .await?;
+
+    match inserted {
+        Some(record) => Ok(outcome(push.id, SyncOutcome::Created, record.sync_version)),
+        None => {
+            let record = state.sync_repo.find_measurement(push.id).await?;
+            Ok(conflict(push.id, record))
+        }
+    }
+}
+
+fn outcome(id: Uuid, outcome: SyncOutcome, version: i64) -> SyncPushResult {
+    SyncPushResult {
+        id,
+        outcome,
+        version: Some(version),
+        message: None,
+        server_record: None,
+    }
+}
+
+fn conflict<T: serde::Serialize>(id: Uuid, record: Option<T>) -> SyncPushResult {
+    SyncPushResult {
+        id,
+        outcome: SyncOutcome::Conflict,
+        version: None,
+        message: Some("Record was changed on the server".to_string()),
+        server_record: record.and_then(|r| serde_json::to_value(r).ok()),
+    }
+}
+
+/// Greške koje se odnose na sam zapis idu u rezultat; ostale prekidaju upload
+fn rejection(id: Uuid, error: AppError) -> Result<SyncPushResult, AppError> {
+    match error {
+        AppError::NotFound(m)
+        | AppError::Forbidden(m)
+        | AppError::ValidationError(m)
+        | AppError::Conflict(m) => Ok(SyncPushResult::rejected(id, m)),
+        e => Err(e),
+    }
+}
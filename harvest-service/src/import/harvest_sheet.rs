@@ -0,0 +1,61 @@
+use std::io::Cursor;
+
+use calamine::{Data, Reader, Xlsx};
+
+/// Zaglavlje i redovi tabele kao tekst
+pub type SheetTable = (Vec<String>, Vec<Vec<String>>);
+
+/// CSV iz Excel-a: BOM se preskače, a separator može biti i ';' (lokalna podešavanja)
+pub fn read_csv_table(bytes: &[u8]) -> Result<SheetTable, Box<dyn std::error::Error>> {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+
+    let header_line = bytes.split(|b| *b == b'\n').next().unwrap_or_default();
+    let delimiter = if header_line.contains(&b';') && !header_line.contains(&b',') {
+        b';'
+    } else {
+        b','
+    };
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_reader(bytes);
+
+    let headers = reader.headers()?.iter().map(str::to_string).collect();
+    let rows = reader
+        .records()
+        .map(|record| record.map(|r| r.iter().map(str::to_string).collect()))
+        .collect::<Result<Vec<Vec<String>>, _>>()?;
+
+    Ok((headers, rows))
+}
+
+/// Prvi list XLSX fajla; datumi se pretvaraju u ISO zapis
+pub fn read_xlsx_table(bytes: &[u8]) -> Result<SheetTable, Box<dyn std::error::Error>> {
+    let mut workbook: Xlsx<_> = Xlsx::new(Cursor::new(bytes))?;
+    let range = workbook
+        .worksheet_range_at(0)
+        .ok_or("Workbook has no sheets")??;
+
+    let mut rows = range.rows().map(|row| row.iter().map(cell_text).collect::<Vec<String>>());
+    let headers = rows.next().unwrap_or_default();
+
+    Ok((headers, rows.collect()))
+}
+
+fn cell_text(cell: &Data) -> String {
+    match cell {
+        Data::Empty => String::new(),
+        Data::String(s) => s.clone(),
+        Data::Float(f) => f.to_string(),
+        Data::Int(i) => i.to_string(),
+        Data::Bool(b) => b.to_string(),
+        Data::DateTime(d) => match d.as_datetime() {
+            Some(t) if t.time() == chrono::NaiveTime::MIN => t.format("%Y-%m-%d").to_string(),
+            Some(t) => t.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            None => d.to_string(),
+        },
+        Data::DateTimeIso(s) | Data::DurationIso(s) => s.clone(),
+        Data::Error(e) => format!("#{:?}", e),
+    }
+}
@@ -0,0 +1,3 @@
+pub mod harvest_sheet;
+
+pub use harvest_sheet::{read_csv_table, read_xlsx_table};
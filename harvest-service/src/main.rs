@@ -1,12 +1,16 @@
+mod clients;
 mod config;
 mod db;
 mod error;
+mod export;
 mod extractors;
 mod handlers;
+mod import;
 mod middleware;
 mod models;
 mod routes;
 mod pdf;
+mod storage;
 
 use std::net::SocketAddr;
 
@@ -14,9 +18,14 @@ use tower_http::{cors::{Any, CorsLayer}, trace::TraceLayer};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use crate::{
+    clients::{FermentationClient, VineyardClient},
     config::Settings,
-    db::{create_pool, run_migrations, HarvestRepository},
+    db::{
+        create_pool, run_migrations, AttachmentRepository, ContractRepository, CrewRepository, GradingRepository, HarvestRepository, LoadRepository,
+        MaturityRepository, PlanningRepository, SyncRepository,
+    },
     handlers::AppState,
+    storage::AttachmentStorage,
 };
 
 #[tokio::main]
@@ -38,10 +47,37 @@ async fn main() -> anyhow::Result<()> {
     run_migrations(&pool).await?;
     tracing::info!("Migrations completed");
 
-    let harvest_repo = HarvestRepository::new(pool);
+    let harvest_repo = HarvestRepository::new(pool.clone());
+    let graded = harvest_repo.regrade_ungraded().await?;
+    if graded > 0 {
+        tracing::info!("Graded {} harvests recorded before quality grading", graded);
+    }
+    let load_repo = LoadRepository::new(pool.clone());
+    let crew_repo = CrewRepository::new(pool.clone());
+    let grading_repo = GradingRepository::new(pool.clone());
+    let maturity_repo = MaturityRepository::new(pool.clone());
+    let planning_repo = PlanningRepository::new(pool.clone());
+    let contract_repo = ContractRepository::new(pool.clone());
+    let sync_repo = SyncRepository::new(pool.clone());
+    let attachment_repo = AttachmentRepository::new(pool);
+    let vineyard_client = VineyardClient::new(&settings.vineyard_service_url)?;
+    let fermentation_client = FermentationClient::new(&settings.fermentation_service_url)?;
+    let storage = AttachmentStorage::from_settings(&settings)?;
+    tracing::info!("Attachment storage: {}", settings.attachment_storage);
 
     let app_state = AppState {
         harvest_repo,
+        load_repo,
+        crew_repo,
+        grading_repo,
+        maturity_repo,
+        planning_repo,
+        contract_repo,
+        sync_repo,
+        attachment_repo,
+        vineyard_client,
+        fermentation_client,
+        storage,
         settings: settings.clone(),
     };
 
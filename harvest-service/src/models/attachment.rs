@@ -0,0 +1,256 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+// ============== Enums ==============
+
+/// Čemu je prilog pridružen (parcele, batch-evi i tankovi su u drugim servisima)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "attachment_entity", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum AttachmentEntity {
+    Harvest,
+    QualityMeasurement,
+    Parcel,
+    Batch,
+    Tank,
+}
+
+impl AttachmentEntity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttachmentEntity::Harvest => "harvest",
+            AttachmentEntity::QualityMeasurement => "quality_measurement",
+            AttachmentEntity::Parcel => "parcel",
+            AttachmentEntity::Batch => "batch",
+            AttachmentEntity::Tank => "tank",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "harvest" => Some(AttachmentEntity::Harvest),
+            "quality_measurement" => Some(AttachmentEntity::QualityMeasurement),
+            "parcel" => Some(AttachmentEntity::Parcel),
+            "batch" => Some(AttachmentEntity::Batch),
+            "tank" => Some(AttachmentEntity::Tank),
+            _ => None,
+        }
+    }
+
+    /// Pristup parceli proverava vineyard-service preko njenog vinograda, pa ga
+    /// zahtev za parcelu mora navesti; ostali zapisi ga ne čuvaju
+    pub fn vineyard_scope(&self, vineyard_id: Option<Uuid>) -> Result<Option<Uuid>, String> {
+        match self {
+            AttachmentEntity::Parcel => vineyard_id
+                .map(Some)
+                .ok_or_else(|| "vineyard_id is required for parcel attachments".to_string()),
+            _ => Ok(None),
+        }
+    }
+}
+
+// ============== Attachment ==============
+
+#[derive(Debug, Clone, FromRow)]
+pub struct Attachment {
+    pub id: Uuid,
+    pub entity_type: AttachmentEntity,
+    pub entity_id: Uuid,
+    pub vineyard_id: Option<Uuid>,
+    pub file_name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub storage_key: String,
+    pub thumbnail_key: Option<String>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub description: Option<String>,
+    pub uploaded_by: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Podaci za upis novog priloga (fajl je već u storage-u)
+#[derive(Debug)]
+pub struct NewAttachment {
+    pub id: Uuid,
+    pub entity_type: AttachmentEntity,
+    pub entity_id: Uuid,
+    pub vineyard_id: Option<Uuid>,
+    pub file_name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub storage_key: String,
+    pub thumbnail_key: Option<String>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AttachmentQuery {
+    pub entity_type: AttachmentEntity,
+    pub entity_id: Uuid,
+    /// Obavezan za parcele
+    pub vineyard_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AttachmentResponse {
+    pub id: Uuid,
+    pub entity_type: AttachmentEntity,
+    pub entity_id: Uuid,
+    pub vineyard_id: Option<Uuid>,
+    pub file_name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub has_thumbnail: bool,
+    pub description: Option<String>,
+    pub uploaded_by: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Attachment> for AttachmentResponse {
+    fn from(a: Attachment) -> Self {
+        AttachmentResponse {
+            id: a.id,
+            entity_type: a.entity_type,
+            entity_id: a.entity_id,
+            vineyard_id: a.vineyard_id,
+            file_name: a.file_name,
+            content_type: a.content_type,
+            size_bytes: a.size_bytes,
+            width: a.width,
+            height: a.height,
+            has_thumbnail: a.thumbnail_key.is_some(),
+            description: a.description,
+            uploaded_by: a.uploaded_by,
+            created_at: a.created_at,
+        }
+    }
+}
+
+// ============== Provera sadržaja ==============
+
+/// Dozvoljeni tipovi fajlova; tip se određuje iz sadržaja, ne iz imena ili zaglavlja
+pub const ATTACHMENT_TYPES: &[(&str, &str)] = &[
+    ("image/jpeg", "jpg"),
+    ("image/png", "png"),
+    ("image/webp", "webp"),
+    ("application/pdf", "pdf"),
+];
+
+/// Prepoznaj tip fajla po potpisu na početku sadržaja
+pub fn detect_content_type(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if bytes.starts_with(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]) {
+        Some("image/png")
+    } else if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else if bytes.starts_with(b"%PDF-") {
+        Some("application/pdf")
+    } else {
+        None
+    }
+}
+
+pub fn is_image_type(content_type: &str) -> bool {
+    content_type.starts_with("image/")
+}
+
+/// Ime fajla bez putanje i znakova koji bi pokvarili Content-Disposition zaglavlje
+pub fn sanitize_file_name(name: &str, content_type: &str) -> String {
+    let base = name.rsplit(['/', '\\']).next().unwrap_or_default();
+    let cleaned: String = base
+        .chars()
+        .filter(|c| !c.is_control() && *c != '"')
+        .collect::<String>()
+        .trim()
+        .chars()
+        .take(200)
+        .collect();
+
+    if cleaned.is_empty() || cleaned.chars().all(|c| c == '.') {
+        let extension = ATTACHMENT_TYPES
+            .iter()
+            .find(|(t, _)| *t == content_type)
+            .map(|(_, ext)| *ext)
+            .unwrap_or("bin");
+        format!("attachment.{}", extension)
+    } else {
+        cleaned
+    }
+}
+
+/// `inline` Content-Disposition; ASCII ime za stare klijente i UTF-8 ime (RFC 5987)
+pub fn content_disposition(file_name: &str) -> String {
+    let ascii: String = file_name
+        .chars()
+        .map(|c| if c.is_ascii() && !c.is_ascii_control() && c != '"' && c != '\\' { c } else { '_' })
+        .collect();
+
+    let encoded: String = file_name
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect();
+
+    format!("inline; filename=\"{}\"; filename*=UTF-8''{}", ascii, encoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_content_type_by_signature() {
+        assert_eq!(detect_content_type(&[0xFF, 0xD8, 0xFF, 0xE0, 0x00]), Some("image/jpeg"));
+        assert_eq!(
+            detect_content_type(b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR"),
+            Some("image/png")
+        );
+        assert_eq!(detect_content_type(b"RIFF\x24\x00\x00\x00WEBPVP8 "), Some("image/webp"));
+        assert_eq!(detect_content_type(b"%PDF-1.7\n"), Some("application/pdf"));
+        // Izvršni fajl preimenovan u .jpg se ne prihvata
+        assert_eq!(detect_content_type(b"MZ\x90\x00"), None);
+        assert_eq!(detect_content_type(b""), None);
+    }
+
+    #[test]
+    fn test_parcel_attachments_need_vineyard() {
+        let vineyard_id = Uuid::new_v4();
+
+        assert_eq!(
+            AttachmentEntity::Parcel.vineyard_scope(Some(vineyard_id)),
+            Ok(Some(vineyard_id))
+        );
+        // Bez vinograda se pristup parceli ne može proveriti
+        assert!(AttachmentEntity::Parcel.vineyard_scope(None).is_err());
+        assert_eq!(AttachmentEntity::Harvest.vineyard_scope(Some(vineyard_id)), Ok(None));
+    }
+
+    #[test]
+    fn test_sanitize_file_name() {
+        assert_eq!(sanitize_file_name("../../etc/passwd", "application/pdf"), "passwd");
+        assert_eq!(sanitize_file_name("C:\\Users\\ana\\grozd 1.jpg", "image/jpeg"), "grozd 1.jpg");
+        assert_eq!(sanitize_file_name("lab\"cert\n.pdf", "application/pdf"), "labcert.pdf");
+        assert_eq!(sanitize_file_name("", "image/png"), "attachment.png");
+        assert_eq!(sanitize_file_name("..", "image/webp"), "attachment.webp");
+    }
+
+    #[test]
+    fn test_content_disposition_encodes_non_ascii_names() {
+        assert_eq!(
+            content_disposition("grožđe 1.jpg"),
+            "inline; filename=\"gro__e 1.jpg\"; filename*=UTF-8''gro%C5%BE%C4%91e%201.jpg"
+        );
+    }
+}
@@ -0,0 +1,668 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+use crate::models::QualityGrade;
+
+/// Status ugovora o otkupu
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "contract_status", rename_all = "lowercase")]
+pub enum ContractStatus {
+    #[serde(rename = "active")]
+    Active,
+    #[serde(rename = "closed")]
+    Closed,
+}
+
+/// Proizvođač od koga se otkupljuje grožđe
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Grower {
+    pub id: Uuid,
+    pub name: String,
+    pub tax_id: Option<String>,
+    pub address: Option<String>,
+    pub phone: Option<String>,
+    pub email: Option<String>,
+    pub bank_account: Option<String>,
+    pub notes: Option<String>,
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Ugovor o otkupu grožđa za sezonu
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct GrapeContract {
+    pub id: Uuid,
+    pub grower_id: Uuid,
+    pub contract_number: String,
+    pub season: i32,
+    pub grape_variety: Option<String>,
+    pub status: ContractStatus,
+    // Cena
+    pub base_price_per_kg: f64,
+    // Korekcija po Brix-u (po kg, po stepenu iznad / ispod ciljnog)
+    pub target_brix: Option<f64>,
+    pub brix_bonus_per_point: Option<f64>,
+    pub brix_penalty_per_point: Option<f64>,
+    // Korekcija po klasi (% osnovne cene, negativno = umanjenje)
+    pub grade_a_adjustment_pct: f64,
+    pub grade_b_adjustment_pct: f64,
+    pub grade_c_adjustment_pct: f64,
+    // Ugovorena količina
+    pub max_tonnage_kg: Option<f64>,
+    pub over_cap_price_per_kg: Option<f64>, // None = količina preko ugovorene se ne plaća
+    // Meta
+    pub signed_on: Option<NaiveDate>,
+    pub notes: Option<String>,
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Isplata proizvođaču
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct GrowerPayment {
+    pub id: Uuid,
+    pub grower_id: Uuid,
+    pub season: i32,
+    pub amount: f64,
+    pub paid_on: NaiveDate,
+    pub reference: Option<String>,
+    pub notes: Option<String>,
+    pub recorded_by: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Isporuka po ugovoru: tura sa vage, ili berba bez tura (ukupna težina)
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ContractDelivery {
+    pub contract_id: Uuid,
+    pub harvest_id: Uuid,
+    pub load_id: Option<Uuid>,
+    pub ticket_number: Option<String>,
+    pub delivered_at: DateTime<Utc>,
+    pub weight_kg: f64,
+    pub brix: Option<f64>, // sa vage, inače prosek merenja berbe
+    pub quality_grade: Option<QualityGrade>,
+}
+
+/// Obračun isporuke
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct DeliveryPayable {
+    pub contract_id: Uuid,
+    pub harvest_id: Uuid,
+    pub load_id: Option<Uuid>,
+    pub ticket_number: Option<String>,
+    pub delivered_at: DateTime<Utc>,
+    pub weight_kg: f64,
+    pub brix: Option<f64>,
+    pub quality_grade: Option<QualityGrade>,
+    pub base_price_per_kg: f64,
+    pub brix_adjustment_per_kg: f64,
+    pub grade_adjustment_per_kg: f64,
+    pub price_per_kg: f64,
+    pub kg_within_cap: f64,
+    pub kg_over_cap: f64,
+    pub amount: f64,
+}
+
+fn round_cents(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+impl GrapeContract {
+    /// Ugovor posle izmene; uslovi se proveravaju na spojenom ugovoru, ne samo na izmeni
+    pub fn with_update(&self, req: UpdateContractRequest) -> Result<GrapeContract, ValidationError> {
+        fn term<T: Clone>(
+            current: &Option<T>,
+            new: Option<T>,
+            cleared: bool,
+        ) -> Result<Option<T>, ValidationError> {
+            match (new, cleared) {
+                (Some(_), true) => {
+                    let mut err = ValidationError::new("clear");
+                    err.message = Some("A term cannot be both set and cleared".into());
+                    Err(err)
+                }
+                (_, true) => Ok(None),
+                (Some(value), false) => Ok(Some(value)),
+                (None, false) => Ok(current.clone()),
+            }
+        }
+        let cleared = |t: ContractTerm| req.clear.contains(&t);
+
+        let mut contract = self.clone();
+        contract.target_brix = term(
+            &self.target_brix,
+            req.target_brix,
+            cleared(ContractTerm::TargetBrix),
+        )?;
+        contract.brix_bonus_per_point = term(
+            &self.brix_bonus_per_point,
+            req.brix_bonus_per_point,
+            cleared(ContractTerm::BrixBonusPerPoint),
+        )?;
+        contract.brix_penalty_per_point = term(
+            &self.brix_penalty_per_point,
+            req.brix_penalty_per_point,
+            cleared(ContractTerm::BrixPenaltyPerPoint),
+        )?;
+        contract.max_tonnage_kg = term(
+            &self.max_tonnage_kg,
+            req.max_tonnage_kg,
+            cleared(ContractTerm::MaxTonnageKg),
+        )?;
+        contract.over_cap_price_per_kg = term(
+            &self.over_cap_price_per_kg,
+            req.over_cap_price_per_kg,
+            cleared(ContractTerm::OverCapPricePerKg),
+        )?;
+        contract.notes = term(&self.notes, req.notes, cleared(ContractTerm::Notes))?;
+
+        if let Some(status) = req.status {
+            contract.status = status;
+        }
+        if let Some(price) = req.base_price_per_kg {
+            contract.base_price_per_kg = price;
+        }
+        if let Some(pct) = req.grade_a_adjustment_pct {
+            contract.grade_a_adjustment_pct = pct;
+        }
+        if let Some(pct) = req.grade_b_adjustment_pct {
+            contract.grade_b_adjustment_pct = pct;
+        }
+        if let Some(pct) = req.grade_c_adjustment_pct {
+            contract.grade_c_adjustment_pct = pct;
+        }
+
+        check_contract_terms(
+            contract.target_brix,
+            contract.brix_bonus_per_point,
+            contract.brix_penalty_per_point,
+            contract.max_tonnage_kg,
+            contract.over_cap_price_per_kg,
+        )?;
+
+        Ok(contract)
+    }
+
+    /// Cena po kg za isporuku, posle korekcija za Brix i klasu (ne ispod nule)
+    fn price_components(&self, brix: Option<f64>, grade: Option<QualityGrade>) -> (f64, f64, f64) {
+        let brix_adjustment = match (self.target_brix, brix) {
+            (Some(target), Some(brix)) if brix > target => {
+                (brix - target) * self.brix_bonus_per_point.unwrap_or(0.0)
+            }
+            (Some(target), Some(brix)) if brix < target => {
+                -(target - brix) * self.brix_penalty_per_point.unwrap_or(0.0)
+            }
+            _ => 0.0,
+        };
+
+        let grade_pct = match grade {
+            Some(QualityGrade::A) => self.grade_a_adjustment_pct,
+            Some(QualityGrade::B) => self.grade_b_adjustment_pct,
+            Some(QualityGrade::C) => self.grade_c_adjustment_pct,
+            None => 0.0,
+        };
+        let grade_adjustment = self.base_price_per_kg * grade_pct / 100.0;
+
+        let price = (self.base_price_per_kg + brix_adjustment + grade_adjustment).max(0.0);
+
+        (brix_adjustment, grade_adjustment, price)
+    }
+
+    /// Obračun isporuka redom prijema; količina preko ugovorene plaća se po ceni za višak
+    pub fn price_deliveries(&self, deliveries: &[ContractDelivery]) -> Vec<DeliveryPayable> {
+        let mut delivered_kg = 0.0;
+
+        deliveries
+            .iter()
+            .map(|d| {
+                let cap_left = self
+                    .max_tonnage_kg
+                    .map_or(f64::INFINITY, |cap| (cap - delivered_kg).max(0.0));
+                let kg_within_cap = d.weight_kg.min(cap_left);
+                let kg_over_cap = d.weight_kg - kg_within_cap;
+                delivered_kg += d.weight_kg;
+
+                let (brix_adjustment, grade_adjustment, price) =
+                    self.price_components(d.brix, d.quality_grade);
+                let amount = kg_within_cap * price
+                    + kg_over_cap * self.over_cap_price_per_kg.unwrap_or(0.0);
+
+                DeliveryPayable {
+                    contract_id: d.contract_id,
+                    harvest_id: d.harvest_id,
+                    load_id: d.load_id,
+                    ticket_number: d.ticket_number.clone(),
+                    delivered_at: d.delivered_at,
+                    weight_kg: d.weight_kg,
+                    brix: d.brix,
+                    quality_grade: d.quality_grade,
+                    base_price_per_kg: self.base_price_per_kg,
+                    brix_adjustment_per_kg: round_cents(brix_adjustment),
+                    grade_adjustment_per_kg: round_cents(grade_adjustment),
+                    price_per_kg: round_cents(price),
+                    kg_within_cap,
+                    kg_over_cap,
+                    amount: round_cents(amount),
+                }
+            })
+            .collect()
+    }
+}
+
+// ============== Request structs ==============
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateGrowerRequest {
+    #[validate(length(min = 2, max = 255, message = "Name must be at least 2 characters"))]
+    pub name: String,
+
+    #[validate(length(max = 50))]
+    pub tax_id: Option<String>,
+
+    pub address: Option<String>,
+
+    #[validate(length(max = 50))]
+    pub phone: Option<String>,
+
+    #[validate(email)]
+    pub email: Option<String>,
+
+    #[validate(length(max = 100))]
+    pub bank_account: Option<String>,
+
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateGrowerRequest {
+    #[validate(length(min = 2, max = 255))]
+    pub name: Option<String>,
+
+    #[validate(length(max = 50))]
+    pub tax_id: Option<String>,
+
+    pub address: Option<String>,
+
+    #[validate(length(max = 50))]
+    pub phone: Option<String>,
+
+    #[validate(email)]
+    pub email: Option<String>,
+
+    #[validate(length(max = 100))]
+    pub bank_account: Option<String>,
+
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[validate(schema(function = "validate_contract_terms"))]
+pub struct CreateContractRequest {
+    pub grower_id: Uuid,
+
+    #[validate(length(min = 1, max = 50, message = "Contract number is required"))]
+    pub contract_number: String,
+
+    #[validate(range(min = 2000, max = 2100, message = "Season out of range"))]
+    pub season: i32,
+
+    #[validate(length(max = 255))]
+    pub grape_variety: Option<String>,
+
+    #[validate(range(min = 0.0, message = "Price must be positive"))]
+    pub base_price_per_kg: f64,
+
+    #[validate(range(min = 0.0, max = 50.0, message = "Brix must be 0-50"))]
+    pub target_brix: Option<f64>,
+
+    #[validate(range(min = 0.0))]
+    pub brix_bonus_per_point: Option<f64>,
+
+    #[validate(range(min = 0.0))]
+    pub brix_penalty_per_point: Option<f64>,
+
+    #[validate(range(min = -100.0, max = 100.0, message = "Adjustment must be -100..100%"))]
+    #[serde(default)]
+    pub grade_a_adjustment_pct: f64,
+
+    #[validate(range(min = -100.0, max = 100.0, message = "Adjustment must be -100..100%"))]
+    #[serde(default)]
+    pub grade_b_adjustment_pct: f64,
+
+    #[validate(range(min = -100.0, max = 100.0, message = "Adjustment must be -100..100%"))]
+    #[serde(default)]
+    pub grade_c_adjustment_pct: f64,
+
+    #[validate(range(min = 1.0, message = "Tonnage cap must be positive"))]
+    pub max_tonnage_kg: Option<f64>,
+
+    #[validate(range(min = 0.0, message = "Price must be positive"))]
+    pub over_cap_price_per_kg: Option<f64>,
+
+    pub signed_on: Option<NaiveDate>,
+    pub notes: Option<String>,
+}
+
+fn validate_contract_terms(req: &CreateContractRequest) -> Result<(), ValidationError> {
+    check_contract_terms(
+        req.target_brix,
+        req.brix_bonus_per_point,
+        req.brix_penalty_per_point,
+        req.max_tonnage_kg,
+        req.over_cap_price_per_kg,
+    )
+}
+
+/// Korekcija po Brix-u traži ciljni Brix, cena za višak traži ugovorenu količinu
+fn check_contract_terms(
+    target_brix: Option<f64>,
+    brix_bonus_per_point: Option<f64>,
+    brix_penalty_per_point: Option<f64>,
+    max_tonnage_kg: Option<f64>,
+    over_cap_price_per_kg: Option<f64>,
+) -> Result<(), ValidationError> {
+    if (brix_bonus_per_point.is_some() || brix_penalty_per_point.is_some())
+        && target_brix.is_none()
+    {
+        let mut err = ValidationError::new("target_brix");
+        err.message = Some("Brix bonus/penalty requires a target Brix".into());
+        return Err(err);
+    }
+    if over_cap_price_per_kg.is_some() && max_tonnage_kg.is_none() {
+        let mut err = ValidationError::new("max_tonnage_kg");
+        err.message = Some("Over-cap price requires a tonnage cap".into());
+        return Err(err);
+    }
+    Ok(())
+}
+
+/// Uslov ugovora koji se izmenom može obrisati
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ContractTerm {
+    TargetBrix,
+    BrixBonusPerPoint,
+    BrixPenaltyPerPoint,
+    MaxTonnageKg,
+    OverCapPricePerKg,
+    Notes,
+}
+
+/// Izmena ugovora: ne menjaju se proizvođač ni sezona
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateContractRequest {
+    pub status: Option<ContractStatus>,
+
+    #[validate(range(min = 0.0, message = "Price must be positive"))]
+    pub base_price_per_kg: Option<f64>,
+
+    #[validate(range(min = 0.0, max = 50.0, message = "Brix must be 0-50"))]
+    pub target_brix: Option<f64>,
+
+    #[validate(range(min = 0.0))]
+    pub brix_bonus_per_point: Option<f64>,
+
+    #[validate(range(min = 0.0))]
+    pub brix_penalty_per_point: Option<f64>,
+
+    #[validate(range(min = -100.0, max = 100.0))]
+    pub grade_a_adjustment_pct: Option<f64>,
+
+    #[validate(range(min = -100.0, max = 100.0))]
+    pub grade_b_adjustment_pct: Option<f64>,
+
+    #[validate(range(min = -100.0, max = 100.0))]
+    pub grade_c_adjustment_pct: Option<f64>,
+
+    #[validate(range(min = 1.0))]
+    pub max_tonnage_kg: Option<f64>,
+
+    #[validate(range(min = 0.0))]
+    pub over_cap_price_per_kg: Option<f64>,
+
+    pub notes: Option<String>,
+
+    /// Uslovi koji se brišu; izostavljeno polje ostaje kakvo jeste
+    #[serde(default)]
+    pub clear: Vec<ContractTerm>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ContractQuery {
+    pub grower_id: Option<Uuid>,
+    pub season: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LinkContractRequest {
+    /// None = ukloni vezu sa ugovorom
+    pub contract_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct RecordGrowerPaymentRequest {
+    #[validate(range(min = 2000, max = 2100, message = "Season out of range"))]
+    pub season: i32,
+
+    #[validate(range(min = 0.01, message = "Amount must be positive"))]
+    pub amount: f64,
+
+    pub paid_on: NaiveDate,
+
+    #[validate(length(max = 100))]
+    pub reference: Option<String>,
+
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SettlementQuery {
+    pub season: i32,
+}
+
+// ============== Response structs ==============
+
+/// Obračun ugovora u okviru izveštaja
+#[derive(Debug, Serialize)]
+pub struct ContractSettlement {
+    pub contract: GrapeContract,
+    pub deliveries: Vec<DeliveryPayable>,
+    pub total_kg: f64,
+    pub total_amount: f64,
+}
+
+/// Konačni obračun proizvođača za sezonu
+#[derive(Debug, Serialize)]
+pub struct GrowerSettlement {
+    pub grower: Grower,
+    pub season: i32,
+    pub contracts: Vec<ContractSettlement>,
+    pub payments: Vec<GrowerPayment>,
+    pub total_kg: f64,
+    pub total_payable: f64,
+    pub total_paid: f64,
+    pub balance: f64,
+}
+
+impl GrowerSettlement {
+    pub fn new(
+        grower: Grower,
+        season: i32,
+        contracts: Vec<ContractSettlement>,
+        payments: Vec<GrowerPayment>,
+    ) -> Self {
+        let total_kg = contracts.iter().map(|c| c.total_kg).sum();
+        let total_payable = round_cents(contracts.iter().map(|c| c.total_amount).sum());
+        let total_paid = round_cents(payments.iter().map(|p| p.amount).sum());
+
+        GrowerSettlement {
+            grower,
+            season,
+            contracts,
+            payments,
+            total_kg,
+            total_payable,
+            total_paid,
+            balance: round_cents(total_payable - total_paid),
+        }
+    }
+}
+
+impl ContractSettlement {
+    pub fn new(contract: GrapeContract, deliveries: Vec<DeliveryPayable>) -> Self {
+        let total_kg = deliveries.iter().map(|d| d.weight_kg).sum();
+        let total_amount = round_cents(deliveries.iter().map(|d| d.amount).sum());
+
+        ContractSettlement {
+            contract,
+            deliveries,
+            total_kg,
+            total_amount,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contract() -> GrapeContract {
+        GrapeContract {
+            id: Uuid::nil(),
+            grower_id: Uuid::nil(),
+            contract_number: "K-1".to_string(),
+            season: 2026,
+            grape_variety: None,
+            status: ContractStatus::Active,
+            base_price_per_kg: 50.0,
+            target_brix: Some(22.0),
+            brix_bonus_per_point: Some(2.0),
+            brix_penalty_per_point: Some(3.0),
+            grade_a_adjustment_pct: 10.0,
+            grade_b_adjustment_pct: 0.0,
+            grade_c_adjustment_pct: -20.0,
+            max_tonnage_kg: Some(1500.0),
+            over_cap_price_per_kg: Some(20.0),
+            signed_on: None,
+            notes: None,
+            created_by: Uuid::nil(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn delivery(kg: f64, brix: Option<f64>, grade: Option<QualityGrade>) -> ContractDelivery {
+        ContractDelivery {
+            contract_id: Uuid::nil(),
+            harvest_id: Uuid::nil(),
+            load_id: None,
+            ticket_number: None,
+            delivered_at: Utc::now(),
+            weight_kg: kg,
+            brix,
+            quality_grade: grade,
+        }
+    }
+
+    #[test]
+    fn test_price_with_brix_and_grade_adjustments() {
+        let payables = contract().price_deliveries(&[
+            // 50 + 2*2 (Brix 24) + 5 (klasa A) = 59
+            delivery(1000.0, Some(24.0), Some(QualityGrade::A)),
+        ]);
+
+        assert_eq!(payables[0].price_per_kg, 59.0);
+        assert_eq!(payables[0].amount, 59000.0);
+    }
+
+    #[test]
+    fn test_tonnage_cap_applies_over_cap_price() {
+        let payables = contract().price_deliveries(&[
+            delivery(1000.0, None, None),
+            // 500 kg u okviru ugovora, 500 kg po ceni za višak
+            delivery(1000.0, None, None),
+        ]);
+
+        assert_eq!(payables[1].kg_within_cap, 500.0);
+        assert_eq!(payables[1].kg_over_cap, 500.0);
+        assert_eq!(payables[1].amount, 500.0 * 50.0 + 500.0 * 20.0);
+    }
+
+    fn update() -> UpdateContractRequest {
+        UpdateContractRequest {
+            status: None,
+            base_price_per_kg: None,
+            target_brix: None,
+            brix_bonus_per_point: None,
+            brix_penalty_per_point: None,
+            grade_a_adjustment_pct: None,
+            grade_b_adjustment_pct: None,
+            grade_c_adjustment_pct: None,
+            max_tonnage_kg: None,
+            over_cap_price_per_kg: None,
+            notes: None,
+            clear: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_update_validates_merged_terms() {
+        // Brisanje ciljnog Brix-a ostavlja korekcije bez osnova
+        let mut req = update();
+        req.clear = vec![ContractTerm::TargetBrix];
+        assert!(contract().with_update(req).is_err());
+
+        // Zajedno sa korekcijama je u redu
+        let mut req = update();
+        req.clear = vec![
+            ContractTerm::TargetBrix,
+            ContractTerm::BrixBonusPerPoint,
+            ContractTerm::BrixPenaltyPerPoint,
+        ];
+        let updated = contract().with_update(req).unwrap();
+        assert_eq!(updated.target_brix, None);
+        assert_eq!(updated.brix_bonus_per_point, None);
+
+        // Cena za višak na ugovoru bez količine
+        let mut c = contract();
+        c.max_tonnage_kg = None;
+        c.over_cap_price_per_kg = None;
+        let mut req = update();
+        req.over_cap_price_per_kg = Some(10.0);
+        assert!(c.with_update(req).is_err());
+    }
+
+    #[test]
+    fn test_update_keeps_omitted_terms() {
+        let mut req = update();
+        req.base_price_per_kg = Some(60.0);
+        let updated = contract().with_update(req).unwrap();
+
+        assert_eq!(updated.base_price_per_kg, 60.0);
+        assert_eq!(updated.target_brix, Some(22.0));
+        assert_eq!(updated.max_tonnage_kg, Some(1500.0));
+
+        let mut req = update();
+        req.target_brix = Some(21.0);
+        req.clear = vec![ContractTerm::TargetBrix];
+        assert!(contract().with_update(req).is_err());
+    }
+
+    #[test]
+    fn test_penalties_never_make_price_negative() {
+        let mut c = contract();
+        c.brix_penalty_per_point = Some(100.0);
+
+        // 50 - 3*100 - 10 → 0
+        let payables = c.price_deliveries(&[delivery(100.0, Some(19.0), Some(QualityGrade::C))]);
+
+        assert_eq!(payables[0].price_per_kg, 0.0);
+        assert_eq!(payables[0].amount, 0.0);
+    }
+}
@@ -0,0 +1,228 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Osnov obračuna zarade berača
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "pay_basis", rename_all = "snake_case")]
+pub enum PayBasis {
+    #[serde(rename = "hourly")]
+    Hourly,
+    #[serde(rename = "per_kg")]
+    PerKg,
+    #[serde(rename = "per_bin")]
+    PerBin,
+}
+
+impl std::fmt::Display for PayBasis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PayBasis::Hourly => write!(f, "hourly"),
+            PayBasis::PerKg => write!(f, "per_kg"),
+            PayBasis::PerBin => write!(f, "per_bin"),
+        }
+    }
+}
+
+/// Ekipa berača
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PickerCrew {
+    pub id: Uuid,
+    pub name: String,
+    pub leader_name: Option<String>,
+    pub notes: Option<String>,
+    pub daily_capacity_kg: Option<f64>, // koliko ekipa obere dnevno (za planiranje)
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Berač sa tarifama
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Picker {
+    pub id: Uuid,
+    pub crew_id: Option<Uuid>,
+    pub full_name: String,
+    pub employee_code: Option<String>,
+    pub hourly_rate: Option<f64>,
+    pub rate_per_kg: Option<f64>,
+    pub rate_per_bin: Option<f64>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Picker {
+    /// Tarifa za dati osnov obračuna
+    pub fn rate_for(&self, basis: PayBasis) -> Option<f64> {
+        match basis {
+            PayBasis::Hourly => self.hourly_rate,
+            PayBasis::PerKg => self.rate_per_kg,
+            PayBasis::PerBin => self.rate_per_bin,
+        }
+    }
+}
+
+/// Rad berača na berbi (sati, kg, kontejneri i obračunata zarada)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PickerWorkEntry {
+    pub id: Uuid,
+    pub harvest_id: Uuid,
+    pub picker_id: Uuid,
+    pub work_date: NaiveDate,
+    pub hours_worked: f64,
+    pub kg_picked: f64,
+    pub bins_picked: i32,
+    pub pay_basis: PayBasis,
+    pub rate_applied: f64, // tarifa u trenutku unosa
+    pub amount: f64,
+    pub notes: Option<String>,
+    pub recorded_by: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Iznos za isplatu, zaokružen na 2 decimale
+pub fn calculate_pay(basis: PayBasis, rate: f64, hours: f64, kg: f64, bins: i32) -> f64 {
+    let amount = match basis {
+        PayBasis::Hourly => hours * rate,
+        PayBasis::PerKg => kg * rate,
+        PayBasis::PerBin => f64::from(bins) * rate,
+    };
+    (amount * 100.0).round() / 100.0
+}
+
+/// Red obračuna po beraču (za CSV izvoz)
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct PayrollRow {
+    pub picker_id: Uuid,
+    pub full_name: String,
+    pub employee_code: Option<String>,
+    pub crew_name: Option<String>,
+    pub days_worked: i64,
+    pub hours_worked: f64,
+    pub kg_picked: f64,
+    pub bins_picked: i64,
+    pub amount: f64,
+}
+
+// ============== Request structs ==============
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateCrewRequest {
+    #[validate(length(min = 2, max = 255, message = "Name must be at least 2 characters"))]
+    pub name: String,
+
+    #[validate(length(max = 255))]
+    pub leader_name: Option<String>,
+
+    pub notes: Option<String>,
+
+    #[validate(range(min = 1.0, message = "Daily capacity must be positive"))]
+    pub daily_capacity_kg: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateCrewRequest {
+    #[validate(length(min = 2, max = 255))]
+    pub name: Option<String>,
+
+    #[validate(length(max = 255))]
+    pub leader_name: Option<String>,
+
+    pub notes: Option<String>,
+
+    #[validate(range(min = 1.0, message = "Daily capacity must be positive"))]
+    pub daily_capacity_kg: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreatePickerRequest {
+    pub crew_id: Option<Uuid>,
+
+    #[validate(length(min = 2, max = 255, message = "Name must be at least 2 characters"))]
+    pub full_name: String,
+
+    #[validate(length(max = 50))]
+    pub employee_code: Option<String>,
+
+    #[validate(range(min = 0.0, message = "Rate must be positive"))]
+    pub hourly_rate: Option<f64>,
+
+    #[validate(range(min = 0.0, message = "Rate must be positive"))]
+    pub rate_per_kg: Option<f64>,
+
+    #[validate(range(min = 0.0, message = "Rate must be positive"))]
+    pub rate_per_bin: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdatePickerRequest {
+    pub crew_id: Option<Uuid>,
+
+    #[validate(length(min = 2, max = 255))]
+    pub full_name: Option<String>,
+
+    #[validate(range(min = 0.0))]
+    pub hourly_rate: Option<f64>,
+
+    #[validate(range(min = 0.0))]
+    pub rate_per_kg: Option<f64>,
+
+    #[validate(range(min = 0.0))]
+    pub rate_per_bin: Option<f64>,
+
+    pub active: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AssignCrewRequest {
+    pub crew_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct RecordWorkRequest {
+    pub picker_id: Uuid,
+
+    /// Podrazumevano datum berbe
+    pub work_date: Option<NaiveDate>,
+
+    #[validate(range(min = 0.0, max = 24.0, message = "Hours must be 0-24"))]
+    pub hours_worked: f64,
+
+    #[validate(range(min = 0.0, message = "Weight must be positive"))]
+    #[serde(default)]
+    pub kg_picked: f64,
+
+    #[validate(range(min = 0, message = "Bins must be positive"))]
+    #[serde(default)]
+    pub bins_picked: i32,
+
+    pub pay_basis: PayBasis,
+
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PayrollQuery {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calculate_pay_by_basis() {
+        assert_eq!(calculate_pay(PayBasis::Hourly, 650.0, 8.0, 400.0, 20), 5200.0);
+        assert_eq!(calculate_pay(PayBasis::PerKg, 12.5, 8.0, 400.0, 20), 5000.0);
+        assert_eq!(calculate_pay(PayBasis::PerBin, 260.0, 8.0, 400.0, 20), 5200.0);
+    }
+
+    #[test]
+    fn test_calculate_pay_rounds_to_cents() {
+        assert_eq!(calculate_pay(PayBasis::PerKg, 0.333, 0.0, 10.0, 0), 3.33);
+    }
+}
@@ -0,0 +1,336 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+use crate::models::HarvestQuality;
+
+/// Veličina bobice
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "berry_size", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum BerrySize {
+    Small,
+    Medium,
+    Large,
+}
+
+/// Boja bobice
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "berry_color", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum BerryColor {
+    Green,
+    YellowGreen,
+    Golden,
+    Pink,
+    Red,
+    Purple,
+    BlueBlack,
+}
+
+/// Zdravstveno stanje grožđa
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "grape_health", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum GrapeHealth {
+    Excellent,
+    Good,
+    Fair,
+    Poor,
+}
+
+impl GrapeHealth {
+    /// Bodovi zdravlja (0-100) za ocenu kvaliteta
+    pub fn points(&self) -> f64 {
+        match self {
+            GrapeHealth::Excellent => 100.0,
+            GrapeHealth::Good => 80.0,
+            GrapeHealth::Fair => 50.0,
+            GrapeHealth::Poor => 0.0,
+        }
+    }
+}
+
+impl std::fmt::Display for BerrySize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BerrySize::Small => write!(f, "small"),
+            BerrySize::Medium => write!(f, "medium"),
+            BerrySize::Large => write!(f, "large"),
+        }
+    }
+}
+
+impl std::fmt::Display for BerryColor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BerryColor::Green => write!(f, "green"),
+            BerryColor::YellowGreen => write!(f, "yellow_green"),
+            BerryColor::Golden => write!(f, "golden"),
+            BerryColor::Pink => write!(f, "pink"),
+            BerryColor::Red => write!(f, "red"),
+            BerryColor::Purple => write!(f, "purple"),
+            BerryColor::BlueBlack => write!(f, "blue_black"),
+        }
+    }
+}
+
+impl std::fmt::Display for GrapeHealth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GrapeHealth::Excellent => write!(f, "excellent"),
+            GrapeHealth::Good => write!(f, "good"),
+            GrapeHealth::Fair => write!(f, "fair"),
+            GrapeHealth::Poor => write!(f, "poor"),
+        }
+    }
+}
+
+/// Klasa kvaliteta berbe
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "quality_grade")]
+pub enum QualityGrade {
+    A,
+    B,
+    C,
+}
+
+impl std::fmt::Display for QualityGrade {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QualityGrade::A => write!(f, "A"),
+            QualityGrade::B => write!(f, "B"),
+            QualityGrade::C => write!(f, "C"),
+        }
+    }
+}
+
+/// Pravila ocenjivanja: optimalni opsezi (sa tolerancijom van opsega), težine i pragovi klasa
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, Validate, PartialEq)]
+#[validate(schema(function = "validate_scoring_rules"))]
+pub struct ScoringRules {
+    #[validate(range(min = 0.0, max = 50.0))]
+    pub brix_min: f64,
+    #[validate(range(min = 0.0, max = 50.0))]
+    pub brix_max: f64,
+    #[validate(range(min = 0.01, max = 50.0))]
+    pub brix_tolerance: f64,
+
+    #[validate(range(min = 0.0, max = 14.0))]
+    pub ph_min: f64,
+    #[validate(range(min = 0.0, max = 14.0))]
+    pub ph_max: f64,
+    #[validate(range(min = 0.01, max = 14.0))]
+    pub ph_tolerance: f64,
+
+    #[validate(range(min = 0.0, max = 30.0))]
+    pub acidity_min: f64,
+    #[validate(range(min = 0.0, max = 30.0))]
+    pub acidity_max: f64,
+    #[validate(range(min = 0.01, max = 30.0))]
+    pub acidity_tolerance: f64,
+
+    #[validate(range(min = 0.0))]
+    pub weight_brix: f64,
+    #[validate(range(min = 0.0))]
+    pub weight_ph: f64,
+    #[validate(range(min = 0.0))]
+    pub weight_acidity: f64,
+    #[validate(range(min = 0.0))]
+    pub weight_health: f64,
+
+    #[validate(range(min = 0.0, max = 100.0))]
+    pub grade_a_min: f64,
+    #[validate(range(min = 0.0, max = 100.0))]
+    pub grade_b_min: f64,
+}
+
+fn validate_scoring_rules(rules: &ScoringRules) -> Result<(), ValidationError> {
+    if rules.brix_min > rules.brix_max
+        || rules.ph_min > rules.ph_max
+        || rules.acidity_min > rules.acidity_max
+    {
+        return Err(ValidationError::new("band_min_must_not_exceed_max"));
+    }
+    if rules.weight_brix + rules.weight_ph + rules.weight_acidity + rules.weight_health <= 0.0 {
+        return Err(ValidationError::new("weights_must_not_all_be_zero"));
+    }
+    if rules.grade_b_min > rules.grade_a_min {
+        return Err(ValidationError::new("grade_b_min_must_not_exceed_grade_a_min"));
+    }
+    Ok(())
+}
+
+impl Default for ScoringRules {
+    /// Opšta pravila za sorte bez sopstvenog profila
+    fn default() -> Self {
+        ScoringRules {
+            brix_min: 21.0,
+            brix_max: 25.0,
+            brix_tolerance: 4.0,
+            ph_min: 3.2,
+            ph_max: 3.6,
+            ph_tolerance: 0.3,
+            acidity_min: 5.5,
+            acidity_max: 8.0,
+            acidity_tolerance: 2.5,
+            weight_brix: 0.35,
+            weight_ph: 0.2,
+            weight_acidity: 0.2,
+            weight_health: 0.25,
+            grade_a_min: 85.0,
+            grade_b_min: 70.0,
+        }
+    }
+}
+
+/// Bodovi (0-100) za vrednost u odnosu na optimalni opseg:
+/// 100 unutar opsega, linearno do 0 na udaljenosti `tolerance` od opsega
+fn band_points(value: f64, min: f64, max: f64, tolerance: f64) -> f64 {
+    let distance = if value < min {
+        min - value
+    } else if value > max {
+        value - max
+    } else {
+        0.0
+    };
+    (100.0 * (1.0 - distance / tolerance)).clamp(0.0, 100.0)
+}
+
+impl ScoringRules {
+    /// Ponderisana ocena iz prosečnih vrednosti merenja; komponente bez podataka se izostavljaju
+    pub fn score(&self, measurements: &[HarvestQuality]) -> Option<f64> {
+        let average = |values: Vec<f64>| -> Option<f64> {
+            (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+        };
+
+        let brix = average(measurements.iter().filter_map(|m| m.brix).collect());
+        let ph = average(measurements.iter().filter_map(|m| m.ph).collect());
+        let acidity = average(measurements.iter().filter_map(|m| m.acidity).collect());
+        let health = average(
+            measurements
+                .iter()
+                .filter_map(|m| m.grape_health.map(|h| h.points()))
+                .collect(),
+        );
+
+        let components = [
+            (brix.map(|v| band_points(v, self.brix_min, self.brix_max, self.brix_tolerance)), self.weight_brix),
+            (ph.map(|v| band_points(v, self.ph_min, self.ph_max, self.ph_tolerance)), self.weight_ph),
+            (
+                acidity.map(|v| band_points(v, self.acidity_min, self.acidity_max, self.acidity_tolerance)),
+                self.weight_acidity,
+            ),
+            (health, self.weight_health),
+        ];
+
+        let (weighted, total_weight) = components
+            .iter()
+            .filter_map(|(points, weight)| points.map(|p| (p * weight, *weight)))
+            .fold((0.0, 0.0), |(sum, w), (p, weight)| (sum + p, w + weight));
+
+        if total_weight <= 0.0 {
+            return None;
+        }
+
+        Some(((weighted / total_weight) * 10.0).round() / 10.0)
+    }
+
+    pub fn grade(&self, score: f64) -> QualityGrade {
+        if score >= self.grade_a_min {
+            QualityGrade::A
+        } else if score >= self.grade_b_min {
+            QualityGrade::B
+        } else {
+            QualityGrade::C
+        }
+    }
+}
+
+/// Profil ocenjivanja za sortu
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct QualityScoreProfile {
+    pub id: Uuid,
+    pub grape_variety: String,
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub rules: ScoringRules,
+    pub updated_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// ============== Request structs ==============
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpsertQualityProfileRequest {
+    #[validate(length(min = 1, max = 255, message = "Grape variety is required"))]
+    pub grape_variety: String,
+
+    #[serde(flatten)]
+    #[validate(nested)]
+    pub rules: ScoringRules,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn measurement(brix: f64, ph: f64, acidity: f64, health: GrapeHealth) -> HarvestQuality {
+        HarvestQuality {
+            id: Uuid::nil(),
+            harvest_id: Uuid::nil(),
+            brix: Some(brix),
+            ph: Some(ph),
+            acidity: Some(acidity),
+            berry_size: None,
+            berry_color: None,
+            grape_health: Some(health),
+            notes: None,
+            measured_at: Utc::now(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_score_inside_bands_is_grade_a() {
+        let rules = ScoringRules::default();
+        let score = rules
+            .score(&[measurement(23.0, 3.4, 6.5, GrapeHealth::Excellent)])
+            .unwrap();
+
+        assert_eq!(score, 100.0);
+        assert_eq!(rules.grade(score), QualityGrade::A);
+    }
+
+    #[test]
+    fn test_score_penalizes_values_outside_bands() {
+        let rules = ScoringRules::default();
+        // Brix 2 ispod opsega (50 bodova), pH i kiselina u opsegu, zdravlje "fair" (50 bodova)
+        let score = rules
+            .score(&[measurement(19.0, 3.4, 6.5, GrapeHealth::Fair)])
+            .unwrap();
+
+        // (0.35*50 + 0.2*100 + 0.2*100 + 0.25*50) / 1.0
+        assert_eq!(score, 70.0);
+        assert_eq!(rules.grade(score), QualityGrade::B);
+        assert_eq!(rules.grade(69.9), QualityGrade::C);
+    }
+
+    #[test]
+    fn test_score_without_measurements() {
+        assert_eq!(ScoringRules::default().score(&[]), None);
+    }
+
+    #[test]
+    fn test_vocabulary_is_strict() {
+        assert_eq!(
+            serde_json::from_str::<GrapeHealth>("\"good\"").unwrap(),
+            GrapeHealth::Good
+        );
+        assert!(serde_json::from_str::<GrapeHealth>("\"Good \"").is_err());
+        assert!(serde_json::from_str::<GrapeHealth>("\"dobro\"").is_err());
+    }
+}
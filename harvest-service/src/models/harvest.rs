@@ -4,6 +4,8 @@ use sqlx::FromRow;
 use uuid::Uuid;
 use validator::Validate;
 
+use crate::models::{BerryColor, BerrySize, GrapeHealth, HarvestLoadResponse, QualityGrade};
+
 /// Status berbe
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
 #[sqlx(type_name = "harvest_status", rename_all = "lowercase")]
@@ -18,6 +20,22 @@ pub enum HarvestStatus {
     Cancelled,
 }
 
+impl HarvestStatus {
+    /// Dozvoljeni prelazi: planned → in_progress → completed, uz otkazivanje pre završetka.
+    /// completed i cancelled su završna stanja.
+    pub fn allowed_transitions(&self) -> &'static [HarvestStatus] {
+        match self {
+            HarvestStatus::Planned => &[HarvestStatus::InProgress, HarvestStatus::Cancelled],
+            HarvestStatus::InProgress => &[HarvestStatus::Completed, HarvestStatus::Cancelled],
+            HarvestStatus::Completed | HarvestStatus::Cancelled => &[],
+        }
+    }
+
+    pub fn can_transition_to(&self, next: &HarvestStatus) -> bool {
+        self.allowed_transitions().contains(next)
+    }
+}
+
 impl std::fmt::Display for HarvestStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -49,6 +67,24 @@ pub struct Harvest {
     pub created_by: Uuid,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    // Keš iz vineyard-service
+    pub vineyard_name: Option<String>,
+    pub parcel_name: Option<String>,
+    pub grape_variety: Option<String>,
+    pub parcel_area_m2: Option<f64>,
+    // Ocena kvaliteta (računa se iz merenja po profilu sorte)
+    pub quality_score: Option<f64>,
+    pub quality_grade: Option<QualityGrade>,
+    // Otkup - ugovor sa proizvođačem
+    pub contract_id: Option<Uuid>,
+}
+
+/// Prinos po hektaru iz ukupne težine i površine parcele (m²)
+pub fn compute_yield_per_hectare(total_weight_kg: Option<f64>, area_m2: Option<f64>) -> Option<f64> {
+    match (total_weight_kg, area_m2) {
+        (Some(weight), Some(area)) if area > 0.0 => Some(weight / (area / 10_000.0)),
+        _ => None,
+    }
 }
 
 /// Kvalitet grožđa - merenja
@@ -61,15 +97,27 @@ pub struct HarvestQuality {
     pub ph: Option<f64>,       // pH vrednost
     pub acidity: Option<f64>,  // g/L ukupne kiseline
     // Vizuelna procena
-    pub berry_size: Option<String>,   // small/medium/large
-    pub berry_color: Option<String>,  // opis boje
-    pub grape_health: Option<String>, // excellent/good/fair/poor
+    pub berry_size: Option<BerrySize>,
+    pub berry_color: Option<BerryColor>,
+    pub grape_health: Option<GrapeHealth>,
     // Napomene
     pub notes: Option<String>,
     pub measured_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
 }
 
+/// Istorija promena statusa berbe
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct HarvestStatusChange {
+    pub id: Uuid,
+    pub harvest_id: Uuid,
+    pub from_status: Option<HarvestStatus>, // None = kreiranje berbe
+    pub to_status: HarvestStatus,
+    pub changed_by: Uuid,
+    pub reason: Option<String>,
+    pub changed_at: DateTime<Utc>,
+}
+
 // ============== Request structs ==============
 
 #[derive(Debug, Deserialize, Validate)]
@@ -82,9 +130,6 @@ pub struct CreateHarvestRequest {
     #[validate(range(min = 0.0, message = "Weight must be positive"))]
     pub total_weight_kg: Option<f64>,
 
-    #[validate(range(min = 0.0, message = "Yield must be positive"))]
-    pub yield_per_hectare: Option<f64>,
-
     #[validate(length(max = 255))]
     pub weather_condition: Option<String>,
 
@@ -99,15 +144,13 @@ pub struct CreateHarvestRequest {
 
 #[derive(Debug, Deserialize, Validate)]
 pub struct UpdateHarvestRequest {
+    pub parcel_id: Option<Uuid>,
+    pub vineyard_id: Option<Uuid>,
     pub harvest_date: Option<NaiveDate>,
-    pub status: Option<HarvestStatus>,
 
     #[validate(range(min = 0.0, message = "Weight must be positive"))]
     pub total_weight_kg: Option<f64>,
 
-    #[validate(range(min = 0.0, message = "Yield must be positive"))]
-    pub yield_per_hectare: Option<f64>,
-
     #[validate(length(max = 255))]
     pub weather_condition: Option<String>,
 
@@ -120,6 +163,14 @@ pub struct UpdateHarvestRequest {
     pub notes: Option<String>,
 }
 
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateHarvestStatusRequest {
+    pub status: HarvestStatus,
+
+    #[validate(length(max = 1000))]
+    pub reason: Option<String>,
+}
+
 #[derive(Debug, Deserialize, Validate)]
 pub struct AddQualityMeasurementRequest {
     #[validate(range(min = 0.0, max = 50.0, message = "Brix must be 0-50"))]
@@ -131,9 +182,9 @@ pub struct AddQualityMeasurementRequest {
     #[validate(range(min = 0.0, max = 30.0, message = "Acidity out of range"))]
     pub acidity: Option<f64>,
 
-    pub berry_size: Option<String>,
-    pub berry_color: Option<String>,
-    pub grape_health: Option<String>,
+    pub berry_size: Option<BerrySize>,
+    pub berry_color: Option<BerryColor>,
+    pub grape_health: Option<GrapeHealth>,
     pub notes: Option<String>,
 }
 
@@ -144,16 +195,24 @@ pub struct HarvestResponse {
     pub id: Uuid,
     pub parcel_id: Uuid,
     pub vineyard_id: Uuid,
+    pub vineyard_name: Option<String>,
+    pub parcel_name: Option<String>,
+    pub grape_variety: Option<String>,
     pub harvest_date: NaiveDate,
     pub status: HarvestStatus,
     pub total_weight_kg: Option<f64>,
     pub yield_per_hectare: Option<f64>,
+    pub quality_score: Option<f64>,
+    pub quality_grade: Option<QualityGrade>,
+    pub contract_id: Option<Uuid>,
     pub weather_condition: Option<String>,
     pub temperature_celsius: Option<f64>,
     pub humidity_percent: Option<f64>,
     pub notes: Option<String>,
     pub created_by: Uuid,
     pub quality_measurements: Vec<HarvestQualityResponse>,
+    pub status_history: Vec<HarvestStatusChange>,
+    pub loads: Vec<HarvestLoadResponse>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -165,29 +224,69 @@ pub struct HarvestQualityResponse {
     pub brix: Option<f64>,
     pub ph: Option<f64>,
     pub acidity: Option<f64>,
-    pub berry_size: Option<String>,
-    pub berry_color: Option<String>,
-    pub grape_health: Option<String>,
+    pub berry_size: Option<BerrySize>,
+    pub berry_color: Option<BerryColor>,
+    pub grape_health: Option<GrapeHealth>,
     pub notes: Option<String>,
     pub measured_at: DateTime<Utc>,
 }
 
+/// Berba → muljanje → šira → batch-evi (podaci o vinifikaciji iz fermentation-service)
+#[derive(Debug, Serialize)]
+pub struct HarvestLineageResponse {
+    pub harvest_id: Uuid,
+    pub total_weight_kg: Option<f64>,
+    pub crushed_kg: f64,
+    /// Izmereno grožđe koje još nije izmuljano
+    pub uncrushed_kg: Option<f64>,
+    pub must_liters: f64,
+    pub crushes: Vec<LineageCrushShare>,
+    pub batches: Vec<LineageBatchShare>,
+}
+
+/// Udeo berbe u jednom muljanju
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LineageCrushShare {
+    pub crush_id: Uuid,
+    pub crushed_at: DateTime<Utc>,
+    pub weight_kg: f64,
+    pub must_liters: f64,
+}
+
+/// Batch u koji je otišla šira berbe
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LineageBatchShare {
+    pub batch_id: Uuid,
+    pub batch_name: String,
+    pub batch_status: String,
+    pub grape_kg: f64,
+    pub must_liters: f64,
+}
+
 impl From<Harvest> for HarvestResponse {
     fn from(h: Harvest) -> Self {
         HarvestResponse {
             id: h.id,
             parcel_id: h.parcel_id,
             vineyard_id: h.vineyard_id,
+            vineyard_name: h.vineyard_name,
+            parcel_name: h.parcel_name,
+            grape_variety: h.grape_variety,
             harvest_date: h.harvest_date,
             status: h.status,
             total_weight_kg: h.total_weight_kg,
             yield_per_hectare: h.yield_per_hectare,
+            quality_score: h.quality_score,
+            quality_grade: h.quality_grade,
+            contract_id: h.contract_id,
             weather_condition: h.weather_condition,
             temperature_celsius: h.temperature_celsius,
             humidity_percent: h.humidity_percent,
             notes: h.notes,
             created_by: h.created_by,
             quality_measurements: vec![],
+            status_history: vec![],
+            loads: vec![],
             created_at: h.created_at,
             updated_at: h.updated_at,
         }
@@ -209,4 +308,33 @@ impl From<HarvestQuality> for HarvestQualityResponse {
             measured_at: q.measured_at,
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_yield_per_hectare_from_square_meters() {
+        // 2500 m² = 0.25 ha
+        assert_eq!(compute_yield_per_hectare(Some(2000.0), Some(2500.0)), Some(8000.0));
+    }
+
+    #[test]
+    fn test_status_transitions() {
+        assert!(HarvestStatus::Planned.can_transition_to(&HarvestStatus::InProgress));
+        assert!(HarvestStatus::Planned.can_transition_to(&HarvestStatus::Cancelled));
+        assert!(HarvestStatus::InProgress.can_transition_to(&HarvestStatus::Completed));
+        assert!(!HarvestStatus::Planned.can_transition_to(&HarvestStatus::Completed));
+        assert!(!HarvestStatus::Cancelled.can_transition_to(&HarvestStatus::Completed));
+        assert!(!HarvestStatus::Cancelled.can_transition_to(&HarvestStatus::Planned));
+        assert!(!HarvestStatus::Completed.can_transition_to(&HarvestStatus::InProgress));
+    }
+
+    #[test]
+    fn test_yield_per_hectare_missing_data() {
+        assert_eq!(compute_yield_per_hectare(None, Some(2500.0)), None);
+        assert_eq!(compute_yield_per_hectare(Some(2000.0), None), None);
+        assert_eq!(compute_yield_per_hectare(Some(2000.0), Some(0.0)), None);
+    }
+}
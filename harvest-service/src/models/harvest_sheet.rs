@@ -0,0 +1,553 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use uuid::Uuid;
+use validator::{Validate, ValidationErrors};
+
+use crate::models::{AddQualityMeasurementRequest, CreateHarvestRequest, Harvest, HarvestQuality};
+
+/// Kolone tabele za uvoz/izvoz. Jedan red = jedno merenje; redovi sa istim
+/// `harvest_ref` pripadaju istoj berbi, a podaci o berbi se čitaju iz prvog reda.
+pub const HARVEST_SHEET_COLUMNS: [&str; 17] = [
+    "harvest_ref",
+    "vineyard_id",
+    "parcel_id",
+    "harvest_date",
+    "total_weight_kg",
+    "weather_condition",
+    "temperature_celsius",
+    "humidity_percent",
+    "harvest_notes",
+    "measured_at",
+    "brix",
+    "ph",
+    "acidity",
+    "berry_size",
+    "berry_color",
+    "grape_health",
+    "measurement_notes",
+];
+
+/// Kolone sa brojevima (XLSX izvoz ih upisuje kao numeričke ćelije)
+pub const HARVEST_SHEET_NUMERIC_COLUMNS: [&str; 6] = [
+    "total_weight_kg",
+    "temperature_celsius",
+    "humidity_percent",
+    "brix",
+    "ph",
+    "acidity",
+];
+
+const REQUIRED_COLUMNS: [&str; 4] = ["harvest_ref", "vineyard_id", "parcel_id", "harvest_date"];
+const HARVEST_COLUMNS: std::ops::Range<usize> = 1..9;
+const MEASUREMENT_COLUMNS: std::ops::Range<usize> = 9..17;
+
+/// Greška u jednom redu tabele (red 1 je zaglavlje)
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ImportRowError {
+    pub row: usize,
+    pub column: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug)]
+pub struct ImportedMeasurement {
+    pub request: AddQualityMeasurementRequest,
+    pub measured_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug)]
+pub struct ImportedHarvest {
+    pub harvest_ref: String,
+    pub row: usize,
+    pub request: CreateHarvestRequest,
+    pub measurements: Vec<ImportedMeasurement>,
+}
+
+#[derive(Debug, Default)]
+pub struct ParsedHarvestSheet {
+    pub rows_read: usize,
+    pub harvests: Vec<ImportedHarvest>,
+    pub errors: Vec<ImportRowError>,
+}
+
+/// Jedan red tabele sa pristupom po imenu kolone
+struct SheetRow<'a> {
+    number: usize,
+    cells: Vec<&'a str>,
+}
+
+impl SheetRow<'_> {
+    fn cell(&self, column: &str) -> Option<&str> {
+        let index = HARVEST_SHEET_COLUMNS.iter().position(|c| *c == column)?;
+        Some(self.cells[index]).filter(|v| !v.is_empty())
+    }
+
+    fn error(&self, column: &str, message: impl Into<String>) -> ImportRowError {
+        ImportRowError {
+            row: self.number,
+            column: Some(column.to_string()),
+            message: message.into(),
+        }
+    }
+
+    fn parse<T>(
+        &self,
+        column: &str,
+        errors: &mut Vec<ImportRowError>,
+        parse: impl Fn(&str) -> Option<T>,
+        expected: &str,
+    ) -> Option<T> {
+        let raw = self.cell(column)?;
+        let parsed = parse(raw);
+        if parsed.is_none() {
+            errors.push(self.error(column, format!("'{}' is not {}", raw, expected)));
+        }
+        parsed
+    }
+
+    fn text(&self, column: &str) -> Option<String> {
+        self.cell(column).map(str::to_string)
+    }
+}
+
+/// Čita i proverava tabelu: svaka greška se vezuje za red i kolonu. Greška celog
+/// fajla (nedostaje obavezna ili postoji nepoznata kolona) vraća se kao Err.
+pub fn parse_harvest_sheet(
+    headers: &[String],
+    rows: &[Vec<String>],
+) -> Result<ParsedHarvestSheet, String> {
+    let headers: Vec<String> = headers.iter().map(|h| h.trim().to_lowercase()).collect();
+
+    if let Some(unknown) = headers
+        .iter()
+        .find(|h| !h.is_empty() && !HARVEST_SHEET_COLUMNS.contains(&h.as_str()))
+    {
+        return Err(format!("Unknown column '{}'", unknown));
+    }
+    if let Some(missing) = REQUIRED_COLUMNS
+        .iter()
+        .find(|c| !headers.iter().any(|h| h == *c))
+    {
+        return Err(format!("Missing required column '{}'", missing));
+    }
+
+    // Pozicija svake poznate kolone u fajlu (redosled kolona nije bitan)
+    let positions: Vec<Option<usize>> = HARVEST_SHEET_COLUMNS
+        .iter()
+        .map(|c| headers.iter().position(|h| h == c))
+        .collect();
+
+    let mut sheet = ParsedHarvestSheet::default();
+    let mut by_ref: HashMap<String, (usize, Vec<String>, Option<usize>)> = HashMap::new();
+
+    for (index, raw) in rows.iter().enumerate() {
+        let row = SheetRow {
+            number: index + 2,
+            cells: positions
+                .iter()
+                .map(|p| p.and_then(|p| raw.get(p)).map(|v| v.trim()).unwrap_or(""))
+                .collect(),
+        };
+
+        if row.cells.iter().all(|c| c.is_empty()) {
+            continue;
+        }
+        sheet.rows_read += 1;
+
+        let Some(harvest_ref) = row.text("harvest_ref") else {
+            sheet.errors.push(row.error("harvest_ref", "harvest_ref is required"));
+            continue;
+        };
+
+        let harvest_cells: Vec<String> =
+            row.cells[HARVEST_COLUMNS].iter().map(|c| c.to_string()).collect();
+
+        let harvest_index = match by_ref.get(&harvest_ref) {
+            Some((first_row, first_cells, harvest_index)) => {
+                // Ponovljeni podaci o berbi moraju biti prazni ili isti kao u prvom redu
+                for (offset, (cell, first)) in harvest_cells.iter().zip(first_cells).enumerate() {
+                    if !cell.is_empty() && cell != first {
+                        sheet.errors.push(row.error(
+                            HARVEST_SHEET_COLUMNS[HARVEST_COLUMNS.start + offset],
+                            format!("Conflicts with row {} of harvest '{}'", first_row, harvest_ref),
+                        ));
+                    }
+                }
+                *harvest_index
+            }
+            None => {
+                let harvest_index = parse_harvest(&row, &mut sheet.errors).map(|request| {
+                    sheet.harvests.push(ImportedHarvest {
+                        harvest_ref: harvest_ref.clone(),
+                        row: row.number,
+                        request,
+                        measurements: vec![],
+                    });
+                    sheet.harvests.len() - 1
+                });
+                by_ref.insert(harvest_ref, (row.number, harvest_cells, harvest_index));
+                harvest_index
+            }
+        };
+
+        if row.cells[MEASUREMENT_COLUMNS].iter().any(|c| !c.is_empty()) {
+            let measurement = parse_measurement(&row, &mut sheet.errors);
+            if let (Some(measurement), Some(index)) = (measurement, harvest_index) {
+                sheet.harvests[index].measurements.push(measurement);
+            }
+        }
+    }
+
+    Ok(sheet)
+}
+
+fn parse_harvest(row: &SheetRow, errors: &mut Vec<ImportRowError>) -> Option<CreateHarvestRequest> {
+    let before = errors.len();
+
+    let vineyard_id = row.parse("vineyard_id", errors, parse_uuid, "a UUID");
+    let parcel_id = row.parse("parcel_id", errors, parse_uuid, "a UUID");
+    let harvest_date = row.parse("harvest_date", errors, parse_date, "a date (YYYY-MM-DD)");
+
+    for column in ["vineyard_id", "parcel_id", "harvest_date"] {
+        if row.cell(column).is_none() {
+            errors.push(row.error(column, format!("{} is required", column)));
+        }
+    }
+
+    let request = CreateHarvestRequest {
+        vineyard_id: vineyard_id.unwrap_or_default(),
+        parcel_id: parcel_id.unwrap_or_default(),
+        harvest_date: harvest_date.unwrap_or_default(),
+        total_weight_kg: row.parse("total_weight_kg", errors, parse_number, "a number"),
+        weather_condition: row.text("weather_condition"),
+        temperature_celsius: row.parse("temperature_celsius", errors, parse_number, "a number"),
+        humidity_percent: row.parse("humidity_percent", errors, parse_number, "a number"),
+        notes: row.text("harvest_notes"),
+    };
+
+    if let Err(e) = request.validate() {
+        errors.extend(validation_errors(row, &e, "harvest_notes"));
+    }
+
+    (errors.len() == before).then_some(request)
+}
+
+fn parse_measurement(row: &SheetRow, errors: &mut Vec<ImportRowError>) -> Option<ImportedMeasurement> {
+    let before = errors.len();
+
+    let measured_at = row.parse("measured_at", errors, parse_timestamp, "a date/time");
+    let request = AddQualityMeasurementRequest {
+        brix: row.parse("brix", errors, parse_number, "a number"),
+        ph: row.parse("ph", errors, parse_number, "a number"),
+        acidity: row.parse("acidity", errors, parse_number, "a number"),
+        berry_size: row.parse("berry_size", errors, parse_vocabulary, "small, medium or large"),
+        berry_color: row.parse("berry_color", errors, parse_vocabulary, "a known berry color"),
+        grape_health: row.parse(
+            "grape_health",
+            errors,
+            parse_vocabulary,
+            "excellent, good, fair or poor",
+        ),
+        notes: row.text("measurement_notes"),
+    };
+
+    if measured_at.is_some_and(|t| t > Utc::now()) {
+        errors.push(row.error("measured_at", "Measurement time cannot be in the future"));
+    }
+    if let Err(e) = request.validate() {
+        errors.extend(validation_errors(row, &e, "measurement_notes"));
+    }
+
+    (errors.len() == before).then_some(ImportedMeasurement {
+        request,
+        measured_at,
+    })
+}
+
+/// Greške `validator`-a po poljima, sa imenom kolone umesto imena polja
+fn validation_errors(row: &SheetRow, errors: &ValidationErrors, notes_column: &str) -> Vec<ImportRowError> {
+    let mut result: Vec<ImportRowError> = errors
+        .field_errors()
+        .into_iter()
+        .flat_map(|(field, field_errors)| {
+            let column = if field == "notes" { notes_column } else { field };
+            field_errors.iter().map(move |e| {
+                row.error(
+                    column,
+                    e.message
+                        .as_ref()
+                        .map(|m| m.to_string())
+                        .unwrap_or_else(|| format!("Invalid value ({})", e.code)),
+                )
+            })
+        })
+        .collect();
+    result.sort_by(|a, b| a.column.cmp(&b.column));
+    result
+}
+
+fn parse_uuid(value: &str) -> Option<Uuid> {
+    Uuid::parse_str(value).ok()
+}
+
+/// ISO datum ili lokalni zapis (dd.mm.yyyy)
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(value.trim_end_matches('.'), "%d.%m.%Y"))
+        .ok()
+}
+
+/// Prihvata i decimalni zarez
+fn parse_number(value: &str) -> Option<f64> {
+    value.replace(',', ".").parse::<f64>().ok().filter(|v| v.is_finite())
+}
+
+/// RFC 3339 ili datum i vreme bez zone (tumači se kao UTC)
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(t) = DateTime::parse_from_rfc3339(value) {
+        return Some(t.with_timezone(&Utc));
+    }
+    ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .map(|t| t.and_utc())
+}
+
+/// Isti strogi rečnik kao JSON API (snake_case)
+fn parse_vocabulary<T: DeserializeOwned>(value: &str) -> Option<T> {
+    serde_json::from_value(serde_json::Value::String(value.to_string())).ok()
+}
+
+/// Redovi za izvoz u istom formatu koji uvoz prihvata
+pub fn harvest_sheet_rows(harvest: &Harvest, measurements: &[HarvestQuality]) -> Vec<Vec<String>> {
+    let number = |v: Option<f64>| v.map(|v| v.to_string()).unwrap_or_default();
+    let harvest_cells = vec![
+        harvest.id.to_string(),
+        harvest.vineyard_id.to_string(),
+        harvest.parcel_id.to_string(),
+        harvest.harvest_date.format("%Y-%m-%d").to_string(),
+        number(harvest.total_weight_kg),
+        harvest.weather_condition.clone().unwrap_or_default(),
+        number(harvest.temperature_celsius),
+        number(harvest.humidity_percent),
+        harvest.notes.clone().unwrap_or_default(),
+    ];
+
+    if measurements.is_empty() {
+        let mut row = harvest_cells;
+        row.resize(HARVEST_SHEET_COLUMNS.len(), String::new());
+        return vec![row];
+    }
+
+    measurements
+        .iter()
+        .map(|m| {
+            let mut row = harvest_cells.clone();
+            row.extend([
+                m.measured_at.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+                number(m.brix),
+                number(m.ph),
+                number(m.acidity),
+                m.berry_size.map(|v| v.to_string()).unwrap_or_default(),
+                m.berry_color.map(|v| v.to_string()).unwrap_or_default(),
+                m.grape_health.map(|v| v.to_string()).unwrap_or_default(),
+                m.notes.clone().unwrap_or_default(),
+            ]);
+            row
+        })
+        .collect()
+}
+
+// ============== Request / response structs ==============
+
+#[derive(Debug, Deserialize)]
+pub struct HarvestImportQuery {
+    /// Podrazumevano samo provera, bez upisa
+    #[serde(default = "default_dry_run")]
+    pub dry_run: bool,
+}
+
+fn default_dry_run() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HarvestExportQuery {
+    pub vineyard_id: Uuid,
+    pub season: Option<i32>,
+    /// csv (podrazumevano) ili xlsx
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HarvestImportReport {
+    pub dry_run: bool,
+    pub committed: bool,
+    pub rows_read: usize,
+    pub harvests: usize,
+    pub measurements: usize,
+    pub errors: Vec<ImportRowError>,
+    pub created: Vec<ImportedHarvestRef>,
+}
+
+/// Koja berba je nastala iz kog `harvest_ref`
+#[derive(Debug, Serialize)]
+pub struct ImportedHarvestRef {
+    pub harvest_ref: String,
+    pub harvest_id: Uuid,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{GrapeHealth, HarvestStatus};
+
+    fn headers() -> Vec<String> {
+        HARVEST_SHEET_COLUMNS.iter().map(|c| c.to_string()).collect()
+    }
+
+    fn row(cells: &[(&str, &str)]) -> Vec<String> {
+        HARVEST_SHEET_COLUMNS
+            .iter()
+            .map(|c| {
+                cells
+                    .iter()
+                    .find(|(name, _)| name == c)
+                    .map(|(_, v)| v.to_string())
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
+    const VINEYARD: &str = "00000000-0000-0000-0000-000000000001";
+    const PARCEL: &str = "00000000-0000-0000-0000-000000000002";
+
+    #[test]
+    fn test_rows_group_into_harvests_with_measurements() {
+        let rows = vec![
+            row(&[
+                ("harvest_ref", "h1"),
+                ("vineyard_id", VINEYARD),
+                ("parcel_id", PARCEL),
+                ("harvest_date", "15.09.2026"),
+                ("total_weight_kg", "1250,5"),
+                ("brix", "22.4"),
+                ("grape_health", "good"),
+            ]),
+            row(&[("harvest_ref", "h1"), ("parcel_id", PARCEL), ("brix", "23.0")]),
+            row(&[]),
+        ];
+
+        let sheet = parse_harvest_sheet(&headers(), &rows).unwrap();
+
+        assert!(sheet.errors.is_empty(), "{:?}", sheet.errors);
+        assert_eq!(sheet.rows_read, 2);
+        assert_eq!(sheet.harvests.len(), 1);
+
+        let harvest = &sheet.harvests[0];
+        assert_eq!(harvest.request.harvest_date, NaiveDate::from_ymd_opt(2026, 9, 15).unwrap());
+        assert_eq!(harvest.request.total_weight_kg, Some(1250.5));
+        assert_eq!(harvest.measurements.len(), 2);
+        assert_eq!(harvest.measurements[0].request.grape_health, Some(GrapeHealth::Good));
+    }
+
+    #[test]
+    fn test_row_errors_use_validator_rules() {
+        let rows = vec![
+            row(&[
+                ("harvest_ref", "h1"),
+                ("vineyard_id", VINEYARD),
+                ("parcel_id", PARCEL),
+                ("harvest_date", "2026-09-15"),
+                ("humidity_percent", "140"),
+            ]),
+            row(&[("harvest_ref", "h1"), ("ph", "15"), ("berry_color", "plava")]),
+            row(&[("harvest_ref", "h2"), ("vineyard_id", "abc")]),
+            row(&[("harvest_ref", "h1"), ("harvest_date", "2026-09-16")]),
+        ];
+
+        let sheet = parse_harvest_sheet(&headers(), &rows).unwrap();
+        let at = |row: usize, column: &str| {
+            sheet
+                .errors
+                .iter()
+                .any(|e| e.row == row && e.column.as_deref() == Some(column))
+        };
+
+        assert!(at(2, "humidity_percent"));
+        assert!(at(3, "ph"));
+        assert!(at(3, "berry_color"));
+        assert!(at(4, "vineyard_id"));
+        assert!(at(4, "parcel_id"));
+        assert!(at(5, "harvest_date"));
+        assert!(sheet.harvests.is_empty());
+    }
+
+    #[test]
+    fn test_unknown_or_missing_columns_reject_file() {
+        let mut with_unknown = headers();
+        with_unknown.push("tonnage".to_string());
+        assert!(parse_harvest_sheet(&with_unknown, &[]).is_err());
+
+        let without_date: Vec<String> = headers()
+            .into_iter()
+            .filter(|h| h != "harvest_date")
+            .collect();
+        assert!(parse_harvest_sheet(&without_date, &[]).is_err());
+    }
+
+    #[test]
+    fn test_export_rows_round_trip() {
+        let harvest = Harvest {
+            id: Uuid::new_v4(),
+            parcel_id: Uuid::parse_str(PARCEL).unwrap(),
+            vineyard_id: Uuid::parse_str(VINEYARD).unwrap(),
+            harvest_date: NaiveDate::from_ymd_opt(2026, 9, 15).unwrap(),
+            status: HarvestStatus::Completed,
+            total_weight_kg: Some(980.0),
+            yield_per_hectare: None,
+            weather_condition: Some("sunny".to_string()),
+            temperature_celsius: Some(24.5),
+            humidity_percent: None,
+            notes: None,
+            created_by: Uuid::nil(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            vineyard_name: None,
+            parcel_name: None,
+            grape_variety: None,
+            parcel_area_m2: None,
+            quality_score: None,
+            quality_grade: None,
+            contract_id: None,
+        };
+        let measurement = HarvestQuality {
+            id: Uuid::nil(),
+            harvest_id: harvest.id,
+            brix: Some(23.1),
+            ph: Some(3.4),
+            acidity: None,
+            berry_size: None,
+            berry_color: None,
+            grape_health: Some(GrapeHealth::Excellent),
+            notes: Some("north rows".to_string()),
+            measured_at: DateTime::parse_from_rfc3339("2026-09-15T08:30:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+            created_at: Utc::now(),
+        };
+
+        let rows = harvest_sheet_rows(&harvest, std::slice::from_ref(&measurement));
+        let sheet = parse_harvest_sheet(&headers(), &rows).unwrap();
+
+        assert!(sheet.errors.is_empty(), "{:?}", sheet.errors);
+        let imported = &sheet.harvests[0];
+        assert_eq!(imported.request.total_weight_kg, Some(980.0));
+        assert_eq!(imported.request.weather_condition.as_deref(), Some("sunny"));
+        assert_eq!(imported.measurements[0].measured_at, Some(measurement.measured_at));
+        assert_eq!(imported.measurements[0].request.brix, Some(23.1));
+        assert_eq!(imported.measurements[0].request.notes.as_deref(), Some("north rows"));
+    }
+}
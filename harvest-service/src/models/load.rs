@@ -0,0 +1,174 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+use crate::models::HarvestStatus;
+
+/// Vrsta posude u kojoj grožđe stiže na prijem
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "load_container_type", rename_all = "lowercase")]
+pub enum LoadContainerType {
+    #[serde(rename = "bin")]
+    Bin,
+    #[serde(rename = "trailer")]
+    Trailer,
+}
+
+/// Tura grožđa (kontejner / prikolica) izmerena na kolskoj vagi
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct HarvestLoad {
+    pub id: Uuid,
+    pub harvest_id: Uuid,
+    pub ticket_number: String, // broj vagarske potvrde
+    pub container_type: LoadContainerType,
+    pub container_code: String,
+    pub picker_crew: Option<String>,
+    // Vaga
+    pub gross_weight_kg: f64,
+    pub tare_weight_kg: f64,
+    pub net_weight_kg: f64, // gross - tare (računa baza)
+    // Kvalitet na prijemu
+    pub brix: Option<f64>,
+    // Meta
+    pub arrived_at: DateTime<Utc>,
+    pub received_by: Uuid,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+// ============== Request structs ==============
+
+#[derive(Debug, Deserialize, Validate)]
+#[validate(schema(function = "validate_load_weights"))]
+pub struct ReceiveLoadRequest {
+    pub harvest_id: Uuid,
+
+    /// Broj potvrde sa vage; ako izostane, generiše se
+    #[validate(length(min = 1, max = 50))]
+    pub ticket_number: Option<String>,
+
+    pub container_type: LoadContainerType,
+
+    #[validate(length(min = 1, max = 100, message = "Container code is required"))]
+    pub container_code: String,
+
+    #[validate(length(max = 255))]
+    pub picker_crew: Option<String>,
+
+    #[validate(range(min = 0.0, message = "Gross weight must be positive"))]
+    pub gross_weight_kg: f64,
+
+    #[validate(range(min = 0.0, message = "Tare weight must be positive"))]
+    pub tare_weight_kg: f64,
+
+    #[validate(range(min = 0.0, max = 50.0, message = "Brix must be 0-50"))]
+    pub brix: Option<f64>,
+
+    pub arrived_at: Option<DateTime<Utc>>,
+    pub notes: Option<String>,
+}
+
+fn validate_load_weights(req: &ReceiveLoadRequest) -> Result<(), ValidationError> {
+    if req.gross_weight_kg <= req.tare_weight_kg {
+        let mut err = ValidationError::new("weights");
+        err.message = Some("Gross weight must be greater than tare weight".into());
+        return Err(err);
+    }
+    Ok(())
+}
+
+/// Prelaz statusa berbe pri prijemu ture: prva tura pokreće planiranu berbu,
+/// a završena ili otkazana berba ne prima ture
+pub fn status_on_load_receipt(status: &HarvestStatus) -> Result<Option<HarvestStatus>, String> {
+    match status {
+        HarvestStatus::Completed | HarvestStatus::Cancelled => {
+            Err(format!("Cannot receive loads for a {} harvest", status))
+        }
+        HarvestStatus::Planned => Ok(Some(HarvestStatus::InProgress)),
+        HarvestStatus::InProgress => Ok(None),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReceivingQuery {
+    pub date: Option<NaiveDate>,
+}
+
+// ============== Response structs ==============
+
+#[derive(Debug, Serialize)]
+pub struct HarvestLoadResponse {
+    pub id: Uuid,
+    pub harvest_id: Uuid,
+    pub ticket_number: String,
+    pub container_type: LoadContainerType,
+    pub container_code: String,
+    pub picker_crew: Option<String>,
+    pub gross_weight_kg: f64,
+    pub tare_weight_kg: f64,
+    pub net_weight_kg: f64,
+    pub brix: Option<f64>,
+    pub arrived_at: DateTime<Utc>,
+    pub received_by: Uuid,
+    pub notes: Option<String>,
+}
+
+impl From<HarvestLoad> for HarvestLoadResponse {
+    fn from(l: HarvestLoad) -> Self {
+        HarvestLoadResponse {
+            id: l.id,
+            harvest_id: l.harvest_id,
+            ticket_number: l.ticket_number,
+            container_type: l.container_type,
+            container_code: l.container_code,
+            picker_crew: l.picker_crew,
+            gross_weight_kg: l.gross_weight_kg,
+            tare_weight_kg: l.tare_weight_kg,
+            net_weight_kg: l.net_weight_kg,
+            brix: l.brix,
+            arrived_at: l.arrived_at,
+            received_by: l.received_by,
+            notes: l.notes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(gross_weight_kg: f64, tare_weight_kg: f64) -> ReceiveLoadRequest {
+        ReceiveLoadRequest {
+            harvest_id: Uuid::new_v4(),
+            ticket_number: None,
+            container_type: LoadContainerType::Trailer,
+            container_code: "PR-01".to_string(),
+            picker_crew: None,
+            gross_weight_kg,
+            tare_weight_kg,
+            brix: Some(22.5),
+            arrived_at: None,
+            notes: None,
+        }
+    }
+
+    #[test]
+    fn test_load_weights_validation() {
+        assert!(request(3200.0, 1200.0).validate().is_ok());
+        assert!(request(1200.0, 1200.0).validate().is_err());
+        assert!(request(900.0, 1200.0).validate().is_err());
+    }
+
+    #[test]
+    fn test_status_on_load_receipt() {
+        assert_eq!(
+            status_on_load_receipt(&HarvestStatus::Planned),
+            Ok(Some(HarvestStatus::InProgress))
+        );
+        assert_eq!(status_on_load_receipt(&HarvestStatus::InProgress), Ok(None));
+        assert!(status_on_load_receipt(&HarvestStatus::Completed).is_err());
+        assert!(status_on_load_receipt(&HarvestStatus::Cancelled).is_err());
+    }
+}
@@ -0,0 +1,171 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Zrelost semenki (boja)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "seed_ripeness", rename_all = "snake_case")]
+pub enum SeedRipeness {
+    #[serde(rename = "green")]
+    Green,
+    #[serde(rename = "partially_brown")]
+    PartiallyBrown,
+    #[serde(rename = "brown")]
+    Brown,
+}
+
+/// Uzorak zrelosti uzet na parceli pre berbe
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct MaturitySample {
+    pub id: Uuid,
+    pub parcel_id: Uuid,
+    pub vineyard_id: Uuid,
+    pub season: i32,
+    pub sampled_at: DateTime<Utc>,
+    // Hemijska merenja
+    pub brix: Option<f64>,
+    pub ph: Option<f64>,
+    pub titratable_acidity: Option<f64>, // g/L
+    pub yan_mg_l: Option<f64>,           // asimilabilni azot
+    // Bobica i semenka
+    pub berry_weight_g: Option<f64>,
+    pub seed_ripeness: Option<SeedRipeness>,
+    pub sample_size: Option<i32>,
+    // Keš iz vineyard-service
+    pub vineyard_name: Option<String>,
+    pub parcel_name: Option<String>,
+    pub grape_variety: Option<String>,
+    pub parcel_area_m2: Option<f64>,
+    // Berba kojoj su povezana završna merenja
+    pub harvest_id: Option<Uuid>,
+    // Meta
+    pub notes: Option<String>,
+    pub sampled_by: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Dnevni prosek uzoraka (tačka na grafiku)
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct MaturityTrendPoint {
+    pub date: NaiveDate,
+    pub samples: i64,
+    pub brix: Option<f64>,
+    pub ph: Option<f64>,
+    pub titratable_acidity: Option<f64>,
+    pub yan_mg_l: Option<f64>,
+    pub berry_weight_g: Option<f64>,
+}
+
+// ============== Request structs ==============
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateMaturitySampleRequest {
+    pub vineyard_id: Uuid,
+
+    /// Podrazumevano sada; sezona je godina uzorkovanja
+    pub sampled_at: Option<DateTime<Utc>>,
+
+    #[validate(range(min = 0.0, max = 50.0, message = "Brix must be 0-50"))]
+    pub brix: Option<f64>,
+
+    #[validate(range(min = 0.0, max = 14.0, message = "pH must be 0-14"))]
+    pub ph: Option<f64>,
+
+    #[validate(range(min = 0.0, max = 30.0, message = "Titratable acidity out of range"))]
+    pub titratable_acidity: Option<f64>,
+
+    #[validate(range(min = 0.0, max = 1000.0, message = "YAN must be 0-1000 mg/L"))]
+    pub yan_mg_l: Option<f64>,
+
+    #[validate(range(min = 0.01, max = 20.0, message = "Berry weight must be 0.01-20 g"))]
+    pub berry_weight_g: Option<f64>,
+
+    pub seed_ripeness: Option<SeedRipeness>,
+
+    #[validate(range(min = 1, message = "Sample size must be positive"))]
+    pub sample_size: Option<i32>,
+
+    pub notes: Option<String>,
+}
+
+/// Vreme uzorkovanja: podrazumevano sada, nikad u budućnosti
+pub fn resolve_sampled_at(
+    requested: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> Result<DateTime<Utc>, String> {
+    match requested {
+        Some(at) if at > now => Err("Sampling time cannot be in the future".to_string()),
+        Some(at) => Ok(at),
+        None => Ok(now),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MaturityQuery {
+    /// Podrazumevano tekuća godina
+    pub season: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LinkMaturitySamplesRequest {
+    /// Uzorci za povezivanje; podrazumevano poslednji dan uzorkovanja pre berbe
+    pub sample_ids: Option<Vec<Uuid>>,
+}
+
+// ============== Response structs ==============
+
+/// Podaci za grafik zrelosti parcele u sezoni
+#[derive(Debug, Serialize)]
+pub struct MaturityTrendResponse {
+    pub parcel_id: Uuid,
+    pub season: i32,
+    pub grape_variety: Option<String>,
+    pub target_brix: Option<f64>,
+    pub points: Vec<MaturityTrendPoint>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn request() -> CreateMaturitySampleRequest {
+        CreateMaturitySampleRequest {
+            vineyard_id: Uuid::new_v4(),
+            sampled_at: None,
+            brix: Some(21.4),
+            ph: Some(3.35),
+            titratable_acidity: Some(6.8),
+            yan_mg_l: Some(180.0),
+            berry_weight_g: Some(1.6),
+            seed_ripeness: Some(SeedRipeness::PartiallyBrown),
+            sample_size: Some(200),
+            notes: None,
+        }
+    }
+
+    #[test]
+    fn test_sample_validation_ranges() {
+        let invalid = [
+            CreateMaturitySampleRequest { brix: Some(55.0), ..request() },
+            CreateMaturitySampleRequest { ph: Some(15.0), ..request() },
+            CreateMaturitySampleRequest { berry_weight_g: Some(0.0), ..request() },
+            CreateMaturitySampleRequest { sample_size: Some(0), ..request() },
+        ];
+
+        assert!(request().validate().is_ok());
+        assert!(invalid.iter().all(|req| req.validate().is_err()));
+    }
+
+    #[test]
+    fn test_resolve_sampled_at() {
+        let now = Utc.with_ymd_and_hms(2026, 9, 2, 10, 0, 0).unwrap();
+        let earlier = now - Duration::days(3);
+
+        assert_eq!(resolve_sampled_at(None, now), Ok(now));
+        assert_eq!(resolve_sampled_at(Some(earlier), now), Ok(earlier));
+        assert!(resolve_sampled_at(Some(now + Duration::hours(1)), now).is_err());
+    }
+}
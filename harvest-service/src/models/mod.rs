@@ -1,5 +1,25 @@
-﻿pub mod harvest;
+﻿pub mod attachment;
+pub mod contract;
+pub mod crew;
+pub mod grading;
+pub mod harvest;
+pub mod harvest_sheet;
+pub mod load;
+pub mod maturity;
+pub mod planning;
+pub mod stats;
+pub mod sync;
 pub mod token;
 
+pub use attachment::*;
+pub use contract::*;
+pub use crew::*;
+pub use grading::*;
 pub use harvest::*;
+pub use harvest_sheet::*;
+pub use load::*;
+pub use maturity::*;
+pub use planning::*;
+pub use stats::*;
+pub use sync::*;
 pub use token::*;
\ No newline at end of file
@@ -0,0 +1,481 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Ciljna zrelost za sortu
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RipenessTarget {
+    pub id: Uuid,
+    pub grape_variety: String,
+    pub target_brix: f64,
+    pub max_ph: Option<f64>,         // bere se najkasnije kad pH dostigne ovu vrednost
+    pub target_acidity: Option<f64>, // g/L, bere se kad kiselina padne ispod ove vrednosti
+    pub updated_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Status predloga kalendara berbe
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "harvest_plan_status", rename_all = "lowercase")]
+pub enum HarvestPlanStatus {
+    #[serde(rename = "proposed")]
+    Proposed,
+    #[serde(rename = "accepted")]
+    Accepted,
+    #[serde(rename = "discarded")]
+    Discarded,
+}
+
+/// Predlog kalendara berbe za sezonu
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct HarvestPlan {
+    pub id: Uuid,
+    pub season: i32,
+    pub vineyard_id: Option<Uuid>,
+    pub start_date: NaiveDate,
+    pub status: HarvestPlanStatus,
+    pub crew_capacity_kg_per_day: f64,
+    pub press_capacity_kg_per_day: f64,
+    pub created_by: Uuid,
+    pub accepted_by: Option<Uuid>,
+    pub accepted_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Stavka plana - parcela sa prognozom i predloženim terminom
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct HarvestPlanItem {
+    pub id: Uuid,
+    pub plan_id: Uuid,
+    pub parcel_id: Uuid,
+    pub vineyard_id: Uuid,
+    pub vineyard_name: Option<String>,
+    pub parcel_name: Option<String>,
+    pub grape_variety: Option<String>,
+    // Prognoza
+    pub sample_count: i32,
+    pub latest_brix: Option<f64>,
+    pub brix_per_day: Option<f64>,
+    pub predicted_ripe_date: Option<NaiveDate>,
+    // Raspored
+    pub estimated_kg: Option<f64>,
+    pub scheduled_date: Option<NaiveDate>, // None = nije raspoređena (vidi notes)
+    pub finish_date: Option<NaiveDate>,
+    pub notes: Option<String>,
+    pub harvest_id: Option<Uuid>, // berba kreirana prihvatanjem plana
+}
+
+/// Plan pre upisa u bazu
+#[derive(Debug, Clone)]
+pub struct NewHarvestPlan {
+    pub season: i32,
+    pub vineyard_id: Option<Uuid>,
+    pub start_date: NaiveDate,
+    pub crew_capacity_kg_per_day: f64,
+    pub press_capacity_kg_per_day: f64,
+    pub created_by: Uuid,
+}
+
+/// Stavka plana pre upisa u bazu
+#[derive(Debug, Clone)]
+pub struct NewHarvestPlanItem {
+    pub parcel_id: Uuid,
+    pub vineyard_id: Uuid,
+    pub vineyard_name: Option<String>,
+    pub parcel_name: Option<String>,
+    pub grape_variety: Option<String>,
+    pub sample_count: i32,
+    pub latest_brix: Option<f64>,
+    pub brix_per_day: Option<f64>,
+    pub predicted_ripe_date: Option<NaiveDate>,
+    pub estimated_kg: Option<f64>,
+    pub scheduled_date: Option<NaiveDate>,
+    pub finish_date: Option<NaiveDate>,
+    pub notes: Option<String>,
+}
+
+/// Merenje pre berbe (uzorak zrelosti ili merenje kvaliteta uz berbu), sa podacima o parceli
+#[derive(Debug, Clone, FromRow)]
+pub struct ParcelRipenessSample {
+    pub parcel_id: Uuid,
+    pub vineyard_id: Uuid,
+    pub vineyard_name: Option<String>,
+    pub parcel_name: Option<String>,
+    pub grape_variety: Option<String>,
+    pub parcel_area_m2: Option<f64>,
+    pub measured_at: DateTime<Utc>,
+    pub brix: Option<f64>,
+    pub ph: Option<f64>,
+    pub acidity: Option<f64>,
+}
+
+// ============== Forecasting ==============
+
+/// Prognoza ne ide dalje od ovoliko dana posle poslednjeg merenja
+pub const FORECAST_HORIZON_DAYS: i64 = 120;
+/// Manji nagib (po danu) smatra se ravnim trendom
+const TREND_SLOPE_EPS: f64 = 1e-6;
+
+/// Prognoza zrelosti parcele
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct RipenessForecast {
+    pub sample_count: usize,
+    pub latest_brix: Option<f64>,
+    pub brix_per_day: Option<f64>,
+    pub ph_per_day: Option<f64>,
+    pub acidity_per_day: Option<f64>,
+    pub predicted_ripe_date: Option<NaiveDate>,
+    pub note: Option<String>,
+}
+
+/// Linearni trend (metoda najmanjih kvadrata): (nagib po danu, odsečak)
+fn linear_trend(points: &[(f64, f64)]) -> Option<(f64, f64)> {
+    if points.len() < 2 {
+        return None;
+    }
+
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+
+    let sxx: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    if sxx == 0.0 {
+        return None; // sva merenja istog dana
+    }
+    let sxy: f64 = points.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+
+    let slope = sxy / sxx;
+    Some((slope, mean_y - slope * mean_x))
+}
+
+/// Trend serije merenja; x je broj dana od prvog merenja
+fn series_trend(series: &[(NaiveDate, f64)]) -> Option<(f64, f64)> {
+    let first_date = series.first()?.0;
+    let points: Vec<(f64, f64)> = series
+        .iter()
+        .map(|(d, v)| ((*d - first_date).num_days() as f64, *v))
+        .collect();
+    linear_trend(&points)
+}
+
+/// Dan kada vrednost dostiže cilj (rastući ili opadajući trend).
+/// Ako je poslednje merenje već preko cilja, to je dan poslednjeg merenja;
+/// cilj posle horizonta prognoze se ne predviđa.
+fn date_reaching(series: &[(NaiveDate, f64)], target: f64, rising: bool) -> Option<NaiveDate> {
+    let &(first_date, _) = series.first()?;
+    let &(last_date, last_value) = series.last()?;
+
+    let reached = if rising { last_value >= target } else { last_value <= target };
+    if reached {
+        return Some(last_date);
+    }
+
+    let (slope, intercept) = series_trend(series)?;
+    if (rising && slope <= TREND_SLOPE_EPS) || (!rising && slope >= -TREND_SLOPE_EPS) {
+        return None;
+    }
+
+    let last_day = (last_date - first_date).num_days();
+    let days = ((target - intercept) / slope).ceil();
+    if !days.is_finite() || days > (last_day + FORECAST_HORIZON_DAYS) as f64 {
+        return None;
+    }
+
+    first_date.checked_add_signed(Duration::days((days as i64).max(last_day)))
+}
+
+/// Ekstrapolacija zrelosti iz merenja: dan kada Brix dostiže cilj (i kiselina padne na cilj),
+/// ali ne posle dana kada pH pređe dozvoljeni maksimum.
+pub fn forecast_ripeness(
+    samples: &[ParcelRipenessSample],
+    target_brix: f64,
+    max_ph: Option<f64>,
+    target_acidity: Option<f64>,
+) -> RipenessForecast {
+    let series = |f: fn(&ParcelRipenessSample) -> Option<f64>| -> Vec<(NaiveDate, f64)> {
+        let mut values: Vec<(NaiveDate, f64)> = samples
+            .iter()
+            .filter_map(|s| f(s).map(|v| (s.measured_at.date_naive(), v)))
+            .collect();
+        values.sort_by_key(|(d, _)| *d);
+        values
+    };
+
+    let brix = series(|s| s.brix);
+    let ph = series(|s| s.ph);
+    let acidity = series(|s| s.acidity);
+
+    let slope = |series: &[(NaiveDate, f64)]| series_trend(series).map(|(s, _)| s);
+
+    let brix_date = date_reaching(&brix, target_brix, true);
+    let acidity_date = target_acidity.and_then(|t| date_reaching(&acidity, t, false));
+    let ph_date = max_ph.and_then(|t| date_reaching(&ph, t, true));
+
+    let mut note = None;
+    let mut predicted = brix_date.map(|d| acidity_date.map_or(d, |a| d.max(a)));
+
+    if predicted.is_none() {
+        note = Some(if brix.len() < 2 {
+            "Not enough Brix samples to extrapolate ripeness".to_string()
+        } else if slope(&brix).is_some_and(|s| s > TREND_SLOPE_EPS) {
+            format!("Target Brix is not reached within {} days", FORECAST_HORIZON_DAYS)
+        } else {
+            "Brix is not rising".to_string()
+        });
+    }
+
+    if let Some(cap) = ph_date {
+        if predicted.is_none_or(|d| cap < d) {
+            predicted = Some(cap);
+            note = Some("pH limit is reached before target ripeness".to_string());
+        }
+    }
+
+    RipenessForecast {
+        sample_count: samples.len(),
+        latest_brix: brix.last().map(|(_, v)| *v),
+        brix_per_day: slope(&brix),
+        ph_per_day: slope(&ph),
+        acidity_per_day: slope(&acidity),
+        predicted_ripe_date: predicted,
+        note,
+    }
+}
+
+// ============== Scheduling ==============
+
+/// Termin berbe parcele: prvi i poslednji dan
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScheduledSlot {
+    pub start: NaiveDate,
+    pub finish: NaiveDate,
+}
+
+/// Raspored parcela po danima tako da dnevna količina ne pređe kapacitet
+/// (manji od kapaciteta ekipa i prese). Parcele se raspoređuju redom po prognozi zrelosti;
+/// parcela veća od dnevnog kapaciteta se bere više uzastopnih dana.
+/// Vraća None za parcelu koja ne staje u horizont planiranja.
+pub fn schedule_harvests(
+    candidates: &[(NaiveDate, f64)], // (prognoza zrelosti, procenjena količina kg)
+    start_date: NaiveDate,
+    daily_capacity_kg: f64,
+    horizon_days: i64,
+) -> Vec<Option<ScheduledSlot>> {
+    let last_day = start_date + Duration::days(horizon_days);
+    let mut booked: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+    let mut slots = vec![None; candidates.len()];
+
+    let mut order: Vec<usize> = (0..candidates.len()).collect();
+    order.sort_by_key(|&i| candidates[i].0);
+
+    for i in order {
+        let (ripe_date, kg) = candidates[i];
+        let mut day = ripe_date.max(start_date);
+        let mut remaining = kg;
+        let mut usage: Vec<(NaiveDate, f64)> = Vec::new();
+
+        while day <= last_day {
+            let free = daily_capacity_kg - booked.get(&day).copied().unwrap_or(0.0);
+            if free > 0.0 {
+                let take = remaining.min(free);
+                usage.push((day, take));
+                remaining -= take;
+                if remaining <= 0.0 {
+                    break;
+                }
+            }
+            day += Duration::days(1);
+        }
+
+        if remaining > 0.0 || usage.is_empty() {
+            continue;
+        }
+
+        for (d, take) in &usage {
+            *booked.entry(*d).or_insert(0.0) += take;
+        }
+        slots[i] = Some(ScheduledSlot {
+            start: usage.first().unwrap().0,
+            finish: usage.last().unwrap().0,
+        });
+    }
+
+    slots
+}
+
+// ============== Request structs ==============
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpsertRipenessTargetRequest {
+    #[validate(length(min = 1, max = 255, message = "Grape variety is required"))]
+    pub grape_variety: String,
+
+    #[validate(range(min = 0.0, max = 50.0, message = "Brix must be 0-50"))]
+    pub target_brix: f64,
+
+    #[validate(range(min = 0.0, max = 14.0, message = "pH must be 0-14"))]
+    pub max_ph: Option<f64>,
+
+    #[validate(range(min = 0.0, max = 30.0, message = "Acidity out of range"))]
+    pub target_acidity: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RipenessQuery {
+    /// Podrazumevano tekuća godina
+    pub season: Option<i32>,
+    pub vineyard_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateHarvestPlanRequest {
+    pub season: Option<i32>,
+    pub vineyard_id: Option<Uuid>,
+
+    /// Prvi dan koji se planira; podrazumevano danas
+    pub start_date: Option<NaiveDate>,
+
+    #[validate(range(min = 1.0, message = "Press capacity must be positive"))]
+    pub press_capacity_kg_per_day: f64,
+
+    /// Ekipe čiji se kapacitet računa; podrazumevano sve sa unetim kapacitetom
+    pub crew_ids: Option<Vec<Uuid>>,
+
+    /// Prinos za parcele bez ranijih završenih berbi
+    #[validate(range(min = 0.0, max = 50000.0, message = "Yield out of range"))]
+    #[serde(default = "default_yield_kg_per_ha")]
+    pub default_yield_kg_per_ha: f64,
+
+    #[validate(range(min = 1, max = 120, message = "Horizon must be 1-120 days"))]
+    #[serde(default = "default_horizon_days")]
+    pub horizon_days: i64,
+}
+
+fn default_yield_kg_per_ha() -> f64 {
+    8000.0
+}
+
+fn default_horizon_days() -> i64 {
+    60
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AcceptHarvestPlanRequest {
+    /// Stavke koje se prihvataju; podrazumevano sve raspoređene
+    pub item_ids: Option<Vec<Uuid>>,
+}
+
+// ============== Response structs ==============
+
+/// Prognoza zrelosti po parceli
+#[derive(Debug, Serialize)]
+pub struct ParcelRipenessResponse {
+    pub parcel_id: Uuid,
+    pub vineyard_id: Uuid,
+    pub vineyard_name: Option<String>,
+    pub parcel_name: Option<String>,
+    pub grape_variety: Option<String>,
+    pub target_brix: Option<f64>,
+    #[serde(flatten)]
+    pub forecast: Option<RipenessForecast>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HarvestPlanResponse {
+    #[serde(flatten)]
+    pub plan: HarvestPlan,
+    pub items: Vec<HarvestPlanItem>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn sample(day: u32, brix: f64, ph: f64) -> ParcelRipenessSample {
+        ParcelRipenessSample {
+            parcel_id: Uuid::nil(),
+            vineyard_id: Uuid::nil(),
+            vineyard_name: None,
+            parcel_name: None,
+            grape_variety: None,
+            parcel_area_m2: None,
+            measured_at: Utc.with_ymd_and_hms(2026, 8, day, 9, 0, 0).unwrap(),
+            brix: Some(brix),
+            ph: Some(ph),
+            acidity: None,
+        }
+    }
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_forecast_extrapolates_brix() {
+        // +0.25 °Brix dnevno: 18 → 22 za 16 dana od 1. avgusta
+        let samples = vec![sample(1, 18.0, 3.1), sample(5, 19.0, 3.15), sample(9, 20.0, 3.2)];
+        let forecast = forecast_ripeness(&samples, 22.0, None, None);
+
+        assert_eq!(forecast.brix_per_day, Some(0.25));
+        assert_eq!(forecast.predicted_ripe_date, Some(date(8, 17)));
+    }
+
+    #[test]
+    fn test_forecast_capped_by_ph() {
+        // Brix 23 bi bio 21. avgusta, ali pH (+0.025 dnevno) prelazi 3.33 već 15. avgusta
+        let samples = vec![sample(1, 18.0, 3.0), sample(5, 19.0, 3.1), sample(9, 20.0, 3.2)];
+        let forecast = forecast_ripeness(&samples, 23.0, Some(3.33), None);
+
+        assert_eq!(forecast.predicted_ripe_date, Some(date(8, 15)));
+        assert!(forecast.note.is_some());
+    }
+
+    #[test]
+    fn test_forecast_needs_rising_brix() {
+        let samples = vec![sample(1, 20.0, 3.2), sample(5, 19.5, 3.2)];
+        let forecast = forecast_ripeness(&samples, 22.0, None, None);
+        assert_eq!(forecast.predicted_ripe_date, None);
+
+        let ripe = forecast_ripeness(&[sample(1, 23.0, 3.3)], 22.0, None, None);
+        assert_eq!(ripe.predicted_ripe_date, Some(date(8, 1)));
+    }
+
+    #[test]
+    fn test_forecast_flat_or_slow_brix_has_no_date() {
+        // Konstantan Brix daje nagib reda 1e-32; ne sme da pukne na prekoračenju datuma
+        let flat = vec![sample(1, 19.3, 3.2), sample(5, 19.3, 3.2), sample(9, 19.3, 3.2)];
+        let forecast = forecast_ripeness(&flat, 22.0, None, None);
+        assert_eq!(forecast.predicted_ripe_date, None);
+        assert_eq!(forecast.note.as_deref(), Some("Brix is not rising"));
+
+        // +0.01 °Brix dnevno bi cilj dostigao tek za ~300 dana
+        let slow = vec![sample(1, 19.0, 3.2), sample(9, 19.08, 3.2)];
+        let forecast = forecast_ripeness(&slow, 22.0, None, None);
+        assert_eq!(forecast.predicted_ripe_date, None);
+        assert!(forecast.note.is_some_and(|n| n.contains("not reached")));
+    }
+
+    #[test]
+    fn test_schedule_respects_daily_capacity() {
+        let start = date(9, 1);
+        let candidates = vec![
+            (date(9, 2), 6000.0),  // 2. i 3. septembar
+            (date(9, 1), 3000.0),  // 1. septembar
+            (date(9, 3), 2000.0),  // ostatak 3. septembra
+            (date(9, 3), 10000.0), // ne staje u horizont
+        ];
+
+        let slots = schedule_harvests(&candidates, start, 4000.0, 3);
+
+        assert_eq!(slots[1], Some(ScheduledSlot { start: date(9, 1), finish: date(9, 1) }));
+        assert_eq!(slots[0], Some(ScheduledSlot { start: date(9, 2), finish: date(9, 3) }));
+        assert_eq!(slots[2], Some(ScheduledSlot { start: date(9, 3), finish: date(9, 3) }));
+        assert_eq!(slots[3], None);
+    }
+}
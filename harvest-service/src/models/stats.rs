@@ -0,0 +1,313 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Završena berba sa prosečnim merenjima - ulaz za statistiku
+#[derive(Debug, Clone, FromRow)]
+pub struct HarvestStatsSample {
+    pub season: i32,
+    pub parcel_id: Uuid,
+    pub parcel_name: Option<String>,
+    pub grape_variety: Option<String>,
+    pub weight_kg: Option<f64>,
+    pub yield_per_hectare: Option<f64>,
+    pub brix: Option<f64>,
+    pub ph: Option<f64>,
+    pub acidity: Option<f64>,
+    pub quality_score: Option<f64>,
+}
+
+/// Po kojim dimenzijama se grupiše statistika
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatsGrouping {
+    pub season: bool,
+    pub parcel: bool,
+    pub variety: bool,
+}
+
+impl Default for StatsGrouping {
+    fn default() -> Self {
+        StatsGrouping {
+            season: true,
+            parcel: true,
+            variety: true,
+        }
+    }
+}
+
+impl StatsGrouping {
+    /// Parsira listu dimenzija odvojenih zarezom: "season,parcel,variety"
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let mut grouping = StatsGrouping {
+            season: false,
+            parcel: false,
+            variety: false,
+        };
+
+        for part in value.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            match part {
+                "season" => grouping.season = true,
+                "parcel" => grouping.parcel = true,
+                "variety" => grouping.variety = true,
+                other => return Err(format!("Unknown grouping '{}'", other)),
+            }
+        }
+
+        Ok(grouping)
+    }
+}
+
+/// Sažetak jedne veličine: prosek ponderisan kilogramima, opseg i standardna devijacija
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct MetricSummary {
+    pub samples: usize,
+    pub weighted_avg: Option<f64>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub stddev: Option<f64>,
+}
+
+impl MetricSummary {
+    /// Parovi (vrednost, kg). Berbe bez težine učestvuju samo ako nijedna nema težinu,
+    /// tada svaka berba ima isti ponder.
+    pub fn from_weighted(values: &[(f64, f64)]) -> Self {
+        if values.is_empty() {
+            return MetricSummary::default();
+        }
+
+        let total_kg: f64 = values.iter().map(|(_, kg)| kg.max(0.0)).sum();
+        let weight = |kg: f64| if total_kg > 0.0 { kg.max(0.0) } else { 1.0 };
+        let total_weight: f64 = values.iter().map(|(_, kg)| weight(*kg)).sum();
+
+        let mean = values.iter().map(|(v, kg)| v * weight(*kg)).sum::<f64>() / total_weight;
+        let variance = values
+            .iter()
+            .map(|(v, kg)| weight(*kg) * (v - mean).powi(2))
+            .sum::<f64>()
+            / total_weight;
+
+        MetricSummary {
+            samples: values.len(),
+            weighted_avg: Some(round2(mean)),
+            min: values.iter().map(|(v, _)| *v).reduce(f64::min),
+            max: values.iter().map(|(v, _)| *v).reduce(f64::max),
+            stddev: Some(round2(variance.sqrt())),
+        }
+    }
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// Razlika u odnosu na istu grupu prethodne sezone (tekuća - prethodna)
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct YearOverYearDelta {
+    pub previous_season: i32,
+    pub total_weight_kg: f64,
+    pub yield_per_hectare: Option<f64>,
+    pub brix: Option<f64>,
+    pub ph: Option<f64>,
+    pub acidity: Option<f64>,
+    pub quality_score: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HarvestStatsGroup {
+    pub season: Option<i32>,
+    pub parcel_id: Option<Uuid>,
+    pub parcel_name: Option<String>,
+    pub grape_variety: Option<String>,
+    pub harvest_count: usize,
+    pub total_weight_kg: f64,
+    pub yield_per_hectare: MetricSummary,
+    pub brix: MetricSummary,
+    pub ph: MetricSummary,
+    pub acidity: MetricSummary,
+    pub quality_score: MetricSummary,
+    pub year_over_year: Option<YearOverYearDelta>,
+}
+
+type GroupKey = (Option<i32>, Option<Uuid>, Option<String>);
+
+/// Grupiše berbe i računa sažetke; ako se grupiše po sezoni, dodaje razliku
+/// prema istoj parceli/sorti iz prethodne sezone
+pub fn summarize_harvests(
+    samples: &[HarvestStatsSample],
+    grouping: StatsGrouping,
+) -> Vec<HarvestStatsGroup> {
+    let mut buckets: BTreeMap<GroupKey, Vec<&HarvestStatsSample>> = BTreeMap::new();
+    for sample in samples {
+        let key = (
+            grouping.season.then_some(sample.season),
+            grouping.parcel.then_some(sample.parcel_id),
+            if grouping.variety {
+                sample.grape_variety.as_ref().map(|v| v.trim().to_lowercase())
+            } else {
+                None
+            },
+        );
+        buckets.entry(key).or_default().push(sample);
+    }
+
+    let groups: BTreeMap<GroupKey, HarvestStatsGroup> = buckets
+        .into_iter()
+        .map(|(key, members)| {
+            let summary = |field: fn(&HarvestStatsSample) -> Option<f64>| {
+                let values: Vec<(f64, f64)> = members
+                    .iter()
+                    .filter_map(|s| field(s).map(|v| (v, s.weight_kg.unwrap_or(0.0))))
+                    .collect();
+                MetricSummary::from_weighted(&values)
+            };
+
+            let group = HarvestStatsGroup {
+                season: key.0,
+                parcel_id: key.1,
+                parcel_name: key
+                    .1
+                    .and_then(|_| members.iter().find_map(|s| s.parcel_name.clone())),
+                grape_variety: key
+                    .2
+                    .as_ref()
+                    .and_then(|_| members.iter().find_map(|s| s.grape_variety.clone())),
+                harvest_count: members.len(),
+                total_weight_kg: round2(members.iter().filter_map(|s| s.weight_kg).sum()),
+                yield_per_hectare: summary(|s| s.yield_per_hectare),
+                brix: summary(|s| s.brix),
+                ph: summary(|s| s.ph),
+                acidity: summary(|s| s.acidity),
+                quality_score: summary(|s| s.quality_score),
+                year_over_year: None,
+            };
+            (key, group)
+        })
+        .collect();
+
+    groups
+        .iter()
+        .map(|(key, group)| {
+            let previous = key
+                .0
+                .and_then(|season| groups.get(&(Some(season - 1), key.1, key.2.clone())));
+
+            let mut group = group.clone();
+            group.year_over_year = previous.map(|prev| year_over_year(&group, prev));
+            group
+        })
+        .collect()
+}
+
+fn year_over_year(current: &HarvestStatsGroup, previous: &HarvestStatsGroup) -> YearOverYearDelta {
+    let delta = |a: &MetricSummary, b: &MetricSummary| match (a.weighted_avg, b.weighted_avg) {
+        (Some(a), Some(b)) => Some(round2(a - b)),
+        _ => None,
+    };
+
+    YearOverYearDelta {
+        previous_season: previous.season.unwrap_or_default(),
+        total_weight_kg: round2(current.total_weight_kg - previous.total_weight_kg),
+        yield_per_hectare: delta(&current.yield_per_hectare, &previous.yield_per_hectare),
+        brix: delta(&current.brix, &previous.brix),
+        ph: delta(&current.ph, &previous.ph),
+        acidity: delta(&current.acidity, &previous.acidity),
+        quality_score: delta(&current.quality_score, &previous.quality_score),
+    }
+}
+
+// ============== Request structs ==============
+
+#[derive(Debug, Deserialize)]
+pub struct HarvestStatsQuery {
+    pub vineyard_id: Option<Uuid>,
+    pub parcel_id: Option<Uuid>,
+    pub grape_variety: Option<String>,
+    pub season_from: Option<i32>,
+    pub season_to: Option<i32>,
+    /// npr. "season,variety"; podrazumevano sve tri dimenzije
+    pub group_by: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(season: i32, parcel: u128, kg: f64, brix: f64) -> HarvestStatsSample {
+        HarvestStatsSample {
+            season,
+            parcel_id: Uuid::from_u128(parcel),
+            parcel_name: Some(format!("P{}", parcel)),
+            grape_variety: Some("Merlot".to_string()),
+            weight_kg: Some(kg),
+            yield_per_hectare: None,
+            brix: Some(brix),
+            ph: None,
+            acidity: None,
+            quality_score: None,
+        }
+    }
+
+    #[test]
+    fn test_weighted_summary() {
+        // 3000 kg na 20 i 1000 kg na 24 → ponderisano 21, a ne 22
+        let summary = MetricSummary::from_weighted(&[(20.0, 3000.0), (24.0, 1000.0)]);
+
+        assert_eq!(summary.weighted_avg, Some(21.0));
+        assert_eq!(summary.min, Some(20.0));
+        assert_eq!(summary.max, Some(24.0));
+        assert_eq!(summary.stddev, Some(1.73));
+    }
+
+    #[test]
+    fn test_summary_without_weights_is_unweighted() {
+        let summary = MetricSummary::from_weighted(&[(20.0, 0.0), (24.0, 0.0)]);
+
+        assert_eq!(summary.weighted_avg, Some(22.0));
+        assert_eq!(summary.stddev, Some(2.0));
+        assert_eq!(MetricSummary::from_weighted(&[]).weighted_avg, None);
+    }
+
+    #[test]
+    fn test_year_over_year_by_parcel_and_variety() {
+        let samples = vec![
+            sample(2025, 3, 4000.0, 22.0),
+            sample(2026, 3, 5000.0, 23.5),
+            sample(2026, 4, 2000.0, 21.0),
+        ];
+
+        let groups = summarize_harvests(&samples, StatsGrouping::default());
+        assert_eq!(groups.len(), 3);
+
+        let parcel3 = groups
+            .iter()
+            .find(|g| g.season == Some(2026) && g.parcel_id == Some(Uuid::from_u128(3)))
+            .unwrap();
+        let yoy = parcel3.year_over_year.as_ref().unwrap();
+        assert_eq!(yoy.previous_season, 2025);
+        assert_eq!(yoy.total_weight_kg, 1000.0);
+        assert_eq!(yoy.brix, Some(1.5));
+
+        let parcel4 = groups
+            .iter()
+            .find(|g| g.parcel_id == Some(Uuid::from_u128(4)))
+            .unwrap();
+        assert!(parcel4.year_over_year.is_none());
+    }
+
+    #[test]
+    fn test_grouping_by_season_only() {
+        let samples = vec![sample(2026, 3, 3000.0, 20.0), sample(2026, 4, 1000.0, 24.0)];
+
+        let grouping = StatsGrouping::parse("season").unwrap();
+        let groups = summarize_harvests(&samples, grouping);
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].parcel_id, None);
+        assert_eq!(groups[0].grape_variety, None);
+        assert_eq!(groups[0].total_weight_kg, 4000.0);
+        assert_eq!(groups[0].brix.weighted_avg, Some(21.0));
+        assert!(StatsGrouping::parse("season,block").is_err());
+    }
+}
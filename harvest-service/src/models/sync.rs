@@ -0,0 +1,200 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+pub use sync_common::{
+    sync_page_cursor, SyncCursor, SyncOutcome, SyncResult, SyncTombstone, SYNC_PAGE_MAX,
+};
+
+use crate::models::{
+    AddQualityMeasurementRequest, BerryColor, BerrySize, CreateHarvestRequest, GrapeHealth,
+    HarvestStatus, QualityGrade,
+};
+
+// ============== Zapisi u feed-u promena ==============
+
+/// Berba kako je vidi mobilni klijent, sa serverskom verzijom
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct SyncHarvestRecord {
+    pub id: Uuid,
+    pub parcel_id: Uuid,
+    pub vineyard_id: Uuid,
+    pub harvest_date: NaiveDate,
+    pub status: HarvestStatus,
+    pub total_weight_kg: Option<f64>,
+    pub yield_per_hectare: Option<f64>,
+    pub weather_condition: Option<String>,
+    pub temperature_celsius: Option<f64>,
+    pub humidity_percent: Option<f64>,
+    pub notes: Option<String>,
+    pub vineyard_name: Option<String>,
+    pub parcel_name: Option<String>,
+    pub grape_variety: Option<String>,
+    pub quality_score: Option<f64>,
+    pub quality_grade: Option<QualityGrade>,
+    pub created_by: Uuid,
+    pub updated_at: DateTime<Utc>,
+    pub sync_version: i64,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct SyncQualityRecord {
+    pub id: Uuid,
+    pub harvest_id: Uuid,
+    pub brix: Option<f64>,
+    pub ph: Option<f64>,
+    pub acidity: Option<f64>,
+    pub berry_size: Option<BerrySize>,
+    pub berry_color: Option<BerryColor>,
+    pub grape_health: Option<GrapeHealth>,
+    pub notes: Option<String>,
+    pub measured_at: DateTime<Utc>,
+    pub sync_version: i64,
+}
+
+// ============== Odluka o upisu ==============
+
+/// Šta uraditi sa zapisom koji je poslao klijent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncDecision {
+    Insert,
+    Update,
+    Unchanged,
+    Conflict,
+}
+
+/// `current` je serverska verzija zapisa (ako postoji), `same_content` da li je sadržaj
+/// isti kao poslati (ponovljen upload posle izgubljenog odgovora), a `base_version`
+/// verzija od koje je klijent krenuo sa izmenom. Zapisi koji nisu `updatable`
+/// (merenja) se samo dodaju.
+pub fn decide_sync(
+    current: Option<i64>,
+    same_content: bool,
+    base_version: Option<i64>,
+    updatable: bool,
+) -> SyncDecision {
+    match current {
+        None => SyncDecision::Insert,
+        Some(_) if same_content => SyncDecision::Unchanged,
+        Some(version) if updatable && base_version == Some(version) => SyncDecision::Update,
+        Some(_) => SyncDecision::Conflict,
+    }
+}
+
+impl SyncHarvestRecord {
+    pub fn cursor(&self) -> SyncCursor {
+        SyncCursor::new(self.sync_version, self.id)
+    }
+
+    /// Da li zapis na serveru već ima poslate vrednosti
+    pub fn matches(&self, req: &CreateHarvestRequest) -> bool {
+        self.parcel_id == req.parcel_id
+            && self.vineyard_id == req.vineyard_id
+            && self.harvest_date == req.harvest_date
+            && self.total_weight_kg == req.total_weight_kg
+            && self.weather_condition == req.weather_condition
+            && self.temperature_celsius == req.temperature_celsius
+            && self.humidity_percent == req.humidity_percent
+            && self.notes == req.notes
+    }
+}
+
+impl SyncQualityRecord {
+    pub fn cursor(&self) -> SyncCursor {
+        SyncCursor::new(self.sync_version, self.id)
+    }
+
+    pub fn matches(
+        &self,
+        harvest_id: Uuid,
+        req: &AddQualityMeasurementRequest,
+        measured_at: Option<DateTime<Utc>>,
+    ) -> bool {
+        self.harvest_id == harvest_id
+            && self.brix == req.brix
+            && self.ph == req.ph
+            && self.acidity == req.acidity
+            && self.berry_size == req.berry_size
+            && self.berry_color == req.berry_color
+            && self.grape_health == req.grape_health
+            && self.notes == req.notes
+            && measured_at.is_none_or(|t| t == self.measured_at)
+    }
+}
+
+// ============== Request structs ==============
+
+/// Berba napravljena ili izmenjena na uređaju (id generiše klijent)
+#[derive(Debug, Deserialize)]
+pub struct SyncHarvestPush {
+    pub id: Uuid,
+    /// Verzija sa servera od koje je krenula izmena; None za novu berbu
+    pub base_version: Option<i64>,
+    #[serde(flatten)]
+    pub fields: CreateHarvestRequest,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SyncQualityPush {
+    pub id: Uuid,
+    pub harvest_id: Uuid,
+    pub measured_at: Option<DateTime<Utc>>,
+    #[serde(flatten)]
+    pub fields: AddQualityMeasurementRequest,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SyncPushRequest {
+    #[serde(default)]
+    pub harvests: Vec<SyncHarvestPush>,
+    #[serde(default)]
+    pub quality_measurements: Vec<SyncQualityPush>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SyncChangesQuery {
+    #[serde(default)]
+    pub since: SyncCursor,
+    pub limit: Option<i64>,
+    pub vineyard_id: Option<Uuid>,
+}
+
+// ============== Response structs ==============
+
+/// Kod konflikta klijent dobija serversku verziju berbe ili merenja
+pub type SyncPushResult = SyncResult<serde_json::Value>;
+
+#[derive(Debug, Serialize)]
+pub struct SyncPushResponse {
+    pub harvests: Vec<SyncPushResult>,
+    pub quality_measurements: Vec<SyncPushResult>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncChangesResponse {
+    pub harvests: Vec<SyncHarvestRecord>,
+    pub quality_measurements: Vec<SyncQualityRecord>,
+    pub deleted: Vec<SyncTombstone>,
+    /// Sledeći `since`; klijent nastavlja dok je `has_more`
+    pub next_since: SyncCursor,
+    pub has_more: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decide_sync() {
+        assert_eq!(decide_sync(None, false, None, true), SyncDecision::Insert);
+        // Ponovljen upload istog sadržaja ne pravi duplikat ni konflikt
+        assert_eq!(decide_sync(Some(7), true, None, true), SyncDecision::Unchanged);
+        assert_eq!(decide_sync(Some(7), true, Some(3), true), SyncDecision::Unchanged);
+        assert_eq!(decide_sync(Some(7), false, Some(7), true), SyncDecision::Update);
+        // Server se promenio posle verzije od koje je klijent krenuo
+        assert_eq!(decide_sync(Some(9), false, Some(7), true), SyncDecision::Conflict);
+        // Merenja se ne menjaju preko sync-a
+        assert_eq!(decide_sync(Some(7), false, Some(7), false), SyncDecision::Conflict);
+    }
+}
@@ -18,7 +18,7 @@ pub fn generate_harvest_report(
         "Layer 1",
     );
 
-    let current_layer = doc.get_page(page1).get_layer(layer1);
+    let mut current_layer = doc.get_page(page1).get_layer(layer1);
 
     // Load fonts
     let font = doc.add_builtin_font(BuiltinFont::Helvetica)?;
@@ -38,7 +38,7 @@ pub fn generate_harvest_report(
 
     // Date
     current_layer.use_text(
-        &format!("Generated: {}", Utc::now().format("%Y-%m-%d %H:%M")),
+        format!("Generated: {}", Utc::now().format("%Y-%m-%d %H:%M")),
         10.0,
         Mm(20.0),
         Mm(y_position),
@@ -95,6 +95,13 @@ pub fn generate_harvest_report(
                     .unwrap_or_else(|| "N/A".to_string())
             ),
         ),
+        (
+            "Quality Grade:",
+            match (harvest.quality_grade, harvest.quality_score) {
+                (Some(grade), Some(score)) => format!("{} (score {:.1} / 100)", grade, score),
+                _ => "N/A".to_string(),
+            },
+        ),
         ("Status:", harvest.status.to_string()),
     ];
 
@@ -132,12 +139,12 @@ pub fn generate_harvest_report(
             if y_position < 30.0 {
                 // Need new page
                 let (page_num, layer_num) = doc.add_page(Mm(210.0), Mm(297.0), "Layer 1");
-                let current_layer = doc.get_page(page_num).get_layer(layer_num);
+                current_layer = doc.get_page(page_num).get_layer(layer_num);
                 y_position = 270.0;
             }
 
             current_layer.use_text(
-                &format!("Measurement {}", i + 1),
+                format!("Measurement {}", i + 1),
                 12.0,
                 Mm(20.0),
                 Mm(y_position),
@@ -146,7 +153,7 @@ pub fn generate_harvest_report(
             y_position -= 6.0;
 
             current_layer.use_text(
-                &format!(
+                format!(
                     "Date: {}",
                     measurement.measured_at.format("%Y-%m-%d %H:%M")
                 ),
@@ -166,15 +173,15 @@ pub fn generate_harvest_report(
                 ),
                 (
                     "Berry Size:",
-                    measurement.berry_size.clone(),
+                    measurement.berry_size.map(|v| v.to_string()),
                 ),
                 (
                     "Berry Color:",
-                    measurement.berry_color.clone(),
+                    measurement.berry_color.map(|v| v.to_string()),
                 ),
                 (
                     "Grape Health:",
-                    measurement.grape_health.clone(),
+                    measurement.grape_health.map(|v| v.to_string()),
                 ),
             ];
 
@@ -213,8 +220,6 @@ pub fn generate_harvest_report(
 }
 
 fn draw_line(layer: &PdfLayerReference, y_mm: f32) {
-    use printpdf::PdfLayerReference;
-
     let points = vec![
         (Point::new(Mm(20.0), Mm(y_mm)), false),
         (Point::new(Mm(190.0), Mm(y_mm)), false),
@@ -1,4 +1,5 @@
 ﻿use axum::{
+    extract::DefaultBodyLimit,
     middleware,
     routing::{delete, get, patch, post, put},
     Router,
@@ -21,6 +22,26 @@ pub fn create_router(state: AppState, settings: Settings) -> Router {
         .route("/harvests/:harvest_id", put(handlers::update_harvest))
         .route("/harvests/:harvest_id/status", patch(handlers::update_harvest_status))
         .route("/harvests/:harvest_id", delete(handlers::delete_harvest))
+        .route("/harvests/:harvest_id/lineage", get(handlers::get_harvest_lineage))
+        // Spreadsheet import / export
+        .route("/harvests/import", post(handlers::import_harvest_sheet))
+        .route("/harvests/export", get(handlers::export_harvest_sheet))
+        // Offline sync (mobile)
+        .route("/sync/push", post(handlers::push_sync_changes))
+        .route("/sync/changes", get(handlers::get_sync_changes))
+        // Attachments (photos, lab certificates, checklists)
+        .route(
+            "/attachments",
+            post(handlers::upload_attachment)
+                // multipart zaglavlja i ostala polja pored samog fajla
+                .layer(DefaultBodyLimit::max(settings.attachment_max_bytes + 64 * 1024)),
+        )
+        .route("/attachments", get(handlers::list_attachments))
+        .route("/attachments", delete(handlers::purge_entity_attachments))
+        .route("/attachments/:attachment_id", get(handlers::get_attachment))
+        .route("/attachments/:attachment_id", delete(handlers::delete_attachment))
+        .route("/attachments/:attachment_id/content", get(handlers::download_attachment))
+        .route("/attachments/:attachment_id/thumbnail", get(handlers::download_attachment_thumbnail))
         // Quality measurements
         .route("/harvests/:harvest_id/quality", post(handlers::add_quality_measurement))
         .route("/harvests/:harvest_id/quality", get(handlers::list_quality_measurements))
@@ -28,8 +49,67 @@ pub fn create_router(state: AppState, settings: Settings) -> Router {
         // Queries by vineyard / parcel
         .route("/vineyards/:vineyard_id/harvests", get(handlers::list_harvests_by_vineyard))
         .route("/vineyards/:vineyard_id/stats", get(handlers::get_vineyard_harvest_stats))
+        .route("/stats/harvests", get(handlers::get_harvest_stats_breakdown))
         .route("/parcels/:parcel_id/harvests", get(handlers::list_harvests_by_parcel))
         .route("/harvests/:id/pdf", get(handlers::export_harvest_pdf))
+        // Loads / receiving (crush pad)
+        .route("/harvests/:harvest_id/loads", get(handlers::list_harvest_loads))
+        .route("/harvests/:harvest_id/loads/:load_id", delete(handlers::delete_harvest_load))
+        .route("/receiving/loads", post(handlers::receive_load))
+        .route("/receiving/loads", get(handlers::list_received_loads))
+        .route("/receiving/tickets/:ticket_number", get(handlers::get_load_by_ticket))
+        // Crews, pickers, labor and payroll
+        .route("/crews", post(handlers::create_crew))
+        .route("/crews", get(handlers::list_crews))
+        .route("/crews/:crew_id", put(handlers::update_crew))
+        .route("/pickers", post(handlers::create_picker))
+        .route("/pickers", get(handlers::list_pickers))
+        .route("/pickers/:picker_id", put(handlers::update_picker))
+        .route("/harvests/:harvest_id/crews", post(handlers::assign_crew_to_harvest))
+        .route("/harvests/:harvest_id/crews", get(handlers::list_harvest_crews))
+        .route("/harvests/:harvest_id/crews/:crew_id", delete(handlers::unassign_crew_from_harvest))
+        .route("/harvests/:harvest_id/work", post(handlers::record_picker_work))
+        .route("/harvests/:harvest_id/work", get(handlers::list_picker_work))
+        .route("/harvests/:harvest_id/work/:entry_id", delete(handlers::delete_picker_work))
+        .route("/harvests/:harvest_id/payroll.csv", get(handlers::export_harvest_payroll))
+        .route("/payroll.csv", get(handlers::export_period_payroll))
+        // Quality grading
+        .route("/quality-profiles", get(handlers::list_quality_profiles))
+        .route("/quality-profiles", put(handlers::upsert_quality_profile))
+        .route("/quality-profiles/default", get(handlers::get_default_scoring_rules))
+        .route("/quality-profiles/:profile_id", delete(handlers::delete_quality_profile))
+        // Pre-harvest maturity sampling
+        .route("/parcels/:parcel_id/maturity-samples", post(handlers::create_maturity_sample))
+        .route("/parcels/:parcel_id/maturity-samples", get(handlers::list_maturity_samples))
+        .route("/parcels/:parcel_id/maturity-trend", get(handlers::get_maturity_trend))
+        .route("/maturity-samples/:sample_id", delete(handlers::delete_maturity_sample))
+        .route("/harvests/:harvest_id/maturity-samples", post(handlers::link_maturity_samples))
+        .route("/harvests/:harvest_id/maturity-samples", get(handlers::list_harvest_maturity_samples))
+        .route("/harvests/:harvest_id/maturity-samples/:sample_id", delete(handlers::unlink_maturity_sample))
+        // Harvest planning
+        .route("/ripeness-targets", put(handlers::upsert_ripeness_target))
+        .route("/ripeness-targets", get(handlers::list_ripeness_targets))
+        .route("/planning/ripeness", get(handlers::get_ripeness_forecast))
+        .route("/planning/plans", post(handlers::create_harvest_plan))
+        .route("/planning/plans", get(handlers::list_harvest_plans))
+        .route("/planning/plans/:plan_id", get(handlers::get_harvest_plan))
+        .route("/planning/plans/:plan_id/accept", post(handlers::accept_harvest_plan))
+        .route("/planning/plans/:plan_id/discard", post(handlers::discard_harvest_plan))
+        // Grape purchase contracts
+        .route("/growers", post(handlers::create_grower))
+        .route("/growers", get(handlers::list_growers))
+        .route("/growers/:grower_id", get(handlers::get_grower))
+        .route("/growers/:grower_id", put(handlers::update_grower))
+        .route("/growers/:grower_id/payments", post(handlers::record_grower_payment))
+        .route("/growers/:grower_id/payments", get(handlers::list_grower_payments))
+        .route("/growers/:grower_id/settlement", get(handlers::get_grower_settlement))
+        .route("/growers/:grower_id/settlement.csv", get(handlers::export_grower_settlement))
+        .route("/contracts", post(handlers::create_contract))
+        .route("/contracts", get(handlers::list_contracts))
+        .route("/contracts/:contract_id", get(handlers::get_contract))
+        .route("/contracts/:contract_id", put(handlers::update_contract))
+        .route("/contracts/:contract_id/deliveries", get(handlers::list_contract_deliveries))
+        .route("/harvests/:harvest_id/contract", put(handlers::link_harvest_contract))
         .layer(middleware::from_fn(move |req, next| {
             crate::middleware::add_settings(settings.clone(), req, next)
         }));
@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use object_store::{
+    aws::AmazonS3Builder, local::LocalFileSystem, path::Path, Attribute, Attributes, ObjectStore,
+    PutOptions,
+};
+
+use crate::{config::Settings, error::AppError};
+
+/// Skladište fajlova priloga: lokalni disk ili S3-kompatibilan bucket (AWS, MinIO)
+#[derive(Clone)]
+pub struct AttachmentStorage {
+    store: Arc<dyn ObjectStore>,
+    // Lokalni disk ne čuva Content-Type uz objekat
+    content_type_attributes: bool,
+    max_bytes: usize,
+}
+
+impl AttachmentStorage {
+    /// ATTACHMENT_STORAGE=local|s3. Za S3 se kredencijali i region čitaju iz
+    /// standardnih AWS_* promenljivih; ATTACHMENT_S3_ENDPOINT je za MinIO.
+    pub fn from_settings(settings: &Settings) -> anyhow::Result<Self> {
+        match settings.attachment_storage.as_str() {
+            "local" => {
+                std::fs::create_dir_all(&settings.attachment_local_dir)?;
+                let store = LocalFileSystem::new_with_prefix(&settings.attachment_local_dir)?;
+                Ok(Self {
+                    store: Arc::new(store),
+                    content_type_attributes: false,
+                    max_bytes: settings.attachment_max_bytes,
+                })
+            }
+            "s3" => {
+                let bucket = settings.attachment_s3_bucket.as_deref().ok_or_else(|| {
+                    anyhow::anyhow!("ATTACHMENT_S3_BUCKET is required for s3 storage")
+                })?;
+
+                let mut builder = AmazonS3Builder::from_env().with_bucket_name(bucket);
+                if let Some(endpoint) = &settings.attachment_s3_endpoint {
+                    builder = builder
+                        .with_endpoint(endpoint)
+                        .with_allow_http(endpoint.starts_with("http://"));
+                }
+
+                Ok(Self {
+                    store: Arc::new(builder.build()?),
+                    content_type_attributes: true,
+                    max_bytes: settings.attachment_max_bytes,
+                })
+            }
+            other => Err(anyhow::anyhow!(
+                "Unknown ATTACHMENT_STORAGE '{}', expected local or s3",
+                other
+            )),
+        }
+    }
+
+    /// Najveća dozvoljena veličina fajla (ATTACHMENT_MAX_BYTES)
+    pub fn max_bytes(&self) -> usize {
+        self.max_bytes
+    }
+
+    pub async fn put(&self, key: &str, content_type: &str, bytes: Bytes) -> Result<(), AppError> {
+        let mut attributes = Attributes::new();
+        if self.content_type_attributes {
+            attributes.insert(Attribute::ContentType, content_type.to_string().into());
+        }
+
+        self.store
+            .put_opts(&Path::from(key), bytes.into(), PutOptions::from(attributes))
+            .await
+            .map_err(|e| AppError::InternalError(format!("Attachment storage: {}", e)))?;
+
+        Ok(())
+    }
+
+    pub async fn get(&self, key: &str) -> Result<Bytes, AppError> {
+        let result = self.store.get(&Path::from(key)).await.map_err(|e| match e {
+            object_store::Error::NotFound { .. } => {
+                AppError::NotFound("Attachment file not found".to_string())
+            }
+            e => AppError::InternalError(format!("Attachment storage: {}", e)),
+        })?;
+
+        result
+            .bytes()
+            .await
+            .map_err(|e| AppError::InternalError(format!("Attachment storage: {}", e)))
+    }
+
+    /// Brisanje koje ne smeta ako fajla već nema
+    pub async fn delete(&self, key: &str) -> Result<(), AppError> {
+        match self.store.delete(&Path::from(key)).await {
+            Ok(()) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+            Err(e) => Err(AppError::InternalError(format!("Attachment storage: {}", e))),
+        }
+    }
+}
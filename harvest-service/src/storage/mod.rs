@@ -0,0 +1,5 @@
+pub mod attachment_storage;
+pub mod thumbnail;
+
+pub use attachment_storage::*;
+pub use thumbnail::*;
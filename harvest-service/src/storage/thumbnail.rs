@@ -0,0 +1,84 @@
+use std::io::Cursor;
+
+use image::{imageops::FilterType, ImageFormat, ImageReader, Limits};
+
+/// Najveća strana sličice u pikselima
+pub const THUMBNAIL_MAX_SIDE: u32 = 320;
+/// Najveća strana slike koja se dekodira (fotografije sa telefona su ispod 10000 px)
+pub const DECODE_MAX_SIDE: u32 = 10_000;
+/// Najviše memorije za dekodiranu sliku; mali fajl može da najavi ogromnu sliku
+pub const DECODE_MAX_ALLOC: u64 = 256 * 1024 * 1024;
+
+pub struct Thumbnail {
+    pub jpeg: Vec<u8>,
+    /// Dimenzije originalne slike
+    pub width: u32,
+    pub height: u32,
+}
+
+/// JPEG sličica za prikaz u listi priloga. Greška znači da slika nije ispravna.
+pub fn make_thumbnail(bytes: &[u8]) -> Result<Thumbnail, String> {
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(DECODE_MAX_SIDE);
+    limits.max_image_height = Some(DECODE_MAX_SIDE);
+    limits.max_alloc = Some(DECODE_MAX_ALLOC);
+
+    let mut reader = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|e| e.to_string())?;
+    reader.limits(limits);
+    let image = reader.decode().map_err(|e| e.to_string())?;
+    let (width, height) = (image.width(), image.height());
+
+    let thumb = if width > THUMBNAIL_MAX_SIDE || height > THUMBNAIL_MAX_SIDE {
+        image.resize(THUMBNAIL_MAX_SIDE, THUMBNAIL_MAX_SIDE, FilterType::Triangle)
+    } else {
+        image
+    };
+
+    // JPEG nema alfa kanal
+    let mut jpeg = Vec::new();
+    thumb
+        .to_rgb8()
+        .write_to(&mut Cursor::new(&mut jpeg), ImageFormat::Jpeg)
+        .map_err(|e| e.to_string())?;
+
+    Ok(Thumbnail { jpeg, width, height })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageBuffer, Rgba};
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let image = ImageBuffer::from_pixel(width, height, Rgba([120u8, 20, 60, 255]));
+        let mut bytes = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+            .unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_thumbnail_keeps_aspect_ratio() {
+        let thumb = make_thumbnail(&png(1280, 640)).unwrap();
+        let decoded = image::load_from_memory(&thumb.jpeg).unwrap();
+
+        assert_eq!((thumb.width, thumb.height), (1280, 640));
+        assert_eq!((decoded.width(), decoded.height()), (320, 160));
+    }
+
+    #[test]
+    fn test_thumbnail_rejects_corrupt_image() {
+        let mut bytes = png(10, 10);
+        bytes.truncate(20);
+
+        assert!(make_thumbnail(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_thumbnail_rejects_oversized_image() {
+        assert!(make_thumbnail(&png(DECODE_MAX_SIDE + 1, 1)).is_err());
+    }
+}
@@ -0,0 +1,42 @@
+-- Verzija reda je id transakcije koja ga je upisala. Feed vraća samo verzije ispod
+-- najstarije transakcije koja je još u toku (xmin snapshot-a), pa klijent ne može da
+-- preskoči promenu ni kad transakcije završe drugim redom nego što su počele.
+CREATE FUNCTION bump_sync_version() RETURNS TRIGGER AS $$
+BEGIN
+    NEW.sync_version := pg_current_xact_id()::TEXT::BIGINT;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Obrisani redovi, da bi klijent mogao da ih ukloni lokalno. `scope` čuva kolone
+-- obrisanog reda po kojima se feed filtrira.
+CREATE TABLE sync_tombstones (
+                                 entity        VARCHAR(50) NOT NULL,
+                                 entity_id     UUID NOT NULL,
+                                 scope         JSONB NOT NULL DEFAULT '{}',
+                                 sync_version  BIGINT NOT NULL,
+                                 deleted_at    TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                                 PRIMARY KEY (entity, entity_id)
+);
+
+-- Argumenti okidača: vrsta zapisa, pa kolone koje se čuvaju u `scope`
+CREATE FUNCTION record_sync_tombstone() RETURNS TRIGGER AS $$
+DECLARE
+    tombstone_scope JSONB := '{}';
+BEGIN
+    FOR i IN 1 .. TG_NARGS - 1 LOOP
+        tombstone_scope := tombstone_scope
+            || jsonb_build_object(TG_ARGV[i], to_jsonb(OLD) -> TG_ARGV[i]);
+    END LOOP;
+
+    INSERT INTO sync_tombstones (entity, entity_id, scope, sync_version)
+    VALUES (TG_ARGV[0], OLD.id, tombstone_scope, pg_current_xact_id()::TEXT::BIGINT)
+    ON CONFLICT (entity, entity_id)
+        DO UPDATE SET scope        = EXCLUDED.scope,
+                      sync_version = EXCLUDED.sync_version,
+                      deleted_at   = NOW();
+    RETURN OLD;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE INDEX idx_sync_tombstones_version ON sync_tombstones(sync_version, entity_id);
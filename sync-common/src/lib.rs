@@ -0,0 +1,215 @@
+//! Zajednički delovi offline sinhronizacije: verzije redova, kursor feed-a promena,
+//! tombstone-i i rezultat upisa. SQL za okidače je u `sql/sync_versioning.sql`;
+//! migracije servisa ga sadrže doslovno (proverava test ispod).
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sqlx::{FromRow, PgExecutor};
+use uuid::Uuid;
+
+/// Najviše zapisa po tipu u jednoj strani feed-a promena
+pub const SYNC_PAGE_MAX: i64 = 500;
+
+// ============== Kursor ==============
+
+/// Pozicija u feed-u: verzija reda (id transakcije koja ga je upisala) i id reda.
+/// Jedna transakcija može upisati mnogo redova iste verzije, pa id razdvaja strane
+/// unutar nje. U URL-u je `verzija` ili `verzija:id`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SyncCursor {
+    pub version: i64,
+    pub id: Uuid,
+}
+
+impl SyncCursor {
+    pub fn new(version: i64, id: Uuid) -> Self {
+        Self { version, id }
+    }
+}
+
+impl fmt::Display for SyncCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.id.is_nil() {
+            write!(f, "{}", self.version)
+        } else {
+            write!(f, "{}:{}", self.version, self.id)
+        }
+    }
+}
+
+impl FromStr for SyncCursor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid sync cursor: {}", s);
+        let (version, id) = match s.split_once(':') {
+            Some((version, id)) => (version, id.parse().map_err(|_| invalid())?),
+            None => (s, Uuid::nil()),
+        };
+        let version = version.parse().map_err(|_| invalid())?;
+
+        Ok(Self { version, id })
+    }
+}
+
+impl Serialize for SyncCursor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for SyncCursor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Kursor sledeće strane feed-a. Svaka lista je učitana sa `limit + 1` redova
+/// rastuće po kursoru; ako je neka skraćena, kursor staje na njenom poslednjem
+/// vraćenom redu, da se ništa iz ostalih lista ne preskoči.
+pub fn sync_page_cursor(
+    since: SyncCursor,
+    limit: usize,
+    cursors: &[&[SyncCursor]],
+) -> (SyncCursor, bool) {
+    let truncated_at = cursors
+        .iter()
+        .filter(|list| list.len() > limit)
+        .map(|list| list[limit - 1])
+        .min();
+
+    match truncated_at {
+        Some(cursor) => (cursor, true),
+        None => (
+            cursors
+                .iter()
+                .filter_map(|list| list.last().copied())
+                .max()
+                .unwrap_or(since)
+                .max(since),
+            false,
+        ),
+    }
+}
+
+/// Verzije ispod ove vrednosti pripadaju završenim transakcijama; feed i agregati
+/// čitaju samo njih. Dugačka transakcija u bazi zadržava watermark dok ne završi.
+pub async fn sync_watermark<'e, E: PgExecutor<'e>>(executor: E) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar("SELECT pg_snapshot_xmin(pg_current_snapshot())::TEXT::BIGINT")
+        .fetch_one(executor)
+        .await
+}
+
+// ============== Zapisi ==============
+
+/// Obrisan zapis
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct SyncTombstone {
+    pub entity: String,
+    pub entity_id: Uuid,
+    pub sync_version: i64,
+    pub deleted_at: DateTime<Utc>,
+}
+
+impl SyncTombstone {
+    pub fn cursor(&self) -> SyncCursor {
+        SyncCursor::new(self.sync_version, self.entity_id)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncOutcome {
+    Created,
+    Updated,
+    Unchanged,
+    Conflict,
+    Rejected,
+}
+
+/// Rezultat za jedan poslati zapis; kod konflikta klijent dobija serversku verziju
+#[derive(Debug, Serialize)]
+pub struct SyncResult<T> {
+    pub id: Uuid,
+    pub outcome: SyncOutcome,
+    pub version: Option<i64>,
+    pub message: Option<String>,
+    pub server_record: Option<T>,
+}
+
+impl<T> SyncResult<T> {
+    pub fn rejected(id: Uuid, message: impl Into<String>) -> Self {
+        SyncResult {
+            id,
+            outcome: SyncOutcome::Rejected,
+            version: None,
+            message: Some(message.into()),
+            server_record: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SYNC_VERSIONING_SQL: &str = include_str!("../sql/sync_versioning.sql");
+
+    fn at(version: i64) -> SyncCursor {
+        SyncCursor::new(version, Uuid::nil())
+    }
+
+    #[test]
+    fn test_page_cursor_without_truncation() {
+        assert_eq!(sync_page_cursor(at(10), 3, &[&[at(11), at(14)], &[at(12)]]), (at(14), false));
+        assert_eq!(sync_page_cursor(at(10), 3, &[&[], &[]]), (at(10), false));
+    }
+
+    #[test]
+    fn test_page_cursor_stops_at_shortest_truncated_list() {
+        // Prva lista ima još redova posle 13; druga se zato ne sme vratiti preko 13
+        let (cursor, has_more) =
+            sync_page_cursor(at(10), 2, &[&[at(11), at(13), at(20)], &[at(12), at(15)]]);
+
+        assert_eq!(cursor, at(13));
+        assert!(has_more);
+    }
+
+    #[test]
+    fn test_page_cursor_splits_one_transaction() {
+        // Tri reda iste verzije (jedna transakcija) na strani od dva
+        let ids = [Uuid::from_u128(1), Uuid::from_u128(2), Uuid::from_u128(3)];
+        let rows: Vec<SyncCursor> = ids.iter().map(|id| SyncCursor::new(40, *id)).collect();
+
+        let (cursor, has_more) = sync_page_cursor(at(10), 2, &[&rows]);
+
+        assert_eq!(cursor, SyncCursor::new(40, ids[1]));
+        assert!(has_more);
+        assert!(rows[2] > cursor);
+    }
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let cursor = SyncCursor::new(812, Uuid::from_u128(7));
+
+        assert_eq!(cursor.to_string().parse::<SyncCursor>(), Ok(cursor));
+        assert_eq!("812".parse::<SyncCursor>(), Ok(at(812)));
+        assert!("812:nije-uuid".parse::<SyncCursor>().is_err());
+    }
+
+    #[test]
+    fn test_service_migrations_use_shared_sql() {
+        for migration in [
+            include_str!("../../harvest-service/migrations/20261018170000_offline_sync.up.sql"),
+            include_str!("../../fermentation-service/migrations/20261018170000_offline_sync.up.sql"),
+        ] {
+            assert!(migration.contains(SYNC_VERSIONING_SQL));
+            assert!(!migration.contains("pg_advisory_xact_lock"));
+        }
+    }
+}